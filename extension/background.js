@@ -31,11 +31,17 @@ function connect() {
   try {
     ws = new WebSocket(FGP_WS_URL);
 
-    ws.onopen = () => {
+    ws.onopen = async () => {
       console.log('[FGP] Connected to daemon');
       connectionStatus = 'connected';
       updateBadge('connected');
 
+      // A daemon started with --extension-auth expects the token first
+      const { fgpAuthToken } = await chrome.storage.local.get('fgpAuthToken');
+      if (fgpAuthToken) {
+        send({ type: 'auth', token: fgpAuthToken });
+      }
+
       // Send hello message
       send({ type: 'hello', version: FGP_EXTENSION_VERSION, capabilities: getCapabilities() });
     };
@@ -485,6 +491,18 @@ chrome.runtime.onMessage.addListener((message, sender, sendResponse) => {
     return true;
   }
 
+  if (message.type === 'setAuthToken') {
+    chrome.storage.local.set({ fgpAuthToken: message.token }).then(() => {
+      if (ws) {
+        ws.close();
+      }
+      ws = null;
+      connect();
+      sendResponse({ ok: true });
+    });
+    return true;
+  }
+
   if (message.type === 'reconnect') {
     if (ws) {
       ws.close();
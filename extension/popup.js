@@ -3,6 +3,8 @@
 const statusDot = document.getElementById('statusDot');
 const statusText = document.getElementById('statusText');
 const connectBtn = document.getElementById('connectBtn');
+const tokenInput = document.getElementById('tokenInput');
+const tokenBtn = document.getElementById('tokenBtn');
 
 // Check connection status
 async function checkStatus() {
@@ -34,6 +36,13 @@ connectBtn.addEventListener('click', async () => {
   setTimeout(checkStatus, 1000);
 });
 
+tokenBtn.addEventListener('click', async () => {
+  updateUI('connecting');
+  await chrome.runtime.sendMessage({ type: 'setAuthToken', token: tokenInput.value.trim() });
+  tokenInput.value = '';
+  setTimeout(checkStatus, 1000);
+});
+
 // Check status on popup open
 checkStatus();
 
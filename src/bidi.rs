@@ -0,0 +1,397 @@
+//! WebDriver BiDi subset served on the extension bridge port.
+//!
+//! With `start --protocol bidi`, WebSocket clients that connect to `/session`
+//! on the bridge port speak WebDriver BiDi instead of the extension protocol.
+//! BiDi browsing contexts map onto daemon sessions (`"default"` is the
+//! default session), so tooling and FGP calls can share a page. A command
+//! for a context waits in that session's lane behind FGP calls, and a browser
+//! that died is relaunched the same way; its sessions then report
+//! `no such frame`.
+//!
+//! Supported commands:
+//! - `session.new`, `session.status`, `session.end`
+//! - `browsingContext.getTree`, `browsingContext.navigate`,
+//!   `browsingContext.captureScreenshot`
+//! - `script.evaluate`
+//!
+//! Anything else is answered with an `unknown command` error.
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::runtime::Handle;
+
+use crate::lanes::SessionLanes;
+use crate::models::ScreenshotFormat;
+use crate::service::ServiceClient;
+
+/// Routes BiDi commands to the service's browser client.
+pub struct BidiTarget {
+    client: ServiceClient,
+    /// The service's session lanes
+    lanes: Arc<SessionLanes>,
+    /// Runtime the browser connection lives on
+    runtime: Handle,
+}
+
+/// A BiDi command message.
+#[derive(Debug, Deserialize)]
+struct BidiCommand {
+    id: u64,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// A BiDi error code and message.
+#[derive(Debug, PartialEq)]
+struct BidiError {
+    code: &'static str,
+    message: String,
+}
+
+impl BidiError {
+    fn new(code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn invalid_argument(message: impl Into<String>) -> Self {
+        Self::new("invalid argument", message)
+    }
+}
+
+/// Map a client error to a BiDi error.
+fn from_client_error(e: anyhow::Error) -> BidiError {
+    let message = e.to_string();
+    if is_missing_session(&message) {
+        BidiError::new("no such frame", message)
+    } else {
+        BidiError::new("unknown error", message)
+    }
+}
+
+impl BidiTarget {
+    pub fn new(client: ServiceClient, lanes: Arc<SessionLanes>, runtime: Handle) -> Self {
+        Self {
+            client,
+            lanes,
+            runtime,
+        }
+    }
+
+    /// Answer one BiDi message with its JSON response.
+    pub async fn handle(&self, text: &str) -> String {
+        let client = self.client.clone();
+        let lanes = self.lanes.clone();
+        let text = text.to_string();
+
+        // Browser calls run on the service runtime that owns the CDP connection
+        let response = self
+            .runtime
+            .spawn(async move { respond(&client, &lanes, &text).await })
+            .await
+            .unwrap_or_else(|e| {
+                error_response(None, &BidiError::new("unknown error", e.to_string()))
+            });
+        response.to_string()
+    }
+}
+
+async fn respond(client: &ServiceClient, lanes: &SessionLanes, text: &str) -> Value {
+    let command = match parse_command(text) {
+        Ok(command) => command,
+        Err((id, error)) => return error_response(id, &error),
+    };
+
+    // Held until the command returns, like an FGP call's turn
+    let _turn = match command_context(&command) {
+        Some(context) => Some(lanes.enter_async(context).await),
+        None => None,
+    };
+
+    match dispatch(client, &command).await {
+        Ok(result) => json!({"type": "success", "id": command.id, "result": result}),
+        Err(error) => error_response(Some(command.id), &error),
+    }
+}
+
+fn parse_command(text: &str) -> Result<BidiCommand, (Option<u64>, BidiError)> {
+    let value: Value = serde_json::from_str(text)
+        .map_err(|e| (None, BidiError::invalid_argument(e.to_string())))?;
+    let id = value.get("id").and_then(|v| v.as_u64());
+
+    serde_json::from_value(value).map_err(|e| {
+        (
+            id,
+            BidiError::invalid_argument(format!("Malformed command: {}", e)),
+        )
+    })
+}
+
+/// Browsing context (session) a command runs in: `context`, or for
+/// `script.evaluate`, `target.context`.
+fn command_context(command: &BidiCommand) -> Option<&str> {
+    let params = &command.params;
+    params
+        .get("context")
+        .or_else(|| params.get("target").and_then(|t| t.get("context")))
+        .and_then(|v| v.as_str())
+}
+
+fn error_response(id: Option<u64>, error: &BidiError) -> Value {
+    json!({
+        "type": "error",
+        "id": id,
+        "error": error.code,
+        "message": error.message,
+    })
+}
+
+async fn dispatch(client: &ServiceClient, command: &BidiCommand) -> Result<Value, BidiError> {
+    let params = &command.params;
+
+    // Commands that don't touch the browser
+    match command.method.as_str() {
+        "session.status" => {
+            let ready = client.current().await.is_some();
+            return Ok(json!({"ready": ready, "message": "fgp-browser"}));
+        }
+        "session.new" => {
+            return Ok(json!({
+                "sessionId": uuid::Uuid::new_v4().to_string(),
+                "capabilities": {
+                    "browserName": "chrome",
+                    "platformName": std::env::consts::OS,
+                    "acceptInsecureCerts": false,
+                    "setWindowRect": false,
+                },
+            }));
+        }
+        "session.end" => return Ok(json!({})),
+        _ => {}
+    }
+
+    let client = client
+        .get()
+        .await
+        .map_err(|e| BidiError::new("unknown error", format!("{:#}", e)))?;
+
+    match command.method.as_str() {
+        "browsingContext.getTree" => {
+            let contexts: Vec<Value> = client
+                .list_sessions()
+                .await
+                .into_iter()
+                .map(|s| {
+                    json!({
+                        "context": s.id,
+                        "url": s.url.unwrap_or_else(|| "about:blank".to_string()),
+                        "userContext": "default",
+                        "children": [],
+                        "parent": null,
+                    })
+                })
+                .collect();
+            Ok(json!({"contexts": contexts}))
+        }
+        "browsingContext.navigate" => {
+            let context = str_param(params, "context")?;
+            let url = str_param(params, "url")?;
+            let result = client
+                .navigate(url, Some(context))
+                .await
+                .map_err(from_client_error)?;
+            Ok(json!({"navigation": null, "url": result.url}))
+        }
+        "browsingContext.captureScreenshot" => {
+            let context = str_param(params, "context")?;
+            let format = screenshot_format(params.get("format"))?;
+            let result = client
+                .screenshot(None, format, Some(context))
+                .await
+                .map_err(from_client_error)?;
+            Ok(json!({"data": result.data.unwrap_or_default()}))
+        }
+        "script.evaluate" => {
+            let expression = str_param(params, "expression")?;
+            let context = params
+                .get("target")
+                .ok_or_else(|| BidiError::invalid_argument("Missing 'target'"))
+                .and_then(|target| str_param(target, "context"))?;
+
+            match client.evaluate(expression, Some(context)).await {
+                Ok(value) => Ok(json!({
+                    "type": "success",
+                    "result": remote_value(&value),
+                    "realm": context,
+                })),
+                Err(e) if is_missing_session(&e.to_string()) => Err(from_client_error(e)),
+                Err(e) => Ok(json!({
+                    "type": "exception",
+                    "exceptionDetails": {
+                        "text": e.to_string(),
+                        "lineNumber": 0,
+                        "columnNumber": 0,
+                        "exception": {"type": "error"},
+                        "stackTrace": {"callFrames": []},
+                    },
+                    "realm": context,
+                })),
+            }
+        }
+        other => Err(BidiError::new(
+            "unknown command",
+            format!("Unsupported command: {}", other),
+        )),
+    }
+}
+
+/// Whether a client error means the context (session) doesn't exist.
+fn is_missing_session(message: &str) -> bool {
+    message.starts_with("Session not found") || message.starts_with("SESSION_LOST")
+}
+
+fn str_param<'a>(params: &'a Value, name: &str) -> Result<&'a str, BidiError> {
+    params
+        .get(name)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| BidiError::invalid_argument(format!("Missing '{}'", name)))
+}
+
+/// BiDi `ImageFormat`: `{type: "image/png" | "image/jpeg", quality?: 0.0-1.0}`.
+fn screenshot_format(format: Option<&Value>) -> Result<ScreenshotFormat, BidiError> {
+    let Some(format) = format.filter(|f| !f.is_null()) else {
+        return Ok(ScreenshotFormat::Png);
+    };
+
+    let quality = format
+        .get("quality")
+        .and_then(|q| q.as_f64())
+        .map(|q| (q.clamp(0.0, 1.0) * 100.0).round() as u8)
+        .unwrap_or(80);
+
+    match format.get("type").and_then(|t| t.as_str()) {
+        Some("image/png") | None => Ok(ScreenshotFormat::Png),
+        Some("image/jpeg") => Ok(ScreenshotFormat::Jpeg { quality }),
+        Some("image/webp") => Ok(ScreenshotFormat::Webp { quality }),
+        Some(other) => Err(BidiError::new(
+            "unsupported operation",
+            format!("Unsupported image format: {}", other),
+        )),
+    }
+}
+
+/// Serialize a JSON value as a BiDi `RemoteValue`.
+fn remote_value(value: &Value) -> Value {
+    match value {
+        Value::Null => json!({"type": "null"}),
+        Value::Bool(b) => json!({"type": "boolean", "value": b}),
+        Value::Number(n) => json!({"type": "number", "value": n}),
+        Value::String(s) => json!({"type": "string", "value": s}),
+        Value::Array(items) => json!({
+            "type": "array",
+            "value": items.iter().map(remote_value).collect::<Vec<_>>(),
+        }),
+        Value::Object(map) => json!({
+            "type": "object",
+            "value": map
+                .iter()
+                .map(|(k, v)| json!([k, remote_value(v)]))
+                .collect::<Vec<_>>(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        let command = parse_command(
+            r#"{"id": 3, "method": "browsingContext.navigate", "params": {"context": "default", "url": "https://example.com"}}"#,
+        )
+        .unwrap();
+        assert_eq!(command.id, 3);
+        assert_eq!(command.method, "browsingContext.navigate");
+        assert_eq!(
+            str_param(&command.params, "url").unwrap(),
+            "https://example.com"
+        );
+
+        let (id, error) = parse_command(r#"{"id": 4}"#).unwrap_err();
+        assert_eq!(id, Some(4));
+        assert_eq!(error.code, "invalid argument");
+
+        let (id, _) = parse_command("not json").unwrap_err();
+        assert_eq!(id, None);
+    }
+
+    #[test]
+    fn test_command_context() {
+        let command = parse_command(
+            r#"{"id": 1, "method": "browsingContext.navigate", "params": {"context": "agent-a", "url": "about:blank"}}"#,
+        )
+        .unwrap();
+        assert_eq!(command_context(&command), Some("agent-a"));
+
+        let command = parse_command(
+            r#"{"id": 2, "method": "script.evaluate", "params": {"expression": "1", "target": {"context": "default"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(command_context(&command), Some("default"));
+
+        let command = parse_command(r#"{"id": 3, "method": "browsingContext.getTree"}"#).unwrap();
+        assert_eq!(command_context(&command), None);
+    }
+
+    #[test]
+    fn test_remote_value() {
+        assert_eq!(remote_value(&Value::Null), json!({"type": "null"}));
+        assert_eq!(
+            remote_value(&json!([1, "a"])),
+            json!({"type": "array", "value": [
+                {"type": "number", "value": 1},
+                {"type": "string", "value": "a"},
+            ]})
+        );
+        assert_eq!(
+            remote_value(&json!({"ok": true})),
+            json!({"type": "object", "value": [["ok", {"type": "boolean", "value": true}]]})
+        );
+    }
+
+    #[test]
+    fn test_screenshot_format() {
+        assert_eq!(screenshot_format(None).unwrap(), ScreenshotFormat::Png);
+        assert_eq!(
+            screenshot_format(Some(&json!({"type": "image/jpeg", "quality": 0.5}))).unwrap(),
+            ScreenshotFormat::Jpeg { quality: 50 }
+        );
+        assert_eq!(
+            screenshot_format(Some(&json!({"type": "image/gif"})))
+                .unwrap_err()
+                .code,
+            "unsupported operation"
+        );
+    }
+
+    #[test]
+    fn test_from_client_error() {
+        let lost = anyhow::anyhow!("SESSION_LOST: session 'a' was closed when Chrome exited");
+        assert_eq!(from_client_error(lost).code, "no such frame");
+        let other = anyhow::anyhow!("Navigation failed");
+        assert_eq!(from_client_error(other).code, "unknown error");
+    }
+
+    #[test]
+    fn test_error_response() {
+        let response = error_response(Some(7), &BidiError::new("unknown command", "nope"));
+        assert_eq!(response["type"], "error");
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["error"], "unknown command");
+    }
+}
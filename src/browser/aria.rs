@@ -7,41 +7,211 @@ use chromiumoxide::cdp::browser_protocol::accessibility::{
 use chromiumoxide::page::Page;
 use serde::Deserialize;
 use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet, VecDeque};
 
-use crate::models::AriaNode;
+use super::frames::list_frames;
+use crate::models::{AriaNode, AriaNodeChange, SnapshotDiff};
 
 /// Extract ARIA accessibility tree from page.
-pub async fn extract_aria_tree(page: &Page) -> Result<Vec<AriaNode>> {
+///
+/// `max_depth` limits how many levels of kept nodes are returned (0 = top
+/// level only). Nodes at the limit whose descendants were dropped are marked
+/// `truncated`. `None` returns every node.
+///
+/// With `include_frames`, nodes from same-process iframes are appended after
+/// the top document, tagged with their frame index.
+pub async fn extract_aria_tree(
+    page: &Page,
+    max_depth: Option<usize>,
+    include_frames: bool,
+) -> Result<Vec<AriaNode>> {
     let mut counter = 0;
 
     // Try CDP accessibility tree first
     if let Ok(response) = page.execute(GetFullAxTreeParams::default()).await {
-        // Single-pass extraction - no clones, references only
-        let capacity = response.nodes.len() / 4; // Most nodes filtered out
-        let mut nodes = Vec::with_capacity(capacity);
-
-        for node in &response.nodes {
-            if is_interactive_node(node) || has_role_or_name(node) {
-                nodes.push(convert_node_ref(node, &mut counter));
-            }
-        }
+        let mut nodes = convert_ax_nodes(&response.nodes, max_depth, None, &mut counter);
 
         if !nodes.is_empty() {
             tracing::debug!(
                 "Extracted {} nodes from CDP accessibility tree",
                 nodes.len()
             );
+            if include_frames {
+                nodes.extend(extract_frame_trees(page, max_depth, &mut counter).await?);
+            }
             return Ok(nodes);
         }
     }
 
     // Fallback to DOM traversal - more reliable on macOS
     tracing::debug!("CDP accessibility tree empty, falling back to DOM traversal");
-    let nodes = extract_dom_interactives(page, &mut counter).await?;
+    let nodes = extract_dom_interactives(page, max_depth, &mut counter).await?;
 
     Ok(nodes)
 }
 
+/// Filter and convert a CDP accessibility tree, applying the depth limit.
+fn convert_ax_nodes(
+    all: &[CdpAxNode],
+    max_depth: Option<usize>,
+    frame: Option<usize>,
+    counter: &mut usize,
+) -> Vec<AriaNode> {
+    let kept: Vec<&CdpAxNode> = all
+        .iter()
+        .filter(|node| is_interactive_node(node) || has_role_or_name(node))
+        .collect();
+
+    let (keep, truncated) = apply_depth_limit(&cdp_ancestry(all, &kept), max_depth);
+
+    // Single-pass extraction - no clones, references only
+    let mut nodes = Vec::with_capacity(kept.len());
+    for (i, node) in kept.iter().enumerate() {
+        if keep[i] {
+            let mut aria_node = convert_node_ref(node, truncated[i], counter);
+            aria_node.frame = frame;
+            nodes.push(aria_node);
+        }
+    }
+    nodes
+}
+
+/// Accessibility trees of child frames. Frames that can't be read from this
+/// page (cross-origin, out of process) are skipped.
+async fn extract_frame_trees(
+    page: &Page,
+    max_depth: Option<usize>,
+    counter: &mut usize,
+) -> Result<Vec<AriaNode>> {
+    let mut nodes = Vec::new();
+
+    for frame in list_frames(page).await? {
+        let params = GetFullAxTreeParams::builder().frame_id(frame.id).build();
+        match page.execute(params).await {
+            Ok(response) => nodes.extend(convert_ax_nodes(
+                &response.nodes,
+                max_depth,
+                Some(frame.info.index),
+                counter,
+            )),
+            Err(e) => tracing::debug!(
+                "Skipping frame {} ({}): {}",
+                frame.info.index,
+                frame.info.url,
+                e
+            ),
+        }
+    }
+
+    Ok(nodes)
+}
+
+/// Depth and nearest kept ancestor (as an index into `kept`) for each kept
+/// CDP node. Ignored nodes in between don't count towards depth.
+fn cdp_ancestry(all: &[CdpAxNode], kept: &[&CdpAxNode]) -> Vec<(usize, Option<usize>)> {
+    let by_id: HashMap<&str, &CdpAxNode> = all.iter().map(|n| (n.node_id.as_ref(), n)).collect();
+    let kept_index: HashMap<&str, usize> = kept
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (n.node_id.as_ref(), i))
+        .collect();
+
+    // Nearest kept ancestor of every kept node, walking through ignored nodes
+    let parents: Vec<Option<usize>> = kept
+        .iter()
+        .map(|node| {
+            let mut current = node.parent_id.as_ref().map(|id| id.as_ref());
+            while let Some(id) = current {
+                if let Some(&i) = kept_index.get(id) {
+                    return Some(i);
+                }
+                current = by_id
+                    .get(id)
+                    .and_then(|n| n.parent_id.as_ref())
+                    .map(|id| id.as_ref());
+            }
+            None
+        })
+        .collect();
+
+    let mut depths: Vec<Option<usize>> = vec![None; kept.len()];
+    for start in 0..kept.len() {
+        // Walk up to the first ancestor with a known depth, then fill back down
+        let mut chain = Vec::new();
+        let mut current = Some(start);
+        let mut base = 0;
+        while let Some(i) = current {
+            if let Some(depth) = depths[i] {
+                base = depth + 1;
+                break;
+            }
+            if chain.contains(&i) {
+                break;
+            }
+            chain.push(i);
+            current = parents[i];
+        }
+        for (offset, &i) in chain.iter().rev().enumerate() {
+            depths[i] = Some(base + offset);
+        }
+    }
+
+    depths
+        .into_iter()
+        .zip(parents)
+        .map(|(depth, parent)| (depth.unwrap_or(0), parent))
+        .collect()
+}
+
+/// Decide which nodes survive a depth limit, given each node's depth and the
+/// index of its parent. Returns `(keep, truncated)` flags aligned with input.
+fn apply_depth_limit(
+    ancestry: &[(usize, Option<usize>)],
+    max_depth: Option<usize>,
+) -> (Vec<bool>, Vec<bool>) {
+    let mut keep = vec![true; ancestry.len()];
+    let mut truncated = vec![false; ancestry.len()];
+
+    if let Some(limit) = max_depth {
+        for (i, &(depth, parent)) in ancestry.iter().enumerate() {
+            if depth > limit {
+                keep[i] = false;
+                if depth == limit + 1 {
+                    if let Some(p) = parent {
+                        truncated[p] = true;
+                    }
+                }
+            }
+        }
+    }
+
+    (keep, truncated)
+}
+
+/// Roles an agent can act on (click, type, select).
+const ACTIONABLE_ROLES: &[&str] = &[
+    "button",
+    "link",
+    "textbox",
+    "checkbox",
+    "radio",
+    "combobox",
+    "listbox",
+    "menuitem",
+    "tab",
+    "slider",
+    "searchbox",
+    "spinbutton",
+    "switch",
+    "option",
+    "menuitemcheckbox",
+    "menuitemradio",
+    "treeitem",
+];
+
+/// Headings, images and landmarks kept in full snapshots for orientation.
+const STRUCTURAL_ROLES: &[&str] = &["heading", "img", "navigation", "main", "article", "section"];
+
 /// Check if a node is interactive and should be included.
 fn is_interactive_node(node: &CdpAxNode) -> bool {
     let role_match = node
@@ -50,37 +220,30 @@ fn is_interactive_node(node: &CdpAxNode) -> bool {
         .and_then(|role| role.value.as_ref())
         .is_some_and(|value| {
             let role_str = json_as_str(value).unwrap_or("");
-            matches!(
-                role_str,
-                "button"
-                    | "link"
-                    | "textbox"
-                    | "checkbox"
-                    | "radio"
-                    | "combobox"
-                    | "listbox"
-                    | "menuitem"
-                    | "tab"
-                    | "slider"
-                    | "searchbox"
-                    | "spinbutton"
-                    | "switch"
-                    | "option"
-                    | "menuitemcheckbox"
-                    | "menuitemradio"
-                    | "treeitem"
-                    | "heading"
-                    | "img"
-                    | "navigation"
-                    | "main"
-                    | "article"
-                    | "section"
-            )
+            ACTIONABLE_ROLES.contains(&role_str) || STRUCTURAL_ROLES.contains(&role_str)
         });
 
     role_match || is_focusable(node)
 }
 
+/// Keep only nodes an agent can act on: actionable roles and focusable
+/// elements. Children of dropped nodes move up to the nearest kept ancestor.
+pub fn snapshot_interactive_only(nodes: &[AriaNode]) -> Vec<AriaNode> {
+    let mut out = Vec::new();
+    for node in nodes {
+        let children = snapshot_interactive_only(&node.children);
+        if node.focusable || ACTIONABLE_ROLES.contains(&node.role.as_str()) {
+            out.push(AriaNode {
+                children,
+                ..node.clone()
+            });
+        } else {
+            out.extend(children);
+        }
+    }
+    out
+}
+
 fn has_role_or_name(node: &CdpAxNode) -> bool {
     node.role.as_ref().and_then(|r| r.value.as_ref()).is_some()
         || node.name.as_ref().and_then(|n| n.value.as_ref()).is_some()
@@ -124,9 +287,21 @@ struct DomSnapshotNode {
     focusable: bool,
     #[serde(default)]
     focused: bool,
+    #[serde(default)]
+    depth: usize,
+    #[serde(default)]
+    parent: Option<usize>,
+    #[serde(default)]
+    in_shadow_dom: bool,
 }
 
-async fn extract_dom_interactives(page: &Page, counter: &mut usize) -> Result<Vec<AriaNode>> {
+/// DOM-based fallback snapshot. Open shadow roots are traversed, and their
+/// elements are tagged `in_shadow_dom`.
+async fn extract_dom_interactives(
+    page: &Page,
+    max_depth: Option<usize>,
+    counter: &mut usize,
+) -> Result<Vec<AriaNode>> {
     let script = r#"(() => {
         const roleFor = (el) => {
             const explicit = el.getAttribute && el.getAttribute('role');
@@ -170,21 +345,48 @@ async fn extract_dom_interactives(page: &Page, counter: &mut usize) -> Result<Ve
             '[role]', 'img', 'nav', 'main', 'article', 'section',
             'h1', 'h2', 'h3', 'h4', 'h5', 'h6', '[contenteditable]'
         ].join(',');
+        // Matches in document order, descending into open shadow roots
+        // right after their host so ancestors still come first
+        const elements = [];
+        const collect = (root, inShadow) => {
+            for (const el of root.querySelectorAll('*')) {
+                if (el.matches(selector)) elements.push([el, inShadow]);
+                if (el.shadowRoot) collect(el.shadowRoot, true);
+            }
+        };
+        collect(document, false);
+        // Parent element, crossing shadow boundaries to the host
+        const parentOf = (el) =>
+            el.parentElement || (el.parentNode instanceof ShadowRoot ? el.parentNode.host : null);
+        let active = document.activeElement;
+        while (active && active.shadowRoot && active.shadowRoot.activeElement) {
+            active = active.shadowRoot.activeElement;
+        }
         const nodes = [];
         const seen = new Set();
-        for (const el of document.querySelectorAll(selector)) {
+        const indexOf = new Map();
+        for (const [el, inShadow] of elements) {
             if (seen.has(el)) continue;
             seen.add(el);
             const role = roleFor(el);
             if (!role) continue;
             const name = nameFor(el);
             const value = 'value' in el ? el.value : null;
+            // Document order guarantees ancestors were indexed first
+            let parent = null;
+            for (let p = parentOf(el); p; p = parentOf(p)) {
+                if (indexOf.has(p)) { parent = indexOf.get(p); break; }
+            }
+            indexOf.set(el, nodes.length);
             nodes.push({
                 role,
                 name,
                 value,
                 focusable: el.tabIndex >= 0,
-                focused: document.activeElement === el,
+                focused: active === el,
+                depth: parent === null ? 0 : nodes[parent].depth + 1,
+                parent,
+                in_shadow_dom: inShadow,
             });
         }
         return nodes;
@@ -197,10 +399,15 @@ async fn extract_dom_interactives(page: &Page, counter: &mut usize) -> Result<Ve
         .into_value()
         .context("Failed to parse DOM fallback for ARIA snapshot")?;
 
+    let ancestry: Vec<(usize, Option<usize>)> =
+        dom_nodes.iter().map(|n| (n.depth, n.parent)).collect();
+    let (keep, truncated) = apply_depth_limit(&ancestry, max_depth);
+
     let nodes = dom_nodes
         .into_iter()
-        .filter(|n| !n.role.is_empty())
-        .map(|n| {
+        .enumerate()
+        .filter(|(i, n)| keep[*i] && !n.role.is_empty())
+        .map(|(i, n)| {
             *counter += 1;
             let ref_id = format!("@e{}", counter);
             let name = n.name.and_then(|s| {
@@ -227,6 +434,9 @@ async fn extract_dom_interactives(page: &Page, counter: &mut usize) -> Result<Ve
                 focusable: n.focusable,
                 focused: n.focused,
                 children: vec![],
+                truncated: truncated[i],
+                frame: None,
+                in_shadow_dom: n.in_shadow_dom,
             }
         })
         .collect();
@@ -235,7 +445,7 @@ async fn extract_dom_interactives(page: &Page, counter: &mut usize) -> Result<Ve
 }
 
 /// Convert CDP AxNode reference to our AriaNode format - zero-copy extraction.
-fn convert_node_ref(node: &CdpAxNode, counter: &mut usize) -> AriaNode {
+fn convert_node_ref(node: &CdpAxNode, truncated: bool, counter: &mut usize) -> AriaNode {
     *counter += 1;
     let ref_id = format!("@e{}", counter);
 
@@ -296,9 +506,141 @@ fn convert_node_ref(node: &CdpAxNode, counter: &mut usize) -> AriaNode {
         focusable,
         focused,
         children: vec![], // Flatten for LLM consumption
+        truncated,
+        frame: None,
+        in_shadow_dom: false,
     }
 }
 
+/// Render ARIA nodes as indented Markdown, one line per node.
+///
+/// Headings become `## name`; everything else is `- [role] name (value)`.
+/// Each line ends with the node's `@eN` ref so it can still be targeted.
+pub fn render_as_markdown(nodes: &[AriaNode]) -> String {
+    let mut out = String::new();
+    render_nodes(nodes, 0, &mut out);
+    out
+}
+
+fn render_nodes(nodes: &[AriaNode], depth: usize, out: &mut String) {
+    for node in nodes {
+        let name = node
+            .name
+            .as_deref()
+            .map(collapse_whitespace)
+            .unwrap_or_default();
+
+        if node.role == "heading" && !name.is_empty() {
+            out.push_str(&format!("## {} {}\n", name, node.ref_id));
+        } else {
+            out.push_str(&"  ".repeat(depth));
+            out.push_str(&format!("- [{}]", node.role));
+            if !name.is_empty() {
+                out.push(' ');
+                out.push_str(&name);
+            }
+            if let Some(value) = node.value.as_deref().map(collapse_whitespace) {
+                if !value.is_empty() {
+                    out.push_str(&format!(" ({})", value));
+                }
+            }
+            out.push(' ');
+            out.push_str(&node.ref_id);
+            out.push('\n');
+        }
+
+        render_nodes(&node.children, depth + 1, out);
+    }
+}
+
+/// Names can contain newlines and runs of spaces; keep each node on one line.
+fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Compare two snapshots.
+///
+/// Nodes are matched on (frame, role, name), pairing the nth occurrence in
+/// `before` with the nth in `after`. Matched nodes whose value or state
+/// differs are reported as changed; the rest are added or removed.
+pub fn diff_aria_snapshots(before: &[AriaNode], after: &[AriaNode]) -> SnapshotDiff {
+    let before = flatten_nodes(before);
+    let after = flatten_nodes(after);
+
+    let mut unmatched: HashMap<DiffKey, VecDeque<&AriaNode>> = HashMap::new();
+    for node in &before {
+        unmatched.entry(diff_key(node)).or_default().push_back(node);
+    }
+
+    let mut diff = SnapshotDiff::default();
+    for node in &after {
+        match unmatched
+            .get_mut(&diff_key(node))
+            .and_then(|queue| queue.pop_front())
+        {
+            Some(old) => {
+                let fields = changed_fields(old, node);
+                if !fields.is_empty() {
+                    diff.changed.push(AriaNodeChange {
+                        before: (*old).clone(),
+                        after: (*node).clone(),
+                        fields,
+                    });
+                }
+            }
+            None => diff.added.push((*node).clone()),
+        }
+    }
+
+    // Keep removed nodes in their original document order
+    let left: HashSet<&str> = unmatched
+        .values()
+        .flatten()
+        .map(|n| n.ref_id.as_str())
+        .collect();
+    diff.removed = before
+        .iter()
+        .filter(|n| left.contains(n.ref_id.as_str()))
+        .map(|n| (*n).clone())
+        .collect();
+
+    diff
+}
+
+/// Frame, role and name: what identifies a node across snapshots.
+type DiffKey<'a> = (Option<usize>, &'a str, Option<&'a str>);
+
+fn diff_key(node: &AriaNode) -> DiffKey<'_> {
+    (node.frame, node.role.as_str(), node.name.as_deref())
+}
+
+fn changed_fields(before: &AriaNode, after: &AriaNode) -> Vec<String> {
+    let mut fields = Vec::new();
+    if before.value != after.value {
+        fields.push("value".to_string());
+    }
+    if before.focusable != after.focusable {
+        fields.push("focusable".to_string());
+    }
+    if before.focused != after.focused {
+        fields.push("focused".to_string());
+    }
+    fields
+}
+
+/// All nodes in document order, without their children.
+fn flatten_nodes(nodes: &[AriaNode]) -> Vec<AriaNode> {
+    let mut out = Vec::new();
+    for node in nodes {
+        out.push(AriaNode {
+            children: Vec::new(),
+            ..node.clone()
+        });
+        out.extend(flatten_nodes(&node.children));
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -355,7 +697,9 @@ mod tests {
     #[test]
     fn test_interactive_roles() {
         // Test that we correctly identify interactive roles
-        // Note: These tests check the string matching logic without CDP nodes
+        let is_interactive =
+            |role: &str| ACTIONABLE_ROLES.contains(&role) || STRUCTURAL_ROLES.contains(&role);
+
         let interactive_roles = vec![
             "button",
             "link",
@@ -384,32 +728,7 @@ mod tests {
 
         for role in interactive_roles {
             assert!(
-                matches!(
-                    role,
-                    "button"
-                        | "link"
-                        | "textbox"
-                        | "checkbox"
-                        | "radio"
-                        | "combobox"
-                        | "listbox"
-                        | "menuitem"
-                        | "tab"
-                        | "slider"
-                        | "searchbox"
-                        | "spinbutton"
-                        | "switch"
-                        | "option"
-                        | "menuitemcheckbox"
-                        | "menuitemradio"
-                        | "treeitem"
-                        | "heading"
-                        | "img"
-                        | "navigation"
-                        | "main"
-                        | "article"
-                        | "section"
-                ),
+                is_interactive(role),
                 "Role '{}' should be considered interactive",
                 role
             );
@@ -419,32 +738,7 @@ mod tests {
         let non_interactive = vec!["generic", "group", "document", "application"];
         for role in non_interactive {
             assert!(
-                !matches!(
-                    role,
-                    "button"
-                        | "link"
-                        | "textbox"
-                        | "checkbox"
-                        | "radio"
-                        | "combobox"
-                        | "listbox"
-                        | "menuitem"
-                        | "tab"
-                        | "slider"
-                        | "searchbox"
-                        | "spinbutton"
-                        | "switch"
-                        | "option"
-                        | "menuitemcheckbox"
-                        | "menuitemradio"
-                        | "treeitem"
-                        | "heading"
-                        | "img"
-                        | "navigation"
-                        | "main"
-                        | "article"
-                        | "section"
-                ),
+                !is_interactive(role),
                 "Role '{}' should NOT be considered interactive",
                 role
             );
@@ -468,4 +762,122 @@ mod tests {
         let ref3 = format!("@e{}", counter);
         assert_eq!(ref3, "@e3");
     }
+
+    fn node(ref_id: &str, role: &str, name: Option<&str>, value: Option<&str>) -> AriaNode {
+        AriaNode {
+            ref_id: ref_id.to_string(),
+            role: role.to_string(),
+            name: name.map(|s| s.to_string()),
+            value: value.map(|s| s.to_string()),
+            focusable: false,
+            focused: false,
+            children: vec![],
+            truncated: false,
+            frame: None,
+            in_shadow_dom: false,
+        }
+    }
+
+    #[test]
+    fn test_render_as_markdown() {
+        let mut nav = node("@e2", "navigation", Some("Main"), None);
+        nav.children = vec![node("@e3", "link", Some("Home"), None)];
+
+        let nodes = vec![
+            node("@e1", "heading", Some("Sign   in\n"), None),
+            nav,
+            node("@e4", "textbox", Some("Email"), Some("user@example.com")),
+            node("@e5", "button", None, None),
+        ];
+
+        assert_eq!(
+            render_as_markdown(&nodes),
+            "## Sign in @e1\n\
+             - [navigation] Main @e2\n\
+             \x20 - [link] Home @e3\n\
+             - [textbox] Email (user@example.com) @e4\n\
+             - [button] @e5\n"
+        );
+    }
+
+    #[test]
+    fn test_snapshot_interactive_only() {
+        let mut nav = node("@e2", "navigation", Some("Main"), None);
+        nav.children = vec![node("@e3", "link", Some("Home"), None)];
+        let mut focusable = node("@e5", "generic", Some("Card"), None);
+        focusable.focusable = true;
+
+        let nodes = vec![
+            node("@e1", "heading", Some("Sign in"), None),
+            nav,
+            node("@e4", "textbox", Some("Email"), None),
+            focusable,
+            node("@e6", "img", Some("Logo"), None),
+        ];
+
+        let refs: Vec<String> = snapshot_interactive_only(&nodes)
+            .into_iter()
+            .map(|n| n.ref_id)
+            .collect();
+        assert_eq!(refs, vec!["@e3", "@e4", "@e5"]);
+    }
+
+    #[test]
+    fn test_diff_aria_snapshots() {
+        let mut email = node("@e2", "textbox", Some("Email"), None);
+        let before = vec![
+            node("@e1", "heading", Some("Sign in"), None),
+            email.clone(),
+            node("@e3", "button", Some("Next"), None),
+            node("@e4", "button", Some("Next"), None),
+        ];
+
+        // Refs are renumbered on every snapshot
+        email.ref_id = "@e1".to_string();
+        email.value = Some("user@example.com".to_string());
+        email.focused = true;
+        let after = vec![
+            email,
+            node("@e2", "button", Some("Next"), None),
+            node("@e3", "alert", Some("Check your inbox"), None),
+        ];
+
+        let diff = diff_aria_snapshots(&before, &after);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].role, "alert");
+
+        let removed: Vec<&str> = diff.removed.iter().map(|n| n.ref_id.as_str()).collect();
+        assert_eq!(removed, vec!["@e1", "@e4"]);
+
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].before.ref_id, "@e2");
+        assert_eq!(diff.changed[0].after.ref_id, "@e1");
+        assert_eq!(diff.changed[0].fields, vec!["value", "focused"]);
+
+        assert!(diff_aria_snapshots(&after, &after).changed.is_empty());
+    }
+
+    #[test]
+    fn test_apply_depth_limit() {
+        // main > nav > link, main > button, heading
+        let ancestry = vec![
+            (0, None),
+            (1, Some(0)),
+            (2, Some(1)),
+            (1, Some(0)),
+            (0, None),
+        ];
+
+        let (keep, truncated) = apply_depth_limit(&ancestry, None);
+        assert!(keep.iter().all(|k| *k));
+        assert!(truncated.iter().all(|t| !*t));
+
+        let (keep, truncated) = apply_depth_limit(&ancestry, Some(1));
+        assert_eq!(keep, vec![true, true, false, true, true]);
+        assert_eq!(truncated, vec![false, true, false, false, false]);
+
+        let (keep, truncated) = apply_depth_limit(&ancestry, Some(0));
+        assert_eq!(keep, vec![true, false, false, false, true]);
+        assert_eq!(truncated, vec![true, false, false, false, false]);
+    }
 }
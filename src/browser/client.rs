@@ -13,57 +13,307 @@
 
 use anyhow::{Context, Result};
 use chromiumoxide::browser::{Browser, BrowserConfig};
-use chromiumoxide::cdp::browser_protocol::browser::BrowserContextId;
-use chromiumoxide::cdp::browser_protocol::input::{DispatchKeyEventParams, DispatchKeyEventType};
+use chromiumoxide::error::CdpError;
+use chromiumoxide::handler::Handler;
+use chromiumoxide::handler::viewport::Viewport;
+use chromiumoxide::cdp::browser_protocol::browser::{
+    BrowserContextId, GrantPermissionsParams, PermissionType, ResetPermissionsParams,
+};
+use chromiumoxide::cdp::browser_protocol::emulation::{
+    ClearGeolocationOverrideParams, SetFocusEmulationEnabledParams, SetGeolocationOverrideParams,
+};
+use chromiumoxide::cdp::browser_protocol::input::{
+    DispatchKeyEventParams, DispatchKeyEventType, DispatchMouseEventParams, DispatchMouseEventType,
+    InsertTextParams, MouseButton,
+};
 use chromiumoxide::cdp::browser_protocol::network::{
-    CookieParam, SetCookiesParams, TimeSinceEpoch,
+    Cookie, CookieParam, EventResponseReceived, GetResponseBodyParams, Headers, ResourceType,
+    SetCookiesParams, SetExtraHttpHeadersParams, TimeSinceEpoch,
+};
+use chromiumoxide::cdp::browser_protocol::page::{CaptureScreenshotFormat, PrintToPdfParams};
+use chromiumoxide::cdp::browser_protocol::storage;
+use chromiumoxide::cdp::browser_protocol::target::{
+    CloseTargetParams, CreateTargetParams, GetTargetsParams, TargetId, TargetInfo,
 };
-use chromiumoxide::cdp::browser_protocol::target::CreateBrowserContextParams;
+use chromiumoxide::element::Element;
+use chromiumoxide::layout::Point;
 use chromiumoxide::page::Page;
-use futures::StreamExt;
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use futures::{FutureExt, StreamExt};
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
-use super::aria::extract_aria_tree;
+use super::aria::{extract_aria_tree, snapshot_interactive_only};
+use super::content::extract_main_content;
+use super::console::{spawn_console_listener, watch_console, ConsoleState, DEFAULT_CONSOLE_BUFFER};
+use super::dialog::{answer_pending, spawn_dialog_handler, watch_dialogs, DialogState};
+use super::downloads::{
+    cleanup_downloads, enable_downloads, session_downloads_dir, spawn_download_listener,
+    DownloadTracker,
+};
+use super::emulation::{
+    apply_cpu_throttling, apply_emulation, apply_media, apply_network_conditions, clear_emulation,
+};
+use super::fetch::fetch_url;
+use super::frames::{element_center, evaluate_in, list_frames};
+use super::har::{start_recording, HarState};
+use super::idle::{spawn_idle_cleanup, SessionInUse};
+use super::intercept::{sync_interception, InterceptState};
+use super::limit::{check_limit, limit_reached, SessionLimit, DEFAULT_MAX_SESSIONS};
+use super::metrics::collect_metrics;
+use super::network::{
+    decode_body, enable_capture, set_blocked_urls, start_request_capture, NetworkCapture,
+    NetworkState,
+};
+use super::origin_storage::{cookie_origins, read_items, write_items, OriginStorage};
+use super::pool::{
+    create_context, recycle, spawn_refill, track_origins, SessionPool, VisitedOrigins,
+};
+use super::popups::{remove_page, spawn_popup_listener, sync_pages, PopupState};
+use super::reconnect::{spawn_health_monitor, ReconnectState};
+use super::uploads::UploadDirs;
+use crate::lanes::SessionLanes;
 use crate::models::{
-    AriaSnapshot, ClickResult, FillResult, LocalStorageState, NavigationResult, ScreenshotResult,
-    SerializableCookie,
+    AriaSnapshot, AuthState, BlockRules, BlockStatus, BrowserChannel, CapturedRequest, ClickResult,
+    ConsoleEntry, ContentExtraction, DialogPolicy, DialogStatus, DownloadCleanup, DownloadInfo,
+    ElementBounds, EmulationSettings, FetchResult, FillResult, FrameInfo, FrameTarget, Geolocation,
+    HarResult, HeaderSettings, HttpCredentials, ImageInfo, KeyPress, LinkInfo, LocalStorageState,
+    MediaEmulation, MockRule, NavigationResult, NetworkBody, NetworkConditions, NetworkFilter,
+    NetworkRequest, PageContent, PageInfo, PageList, PageMetrics, PdfOptions, PdfResult,
+    RestoredState, ScreenshotFormat, ScreenshotResult, ScrollToBottomResult, SelectedOption,
+    SerializableCookie, SessionCloneResult, SessionInfo, SessionPoolStats, TabInfo, ViewportSize,
 };
 
+/// How long a connection health check may take before Chrome counts as gone.
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Mouse moves between each pair of drag points.
+const DRAG_MOVES_PER_SEGMENT: usize = 10;
+/// Pause between drag moves, about one frame, so pages see each position.
+const DRAG_MOVE_INTERVAL: Duration = Duration::from_millis(16);
+
+/// How long `new_session_tab` waits for the tab's first page to load.
+const NEW_TAB_LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Pause between height checks while waiting for a feed to load more.
+const SCROLL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Height of the whole document, including what is scrolled out of view.
+const DOCUMENT_HEIGHT_SCRIPT: &str = "Math.max(document.body ? document.body.scrollHeight : 0, \
+     document.documentElement.scrollHeight)";
+
+/// Links and images collected by a single page scan.
+#[derive(Debug, Default, Deserialize)]
+struct PageResources {
+    #[serde(default)]
+    links: Vec<LinkInfo>,
+    #[serde(default)]
+    images: Vec<ImageInfo>,
+}
+
 /// A browser session with isolated context.
 pub struct BrowserSession {
     pub id: String,
+    /// Human-readable label given to `session.new`
+    pub name: Option<String>,
+    pub created_at: DateTime<Utc>,
     pub context_id: Option<BrowserContextId>, // None = default context
-    pub page: Page,
+    /// The session's own tab, then popups opened from it
+    pub pages: Vec<Page>,
+    /// Index into `pages` that commands target
+    pub active_page: usize,
+    /// Popups opened or closed since the session was last used
+    pub popups: Arc<Mutex<PopupState>>,
+    /// Dialog policy and history (dialogs are auto-dismissed by default)
+    pub dialogs: Arc<RwLock<DialogState>>,
+    /// Captured network requests (capture is off by default)
+    pub network: Arc<RwLock<NetworkState>>,
+    /// Outgoing requests recorded for a page action
+    pub capture: Arc<RwLock<NetworkCapture>>,
+    /// Active device emulation (None = browser default viewport)
+    pub emulation: Arc<RwLock<Option<EmulationSettings>>>,
+    /// Network throttling (None = unthrottled)
+    pub network_conditions: Arc<RwLock<Option<NetworkConditions>>>,
+    /// CPU slowdown factor (None = full speed)
+    pub cpu_throttling: Arc<RwLock<Option<f64>>>,
+    /// Emulated CSS media (None = browser defaults)
+    pub media: Arc<RwLock<Option<MediaEmulation>>>,
+    /// Request interception rules (blocking)
+    pub intercept: Arc<RwLock<InterceptState>>,
+    /// URL patterns blocked with `Network.setBlockedURLs`
+    pub blocked_urls: Arc<RwLock<Vec<String>>>,
+    /// Recent console messages and log entries
+    pub console: Arc<RwLock<ConsoleState>>,
+    /// Extra HTTP headers sent with every request
+    pub headers: Arc<RwLock<HashMap<String, String>>>,
+    /// HAR recording (off by default)
+    pub har: Arc<RwLock<HarState>>,
+    /// Caller-defined labels, reported by `session.list`
+    pub metadata: Arc<RwLock<HashMap<String, String>>>,
+    /// Bound to a tab that was already open; closing the session leaves it
+    pub attached: bool,
+    /// When a command last used the session, for the idle timeout
+    pub last_used_at: Arc<Mutex<Instant>>,
+    /// Temporary files from `upload_bytes`, removed when no longer needed
+    pub uploads: Arc<UploadDirs>,
+    /// Origins the session's pages loaded, cleared if its context is recycled
+    pub visited: Arc<VisitedOrigins>,
+}
+
+impl BrowserSession {
+    /// A session on `page`, with the dialog handler and console listener
+    /// already attached to it and everything else at its defaults.
+    pub fn new(
+        id: String,
+        context_id: Option<BrowserContextId>,
+        page: Page,
+        dialogs: Arc<RwLock<DialogState>>,
+        console: Arc<RwLock<ConsoleState>>,
+    ) -> Self {
+        Self {
+            id,
+            name: None,
+            created_at: Utc::now(),
+            context_id,
+            pages: vec![page],
+            active_page: 0,
+            popups: Arc::default(),
+            dialogs,
+            network: Arc::default(),
+            capture: Arc::default(),
+            emulation: Arc::default(),
+            network_conditions: Arc::default(),
+            cpu_throttling: Arc::default(),
+            media: Arc::default(),
+            intercept: Arc::default(),
+            blocked_urls: Arc::default(),
+            metadata: Arc::default(),
+            console,
+            headers: Arc::default(),
+            har: Arc::default(),
+            attached: false,
+            last_used_at: Arc::new(Mutex::new(Instant::now())),
+            uploads: Arc::default(),
+            visited: Arc::default(),
+        }
+    }
+
+    /// The page commands target.
+    pub fn page(&self) -> &Page {
+        &self.pages[self.active_page]
+    }
+
+    /// The session's own tab, which per-page state (network capture,
+    /// emulation) is attached to.
+    pub fn main_page(&self) -> &Page {
+        &self.pages[0]
+    }
+
+    /// Whether `target_id` is one of the session's pages.
+    pub fn has_page(&self, target_id: &TargetId) -> bool {
+        self.pages.iter().any(|p| p.target_id() == target_id)
+    }
+
+    /// Answer dialogs, buffer console messages and track visited origins
+    /// on a page joining the session, like on its own tab.
+    pub async fn watch_page(&self, page: &Page) -> Result<()> {
+        watch_dialogs(page, &self.dialogs).await?;
+        watch_console(page, &self.console).await?;
+        track_origins(page, &self.visited).await
+    }
+
+    /// Mark the session as used now.
+    pub fn touch(&self) {
+        *self.last_used_at.lock().unwrap() = Instant::now();
+    }
+
+    /// Time since a command last used the session.
+    pub fn idle_for(&self) -> Duration {
+        self.last_used_at.lock().unwrap().elapsed()
+    }
 }
 
 /// Chrome browser client with multi-session support for parallel requests.
 pub struct BrowserClient {
-    browser: Browser,
+    browser: Arc<Browser>,
     sessions: Arc<RwLock<HashMap<String, BrowserSession>>>,
     default_session_id: String,
     #[allow(dead_code)]
     user_data_dir: PathBuf,
+    /// Whether we launched a headless Chrome (false for headed and connect mode)
+    headless: bool,
+    /// Downloads across all sessions
+    downloads: Arc<DownloadTracker>,
+    /// Viewport new pages start with (None in connect mode)
+    default_viewport: Option<ViewportSize>,
+    /// Console buffer size for new sessions
+    console_buffer: AtomicUsize,
+    /// Cleared when the CDP connection fails
+    connected: Arc<AtomicBool>,
+    /// Custom sessions that were open when a previous browser exited
+    lost_sessions: Mutex<HashSet<String>>,
+    /// Sessions being opened, one attempt per id at a time
+    opening: SessionLanes,
+    /// Health task progress after the connection drops (connect mode)
+    reconnect: Arc<ReconnectState>,
+    /// Times the browser was relaunched or reconnected before this client
+    restarts: AtomicU32,
+    /// Set on a replacement client until a response has reported the restart
+    restart_unreported: AtomicBool,
+    /// Custom sessions idle this long are closed (0 = never)
+    idle_timeout_secs: Arc<AtomicU64>,
+    /// Tells the idle cleanup which sessions calls are using
+    in_use: Arc<Mutex<Option<SessionInUse>>>,
+    /// Contexts created ahead of time for `create_session`
+    pool: Arc<SessionPool>,
+    /// Custom sessions allowed at once (0 = no limit)
+    max_sessions: AtomicUsize,
 }
 
 impl BrowserClient {
     /// Create a new browser client with a default session.
-    pub async fn new(user_data_dir: PathBuf, headless: bool) -> Result<Self> {
+    ///
+    /// Every page starts at `viewport` so layout is the same on every machine.
+    /// With a `channel`, that system browser is launched instead of the
+    /// bundled chrome-headless-shell.
+    pub async fn new(
+        user_data_dir: PathBuf,
+        headless: bool,
+        viewport: ViewportSize,
+        channel: Option<BrowserChannel>,
+    ) -> Result<Self> {
         // Ensure user data directory exists
         tokio::fs::create_dir_all(&user_data_dir).await?;
 
         // Find Chrome executable - check common paths
-        let chrome_path = Self::find_chrome_executable()?;
+        let chrome_path = match channel {
+            Some(channel) => Self::find_channel_executable(channel)?,
+            None => Self::find_chrome_executable()?,
+        };
+        // Full Chrome builds no longer ship the old headless mode
+        let headless_mode = if channel.is_some() {
+            "--headless=new"
+        } else {
+            "--headless=old"
+        };
 
         let mut builder = BrowserConfig::builder()
             .chrome_executable(chrome_path)
             .user_data_dir(&user_data_dir)
-            .viewport(None)
+            .viewport(Viewport {
+                width: viewport.width,
+                height: viewport.height,
+                ..Default::default()
+            })
+            .window_size(viewport.width, viewport.height)
             .no_sandbox()
             // Performance flags (matching agent-browser/Playwright)
-            .arg("--headless=old")
+            .arg(headless_mode)
             .arg("--disable-gpu")
             .arg("--disable-dev-shm-usage")
             .arg("--disable-back-forward-cache")
@@ -93,12 +343,12 @@ impl BrowserClient {
             .build()
             .map_err(|e| anyhow::anyhow!("Failed to build browser config: {}", e))?;
 
-        let (browser, mut handler) = Browser::launch(config)
+        let (browser, handler) = Browser::launch(config)
             .await
             .context("Failed to launch browser")?;
 
         // Spawn handler task - just drain events, no logging overhead
-        tokio::spawn(async move { while handler.next().await.is_some() {} });
+        let connected = spawn_handler(handler);
 
         // Create default session with pre-warmed page
         let default_page = browser
@@ -107,20 +357,58 @@ impl BrowserClient {
             .context("Failed to create initial page")?;
 
         let default_session_id = "default".to_string();
-        let default_session = BrowserSession {
-            id: default_session_id.clone(),
-            context_id: None, // Uses browser's default context
-            page: default_page,
-        };
+        let dialogs = spawn_dialog_handler(&default_page).await?;
+        let console = spawn_console_listener(&default_page, DEFAULT_CONSOLE_BUFFER).await?;
+        // Uses browser's default context
+        let default_session = BrowserSession::new(
+            default_session_id.clone(),
+            None,
+            default_page,
+            dialogs,
+            console,
+        );
 
         let mut sessions = HashMap::new();
         sessions.insert(default_session_id.clone(), default_session);
+        let sessions = Arc::new(RwLock::new(sessions));
+
+        let downloads = Arc::new(DownloadTracker::default());
+        spawn_download_listener(&browser, downloads.clone(), sessions.clone(), default_session_id.clone()).await?;
+        spawn_popup_listener(&browser, sessions.clone()).await?;
+        enable_downloads(&browser, None, &session_downloads_dir(&default_session_id)).await?;
+
+        let browser = Arc::new(browser);
+        let idle_timeout_secs = Arc::new(AtomicU64::new(0));
+        let pool = Arc::<SessionPool>::default();
+        let in_use = Arc::<Mutex<Option<SessionInUse>>>::default();
+        spawn_idle_cleanup(
+            Arc::downgrade(&browser),
+            Arc::downgrade(&sessions),
+            default_session_id.clone(),
+            idle_timeout_secs.clone(),
+            pool.clone(),
+            in_use.clone(),
+        );
 
         Ok(Self {
             browser,
-            sessions: Arc::new(RwLock::new(sessions)),
+            sessions,
             default_session_id,
             user_data_dir,
+            headless,
+            downloads,
+            default_viewport: Some(viewport),
+            console_buffer: AtomicUsize::new(DEFAULT_CONSOLE_BUFFER),
+            connected,
+            lost_sessions: Mutex::new(HashSet::new()),
+            opening: SessionLanes::default(),
+            reconnect: Arc::default(),
+            restarts: AtomicU32::new(0),
+            restart_unreported: AtomicBool::new(false),
+            idle_timeout_secs,
+            in_use,
+            pool,
+            max_sessions: AtomicUsize::new(DEFAULT_MAX_SESSIONS),
         })
     }
 
@@ -130,7 +418,10 @@ impl BrowserClient {
     /// The browser retains all user sessions, cookies, and localStorage.
     ///
     /// # Arguments
-    /// * `debug_url` - Chrome debugging URL (e.g., "http://localhost:9222")
+    /// * `debug_url` - Chrome debugging endpoint, either HTTP (e.g.,
+    ///   "http://localhost:9222", the WebSocket URL is discovered from
+    ///   `/json/version`) or the DevTools WebSocket URL itself
+    ///   (e.g., "ws://chrome:9222/devtools/browser/<id>")
     ///
     /// # Example
     /// ```bash
@@ -141,18 +432,27 @@ impl BrowserClient {
     /// browser-gateway start --connect http://localhost:9222
     /// ```
     pub async fn connect(debug_url: &str) -> Result<Self> {
-        tracing::info!("Connecting to existing Chrome at: {}", debug_url);
+        let endpoint = parse_connect_endpoint(debug_url)?;
+        tracing::info!("Connecting to existing Chrome at: {}", endpoint.url());
 
-        let (browser, mut handler) = Browser::connect(debug_url)
+        let (browser, handler) = Browser::connect(endpoint.url())
             .await
-            .with_context(|| format!(
-                "Failed to connect to Chrome at {}. \
-                 Make sure Chrome is running with --remote-debugging-port=9222",
-                debug_url
-            ))?;
+            .with_context(|| match &endpoint {
+                ConnectEndpoint::Http(url) => format!(
+                    "Failed to connect to Chrome at {}. \
+                     Make sure Chrome is running with --remote-debugging-port=9222",
+                    url
+                ),
+                ConnectEndpoint::WebSocket(url) => format!(
+                    "Failed to connect to the DevTools WebSocket at {}. \
+                     Check that it matches webSocketDebuggerUrl from /json/version \
+                     (it changes when Chrome restarts)",
+                    url
+                ),
+            })?;
 
         // Spawn handler task - just drain events, no logging overhead
-        tokio::spawn(async move { while handler.next().await.is_some() {} });
+        let connected = spawn_handler(handler);
 
         // Get existing pages or create a new one
         let pages = browser.pages().await.context("Failed to list pages")?;
@@ -170,532 +470,3733 @@ impl BrowserClient {
         };
 
         let default_session_id = "default".to_string();
-        let default_session = BrowserSession {
-            id: default_session_id.clone(),
-            context_id: None, // Uses browser's default context (user's real context!)
-            page: default_page,
-        };
+        let dialogs = spawn_dialog_handler(&default_page).await?;
+        let console = spawn_console_listener(&default_page, DEFAULT_CONSOLE_BUFFER).await?;
+        // Uses browser's default context (user's real context!)
+        let default_session = BrowserSession::new(
+            default_session_id.clone(),
+            None,
+            default_page,
+            dialogs,
+            console,
+        );
 
         let mut sessions = HashMap::new();
         sessions.insert(default_session_id.clone(), default_session);
+        let sessions = Arc::new(RwLock::new(sessions));
+
+        // Track downloads, but leave the user's default download location alone;
+        // only contexts we create get a managed downloads directory.
+        let downloads = Arc::new(DownloadTracker::default());
+        spawn_download_listener(&browser, downloads.clone(), sessions.clone(), default_session_id.clone()).await?;
+        spawn_popup_listener(&browser, sessions.clone()).await?;
 
         // Use a placeholder for user_data_dir since we're connecting to existing browser
         let user_data_dir = PathBuf::from("/connected-browser");
 
         tracing::info!("Connected to user's Chrome - all sessions/logins available!");
 
+        // Reconnect on our own if Chrome restarts
+        let browser = Arc::new(browser);
+        let reconnect = Arc::new(ReconnectState::default());
+        spawn_health_monitor(
+            Arc::downgrade(&browser),
+            debug_url.to_string(),
+            connected.clone(),
+            reconnect.clone(),
+        );
+        let idle_timeout_secs = Arc::new(AtomicU64::new(0));
+        let pool = Arc::<SessionPool>::default();
+        let in_use = Arc::<Mutex<Option<SessionInUse>>>::default();
+        spawn_idle_cleanup(
+            Arc::downgrade(&browser),
+            Arc::downgrade(&sessions),
+            default_session_id.clone(),
+            idle_timeout_secs.clone(),
+            pool.clone(),
+            in_use.clone(),
+        );
+
         Ok(Self {
             browser,
-            sessions: Arc::new(RwLock::new(sessions)),
+            sessions,
             default_session_id,
             user_data_dir,
+            headless: false, // User's browser is always headed
+            downloads,
+            default_viewport: None, // Leave the user's window size alone
+            console_buffer: AtomicUsize::new(DEFAULT_CONSOLE_BUFFER),
+            connected,
+            lost_sessions: Mutex::new(HashSet::new()),
+            opening: SessionLanes::default(),
+            reconnect,
+            restarts: AtomicU32::new(0),
+            restart_unreported: AtomicBool::new(false),
+            idle_timeout_secs,
+            in_use,
+            pool,
+            max_sessions: AtomicUsize::new(DEFAULT_MAX_SESSIONS),
         })
     }
 
-    /// Create a new isolated session with its own browser context.
-    pub async fn create_session(&self, session_id: &str) -> Result<String> {
-        let mut sessions = self.sessions.write().await;
+    /// Whether the health task is reconnecting to Chrome.
+    pub fn is_reconnecting(&self) -> bool {
+        self.reconnect.is_reconnecting.load(Ordering::Relaxed)
+    }
 
-        if sessions.contains_key(session_id) {
-            return Ok(session_id.to_string());
+    /// Reconnect attempts the health task has made since Chrome stopped
+    /// responding (0 while connected).
+    pub fn reconnect_attempts(&self) -> u32 {
+        self.reconnect.attempts.load(Ordering::Relaxed)
+    }
+
+    /// Take the client the health task connected after Chrome restarted.
+    pub fn take_reconnected(&self) -> Option<BrowserClient> {
+        self.reconnect.reconnected.lock().unwrap().take()
+    }
+
+    /// Whether a reconnected client is waiting to replace this one.
+    pub fn has_reconnected(&self) -> bool {
+        self.reconnect.reconnected.lock().unwrap().is_some()
+    }
+
+    /// Whether the CDP connection is still up.
+    ///
+    /// A closed WebSocket doesn't always surface as an error, so with `probe`
+    /// this also round-trips `Browser.getVersion`.
+    pub async fn is_connected(&self, probe: bool) -> bool {
+        if !self.connected.load(Ordering::Relaxed) {
+            return false;
+        }
+        if !probe {
+            return true;
         }
 
-        // Create isolated browser context
-        let context_id = self
-            .browser
-            .create_browser_context(CreateBrowserContextParams::default())
-            .await
-            .context("Failed to create browser context")?;
+        match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, self.browser.version()).await {
+            Ok(Ok(_)) => true,
+            _ => {
+                self.connected.store(false, Ordering::Relaxed);
+                false
+            }
+        }
+    }
 
-        // Create page in the new context
-        let page = self
-            .browser
-            .new_page(
-                chromiumoxide::cdp::browser_protocol::target::CreateTargetParams::builder()
-                    .url("about:blank")
-                    .browser_context_id(context_id.clone())
-                    .build()
-                    .map_err(|e| anyhow::anyhow!("Failed to build target params: {:?}", e))?,
-            )
-            .await
-            .context("Failed to create page in context")?;
+    /// Create a new isolated session with its own browser context.
+    ///
+    /// At the session limit this fails, unless `evict_idle` allows closing
+    /// the least recently used custom session to make room.
+    pub async fn create_session(
+        &self,
+        session_id: &str,
+        name: Option<&str>,
+        evict_idle: bool,
+    ) -> Result<String> {
+        // Concurrent creates of one id wait for the first; other sessions
+        // only wait for the checks below, not for the context
+        let _opening = self.opening.enter_async(session_id).await;
+        let evicted = {
+            let mut sessions = self.sessions.write().await;
+            if sessions.contains_key(session_id) {
+                return Ok(session_id.to_string());
+            }
+            self.make_room(&mut sessions, session_id, evict_idle)?
+        };
+        if let Some(evicted) = evicted {
+            retire_session(&self.browser, &self.pool, evicted).await?;
+        }
+        self.lost_sessions.lock().unwrap().remove(session_id);
 
-        let session = BrowserSession {
-            id: session_id.to_string(),
-            context_id: Some(context_id),
-            page,
+        // Isolated browser context with a blank page, ready-made if the pool has one
+        let pooled = match self.pool.take() {
+            Some(pooled) => pooled,
+            None => create_context(&self.browser).await?,
         };
+        spawn_refill(self.pool.clone(), Arc::downgrade(&self.browser));
+        let (context_id, page) = (pooled.context_id, pooled.page);
 
-        sessions.insert(session_id.to_string(), session);
+        enable_downloads(&self.browser, Some(context_id.clone()), &session_downloads_dir(session_id)).await?;
+
+        let dialogs = spawn_dialog_handler(&page).await?;
+        let console_buffer = self.console_buffer.load(Ordering::Relaxed);
+        let console = spawn_console_listener(&page, console_buffer).await?;
+        let mut session = BrowserSession::new(
+            session_id.to_string(),
+            Some(context_id),
+            page,
+            dialogs,
+            console,
+        );
+        session.name = name.map(str::to_string);
+        track_origins(session.main_page(), &session.visited).await?;
+
+        self.sessions
+            .write()
+            .await
+            .insert(session_id.to_string(), session);
         tracing::info!("Created new session: {}", session_id);
 
         Ok(session_id.to_string())
     }
 
-    /// Close and dispose a session.
+    /// Check there's room for `session_id` under `--max-sessions`, counting
+    /// sessions still being opened. At the limit this fails, unless
+    /// `evict_idle` allows taking out the least recently used custom
+    /// session, which is returned for the caller to retire once it has let
+    /// go of the sessions.
+    fn make_room(
+        &self,
+        sessions: &mut HashMap<String, BrowserSession>,
+        session_id: &str,
+        evict_idle: bool,
+    ) -> Result<Option<BrowserSession>> {
+        let mut open: Vec<(String, Duration)> = sessions
+            .values()
+            .filter(|s| s.id != self.default_session_id)
+            .map(|s| (s.id.clone(), s.idle_for()))
+            .collect();
+        // Others still being opened count too, as just used
+        let opening = self.opening.busy_keys().into_iter().filter(|id| {
+            id != session_id && *id != self.default_session_id && !sessions.contains_key(id)
+        });
+        open.extend(opening.map(|id| (id, Duration::ZERO)));
+
+        let max = self.max_sessions.load(Ordering::Relaxed);
+        match check_limit(&open, max, evict_idle) {
+            SessionLimit::Room => Ok(None),
+            SessionLimit::Evict(id) => match sessions.remove(&id) {
+                Some(session) => {
+                    tracing::info!(
+                        "Session limit ({}) reached; closing least recently used session {}",
+                        max,
+                        id
+                    );
+                    Ok(Some(session))
+                }
+                // Only sessions still being opened are left
+                None => Err(limit_reached(open.len(), max, None)),
+            },
+            SessionLimit::Reached {
+                count,
+                max,
+                least_recent,
+            } => Err(limit_reached(count, max, least_recent.as_deref())),
+        }
+    }
+
+    /// Close a session, recycling its context into the pool when the pool
+    /// is short and disposing it otherwise.
     pub async fn close_session(&self, session_id: &str) -> Result<()> {
         if session_id == self.default_session_id {
             anyhow::bail!("Cannot close default session");
         }
 
-        let mut sessions = self.sessions.write().await;
-
-        if let Some(session) = sessions.remove(session_id) {
-            if let Some(context_id) = session.context_id {
-                self.browser
-                    .dispose_browser_context(context_id)
-                    .await
-                    .context("Failed to dispose browser context")?;
-            }
-            tracing::info!("Closed session: {}", session_id);
+        // Other sessions needn't wait while the context is cleared
+        let session = self.sessions.write().await.remove(session_id);
+        if let Some(session) = session {
+            retire_session(&self.browser, &self.pool, session).await?;
+            // Replace the context with a fresh one if it wasn't reused
+            spawn_refill(self.pool.clone(), Arc::downgrade(&self.browser));
         }
 
         Ok(())
     }
 
-    /// List all active sessions.
-    pub async fn list_sessions(&self) -> Vec<String> {
-        let sessions = self.sessions.read().await;
-        sessions
-            .values()
-            .map(|session| session.id.clone())
-            .collect()
-    }
+    /// Create session `new_id` with copies of `source_id`'s cookies and its
+    /// current origin's localStorage, e.g. to fan out logged-in sessions.
+    ///
+    /// localStorage can only be written with its origin loaded, so the clone
+    /// visits the source's URL; with `navigate` it stays there (reloaded so
+    /// the page sees the storage), otherwise it goes back to about:blank.
+    /// If any step fails, the new session is closed again.
+    pub async fn clone_session(
+        &self,
+        source_id: &str,
+        new_id: &str,
+        name: Option<&str>,
+        navigate: bool,
+    ) -> Result<SessionCloneResult> {
+        if self.sessions.read().await.contains_key(new_id) {
+            anyhow::bail!("Session '{}' already exists", new_id);
+        }
 
-    /// Get page for a session (or default).
-    async fn get_page(&self, session_id: Option<&str>) -> Result<Page> {
-        let sessions = self.sessions.read().await;
-        let sid = session_id.unwrap_or(&self.default_session_id);
+        let source_context = self.get_context_id(Some(source_id)).await?;
+        let cookies = self
+            .browser
+            .execute(storage::GetCookiesParams {
+                browser_context_id: source_context,
+            })
+            .await
+            .context("Failed to read the source session's cookies")?
+            .result
+            .cookies;
+        let local_storage = self.get_local_storage(Some(source_id)).await?;
+        let url = self.get_page(Some(source_id)).await?.url().await?;
+
+        self.create_session(new_id, name, false).await?;
+        let copied = async {
+            let context = self.get_context_id(Some(new_id)).await?;
+            let cookie_count = cookies.len();
+            if !cookies.is_empty() {
+                self.browser
+                    .execute(storage::SetCookiesParams {
+                        cookies: cookies.into_iter().map(cookie_param).collect(),
+                        browser_context_id: context,
+                    })
+                    .await
+                    .context("Failed to copy cookies")?;
+            }
 
-        sessions
-            .get(sid)
-            .map(|s| s.page.clone())
-            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", sid))
-    }
+            // Only http(s) pages have an origin another session can load
+            let url = url.filter(|u| u.starts_with("http://") || u.starts_with("https://"));
+            let has_storage = !local_storage.items.is_empty();
+            let mut landed = None;
+            if let Some(url) = url.filter(|_| navigate || has_storage) {
+                self.navigate(&url, Some(new_id)).await?;
+                let page = self.get_page(Some(new_id)).await?;
+                if has_storage {
+                    self.set_local_storage(&local_storage, Some(new_id)).await?;
+                }
+                if !navigate {
+                    page.goto("about:blank").await?;
+                } else {
+                    if has_storage {
+                        page.reload().await?;
+                    }
+                    landed = Some(url);
+                }
+            }
 
-    /// Navigate to a URL.
-    pub async fn navigate(&self, url: &str, session_id: Option<&str>) -> Result<NavigationResult> {
-        let page = self.get_page(session_id).await?;
+            Ok::<_, anyhow::Error>(SessionCloneResult {
+                session_id: new_id.to_string(),
+                source_session_id: source_id.to_string(),
+                cookies: cookie_count,
+                local_storage_items: local_storage.items.len(),
+                url: landed,
+            })
+        }
+        .await;
 
-        page.goto(url).await.context("Navigation failed")?;
-        page.wait_for_navigation().await.ok();
+        if copied.is_err() {
+            self.close_session(new_id).await.ok();
+        }
+        copied.context("Failed to clone session")
+    }
 
-        let current_url = page.url().await?.unwrap_or_default();
-        let title = page.get_title().await?.unwrap_or_default();
+    /// Restore a saved auth state into a session: cookies, then every
+    /// saved origin's localStorage, then the page at `url` if given, so the
+    /// page starts out with both.
+    pub async fn restore_auth_state(
+        &self,
+        state: &AuthState,
+        url: Option<&str>,
+        session_id: Option<&str>,
+    ) -> Result<RestoredState> {
+        self.set_cookies(&state.cookies, session_id).await?;
+        let cookie_domains: BTreeSet<String> =
+            state.cookies.iter().map(|c| c.domain.clone()).collect();
 
-        Ok(NavigationResult {
-            url: current_url.to_string(),
-            title,
-            status: None,
+        let local_storage_origins = self
+            .load_local_storage(&state.local_storage, session_id)
+            .await?;
+        let local_storage_items = state
+            .local_storage
+            .iter()
+            .filter(|saved| local_storage_origins.contains(&saved.origin))
+            .map(|saved| saved.items.len())
+            .sum();
+        if let Some(url) = url {
+            self.navigate(url, session_id).await?;
+        }
+
+        Ok(RestoredState {
+            cookie_domains: cookie_domains.into_iter().collect(),
+            local_storage_items,
+            local_storage_origins,
+            url: url.map(str::to_string),
         })
     }
 
-    /// Get ARIA accessibility tree snapshot.
-    pub async fn snapshot(&self, session_id: Option<&str>) -> Result<AriaSnapshot> {
+    /// A session's cookies and localStorage, for `browser.state.save`.
+    ///
+    /// localStorage is read for each of `origins`, or by default for the
+    /// page's origin and the origins the session has cookies for (see
+    /// `cookie_origins`). Origins with nothing stored are left out.
+    pub async fn capture_auth_state(
+        &self,
+        origins: Option<&[String]>,
+        session_id: Option<&str>,
+    ) -> Result<AuthState> {
+        let cookies = self.get_cookies(session_id).await?;
+        let context = self.get_context_id(session_id).await?;
         let page = self.get_page(session_id).await?;
 
-        let url = page.url().await?.unwrap_or_default().to_string();
-        let title = page.get_title().await?.unwrap_or_default();
+        let origins: BTreeSet<String> = match origins {
+            Some(origins) => origins
+                .iter()
+                .map(|o| o.trim_end_matches('/').to_string())
+                .collect(),
+            None => {
+                // An isolated session's whole cookie jar is its own
+                let jar: Vec<(String, bool)> = match &context {
+                    Some(context) => self
+                        .browser
+                        .execute(storage::GetCookiesParams {
+                            browser_context_id: Some(context.clone()),
+                        })
+                        .await
+                        .context("Failed to read the session's cookies")?
+                        .result
+                        .cookies
+                        .into_iter()
+                        .map(|c| (c.domain, c.secure))
+                        .collect(),
+                    None => cookies
+                        .iter()
+                        .map(|c| (c.domain.clone(), c.secure))
+                        .collect(),
+                };
+                let current: String = page.evaluate("location.origin").await?.into_value()?;
+                let jar = jar
+                    .iter()
+                    .map(|(domain, secure)| (domain.as_str(), *secure));
+                cookie_origins(jar)
+                    .into_iter()
+                    .chain(Some(current).filter(|o| o.starts_with("http")))
+                    .collect()
+            }
+        };
 
-        let nodes = extract_aria_tree(&page).await?;
-        let element_count = count_nodes(&nodes);
+        let mut storage = OriginStorage::new(&self.browser, context, page);
+        let mut local_storage = Vec::new();
+        let mut read = Ok(());
+        for origin in origins {
+            match storage.read(&origin).await {
+                Ok(items) if items.is_empty() => {}
+                Ok(items) => local_storage.push(LocalStorageState { origin, items }),
+                Err(e) => {
+                    read = Err(e);
+                    break;
+                }
+            }
+        }
+        storage.close().await;
+        read?;
 
-        Ok(AriaSnapshot {
-            url,
-            title,
-            nodes,
-            element_count,
+        Ok(AuthState {
+            cookies,
+            local_storage,
+            saved_at: Utc::now().to_rfc3339(),
         })
     }
 
-    /// Export cookies for a session.
-    pub async fn get_cookies(&self, session_id: Option<&str>) -> Result<Vec<SerializableCookie>> {
-        let page = self.get_page(session_id).await?;
-        let cookies = page.get_cookies().await?;
-
-        Ok(cookies
-            .into_iter()
-            .map(|cookie| SerializableCookie {
-                name: cookie.name,
-                value: cookie.value,
-                domain: cookie.domain,
-                path: cookie.path,
-                expires: if cookie.session {
-                    None
-                } else {
-                    Some(cookie.expires)
-                },
-                secure: cookie.secure,
-                http_only: cookie.http_only,
-                same_site: cookie.same_site,
-            })
-            .collect())
-    }
-
-    /// Restore cookies for a session.
-    pub async fn set_cookies(
+    /// Replace each saved origin's localStorage with its items, without
+    /// navigating the session. Returns the origins written.
+    pub async fn load_local_storage(
         &self,
-        cookies: &[SerializableCookie],
+        states: &[LocalStorageState],
         session_id: Option<&str>,
-    ) -> Result<()> {
-        if cookies.is_empty() {
-            return Ok(());
+    ) -> Result<Vec<String>> {
+        let states: Vec<&LocalStorageState> = states
+            .iter()
+            .filter(|s| !s.items.is_empty() && s.origin.starts_with("http"))
+            .collect();
+        if states.is_empty() {
+            return Ok(Vec::new());
         }
 
+        let context = self.get_context_id(session_id).await?;
         let page = self.get_page(session_id).await?;
-        let params: Vec<CookieParam> = cookies
-            .iter()
-            .map(|cookie| {
-                let mut param = CookieParam::new(cookie.name.clone(), cookie.value.clone());
-                param.domain = Some(cookie.domain.clone());
-                param.path = Some(cookie.path.clone());
-                param.secure = Some(cookie.secure);
-                param.http_only = Some(cookie.http_only);
-                param.same_site = cookie.same_site.clone();
-                param.expires = cookie.expires.map(TimeSinceEpoch::new);
-                param
-            })
-            .collect();
+        let mut storage = OriginStorage::new(&self.browser, context, page);
+        let mut written = Vec::new();
+        let mut result = Ok(());
+        for state in states {
+            if let Err(e) = storage.write(&state.origin, &state.items, true).await {
+                result = Err(e);
+                break;
+            }
+            written.push(state.origin.clone());
+        }
+        storage.close().await;
 
-        page.execute(SetCookiesParams::new(params)).await?;
-        Ok(())
+        result.map(|_| written)
     }
 
-    /// Capture localStorage for a session.
-    pub async fn get_local_storage(&self, session_id: Option<&str>) -> Result<LocalStorageState> {
-        let page = self.get_page(session_id).await?;
-        let origin: String = page
-            .evaluate("location.origin")
-            .await
-            .context("Failed to evaluate location.origin")?
-            .into_value()
-            .context("Failed to parse location.origin")?;
+    /// Allow at most `max` custom sessions at once (0 = no limit).
+    pub fn set_max_sessions(&self, max: usize) {
+        self.max_sessions.store(max, Ordering::Relaxed);
+    }
 
-        let entries: Vec<(String, String)> = page
-            .evaluate("(() => { try { return Object.entries(localStorage); } catch (_) { return []; } })()")
-            .await
-            .context("Failed to read localStorage entries")?
-            .into_value()
-            .context("Failed to parse localStorage entries")?;
+    /// Keep `size` contexts ready for `create_session` (0 turns the pool off).
+    pub fn set_session_pool(&self, size: usize) {
+        self.pool.set_target(size);
+        spawn_refill(self.pool.clone(), Arc::downgrade(&self.browser));
+    }
 
-        let items: HashMap<String, String> = entries.into_iter().collect();
+    pub fn session_pool_stats(&self) -> SessionPoolStats {
+        self.pool.stats()
+    }
 
-        Ok(LocalStorageState { origin, items })
+    /// Close custom sessions unused for `secs` seconds (0 turns this off).
+    /// The cleanup task checks once a minute.
+    pub fn set_idle_timeout(&self, secs: u64) {
+        self.idle_timeout_secs.store(secs, Ordering::Relaxed);
     }
 
-    /// Restore localStorage for a session.
-    pub async fn set_local_storage(
-        &self,
-        state: &LocalStorageState,
-        session_id: Option<&str>,
-    ) -> Result<()> {
-        let page = self.get_page(session_id).await?;
-        let payload = serde_json::to_string(&state.items)?;
-        let script = format!(
-            r#"(function() {{
-                const items = {};
-                try {{ localStorage.clear(); }} catch (_) {{}}
-                for (const [k, v] of Object.entries(items)) {{
-                    try {{ localStorage.setItem(k, v); }} catch (_) {{}}
-                }}
-            }})()"#,
-            payload
-        );
+    /// Let the idle cleanup skip sessions a call is using or waiting for.
+    pub fn set_session_in_use(&self, in_use: SessionInUse) {
+        *self.in_use.lock().unwrap() = Some(in_use);
+    }
 
-        page.evaluate(script).await?;
-        Ok(())
+    /// Custom session ids to carry over when this client is replaced,
+    /// including ones it already knew were lost.
+    pub async fn custom_session_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .sessions
+            .read()
+            .await
+            .keys()
+            .filter(|id| **id != self.default_session_id)
+            .cloned()
+            .collect();
+        ids.extend(self.lost_sessions.lock().unwrap().iter().cloned());
+        ids
     }
 
-    /// Take a screenshot.
-    pub async fn screenshot(
-        &self,
-        path: Option<&str>,
-        session_id: Option<&str>,
-    ) -> Result<ScreenshotResult> {
-        let page = self.get_page(session_id).await?;
+    /// Report these sessions as `SESSION_LOST` until they're created again.
+    pub fn mark_sessions_lost(&self, ids: Vec<String>) {
+        self.lost_sessions.lock().unwrap().extend(ids);
+    }
 
-        let screenshot_data = page
-            .screenshot(
-                chromiumoxide::page::ScreenshotParams::builder()
-                    .full_page(true)
-                    .build(),
-            )
-            .await?;
+    /// Mark this client as replacing one whose browser died, making
+    /// `restarts` restarts since the daemon started.
+    pub fn mark_restarted(&self, restarts: u32) {
+        self.restarts.store(restarts, Ordering::Relaxed);
+        self.restart_unreported.store(true, Ordering::Relaxed);
+    }
 
-        let (width, height) = (1920, 1080);
+    /// Kill a launched browser whose connection died, so a relaunch can
+    /// take over its profile. Connect mode leaves the user's Chrome alone.
+    pub async fn dispose(self: Arc<Self>) {
+        // Still used by another request; the process is killed on drop
+        let Ok(mut client) = Arc::try_unwrap(self) else {
+            return;
+        };
+        // The health task only holds a weak reference
+        if let Some(browser) = Arc::get_mut(&mut client.browser) {
+            if let Some(Err(e)) = browser.kill().await {
+                tracing::warn!("Failed to kill the old browser: {}", e);
+            }
+        }
+    }
 
-        if let Some(file_path) = path {
-            tokio::fs::write(file_path, &screenshot_data).await?;
-            Ok(ScreenshotResult {
-                data: None,
-                path: Some(file_path.to_string()),
-                width,
-                height,
-            })
+    /// Browser restarts since the daemon started.
+    pub fn restart_count(&self) -> u32 {
+        self.restarts.load(Ordering::Relaxed)
+    }
+
+    /// Whether the browser restarted since the last response said so. Only
+    /// returns true once per restart.
+    pub fn take_restart_notice(&self) -> bool {
+        self.restart_unreported.swap(false, Ordering::Relaxed)
+    }
+
+    /// Error for a session id that doesn't exist.
+    fn session_not_found(&self, sid: &str) -> anyhow::Error {
+        if self.lost_sessions.lock().unwrap().contains(sid) {
+            anyhow::anyhow!(
+                "SESSION_LOST: session '{}' was closed when Chrome exited; create it again with session.new",
+                sid
+            )
         } else {
-            let encoded = base64::Engine::encode(
-                &base64::engine::general_purpose::STANDARD,
-                &screenshot_data,
-            );
-            Ok(ScreenshotResult {
-                data: Some(encoded),
-                path: None,
-                width,
-                height,
-            })
+            anyhow::anyhow!("Session not found: {}", sid)
         }
     }
 
-    /// Click an element.
-    pub async fn click(&self, selector: &str, session_id: Option<&str>) -> Result<ClickResult> {
-        let page = self.get_page(session_id).await?;
-
-        let css_selector = resolve_selector(selector);
+    /// List all active sessions, sorted by ID.
+    pub async fn list_sessions(&self) -> Vec<SessionInfo> {
+        let described: Vec<(SessionInfo, Page)> = {
+            let sessions = self.sessions.read().await;
+            let mut described = Vec::with_capacity(sessions.len());
+            for session in sessions.values() {
+                described.push((self.describe_session(session).await, session.page().clone()));
+            }
+            described
+        };
 
-        let element = page
-            .find_element(&css_selector)
-            .await
-            .context("Element not found")?;
+        // Ask the pages for their URL and title without holding the sessions
+        let mut infos = Vec::with_capacity(described.len());
+        for (info, page) in described {
+            infos.push(with_location(info, &page).await);
+        }
+        infos.sort_by(|a, b| a.id.cmp(&b.id));
+        infos
+    }
 
-        element.click().await?;
+    /// Details of one session (the default session if `session_id` is None).
+    pub async fn session_info(&self, session_id: Option<&str>) -> Result<SessionInfo> {
+        let sid = session_id.unwrap_or(&self.default_session_id);
+        let (info, page) = {
+            let sessions = self.sessions.read().await;
+            let session = sessions
+                .get(sid)
+                .ok_or_else(|| self.session_not_found(sid))?;
+            (self.describe_session(session).await, session.page().clone())
+        };
+        Ok(with_location(info, &page).await)
+    }
 
-        Ok(ClickResult {
-            success: true,
-            element: Some(selector.to_string()),
-        })
+    /// A session's settings, without its page's URL and title (see
+    /// `with_location`).
+    async fn describe_session(&self, session: &BrowserSession) -> SessionInfo {
+        SessionInfo {
+            id: session.id.clone(),
+            name: session.name.clone(),
+            created_at: session.created_at.to_rfc3339(),
+            url: None,
+            title: None,
+            active: session.id == self.default_session_id,
+            headers: session.headers.read().await.clone(),
+            auth_username: session
+                .intercept
+                .read()
+                .await
+                .credentials
+                .as_ref()
+                .map(|c| c.username.clone()),
+            media: session.media.read().await.clone(),
+            attached: session.attached,
+            metadata: session.metadata.read().await.clone(),
+        }
     }
 
-    /// Fill an input field.
-    pub async fn fill(
+    /// Set a metadata label on a session, replacing any previous value.
+    pub async fn set_session_metadata(
         &self,
-        selector: &str,
+        key: &str,
         value: &str,
         session_id: Option<&str>,
-    ) -> Result<FillResult> {
-        let page = self.get_page(session_id).await?;
-
-        let css_selector = resolve_selector(selector);
+    ) -> Result<HashMap<String, String>> {
+        let metadata = self.get_metadata(session_id).await?;
+        let mut metadata = metadata.write().await;
+        metadata.insert(key.to_string(), value.to_string());
+        Ok(metadata.clone())
+    }
 
-        let element = page
-            .find_element(&css_selector)
-            .await
-            .context("Element not found")?;
+    /// A session's metadata labels.
+    pub async fn session_metadata(
+        &self,
+        session_id: Option<&str>,
+    ) -> Result<HashMap<String, String>> {
+        let metadata = self.get_metadata(session_id).await?;
+        let metadata = metadata.read().await.clone();
+        Ok(metadata)
+    }
 
-        element.click().await?;
-        element.type_str(value).await?;
+    async fn get_metadata(
+        &self,
+        session_id: Option<&str>,
+    ) -> Result<Arc<RwLock<HashMap<String, String>>>> {
+        let sessions = self.sessions.read().await;
+        let sid = session_id.unwrap_or(&self.default_session_id);
 
-        Ok(FillResult {
-            success: true,
-            value: value.to_string(),
-        })
+        sessions
+            .get(sid)
+            .map(|s| s.metadata.clone())
+            .ok_or_else(|| self.session_not_found(sid))
     }
 
-    /// Press a key.
-    pub async fn press(&self, key: &str, session_id: Option<&str>) -> Result<()> {
-        let page = self.get_page(session_id).await?;
+    /// List open tabs (page targets), in the browser's order.
+    pub async fn list_tabs(&self) -> Result<Vec<TabInfo>> {
+        let owners: HashMap<TargetId, String> = self
+            .sessions
+            .read()
+            .await
+            .values()
+            .flat_map(|s| {
+                s.pages
+                    .iter()
+                    .map(|p| (p.target_id().clone(), s.id.clone()))
+            })
+            .collect();
+        let targets = self
+            .browser
+            .execute(GetTargetsParams::default())
+            .await
+            .context("Failed to list targets")?
+            .result
+            .target_infos;
 
-        page.execute(
-            DispatchKeyEventParams::builder()
-                .r#type(DispatchKeyEventType::KeyDown)
-                .key(key)
-                .build()
-                .map_err(|e| anyhow::anyhow!("Failed to build key event: {:?}", e))?,
-        )
-        .await?;
+        Ok(targets
+            .into_iter()
+            .filter(|t| t.r#type == "page")
+            .map(|t| {
+                let session_id = owners.get(&t.target_id).cloned();
+                TabInfo {
+                    active: session_id.as_deref() == Some(self.default_session_id.as_str()),
+                    session_id,
+                    target_id: t.target_id.inner().clone(),
+                    url: t.url,
+                    title: t.title,
+                }
+            })
+            .collect())
+    }
 
-        page.execute(
-            DispatchKeyEventParams::builder()
-                .r#type(DispatchKeyEventType::KeyUp)
-                .key(key)
-                .build()
-                .map_err(|e| anyhow::anyhow!("Failed to build key event: {:?}", e))?,
-        )
-        .await?;
+    /// Open `url` in a new tab of the browser's default context, driven by a
+    /// new session named after the tab (`tab-<target prefix>`).
+    ///
+    /// Unlike `create_session`, the tab shares cookies and logins with the
+    /// default session, as if the user had opened it. The session counts
+    /// toward the session limit; at the limit the tab is closed again.
+    pub async fn open_new_tab(&self, url: &str) -> Result<TabInfo> {
+        let page = self
+            .browser
+            .new_page(url)
+            .await
+            .with_context(|| format!("Failed to open tab for {}", url))?;
+        page.wait_for_navigation().await.ok();
 
-        Ok(())
-    }
+        let target_id = page.target_id().inner().clone();
+        let mut session_id =
+            format!("tab-{}", target_id.chars().take(8).collect::<String>()).to_lowercase();
+        let _opening = self.opening.enter_async(&session_id).await;
+        let room = self.make_room(&mut *self.sessions.write().await, &session_id, false);
+        if let Err(e) = room {
+            page.close().await.ok();
+            return Err(e);
+        }
 
-    // =========================================================================
-    // NEW METHODS FOR FEATURE PARITY
-    // =========================================================================
+        let dialogs = spawn_dialog_handler(&page).await?;
+        let console_buffer = self.console_buffer.load(Ordering::Relaxed);
+        let console = spawn_console_listener(&page, console_buffer).await?;
+        let url = page.url().await?.unwrap_or_default();
+        let title = page.get_title().await?.unwrap_or_default();
 
-    /// Select an option from a dropdown.
-    pub async fn select(
-        &self,
-        selector: &str,
-        value: &str,
-        session_id: Option<&str>,
-    ) -> Result<()> {
-        let page = self.get_page(session_id).await?;
-        let css_selector = resolve_selector(selector);
+        let mut sessions = self.sessions.write().await;
+        if sessions.contains_key(&session_id) {
+            session_id = format!("tab-{}", target_id.to_lowercase());
+        }
+        let tab = TabInfo {
+            target_id,
+            url,
+            title,
+            active: false,
+            session_id: Some(session_id.clone()),
+        };
+        let session = BrowserSession::new(session_id.clone(), None, page, dialogs, console);
+        sessions.insert(session_id.clone(), session);
+        tracing::info!("Opened tab {} as session {}", tab.target_id, session_id);
 
-        // Use JSON encoding for safe string escaping
-        let selector_json = serde_json::to_string(&css_selector)?;
-        let value_json = serde_json::to_string(value)?;
+        Ok(tab)
+    }
 
-        let script = format!(
-            r#"(() => {{
-                const sel = {};
-                const val = {};
-                const el = document.querySelector(sel);
-                if (!el) throw new Error('Element not found: ' + sel);
-                el.value = val;
-                el.dispatchEvent(new Event('change', {{ bubbles: true }}));
-                return true;
-            }})()"#,
-            selector_json, value_json
-        );
+    /// Point the default session at an existing tab chosen by URL substring
+    /// or by its index in `list_tabs`.
+    pub async fn attach_tab(&self, url: Option<&str>, index: Option<usize>) -> Result<TabInfo> {
+        let tabs = self.list_tabs().await?;
+        let mut tab = pick_tab(&tabs, url, index)?.clone();
 
-        page.evaluate(script)
-            .await
-            .context("Failed to select option")?;
+        self.switch_tab(&tab.target_id).await?;
 
-        Ok(())
+        tab.active = true;
+        tab.session_id = Some(self.default_session_id.clone());
+        Ok(tab)
     }
 
-    /// Set checkbox/radio state.
-    pub async fn check(
-        &self,
-        selector: &str,
-        checked: bool,
-        session_id: Option<&str>,
-    ) -> Result<()> {
-        let page = self.get_page(session_id).await?;
-        let css_selector = resolve_selector(selector);
+    /// Close a session's tab, or a tab without a session by its target ID.
+    ///
+    /// Tabs no session drives, and tabs a session attached to, were opened
+    /// by the user, so closing one needs `force`. The default session's tab
+    /// is never closed.
+    pub async fn close_tab(&self, id: &str, force: bool) -> Result<()> {
+        if id == self.default_session_id {
+            anyhow::bail!("Cannot close the default session's tab");
+        }
 
-        let selector_json = serde_json::to_string(&css_selector)?;
+        let mut sessions = self.sessions.write().await;
 
-        let script = format!(
-            r#"(() => {{
-                const sel = {};
-                const el = document.querySelector(sel);
-                if (!el) throw new Error('Element not found: ' + sel);
-                if (el.checked !== {}) {{
-                    el.click();
-                }}
-                return el.checked;
-            }})()"#,
-            selector_json, checked
-        );
+        if sessions.get(id).is_some_and(|s| s.attached) && !force {
+            anyhow::bail!(
+                "Session '{}' is attached to a tab the daemon didn't open; pass force to close it anyway",
+                id
+            );
+        }
+        if let Some(mut session) = sessions.remove(id) {
+            drop(sessions);
+            // Close an attached tab too, rather than just letting go of it
+            session.attached = false;
+            retire_session(&self.browser, &self.pool, session).await?;
+            spawn_refill(self.pool.clone(), Arc::downgrade(&self.browser));
+            tracing::info!("Closed tab of session: {}", id);
+            return Ok(());
+        }
+
+        let target_id = TargetId::from(id.to_string());
+        if let Some(owner) = sessions.values().find(|s| s.has_page(&target_id)) {
+            anyhow::bail!("Tab {} belongs to session '{}'", id, owner.id);
+        }
+        drop(sessions);
 
-        page.evaluate(script)
+        let is_tab = self
+            .browser
+            .execute(GetTargetsParams::default())
             .await
-            .context("Failed to set checkbox state")?;
+            .context("Failed to list targets")?
+            .result
+            .target_infos
+            .iter()
+            .any(|t| t.r#type == "page" && t.target_id == target_id);
+        if !is_tab {
+            anyhow::bail!("Session or tab not found: {}", id);
+        }
+        if !force {
+            anyhow::bail!(
+                "Tab {} wasn't opened by the daemon; pass force to close it anyway",
+                id
+            );
+        }
 
+        self.browser
+            .execute(CloseTargetParams::new(target_id))
+            .await
+            .context("Failed to close tab")?;
+        tracing::info!("Closed user tab: {}", id);
         Ok(())
     }
 
-    /// Hover over an element.
-    pub async fn hover(&self, selector: &str, session_id: Option<&str>) -> Result<()> {
-        let page = self.get_page(session_id).await?;
-        let css_selector = resolve_selector(selector);
+    /// Point the default session at another tab and bring it to the front.
+    ///
+    /// Per-page state (network capture, HAR, interception, emulation,
+    /// console history) doesn't follow; the new tab starts fresh.
+    pub async fn switch_tab(&self, target_id: &str) -> Result<()> {
+        let target = TargetId::from(target_id.to_string());
+        let tab_owner = |sessions: &HashMap<String, BrowserSession>| {
+            sessions
+                .values()
+                .find(|s| s.has_page(&target))
+                .map(|owner| (owner.id.clone(), owner.page().clone()))
+        };
 
-        let element = page
-            .find_element(&css_selector)
+        let owner = tab_owner(&*self.sessions.read().await);
+        if let Some((owner, page)) = owner {
+            if owner == self.default_session_id {
+                page.bring_to_front().await?;
+                return Ok(());
+            }
+            anyhow::bail!("Tab {} belongs to session '{}'", target_id, owner);
+        }
+
+        let page = self
+            .browser
+            .get_page(target.clone())
+            .await
+            .map_err(|_| anyhow::anyhow!("Tab not found: {}", target_id))?;
+        let dialogs = spawn_dialog_handler(&page).await?;
+        let console_buffer = self.console_buffer.load(Ordering::Relaxed);
+        let console = spawn_console_listener(&page, console_buffer).await?;
+        page.bring_to_front()
             .await
-            .context("Element not found")?;
+            .context("Failed to activate tab")?;
 
-        element.hover().await?;
+        let old = {
+            let mut sessions = self.sessions.write().await;
+            // Another session may have taken the tab meanwhile
+            if let Some((owner, _)) = tab_owner(&sessions) {
+                anyhow::bail!("Tab {} belongs to session '{}'", target_id, owner);
+            }
+            let session = BrowserSession::new(
+                self.default_session_id.clone(),
+                None,
+                page,
+                dialogs,
+                console,
+            );
+            sessions.insert(self.default_session_id.clone(), session)
+        };
+        if let Some(old) = old {
+            release_tab(&old).await?;
+        }
 
+        tracing::info!("Default session switched to tab {}", target_id);
         Ok(())
     }
 
-    /// Scroll to element or by amount.
-    pub async fn scroll(
-        &self,
-        selector: Option<&str>,
-        x: i32,
-        y: i32,
-        session_id: Option<&str>,
-    ) -> Result<()> {
-        let page = self.get_page(session_id).await?;
+    /// List page targets, by default only the ones no session drives yet
+    /// (candidates for `attach_session`).
+    pub async fn list_targets(&self, all: bool) -> Result<Vec<TabInfo>> {
+        let mut tabs = self.list_tabs().await?;
+        if !all {
+            tabs.retain(|t| t.session_id.is_none());
+        }
+        Ok(tabs)
+    }
 
-        let script = if let Some(sel) = selector {
-            let css_selector = resolve_selector(sel);
-            let selector_json = serde_json::to_string(&css_selector)?;
-            format!(
-                r#"(() => {{
-                    const sel = {};
-                    const el = document.querySelector(sel);
-                    if (el) el.scrollIntoView({{ behavior: 'instant', block: 'center' }});
-                }})()"#,
-                selector_json
-            )
-        } else {
-            format!("window.scrollBy({}, {})", x, y)
+    /// Create a session that drives a tab that's already open (in connect
+    /// mode, one of the user's logged-in tabs) instead of a new page.
+    ///
+    /// The tab is chosen by target ID, or the first tab no session drives
+    /// whose URL or title contains the given text. Closing the session
+    /// detaches from the tab without closing it. Counts toward the session
+    /// limit like `create_session`, without evicting.
+    pub async fn attach_session(&self, session_id: &str, target: &TabQuery<'_>) -> Result<TabInfo> {
+        let tabs = self.list_tabs().await?;
+        let mut tab = match_tab(&tabs, target)?.clone();
+        let target_id = TargetId::from(tab.target_id.clone());
+
+        let _opening = self.opening.enter_async(session_id).await;
+        let tab_owner = |sessions: &HashMap<String, BrowserSession>| -> Result<()> {
+            if let Some(owner) = sessions.values().find(|s| s.has_page(&target_id)) {
+                anyhow::bail!("Tab {} belongs to session '{}'", tab.target_id, owner.id);
+            }
+            Ok(())
         };
+        {
+            let mut sessions = self.sessions.write().await;
+            if sessions.contains_key(session_id) {
+                anyhow::bail!("Session '{}' already exists", session_id);
+            }
+            tab_owner(&sessions)?;
+            self.make_room(&mut sessions, session_id, false)?;
+        }
 
-        page.evaluate(script).await?;
+        let page = self
+            .browser
+            .get_page(target_id.clone())
+            .await
+            .map_err(|_| anyhow::anyhow!("TAB_NOT_FOUND: tab {} closed", tab.target_id))?;
+        let dialogs = spawn_dialog_handler(&page).await?;
+        let console_buffer = self.console_buffer.load(Ordering::Relaxed);
+        let console = spawn_console_listener(&page, console_buffer).await?;
+        page.bring_to_front()
+            .await
+            .context("Failed to activate tab")?;
 
-        Ok(())
+        let mut sessions = self.sessions.write().await;
+        // Another session may have taken the tab meanwhile
+        tab_owner(&sessions)?;
+        let mut session = BrowserSession::new(session_id.to_string(), None, page, dialogs, console);
+        session.attached = true;
+        sessions.insert(session_id.to_string(), session);
+        self.lost_sessions.lock().unwrap().remove(session_id);
+        tracing::info!("Session {} attached to tab {}", session_id, tab.target_id);
+
+        tab.session_id = Some(session_id.to_string());
+        Ok(tab)
     }
 
-    /// Press a key with modifiers (Ctrl, Shift, Alt, Meta).
-    pub async fn press_combo(
-        &self,
-        modifiers: &[&str],
-        key: &str,
-        session_id: Option<&str>,
-    ) -> Result<()> {
-        let page = self.get_page(session_id).await?;
+    /// Get page for a session (or default).
+    ///
+    /// Popups opened or closed since the session was last used are synced
+    /// first, so a closed active popup hands back its opener.
+    async fn get_page(&self, session_id: Option<&str>) -> Result<Page> {
+        let sid = session_id.unwrap_or(&self.default_session_id);
 
-        // Calculate modifier flags
-        let modifier_flags: i64 = modifiers.iter().fold(0, |acc, m| {
-            acc | match m.to_lowercase().as_str() {
-                "ctrl" | "control" => 1,
-                "shift" => 2,
-                "alt" => 4,
-                "meta" | "cmd" | "command" => 8,
-                _ => 0,
+        {
+            let sessions = self.sessions.read().await;
+            let session = sessions
+                .get(sid)
+                .ok_or_else(|| self.session_not_found(sid))?;
+            session.touch();
+            if !session.popups.lock().unwrap().has_pending() {
+                return Ok(session.page().clone());
             }
-        });
+        }
 
-        // Send keyDown with modifiers
-        page.execute(
-            DispatchKeyEventParams::builder()
-                .r#type(DispatchKeyEventType::KeyDown)
-                .key(key)
-                .modifiers(modifier_flags)
-                .build()
-                .map_err(|e| anyhow::anyhow!("Failed to build key event: {:?}", e))?,
-        )
-        .await?;
+        let mut sessions = self.sessions.write().await;
+        let session = sessions
+            .get_mut(sid)
+            .ok_or_else(|| self.session_not_found(sid))?;
+        sync_pages(&self.browser, session).await;
+        Ok(session.page().clone())
+    }
 
-        // Send keyUp
-        page.execute(
-            DispatchKeyEventParams::builder()
-                .r#type(DispatchKeyEventType::KeyUp)
-                .key(key)
-                .modifiers(modifier_flags)
-                .build()
-                .map_err(|e| anyhow::anyhow!("Failed to build key event: {:?}", e))?,
-        )
-        .await?;
+    /// A session's pages, with popups opened or closed since it was last
+    /// used synced first.
+    async fn session_pages(&self, sid: &str) -> Result<Vec<Page>> {
+        {
+            let sessions = self.sessions.read().await;
+            let session = sessions
+                .get(sid)
+                .ok_or_else(|| self.session_not_found(sid))?;
+            if !session.popups.lock().unwrap().has_pending() {
+                return Ok(session.pages.clone());
+            }
+        }
 
-        Ok(())
+        let mut sessions = self.sessions.write().await;
+        let session = sessions
+            .get_mut(sid)
+            .ok_or_else(|| self.session_not_found(sid))?;
+        sync_pages(&self.browser, session).await;
+        Ok(session.pages.clone())
     }
 
-    /// Upload a file to an input element.
-    pub async fn upload(
-        &self,
+    /// Which other session has `target_id` among its pages.
+    async fn page_owner(&self, sid: &str, target_id: &TargetId) -> Option<String> {
+        self.sessions
+            .read()
+            .await
+            .values()
+            .find(|s| s.id != sid && s.has_page(target_id))
+            .map(|s| s.id.clone())
+    }
+
+    /// The session's own tab, which per-page state goes on whichever page
+    /// commands target.
+    async fn get_main_page(&self, session_id: Option<&str>) -> Result<Page> {
+        let sid = session_id.unwrap_or(&self.default_session_id);
+        let sessions = self.sessions.read().await;
+        let session = sessions
+            .get(sid)
+            .ok_or_else(|| self.session_not_found(sid))?;
+        session.touch();
+        Ok(session.main_page().clone())
+    }
+
+    /// List a session's pages: its own tab and the popups opened from it.
+    ///
+    /// Also reports active popups that closed since the last listing.
+    pub async fn list_pages(&self, session_id: Option<&str>) -> Result<PageList> {
+        let sid = session_id.unwrap_or(&self.default_session_id);
+        let mut sessions = self.sessions.write().await;
+        let session = sessions
+            .get_mut(sid)
+            .ok_or_else(|| self.session_not_found(sid))?;
+        sync_pages(&self.browser, session).await;
+
+        let (openers, closed) = {
+            let mut popups = session.popups.lock().unwrap();
+            let openers: Vec<Option<TargetId>> = session
+                .pages
+                .iter()
+                .map(|p| popups.opener(p.target_id()).cloned())
+                .collect();
+            (openers, std::mem::take(&mut popups.switched_back))
+        };
+
+        let mut pages = Vec::with_capacity(session.pages.len());
+        for (index, (page, opener)) in session.pages.iter().zip(openers).enumerate() {
+            pages.push(PageInfo {
+                index,
+                target_id: page.target_id().inner().clone(),
+                url: page.url().await.ok().flatten().unwrap_or_default(),
+                title: page.get_title().await.ok().flatten().unwrap_or_default(),
+                opener: opener
+                    .and_then(|opener| session.pages.iter().position(|p| *p.target_id() == opener)),
+                active: index == session.active_page,
+            });
+        }
+
+        Ok(PageList {
+            pages,
+            active: session.active_page,
+            closed,
+        })
+    }
+
+    /// Point a session's commands at another of its pages (see `list_pages`).
+    ///
+    /// Dialogs and console messages are handled on every page; network
+    /// capture and emulation stay on the session's own tab.
+    pub async fn switch_page(&self, index: usize, session_id: Option<&str>) -> Result<PageInfo> {
+        let sid = session_id.unwrap_or(&self.default_session_id);
+        let pages = self.session_pages(sid).await?;
+        let Some(page) = pages.get(index) else {
+            anyhow::bail!(
+                "PAGE_NOT_FOUND: session '{}' has no page {} ({} open)",
+                sid,
+                index,
+                pages.len()
+            );
+        };
+        page.bring_to_front()
+            .await
+            .context("Failed to activate page")?;
+
+        let (index, opener) = {
+            let mut sessions = self.sessions.write().await;
+            let session = sessions
+                .get_mut(sid)
+                .ok_or_else(|| self.session_not_found(sid))?;
+            // The page may have closed, or moved when one before it did
+            let index = session
+                .pages
+                .iter()
+                .position(|p| p.target_id() == page.target_id())
+                .with_context(|| format!("PAGE_NOT_FOUND: page {} closed", index))?;
+            session.active_page = index;
+            let opener = session
+                .popups
+                .lock()
+                .unwrap()
+                .opener(page.target_id())
+                .cloned();
+            let opener = opener
+                .and_then(|opener| session.pages.iter().position(|p| *p.target_id() == opener));
+            (index, opener)
+        };
+        tracing::info!("Session {} switched to page {}", sid, index);
+
+        Ok(PageInfo {
+            index,
+            target_id: page.target_id().inner().clone(),
+            url: page.url().await.ok().flatten().unwrap_or_default(),
+            title: page.get_title().await.ok().flatten().unwrap_or_default(),
+            opener,
+            active: true,
+        })
+    }
+
+    /// Page targets in a session's browser context, in the browser's order.
+    ///
+    /// Sessions without their own context share the default one, which in
+    /// connect mode holds the user's tabs too.
+    async fn context_tabs(&self, sid: &str) -> Result<Vec<TargetInfo>> {
+        let (context_id, custom) = {
+            let sessions = self.sessions.read().await;
+            let session = sessions
+                .get(sid)
+                .ok_or_else(|| self.session_not_found(sid))?;
+            let custom: Vec<BrowserContextId> = sessions
+                .values()
+                .filter_map(|s| s.context_id.clone())
+                .collect();
+            (session.context_id.clone(), custom)
+        };
+        let targets = self
+            .browser
+            .execute(GetTargetsParams::default())
+            .await
+            .context("Failed to list targets")?
+            .result
+            .target_infos;
+
+        Ok(targets
+            .into_iter()
+            .filter(|t| t.r#type == "page")
+            .filter(|t| match (&context_id, &t.browser_context_id) {
+                (Some(context_id), Some(target_context)) => context_id == target_context,
+                (Some(_), None) => false,
+                (None, target_context) => {
+                    target_context.as_ref().is_none_or(|c| !custom.contains(c))
+                }
+            })
+            .collect())
+    }
+
+    /// List the tabs in a session's browser context, marking the one its
+    /// commands use.
+    pub async fn list_session_tabs(&self, session_id: Option<&str>) -> Result<Vec<TabInfo>> {
+        let sid = session_id.unwrap_or(&self.default_session_id);
+        let targets = self.context_tabs(sid).await?;
+        let sessions = self.sessions.read().await;
+        let active = sessions
+            .get(sid)
+            .ok_or_else(|| self.session_not_found(sid))?
+            .page()
+            .target_id()
+            .clone();
+
+        Ok(targets
+            .into_iter()
+            .map(|t| TabInfo {
+                active: t.target_id == active,
+                session_id: sessions
+                    .values()
+                    .find(|s| s.has_page(&t.target_id))
+                    .map(|s| s.id.clone()),
+                target_id: t.target_id.inner().clone(),
+                url: t.url,
+                title: t.title,
+            })
+            .collect())
+    }
+
+    /// Open a tab in the session's browser context and point its commands
+    /// at it.
+    pub async fn new_session_tab(
+        &self,
+        url: Option<&str>,
+        session_id: Option<&str>,
+    ) -> Result<TabInfo> {
+        let sid = session_id.unwrap_or(&self.default_session_id);
+        let context_id = {
+            let sessions = self.sessions.read().await;
+            sessions
+                .get(sid)
+                .ok_or_else(|| self.session_not_found(sid))?
+                .context_id
+                .clone()
+        };
+
+        // Load the tab before locking the sessions, so other sessions don't wait on it
+        let mut params = CreateTargetParams::new(url.unwrap_or("about:blank"));
+        params.browser_context_id = context_id;
+        let page = self
+            .browser
+            .new_page(params)
+            .await
+            .context("Failed to open tab")?;
+        if url.is_some() {
+            tokio::time::timeout(NEW_TAB_LOAD_TIMEOUT, page.wait_for_navigation())
+                .await
+                .ok();
+        }
+
+        let tab = TabInfo {
+            target_id: page.target_id().inner().clone(),
+            url: page.url().await?.unwrap_or_default(),
+            title: page.get_title().await?.unwrap_or_default(),
+            active: true,
+            session_id: Some(sid.to_string()),
+        };
+
+        let mut sessions = self.sessions.write().await;
+        // The session may have been closed while the tab loaded
+        let Some(session) = sessions.get_mut(sid) else {
+            page.close().await.ok();
+            return Err(self.session_not_found(sid));
+        };
+        if let Err(e) = session.watch_page(&page).await {
+            page.close().await.ok();
+            return Err(e);
+        }
+        session.pages.push(page);
+        session.active_page = session.pages.len() - 1;
+        tracing::info!("Session {} opened tab {}", sid, tab.target_id);
+
+        Ok(tab)
+    }
+
+    /// Point a session's commands at another tab in its browser context.
+    ///
+    /// Like `switch_page`, network capture and emulation stay on the
+    /// session's own tab.
+    pub async fn switch_session_tab(
+        &self,
+        target_id: &str,
+        session_id: Option<&str>,
+    ) -> Result<TabInfo> {
+        let sid = session_id.unwrap_or(&self.default_session_id);
+        let target = self
+            .context_tabs(sid)
+            .await?
+            .into_iter()
+            .find(|t| t.target_id.inner() == target_id)
+            .ok_or_else(|| {
+                anyhow::anyhow!("PAGE_NOT_FOUND: no tab {} in session '{}'", target_id, sid)
+            })?;
+        if let Some(owner) = self.page_owner(sid, &target.target_id).await {
+            anyhow::bail!("Tab {} belongs to session '{}'", target_id, owner);
+        }
+
+        let pages = self.session_pages(sid).await?;
+        let page = match pages.iter().find(|p| *p.target_id() == target.target_id) {
+            Some(page) => page.clone(),
+            None => self
+                .browser
+                .get_page(target.target_id.clone())
+                .await
+                .map_err(|_| anyhow::anyhow!("PAGE_NOT_FOUND: tab {} closed", target_id))?,
+        };
+        page.bring_to_front()
+            .await
+            .context("Failed to activate tab")?;
+
+        let mut sessions = self.sessions.write().await;
+        if let Some(owner) = sessions
+            .values()
+            .find(|s| s.id != sid && s.has_page(&target.target_id))
+        {
+            anyhow::bail!("Tab {} belongs to session '{}'", target_id, owner.id);
+        }
+        let session = sessions
+            .get_mut(sid)
+            .ok_or_else(|| self.session_not_found(sid))?;
+        let index = match session
+            .pages
+            .iter()
+            .position(|p| *p.target_id() == target.target_id)
+        {
+            Some(index) => index,
+            None => {
+                session.watch_page(&page).await?;
+                session.pages.push(page);
+                session.pages.len() - 1
+            }
+        };
+        session.active_page = index;
+        tracing::info!("Session {} switched to tab {}", sid, target_id);
+
+        Ok(TabInfo {
+            target_id: target_id.to_string(),
+            url: target.url,
+            title: target.title,
+            active: true,
+            session_id: Some(sid.to_string()),
+        })
+    }
+
+    /// Close a tab in a session's browser context. If its commands were
+    /// using it, they move back to the page that opened it or the session's
+    /// own tab.
+    ///
+    /// As with `close_tab`, tabs the session isn't driving need `force`;
+    /// the session's own tab is closed with `session.close`.
+    pub async fn close_session_tab(
+        &self,
+        target_id: &str,
+        force: bool,
+        session_id: Option<&str>,
+    ) -> Result<()> {
+        let sid = session_id.unwrap_or(&self.default_session_id);
+        let target_id = TargetId::from(target_id.to_string());
+        let in_context = self
+            .context_tabs(sid)
+            .await?
+            .iter()
+            .any(|t| t.target_id == target_id);
+        if let Some(owner) = self.page_owner(sid, &target_id).await {
+            anyhow::bail!("Tab {} belongs to session '{}'", target_id.inner(), owner);
+        }
+
+        let index = self
+            .session_pages(sid)
+            .await?
+            .iter()
+            .position(|p| *p.target_id() == target_id);
+        match index {
+            Some(0) => anyhow::bail!(
+                "Tab {} is the main tab of session '{}'; use session.close instead",
+                target_id.inner(),
+                sid
+            ),
+            Some(_) => {}
+            None if !in_context => anyhow::bail!(
+                "PAGE_NOT_FOUND: no tab {} in session '{}'",
+                target_id.inner(),
+                sid
+            ),
+            None if !force => anyhow::bail!(
+                "Tab {} wasn't opened by the daemon; pass force to close it anyway",
+                target_id.inner()
+            ),
+            None => {}
+        }
+
+        self.browser
+            .execute(CloseTargetParams::new(target_id.clone()))
+            .await
+            .context("Failed to close tab")?;
+        let mut sessions = self.sessions.write().await;
+        if let Some(session) = sessions.get_mut(sid) {
+            // Look it up again: pages before it may have closed meanwhile
+            if let Some(index) = session
+                .pages
+                .iter()
+                .position(|p| *p.target_id() == target_id)
+            {
+                remove_page(session, index);
+            }
+        }
+        tracing::info!("Session {} closed tab {}", sid, target_id.inner());
+        Ok(())
+    }
+
+    /// Get dialog state for a session (or default).
+    async fn get_dialogs(&self, session_id: Option<&str>) -> Result<Arc<RwLock<DialogState>>> {
+        let sessions = self.sessions.read().await;
+        let sid = session_id.unwrap_or(&self.default_session_id);
+
+        sessions
+            .get(sid)
+            .map(|s| s.dialogs.clone())
+            .ok_or_else(|| self.session_not_found(sid))
+    }
+
+    /// Update the dialog policy (if given) and return handled dialogs.
+    ///
+    /// Switching from `manual` to accept or dismiss answers a dialog that is
+    /// still open. With `clear`, the returned dialogs are removed from the
+    /// session buffer (except one still open).
+    pub async fn dialog(
+        &self,
+        policy: Option<DialogPolicy>,
+        clear: bool,
+        session_id: Option<&str>,
+    ) -> Result<DialogStatus> {
+        let dialogs = self.get_dialogs(session_id).await?;
+        let mut state = dialogs.write().await;
+
+        if let Some(policy) = policy {
+            state.policy = policy;
+            answer_pending(&mut state).await?;
+        }
+
+        let records = if clear {
+            state.drain_answered()
+        } else {
+            state.history.iter().cloned().collect()
+        };
+
+        Ok(DialogStatus {
+            policy: state.policy.clone(),
+            dialogs: records,
+        })
+    }
+
+    /// Get device emulation state for a session (or default).
+    async fn get_emulation(
+        &self,
+        session_id: Option<&str>,
+    ) -> Result<Arc<RwLock<Option<EmulationSettings>>>> {
+        let sessions = self.sessions.read().await;
+        let sid = session_id.unwrap_or(&self.default_session_id);
+
+        sessions
+            .get(sid)
+            .map(|s| s.emulation.clone())
+            .ok_or_else(|| self.session_not_found(sid))
+    }
+
+    /// Emulate a device viewport, or clear emulation when `settings` is None.
+    pub async fn emulate(
+        &self,
+        settings: Option<EmulationSettings>,
+        session_id: Option<&str>,
+    ) -> Result<Option<EmulationSettings>> {
+        let page = self.get_main_page(session_id).await?;
+        let emulation = self.get_emulation(session_id).await?;
+
+        match &settings {
+            Some(settings) => apply_emulation(&page, settings).await?,
+            None => clear_emulation(&page).await?,
+        }
+
+        *emulation.write().await = settings.clone();
+        Ok(settings)
+    }
+
+    /// Network throttling state for a session (or default).
+    async fn get_network_conditions(
+        &self,
+        session_id: Option<&str>,
+    ) -> Result<Arc<RwLock<Option<NetworkConditions>>>> {
+        let sessions = self.sessions.read().await;
+        let sid = session_id.unwrap_or(&self.default_session_id);
+
+        sessions
+            .get(sid)
+            .map(|s| s.network_conditions.clone())
+            .ok_or_else(|| self.session_not_found(sid))
+    }
+
+    /// Throttle the session's network, or lift throttling when `conditions` is None.
+    pub async fn set_network_conditions(
+        &self,
+        conditions: Option<NetworkConditions>,
+        session_id: Option<&str>,
+    ) -> Result<Option<NetworkConditions>> {
+        let page = self.get_main_page(session_id).await?;
+        let state = self.get_network_conditions(session_id).await?;
+
+        apply_network_conditions(&page, conditions.as_ref()).await?;
+
+        *state.write().await = conditions.clone();
+        Ok(conditions)
+    }
+
+    /// Slow the session's CPU down by `rate` (e.g. 4 = 4x slower); 1 lifts
+    /// throttling. Returns the rate now in effect (None at full speed).
+    pub async fn set_cpu_throttling(
+        &self,
+        rate: f64,
+        session_id: Option<&str>,
+    ) -> Result<Option<f64>> {
+        let page = self.get_main_page(session_id).await?;
+        let state = {
+            let sessions = self.sessions.read().await;
+            let sid = session_id.unwrap_or(&self.default_session_id);
+            sessions
+                .get(sid)
+                .map(|s| s.cpu_throttling.clone())
+                .ok_or_else(|| self.session_not_found(sid))?
+        };
+
+        apply_cpu_throttling(&page, rate).await?;
+
+        let throttling = (rate > 1.0).then_some(rate);
+        *state.write().await = throttling;
+        Ok(throttling)
+    }
+
+    /// CPU slowdown in effect for a session (None at full speed).
+    async fn cpu_throttling_rate(&self, session_id: Option<&str>) -> Option<f64> {
+        let sessions = self.sessions.read().await;
+        let session = sessions.get(session_id.unwrap_or(&self.default_session_id))?;
+        let rate = *session.cpu_throttling.read().await;
+        rate
+    }
+
+    /// Emulated media state for a session (or default).
+    async fn get_media(
+        &self,
+        session_id: Option<&str>,
+    ) -> Result<Arc<RwLock<Option<MediaEmulation>>>> {
+        let sessions = self.sessions.read().await;
+        let sid = session_id.unwrap_or(&self.default_session_id);
+
+        sessions
+            .get(sid)
+            .map(|s| s.media.clone())
+            .ok_or_else(|| self.session_not_found(sid))
+    }
+
+    /// Merge `update` into the session's media emulation, or clear it when None.
+    ///
+    /// Returns the settings now in effect (None when nothing is emulated).
+    pub async fn emulate_media(
+        &self,
+        update: Option<MediaEmulation>,
+        session_id: Option<&str>,
+    ) -> Result<Option<MediaEmulation>> {
+        let page = self.get_main_page(session_id).await?;
+        let media = self.get_media(session_id).await?;
+        let mut media = media.write().await;
+
+        let settings = match update {
+            Some(update) => media.clone().unwrap_or_default().merged(update),
+            None => MediaEmulation::default(),
+        };
+        apply_media(&page, &settings).await?;
+
+        *media = (!settings.is_empty()).then_some(settings);
+        Ok(media.clone())
+    }
+
+    /// Resize the viewport, keeping any active device emulation otherwise intact.
+    pub async fn set_viewport(
+        &self,
+        viewport: ViewportSize,
+        session_id: Option<&str>,
+    ) -> Result<EmulationSettings> {
+        let current = self.get_emulation(session_id).await?.read().await.clone();
+
+        let settings = match current {
+            Some(current) => EmulationSettings {
+                width: viewport.width,
+                height: viewport.height,
+                ..current
+            },
+            None => EmulationSettings {
+                width: viewport.width,
+                height: viewport.height,
+                device_scale_factor: 1.0,
+                is_mobile: false,
+                user_agent: None,
+            },
+        };
+
+        self.emulate(Some(settings.clone()), session_id).await?;
+        Ok(settings)
+    }
+
+    /// Override `navigator.geolocation` and grant the geolocation permission.
+    ///
+    /// The permission is granted for the page's current origin, or for every
+    /// origin in the session's context when the page has no web origin yet.
+    pub async fn set_geolocation(
+        &self,
+        latitude: f64,
+        longitude: f64,
+        accuracy: f64,
+        session_id: Option<&str>,
+    ) -> Result<Geolocation> {
+        let page = self.get_main_page(session_id).await?;
+        let origin = self
+            .grant_permissions(vec![PermissionType::Geolocation], None, session_id)
+            .await
+            .context("Failed to grant geolocation permission")?;
+
+        page.execute(SetGeolocationOverrideParams {
+            latitude: Some(latitude),
+            longitude: Some(longitude),
+            accuracy: Some(accuracy),
+        })
+        .await
+        .context("Failed to set geolocation override")?;
+
+        Ok(Geolocation {
+            latitude,
+            longitude,
+            accuracy,
+            origin,
+        })
+    }
+
+    /// Grant permissions in the session's browser context without prompting.
+    ///
+    /// Without an explicit `origin`, the page's current origin is used, or
+    /// every origin when the page has no web origin yet. Returns the origin.
+    pub async fn grant_permissions(
+        &self,
+        permissions: Vec<PermissionType>,
+        origin: Option<String>,
+        session_id: Option<&str>,
+    ) -> Result<Option<String>> {
+        let page = self.get_page(session_id).await?;
+        let context_id = self.get_context_id(session_id).await?;
+
+        let origin = match origin {
+            Some(origin) => Some(origin),
+            None => {
+                let current: String = page.evaluate("location.origin").await?.into_value()?;
+                current.starts_with("http").then_some(current)
+            }
+        };
+
+        self.browser
+            .execute(GrantPermissionsParams {
+                permissions,
+                origin: origin.clone(),
+                browser_context_id: context_id,
+            })
+            .await
+            .context("Failed to grant permissions")?;
+
+        Ok(origin)
+    }
+
+    /// Reset all permission grants in the session's browser context.
+    pub async fn reset_permissions(&self, session_id: Option<&str>) -> Result<()> {
+        let context_id = self.get_context_id(session_id).await?;
+
+        self.browser
+            .execute(ResetPermissionsParams {
+                browser_context_id: context_id,
+            })
+            .await
+            .context("Failed to reset permissions")?;
+
+        Ok(())
+    }
+
+    /// Read the clipboard's text with `navigator.clipboard.readText()`.
+    pub async fn clipboard_read(&self, session_id: Option<&str>) -> Result<String> {
+        let page = self.clipboard_page(session_id).await?;
+        let text = page
+            .evaluate(clipboard_script("navigator.clipboard.readText()"))
+            .await
+            .context("Failed to read the clipboard")?
+            .into_value()?;
+        Ok(text)
+    }
+
+    /// Put `text` on the clipboard with `navigator.clipboard.writeText()`.
+    pub async fn clipboard_write(&self, text: &str, session_id: Option<&str>) -> Result<()> {
+        let page = self.clipboard_page(session_id).await?;
+        let call = format!(
+            "navigator.clipboard.writeText({})",
+            serde_json::to_string(text)?
+        );
+        page.evaluate(clipboard_script(&call))
+            .await
+            .context("Failed to write the clipboard")?;
+        Ok(())
+    }
+
+    /// The session's page, ready for the Clipboard API: clipboard permissions
+    /// granted for its origin and focus emulated (the API rejects calls from
+    /// unfocused pages, and a headless page never has focus). Both stay on.
+    async fn clipboard_page(&self, session_id: Option<&str>) -> Result<Page> {
+        let clipboard = vec![
+            PermissionType::ClipboardReadWrite,
+            PermissionType::ClipboardSanitizedWrite,
+        ];
+        self.grant_permissions(clipboard, None, session_id).await?;
+
+        let page = self.get_page(session_id).await?;
+        page.execute(SetFocusEmulationEnabledParams::new(true))
+            .await
+            .context("Failed to emulate page focus")?;
+        Ok(page)
+    }
+
+    /// Browser context of a session (None = the browser's default context).
+    async fn get_context_id(&self, session_id: Option<&str>) -> Result<Option<BrowserContextId>> {
+        let sessions = self.sessions.read().await;
+        let sid = session_id.unwrap_or(&self.default_session_id);
+        sessions
+            .get(sid)
+            .map(|s| s.context_id.clone())
+            .ok_or_else(|| self.session_not_found(sid))
+    }
+
+    /// Remove the geolocation override.
+    pub async fn clear_geolocation(&self, session_id: Option<&str>) -> Result<()> {
+        let page = self.get_main_page(session_id).await?;
+
+        page.execute(ClearGeolocationOverrideParams::default())
+            .await
+            .context("Failed to clear geolocation override")?;
+
+        Ok(())
+    }
+
+    /// Get network capture state for a session (or default).
+    async fn get_network(&self, session_id: Option<&str>) -> Result<Arc<RwLock<NetworkState>>> {
+        let sessions = self.sessions.read().await;
+        let sid = session_id.unwrap_or(&self.default_session_id);
+
+        sessions
+            .get(sid)
+            .map(|s| s.network.clone())
+            .ok_or_else(|| self.session_not_found(sid))
+    }
+
+    /// Start capturing network requests.
+    pub async fn network_enable(&self, session_id: Option<&str>) -> Result<()> {
+        let page = self.get_main_page(session_id).await?;
+        let network = self.get_network(session_id).await?;
+        enable_capture(&page, &network).await
+    }
+
+    /// Stop capturing network requests (captured requests are kept).
+    pub async fn network_disable(&self, session_id: Option<&str>) -> Result<()> {
+        let network = self.get_network(session_id).await?;
+        network.write().await.disable();
+        Ok(())
+    }
+
+    /// List captured requests matching a filter. Returns (capture enabled, requests).
+    pub async fn network_list(
+        &self,
+        filter: &NetworkFilter,
+        session_id: Option<&str>,
+    ) -> Result<(bool, Vec<NetworkRequest>)> {
+        let network = self.get_network(session_id).await?;
+        let state = network.read().await;
+        Ok((state.is_enabled(), state.list(filter)))
+    }
+
+    /// Get the request capture for a session (or default).
+    async fn get_capture(&self, session_id: Option<&str>) -> Result<Arc<RwLock<NetworkCapture>>> {
+        let sessions = self.sessions.read().await;
+        let sid = session_id.unwrap_or(&self.default_session_id);
+
+        sessions
+            .get(sid)
+            .map(|s| s.capture.clone())
+            .ok_or_else(|| self.session_not_found(sid))
+    }
+
+    /// Start recording outgoing requests, discarding any earlier capture.
+    pub async fn start_network_capture(&self, session_id: Option<&str>) -> Result<()> {
+        let page = self.get_main_page(session_id).await?;
+        let capture = self.get_capture(session_id).await?;
+        start_request_capture(&page, &capture).await
+    }
+
+    /// Stop recording and return the requests made since `start_network_capture`.
+    pub async fn stop_network_capture(
+        &self,
+        session_id: Option<&str>,
+    ) -> Result<Vec<CapturedRequest>> {
+        let capture = self.get_capture(session_id).await?;
+        let mut capture = capture.write().await;
+        if !capture.is_active() {
+            anyhow::bail!(
+                "Network capture is not active; call browser.network.capture_start first"
+            );
+        }
+        Ok(capture.stop())
+    }
+
+    /// Get request interception state for a session (or default).
+    async fn get_intercept(&self, session_id: Option<&str>) -> Result<Arc<RwLock<InterceptState>>> {
+        let sessions = self.sessions.read().await;
+        let sid = session_id.unwrap_or(&self.default_session_id);
+
+        sessions
+            .get(sid)
+            .map(|s| s.intercept.clone())
+            .ok_or_else(|| self.session_not_found(sid))
+    }
+
+    async fn get_blocked_urls(&self, session_id: Option<&str>) -> Result<Arc<RwLock<Vec<String>>>> {
+        let sessions = self.sessions.read().await;
+        let sid = session_id.unwrap_or(&self.default_session_id);
+
+        sessions
+            .get(sid)
+            .map(|s| s.blocked_urls.clone())
+            .ok_or_else(|| self.session_not_found(sid))
+    }
+
+    /// Add block rules (merged with existing ones) and return the active rules.
+    pub async fn network_block(
+        &self,
+        rules: BlockRules,
+        session_id: Option<&str>,
+    ) -> Result<BlockStatus> {
+        let page = self.get_main_page(session_id).await?;
+        let intercept = self.get_intercept(session_id).await?;
+
+        {
+            let mut state = intercept.write().await;
+            for pattern in rules.url_patterns {
+                if !state.block.url_patterns.contains(&pattern) {
+                    state.block.url_patterns.push(pattern);
+                }
+            }
+            for resource_type in rules.resource_types {
+                if !state.block.resource_types.contains(&resource_type) {
+                    state.block.resource_types.push(resource_type);
+                }
+            }
+        }
+
+        sync_interception(&page, &intercept).await?;
+        self.network_rules(session_id).await
+    }
+
+    /// Remove all block rules and blocked URL patterns.
+    pub async fn network_unblock(&self, session_id: Option<&str>) -> Result<BlockStatus> {
+        let page = self.get_main_page(session_id).await?;
+        let intercept = self.get_intercept(session_id).await?;
+
+        intercept.write().await.block = BlockRules::default();
+        sync_interception(&page, &intercept).await?;
+        self.clear_blocked_urls(session_id).await?;
+        self.network_rules(session_id).await
+    }
+
+    /// Block requests whose URL matches any of `patterns` (`*` wildcards),
+    /// added to the session's patterns.
+    ///
+    /// Chrome drops these requests itself, so unlike `network_block` nothing
+    /// is paused, but they don't count towards `blocked_count`.
+    pub async fn block_url_patterns(
+        &self,
+        patterns: &[&str],
+        session_id: Option<&str>,
+    ) -> Result<()> {
+        let page = self.get_main_page(session_id).await?;
+        let blocked_urls = self.get_blocked_urls(session_id).await?;
+
+        let mut current = blocked_urls.write().await;
+        let mut updated = current.clone();
+        for pattern in patterns {
+            if !updated.iter().any(|p| p == pattern) {
+                updated.push(pattern.to_string());
+            }
+        }
+        set_blocked_urls(&page, &updated).await?;
+        *current = updated;
+        Ok(())
+    }
+
+    /// Remove the session's blocked URL patterns.
+    pub async fn clear_blocked_urls(&self, session_id: Option<&str>) -> Result<()> {
+        let page = self.get_main_page(session_id).await?;
+        let blocked_urls = self.get_blocked_urls(session_id).await?;
+
+        let mut current = blocked_urls.write().await;
+        if !current.is_empty() {
+            set_blocked_urls(&page, &[]).await?;
+            current.clear();
+        }
+        Ok(())
+    }
+
+    /// Active block rules and how many requests they have blocked.
+    pub async fn network_rules(&self, session_id: Option<&str>) -> Result<BlockStatus> {
+        let intercept = self.get_intercept(session_id).await?;
+        let blocked_urls = self
+            .get_blocked_urls(session_id)
+            .await?
+            .read()
+            .await
+            .clone();
+        let state = intercept.read().await;
+        Ok(BlockStatus {
+            rules: state.block.clone(),
+            blocked_count: state.blocked_count,
+            blocked_urls,
+        })
+    }
+
+    /// Serve a canned response for requests matching `url_pattern`.
+    ///
+    /// Mocks take precedence over block rules for the same request.
+    pub async fn network_mock(
+        &self,
+        url_pattern: String,
+        status: u16,
+        headers: HashMap<String, String>,
+        body: Vec<u8>,
+        session_id: Option<&str>,
+    ) -> Result<MockRule> {
+        let page = self.get_main_page(session_id).await?;
+        let intercept = self.get_intercept(session_id).await?;
+
+        let rule = intercept
+            .write()
+            .await
+            .add_mock(url_pattern, status, headers, body);
+        sync_interception(&page, &intercept).await?;
+        Ok(rule)
+    }
+
+    /// Active mocks with their hit counts.
+    pub async fn network_mocks(&self, session_id: Option<&str>) -> Result<Vec<MockRule>> {
+        let intercept = self.get_intercept(session_id).await?;
+        let mocks = intercept.read().await.mocks();
+        Ok(mocks)
+    }
+
+    /// Remove one mock by ID, or all mocks. Returns how many were removed.
+    pub async fn network_unmock(&self, id: Option<&str>, session_id: Option<&str>) -> Result<usize> {
+        let page = self.get_main_page(session_id).await?;
+        let intercept = self.get_intercept(session_id).await?;
+
+        let removed = intercept.write().await.remove_mocks(id)?;
+        sync_interception(&page, &intercept).await?;
+        Ok(removed)
+    }
+
+    /// Set extra HTTP headers and/or auth credentials for a session.
+    ///
+    /// `headers` replaces the current header set; `clear` removes headers and
+    /// credentials before applying the rest. Both persist across navigations.
+    pub async fn set_headers(
+        &self,
+        headers: Option<HashMap<String, String>>,
+        credentials: Option<HttpCredentials>,
+        clear: bool,
+        session_id: Option<&str>,
+    ) -> Result<HeaderSettings> {
+        let page = self.get_main_page(session_id).await?;
+        let (session_headers, intercept) = {
+            let sessions = self.sessions.read().await;
+            let sid = session_id.unwrap_or(&self.default_session_id);
+            sessions
+                .get(sid)
+                .map(|s| (s.headers.clone(), s.intercept.clone()))
+                .ok_or_else(|| self.session_not_found(sid))?
+        };
+
+        if clear || headers.is_some() {
+            let mut current = session_headers.write().await;
+            let headers = headers.unwrap_or_default();
+            page.execute(SetExtraHttpHeadersParams::new(Headers::new(
+                serde_json::to_value(&headers)?,
+            )))
+            .await
+            .context("Failed to set extra HTTP headers")?;
+            *current = headers;
+        }
+
+        if clear || credentials.is_some() {
+            intercept.write().await.credentials = credentials;
+            sync_interception(&page, &intercept).await?;
+        }
+
+        let headers = session_headers.read().await.clone();
+        let auth_username = intercept
+            .read()
+            .await
+            .credentials
+            .as_ref()
+            .map(|c| c.username.clone());
+        Ok(HeaderSettings {
+            headers,
+            auth_username,
+        })
+    }
+
+    /// Fetch the response body of a captured request, cut at `max_bytes`.
+    pub async fn network_body(
+        &self,
+        request_id: &str,
+        max_bytes: usize,
+        session_id: Option<&str>,
+    ) -> Result<NetworkBody> {
+        let page = self.get_page(session_id).await?;
+        let network = self.get_network(session_id).await?;
+
+        let request = network.read().await.get(request_id).cloned().ok_or_else(|| {
+            anyhow::anyhow!(
+                "NOT_AVAILABLE: request '{}' is not in the capture buffer (never captured or evicted)",
+                request_id
+            )
+        })?;
+
+        let response = page
+            .execute(GetResponseBodyParams::new(request_id.to_string()))
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "NOT_AVAILABLE: response body for request '{}' is no longer available: {}",
+                    request_id,
+                    e
+                )
+            })?;
+
+        decode_body(
+            &request,
+            response.result.body,
+            response.result.base64_encoded,
+            max_bytes,
+        )
+    }
+
+    /// Clear captured requests, returning how many were removed.
+    pub async fn network_clear(&self, session_id: Option<&str>) -> Result<usize> {
+        let network = self.get_network(session_id).await?;
+        let cleared = network.write().await.clear();
+        Ok(cleared)
+    }
+
+    /// Get HAR recording state for a session (or default).
+    async fn get_har(&self, session_id: Option<&str>) -> Result<Arc<RwLock<HarState>>> {
+        let sessions = self.sessions.read().await;
+        let sid = session_id.unwrap_or(&self.default_session_id);
+
+        sessions
+            .get(sid)
+            .map(|s| s.har.clone())
+            .ok_or_else(|| self.session_not_found(sid))
+    }
+
+    /// Start recording a HAR for a session. No-op if already recording.
+    pub async fn har_start(&self, session_id: Option<&str>) -> Result<()> {
+        let page = self.get_main_page(session_id).await?;
+        let har = self.get_har(session_id).await?;
+        start_recording(&page, &har).await
+    }
+
+    /// Stop recording and write the HAR 1.2 log to `path`.
+    pub async fn har_stop(&self, path: &str, session_id: Option<&str>) -> Result<HarResult> {
+        let har = self.get_har(session_id).await?;
+
+        let (log, dropped) = {
+            let mut state = har.write().await;
+            if !state.is_recording() {
+                anyhow::bail!("HAR recording is not active; call browser.har.start first");
+            }
+            state.finish()
+        };
+
+        let json = serde_json::to_vec_pretty(&log)?;
+        tokio::fs::write(path, &json)
+            .await
+            .with_context(|| format!("Failed to write HAR to {}", path))?;
+
+        Ok(HarResult {
+            path: path.to_string(),
+            entry_count: log.log.entries.len(),
+            dropped,
+            size_bytes: json.len(),
+        })
+    }
+
+    /// Set the console buffer size for all current and future sessions.
+    pub async fn set_console_buffer(&self, size: usize) {
+        self.console_buffer.store(size, Ordering::Relaxed);
+        for session in self.sessions.read().await.values() {
+            session.console.write().await.set_capacity(size);
+        }
+    }
+
+    /// Get console messages for a session, optionally filtered by level.
+    ///
+    /// With `clear`, the whole session buffer is emptied after reading.
+    pub async fn console_get(
+        &self,
+        level: Option<&str>,
+        limit: Option<usize>,
+        clear: bool,
+        session_id: Option<&str>,
+    ) -> Result<Vec<ConsoleEntry>> {
+        let console = {
+            let sessions = self.sessions.read().await;
+            let sid = session_id.unwrap_or(&self.default_session_id);
+            sessions
+                .get(sid)
+                .map(|s| s.console.clone())
+                .ok_or_else(|| self.session_not_found(sid))?
+        };
+
+        let mut state = console.write().await;
+        let entries = state.list(level, limit);
+        if clear {
+            state.clear();
+        }
+        Ok(entries)
+    }
+
+    /// List downloads started in a session.
+    pub async fn list_downloads(&self, session_id: Option<&str>) -> Result<Vec<DownloadInfo>> {
+        let sid = self.resolve_session_id(session_id).await?;
+        Ok(self.downloads.list(&sid).await)
+    }
+
+    /// Wait for the next download in a session to finish.
+    ///
+    /// With `new_only`, downloads that started before this call are ignored.
+    pub async fn wait_for_download(
+        &self,
+        timeout_ms: u64,
+        new_only: bool,
+        session_id: Option<&str>,
+    ) -> Result<DownloadInfo> {
+        let sid = self.resolve_session_id(session_id).await?;
+        let since = if new_only { self.downloads.mark() } else { 0 };
+        self.downloads
+            .wait(&sid, since, Duration::from_millis(timeout_ms))
+            .await
+    }
+
+    /// Remove downloaded files older than `max_age` across all sessions.
+    pub async fn cleanup_downloads(&self, max_age: Duration) -> Result<DownloadCleanup> {
+        let in_progress = self.downloads.in_progress_paths().await;
+        let result = cleanup_downloads(max_age, in_progress).await?;
+        self.downloads.prune_missing().await;
+        Ok(result)
+    }
+
+    /// Resolve a session id (or the default) and check that it exists.
+    async fn resolve_session_id(&self, session_id: Option<&str>) -> Result<String> {
+        let sid = session_id.unwrap_or(&self.default_session_id);
+        if !self.sessions.read().await.contains_key(sid) {
+            return Err(self.session_not_found(sid));
+        }
+        Ok(sid.to_string())
+    }
+
+    /// Navigate to a URL.
+    pub async fn navigate(&self, url: &str, session_id: Option<&str>) -> Result<NavigationResult> {
+        let page = self.get_page(session_id).await?;
+
+        // The main document's response carries the status (none for data: URLs)
+        let mut responses = page.event_listener::<EventResponseReceived>().await?;
+        let main_frame = page.mainframe().await?;
+
+        let started = Instant::now();
+        page.goto(url).await.context("Navigation failed")?;
+        page.wait_for_navigation().await.ok();
+        let load_time_ms = started.elapsed().as_millis() as u64;
+
+        let mut status = None;
+        while let Some(Some(event)) = responses.next().now_or_never() {
+            if event.r#type == ResourceType::Document && event.frame_id == main_frame {
+                status = Some(event.response.status as u16);
+            }
+        }
+
+        let current_url = page.url().await?.unwrap_or_default();
+        let title = page.get_title().await?.unwrap_or_default();
+        let network_conditions = self
+            .get_network_conditions(session_id)
+            .await?
+            .read()
+            .await
+            .clone();
+        let cpu_throttling_rate = self.cpu_throttling_rate(session_id).await;
+
+        Ok(NavigationResult {
+            url: current_url.to_string(),
+            title,
+            status,
+            network_conditions,
+            cpu_throttling_rate,
+            load_time_ms: Some(load_time_ms),
+        })
+    }
+
+    /// Performance metrics and navigation timing for the current page.
+    pub async fn page_metrics(&self, session_id: Option<&str>) -> Result<PageMetrics> {
+        let page = self.get_page(session_id).await?;
+        collect_metrics(&page).await
+    }
+
+    /// Evaluate a JavaScript expression in the page, awaiting promises.
+    ///
+    /// Returns the value as JSON (`null` for `undefined` or non-serializable values).
+    pub async fn evaluate(&self, expression: &str, session_id: Option<&str>) -> Result<serde_json::Value> {
+        let page = self.get_page(session_id).await?;
+        let value = evaluate_in(&page, None, expression.to_string()).await?;
+        Ok(value.unwrap_or(serde_json::Value::Null))
+    }
+
+    /// Get ARIA accessibility tree snapshot.
+    pub async fn snapshot(
+        &self,
+        max_depth: Option<usize>,
+        include_frames: bool,
+        interactive_only: bool,
+        session_id: Option<&str>,
+    ) -> Result<AriaSnapshot> {
+        let page = self.get_page(session_id).await?;
+
+        let url = page.url().await?.unwrap_or_default().to_string();
+        let title = page.get_title().await?.unwrap_or_default();
+
+        let mut nodes = extract_aria_tree(&page, max_depth, include_frames).await?;
+        if interactive_only {
+            nodes = snapshot_interactive_only(&nodes);
+        }
+        let element_count = count_nodes(&nodes);
+
+        Ok(AriaSnapshot {
+            url,
+            title,
+            nodes,
+            element_count,
+        })
+    }
+
+    /// List the page's child frames in document order.
+    pub async fn frames(&self, session_id: Option<&str>) -> Result<Vec<FrameInfo>> {
+        let page = self.get_page(session_id).await?;
+        let frames = list_frames(&page).await?;
+        Ok(frames.into_iter().map(|f| f.info).collect())
+    }
+
+    /// Get the outer (or inner) HTML of an element, or of the whole document.
+    pub async fn get_html(
+        &self,
+        selector: Option<&str>,
+        inner: bool,
+        session_id: Option<&str>,
+    ) -> Result<String> {
+        let page = self.get_page(session_id).await?;
+        let property = if inner { "innerHTML" } else { "outerHTML" };
+
+        let script = match selector {
+            Some(sel) => {
+                let selector_json = serde_json::to_string(&resolve_selector(sel))?;
+                format!(
+                    r#"(() => {{
+                        const el = document.querySelector({});
+                        return el ? el.{} : null;
+                    }})()"#,
+                    selector_json, property
+                )
+            }
+            None => format!("document.documentElement.{}", property),
+        };
+
+        let html: Option<String> = page
+            .evaluate(script)
+            .await
+            .context("Failed to get HTML")?
+            .into_value()
+            .context("Failed to parse HTML")?;
+
+        html.ok_or_else(|| element_not_found(selector.unwrap_or_default()))
+    }
+
+    /// Serialized HTML of the document (or `selector`'s subtree) with the
+    /// page's URL and title.
+    ///
+    /// HTML over `max_bytes` fails with `CONTENT_TOO_LARGE` rather than
+    /// being cut off mid-tag.
+    pub async fn content(
+        &self,
+        selector: Option<&str>,
+        max_bytes: usize,
+        session_id: Option<&str>,
+    ) -> Result<PageContent> {
+        let page = self.get_page(session_id).await?;
+        let html = self.get_html(selector, false, session_id).await?;
+        if html.len() > max_bytes {
+            anyhow::bail!(
+                "CONTENT_TOO_LARGE: HTML is {} bytes, over the {} byte limit (raise max_bytes or pass a selector)",
+                html.len(),
+                max_bytes
+            );
+        }
+
+        Ok(PageContent {
+            length: html.len(),
+            html,
+            url: page.url().await?.unwrap_or_default(),
+            title: page.get_title().await?.unwrap_or_default(),
+        })
+    }
+
+    /// Extract the main article content as Markdown (readability-style).
+    pub async fn extract_content(&self, session_id: Option<&str>) -> Result<ContentExtraction> {
+        let page = self.get_page(session_id).await?;
+        extract_main_content(&page).await
+    }
+
+    /// Extract all anchors on the page (or within `selector`).
+    pub async fn extract_links(
+        &self,
+        selector: Option<&str>,
+        same_origin_only: bool,
+        session_id: Option<&str>,
+    ) -> Result<Vec<LinkInfo>> {
+        let resources = self
+            .extract_resources(selector, same_origin_only, session_id)
+            .await?;
+        Ok(resources.links)
+    }
+
+    /// Extract all images on the page (or within `selector`).
+    pub async fn extract_images(
+        &self,
+        selector: Option<&str>,
+        same_origin_only: bool,
+        session_id: Option<&str>,
+    ) -> Result<Vec<ImageInfo>> {
+        let resources = self
+            .extract_resources(selector, same_origin_only, session_id)
+            .await?;
+        Ok(resources.images)
+    }
+
+    /// Collect links and images in one round trip, resolving URLs against the
+    /// document base and de-duplicating by URL.
+    async fn extract_resources(
+        &self,
+        selector: Option<&str>,
+        same_origin_only: bool,
+        session_id: Option<&str>,
+    ) -> Result<PageResources> {
+        let page = self.get_page(session_id).await?;
+        let selector_json = serde_json::to_string(&selector.map(resolve_selector))?;
+
+        let script = format!(
+            r#"(() => {{
+                const sel = {};
+                const sameOriginOnly = {};
+                const roots = sel ? Array.from(document.querySelectorAll(sel)) : [document];
+                if (sel && roots.length === 0) throw new Error('Element not found: ' + sel);
+                const resolve = (raw) => {{
+                    try {{ return new URL(raw, document.baseURI); }} catch (_) {{ return null; }}
+                }};
+                const keep = (url) => url && (!sameOriginOnly || url.origin === location.origin);
+                const links = [];
+                const images = [];
+                const seenLinks = new Set();
+                const seenImages = new Set();
+                for (const root of roots) {{
+                    for (const a of root.querySelectorAll('a[href]')) {{
+                        const url = resolve(a.getAttribute('href'));
+                        if (!keep(url) || url.protocol === 'javascript:') continue;
+                        if (seenLinks.has(url.href)) continue;
+                        seenLinks.add(url.href);
+                        links.push({{
+                            text: (a.innerText || a.textContent || '').trim(),
+                            href: url.href,
+                            rel: a.getAttribute('rel') || '',
+                            target: a.getAttribute('target') || '',
+                        }});
+                    }}
+                    for (const img of root.querySelectorAll('img')) {{
+                        const raw = img.currentSrc || img.getAttribute('src');
+                        if (!raw) continue;
+                        const url = resolve(raw);
+                        if (!keep(url)) continue;
+                        if (seenImages.has(url.href)) continue;
+                        seenImages.add(url.href);
+                        images.push({{
+                            src: url.href,
+                            alt: img.getAttribute('alt') || '',
+                            width: img.naturalWidth || img.width || 0,
+                            height: img.naturalHeight || img.height || 0,
+                        }});
+                    }}
+                }}
+                return {{ links, images }};
+            }})()"#,
+            selector_json, same_origin_only
+        );
+
+        let resources: PageResources = page
+            .evaluate(script)
+            .await
+            .context("Failed to extract links and images")?
+            .into_value()
+            .context("Failed to parse extracted links and images")?;
+
+        Ok(resources)
+    }
+
+    /// Export cookies for a session.
+    pub async fn get_cookies(&self, session_id: Option<&str>) -> Result<Vec<SerializableCookie>> {
+        let page = self.get_page(session_id).await?;
+        let cookies = page.get_cookies().await?;
+
+        Ok(cookies
+            .into_iter()
+            .map(|cookie| SerializableCookie {
+                name: cookie.name,
+                value: cookie.value,
+                domain: cookie.domain,
+                path: cookie.path,
+                expires: if cookie.session {
+                    None
+                } else {
+                    Some(cookie.expires)
+                },
+                secure: cookie.secure,
+                http_only: cookie.http_only,
+                same_site: cookie.same_site,
+            })
+            .collect())
+    }
+
+    /// Restore cookies for a session.
+    pub async fn set_cookies(
+        &self,
+        cookies: &[SerializableCookie],
+        session_id: Option<&str>,
+    ) -> Result<()> {
+        if cookies.is_empty() {
+            return Ok(());
+        }
+
+        let page = self.get_page(session_id).await?;
+        let params: Vec<CookieParam> = cookies
+            .iter()
+            .map(|cookie| {
+                let mut param = CookieParam::new(cookie.name.clone(), cookie.value.clone());
+                param.domain = Some(cookie.domain.clone());
+                param.path = Some(cookie.path.clone());
+                param.secure = Some(cookie.secure);
+                param.http_only = Some(cookie.http_only);
+                param.same_site = cookie.same_site.clone();
+                param.expires = cookie.expires.map(TimeSinceEpoch::new);
+                param
+            })
+            .collect();
+
+        page.execute(SetCookiesParams::new(params)).await?;
+        Ok(())
+    }
+
+    /// Write a session's cookies to `path` as a JSON array, returning how
+    /// many were written.
+    pub async fn export_cookies_to_file(
+        &self,
+        path: &str,
+        session_id: Option<&str>,
+    ) -> Result<usize> {
+        let cookies = self.get_cookies(session_id).await?;
+        let json = serde_json::to_vec_pretty(&cookies)?;
+        tokio::fs::write(path, &json)
+            .await
+            .with_context(|| format!("Failed to write cookies to {}", path))?;
+
+        Ok(cookies.len())
+    }
+
+    /// Load a JSON array of cookies from `path` (an `export_cookies_to_file`
+    /// file or a cookie-export extension's) into a session, returning how
+    /// many were set.
+    pub async fn import_cookies_from_file(
+        &self,
+        path: &str,
+        session_id: Option<&str>,
+    ) -> Result<usize> {
+        let bytes = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("Failed to read cookies from {}", path))?;
+        let cookies: Vec<SerializableCookie> = serde_json::from_slice(&bytes)
+            .with_context(|| format!("{} is not a JSON array of cookies", path))?;
+        self.set_cookies(&cookies, session_id).await?;
+
+        Ok(cookies.len())
+    }
+
+    /// Capture localStorage for a session.
+    pub async fn get_local_storage(&self, session_id: Option<&str>) -> Result<LocalStorageState> {
+        let page = self.get_page(session_id).await?;
+        let origin: String = page
+            .evaluate("location.origin")
+            .await
+            .context("Failed to evaluate location.origin")?
+            .into_value()
+            .context("Failed to parse location.origin")?;
+
+        let entries: Vec<(String, String)> = page
+            .evaluate("(() => { try { return Object.entries(localStorage); } catch (_) { return []; } })()")
+            .await
+            .context("Failed to read localStorage entries")?
+            .into_value()
+            .context("Failed to parse localStorage entries")?;
+
+        let items: HashMap<String, String> = entries.into_iter().collect();
+
+        Ok(LocalStorageState { origin, items })
+    }
+
+    /// Restore localStorage for a session.
+    pub async fn set_local_storage(
+        &self,
+        state: &LocalStorageState,
+        session_id: Option<&str>,
+    ) -> Result<()> {
+        let page = self.get_page(session_id).await?;
+        let payload = serde_json::to_string(&state.items)?;
+        let script = format!(
+            r#"(function() {{
+                const items = {};
+                try {{ localStorage.clear(); }} catch (_) {{}}
+                for (const [k, v] of Object.entries(items)) {{
+                    try {{ localStorage.setItem(k, v); }} catch (_) {{}}
+                }}
+            }})()"#,
+            payload
+        );
+
+        page.evaluate(script).await?;
+        Ok(())
+    }
+
+    /// Write localStorage for `origin` (default: the current page's) to
+    /// `path` as a `LocalStorageState`, returning what was written.
+    ///
+    /// Another origin must be loaded in the page, e.g. in an iframe.
+    pub async fn export_local_storage_to_file(
+        &self,
+        origin: Option<&str>,
+        path: &str,
+        session_id: Option<&str>,
+    ) -> Result<LocalStorageState> {
+        let state = match origin {
+            None => self.get_local_storage(session_id).await?,
+            Some(origin) => {
+                let page = self.get_page(session_id).await?;
+                LocalStorageState {
+                    origin: origin.trim_end_matches('/').to_string(),
+                    items: read_items(&page, origin).await?,
+                }
+            }
+        };
+
+        let json = serde_json::to_vec_pretty(&state)?;
+        tokio::fs::write(path, &json)
+            .await
+            .with_context(|| format!("Failed to write localStorage to {}", path))?;
+
+        Ok(state)
+    }
+
+    /// Add the items in a `LocalStorageState` file to its origin's
+    /// localStorage (the current page's when the file has none), keeping
+    /// existing keys the file doesn't mention.
+    pub async fn import_local_storage_from_file(
+        &self,
+        path: &str,
+        session_id: Option<&str>,
+    ) -> Result<LocalStorageState> {
+        let bytes = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("Failed to read localStorage from {}", path))?;
+        let mut state: LocalStorageState = serde_json::from_slice(&bytes)
+            .with_context(|| format!("{} is not a localStorage export", path))?;
+
+        let page = self.get_page(session_id).await?;
+        if state.origin.is_empty() {
+            state.origin = page
+                .evaluate("location.origin")
+                .await
+                .context("Failed to evaluate location.origin")?
+                .into_value()
+                .context("Failed to parse location.origin")?;
+        }
+        write_items(&page, &state.origin, &state.items, false).await?;
+
+        Ok(state)
+    }
+
+    /// Every record of IndexedDB object store `store_name` in database
+    /// `db_name`, for the current page's origin, as `{key, value}` in key
+    /// order. Dates come back as ISO strings and binary data as byte arrays;
+    /// Blobs and other host objects as `{}`.
+    pub async fn get_indexeddb(
+        &self,
+        db_name: &str,
+        store_name: &str,
+        session_id: Option<&str>,
+    ) -> Result<Vec<serde_json::Value>> {
+        let page = self.get_page(session_id).await?;
+        let read_store = format!(
+            r#"(db) => new Promise((resolve, reject) => {{
+                const name = {};
+                if (!db.objectStoreNames.contains(name)) {{
+                    const stores = Array.from(db.objectStoreNames).join(', ');
+                    throw new Error('STORE_NOT_FOUND: no object store ' + name + ' in ' + db.name + ' (available: ' + stores + ')');
+                }}
+                const records = [];
+                const request = db.transaction(name, 'readonly').objectStore(name).openCursor();
+                request.onerror = () => reject(request.error);
+                request.onsuccess = () => {{
+                    const cursor = request.result;
+                    if (!cursor) return resolve(records);
+                    records.push({{ key: cursor.primaryKey, value: cursor.value }});
+                    cursor.continue();
+                }};
+            }})"#,
+            serde_json::to_string(store_name)?
+        );
+        let records = with_indexeddb(&page, db_name, &read_store).await?;
+        serde_json::from_value(records).context("Failed to parse IndexedDB records")
+    }
+
+    /// Names of the object stores in IndexedDB database `db_name`, for the
+    /// current page's origin.
+    pub async fn list_indexeddb_stores(
+        &self,
+        db_name: &str,
+        session_id: Option<&str>,
+    ) -> Result<Vec<String>> {
+        let page = self.get_page(session_id).await?;
+        let stores =
+            with_indexeddb(&page, db_name, "(db) => Array.from(db.objectStoreNames)").await?;
+        serde_json::from_value(stores).context("Failed to parse IndexedDB store names")
+    }
+
+    /// Take a screenshot.
+    pub async fn screenshot(
+        &self,
+        path: Option<&str>,
+        format: ScreenshotFormat,
+        session_id: Option<&str>,
+    ) -> Result<ScreenshotResult> {
+        let page = self.get_page(session_id).await?;
+        let emulation = self.get_emulation(session_id).await?.read().await.clone();
+
+        // Full-page capture overrides (and then clears) device metrics, so an
+        // emulated session captures its emulated viewport instead.
+        let params = screenshot_params(format)?.full_page(emulation.is_none());
+        let screenshot_data = page.screenshot(params.build()).await?;
+
+        // Full-page capture clears the device metrics override on the way out;
+        // put the default viewport back so later calls see the same layout.
+        if emulation.is_none() {
+            if let Some(viewport) = self.default_viewport {
+                apply_emulation(
+                    &page,
+                    &EmulationSettings {
+                        width: viewport.width,
+                        height: viewport.height,
+                        device_scale_factor: 1.0,
+                        is_mobile: false,
+                        user_agent: None,
+                    },
+                )
+                .await?;
+            }
+        }
+
+        let (width, height) = emulation
+            .map(|e| (e.width, e.height))
+            .or(self.default_viewport.map(|v| (v.width, v.height)))
+            .unwrap_or((1920, 1080));
+
+        screenshot_result(screenshot_data, path, format, width, height).await
+    }
+
+    /// Take a screenshot cropped to one element.
+    ///
+    /// The element is scrolled into view first; parts of it outside the
+    /// viewport are still captured. `width`/`height` are the image's size in
+    /// pixels (the element's CSS size times the device pixel ratio).
+    pub async fn screenshot_element(
+        &self,
+        selector: &str,
+        path: Option<&str>,
+        format: ScreenshotFormat,
+        session_id: Option<&str>,
+    ) -> Result<ScreenshotResult> {
+        let page = self.get_page(session_id).await?;
+        let css_selector = resolve_selector(selector);
+        let selector_json = serde_json::to_string(&css_selector)?;
+
+        // Clip coordinates are relative to the document, not the viewport
+        let script = format!(
+            r#"(() => {{
+                const el = document.querySelector({});
+                if (!el) return null;
+                el.scrollIntoView({{ behavior: 'instant', block: 'center', inline: 'center' }});
+                const r = el.getBoundingClientRect();
+                return {{
+                    x: r.left + window.scrollX, y: r.top + window.scrollY,
+                    width: r.width, height: r.height, dpr: window.devicePixelRatio,
+                }};
+            }})()"#,
+            selector_json
+        );
+        let rect = page
+            .evaluate(script)
+            .await?
+            .into_value::<Option<serde_json::Value>>()?
+            .ok_or_else(|| element_not_found(selector))?;
+        let dimension = |key: &str| rect.get(key).and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let (width, height) = (dimension("width"), dimension("height"));
+        if width <= 0.0 || height <= 0.0 {
+            anyhow::bail!("Element {} has no visible size to capture", selector);
+        }
+
+        let clip = chromiumoxide::cdp::browser_protocol::page::Viewport {
+            x: dimension("x"),
+            y: dimension("y"),
+            width,
+            height,
+            scale: 1.0,
+        };
+        let params = screenshot_params(format)?
+            .clip(clip)
+            .capture_beyond_viewport(true);
+        let screenshot_data = page.screenshot(params.build()).await?;
+
+        let dpr = match dimension("dpr") {
+            dpr if dpr > 0.0 => dpr,
+            _ => 1.0,
+        };
+        let pixels = |css: f64| (css * dpr).ceil() as u32;
+        screenshot_result(screenshot_data, path, format, pixels(width), pixels(height)).await
+    }
+
+    /// Print the page to PDF via `Page.printToPDF`.
+    ///
+    /// Chrome only supports PDF generation in headless mode.
+    pub async fn print_pdf(
+        &self,
+        path: Option<&str>,
+        options: &PdfOptions,
+        session_id: Option<&str>,
+    ) -> Result<PdfResult> {
+        if !self.headless {
+            anyhow::bail!(
+                "PDF generation requires headless Chrome; it is not available in headed or connect mode"
+            );
+        }
+
+        let page = self.get_page(session_id).await?;
+
+        let params = PrintToPdfParams {
+            landscape: Some(options.landscape),
+            print_background: Some(options.print_background),
+            scale: options.scale,
+            paper_width: options.paper_width,
+            paper_height: options.paper_height,
+            margin_top: options.margin,
+            margin_bottom: options.margin,
+            margin_left: options.margin,
+            margin_right: options.margin,
+            ..Default::default()
+        };
+
+        let pdf_data = page.pdf(params).await.context("Failed to print page to PDF")?;
+        let size_bytes = pdf_data.len();
+
+        if let Some(file_path) = path {
+            tokio::fs::write(file_path, &pdf_data).await?;
+            Ok(PdfResult {
+                data: None,
+                path: Some(file_path.to_string()),
+                size_bytes,
+            })
+        } else {
+            let encoded =
+                base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &pdf_data);
+            Ok(PdfResult {
+                data: Some(encoded),
+                path: None,
+                size_bytes,
+            })
+        }
+    }
+
+    /// Fetch a URL with the session's cookies without navigating to it.
+    ///
+    /// The body is written to `path` if given, otherwise returned as base64.
+    pub async fn fetch(
+        &self,
+        url: &str,
+        path: Option<&str>,
+        max_bytes: u64,
+        session_id: Option<&str>,
+    ) -> Result<FetchResult> {
+        let page = self.get_page(session_id).await?;
+        let fetched = fetch_url(&page, url, max_bytes).await?;
+        let size_bytes = fetched.body.len();
+
+        let (data, path) = match path {
+            Some(file_path) => {
+                tokio::fs::write(file_path, &fetched.body)
+                    .await
+                    .with_context(|| format!("Failed to write {}", file_path))?;
+                (None, Some(file_path.to_string()))
+            }
+            None => (
+                Some(base64::Engine::encode(
+                    &base64::engine::general_purpose::STANDARD,
+                    &fetched.body,
+                )),
+                None,
+            ),
+        };
+
+        Ok(FetchResult {
+            url: fetched.final_url,
+            status: fetched.status,
+            content_type: fetched.content_type,
+            data,
+            path,
+            size_bytes,
+            via: fetched.via.to_string(),
+        })
+    }
+
+    /// Click an element.
+    pub async fn click(
+        &self,
+        selector: &str,
+        frame: Option<&FrameTarget>,
+        session_id: Option<&str>,
+    ) -> Result<ClickResult> {
+        let page = self.get_page(session_id).await?;
+
+        let css_selector = resolve_selector(selector);
+
+        if let Some(frame) = frame {
+            let point = element_center(&page, frame, &css_selector).await?;
+            page.click(point).await?;
+        } else {
+            let element = page
+                .find_element(&css_selector)
+                .await
+                .map_err(|_| element_not_found(selector))?;
+
+            element.click().await?;
+        }
+
+        Ok(ClickResult {
+            success: true,
+            element: Some(selector.to_string()),
+            context_menu: None,
+        })
+    }
+
+    /// Double-click an element with real mouse events, so `dblclick`
+    /// listeners fire (and text under the pointer gets word-selected).
+    ///
+    /// Sends two press/release pairs at the element's center, the second
+    /// with `clickCount: 2`, the way Chrome reports a physical double-click.
+    pub async fn double_click(
+        &self,
+        selector: &str,
+        session_id: Option<&str>,
+    ) -> Result<ClickResult> {
+        use DispatchMouseEventType::{MousePressed, MouseReleased};
+        let page = self.get_page(session_id).await?;
+        let element = page
+            .find_element(resolve_selector(selector))
+            .await
+            .map_err(|_| element_not_found(selector))?;
+        element.scroll_into_view().await?;
+        let point = box_center(&element).await?;
+
+        page.move_mouse(point).await?;
+        for click_count in 1..=2 {
+            for (event_type, buttons) in [(MousePressed, 1), (MouseReleased, 0)] {
+                let event = DispatchMouseEventParams::builder()
+                    .r#type(event_type)
+                    .x(point.x)
+                    .y(point.y)
+                    .button(MouseButton::Left)
+                    .buttons(buttons)
+                    .click_count(click_count)
+                    .build()
+                    .map_err(|e| anyhow::anyhow!("Failed to build mouse event: {}", e))?;
+                page.execute(event)
+                    .await
+                    .context("Failed to dispatch mouse event")?;
+            }
+        }
+
+        Ok(ClickResult {
+            success: true,
+            element: Some(selector.to_string()),
+            context_menu: None,
+        })
+    }
+
+    /// Fill an input field.
+    ///
+    /// With `clear`, the field's current content is removed first. A nonzero
+    /// `delay_ms` types one character at a time with that pause between
+    /// keystrokes. The field stays focused afterwards, so `press("Enter")`
+    /// submits it.
+    pub async fn fill(
+        &self,
+        selector: &str,
+        value: &str,
+        clear: bool,
+        delay_ms: u64,
+        frame: Option<&FrameTarget>,
+        session_id: Option<&str>,
+    ) -> Result<FillResult> {
+        let page = self.get_page(session_id).await?;
+
+        let css_selector = resolve_selector(selector);
+
+        // Key events go to the focused frame, so focus the field first
+        let element = if let Some(frame) = frame {
+            let point = element_center(&page, frame, &css_selector).await?;
+            page.click(point).await?;
+            None
+        } else {
+            let element = page
+                .find_element(&css_selector)
+                .await
+                .map_err(|_| element_not_found(selector))?;
+            element.click().await?;
+            Some(element)
+        };
+
+        if clear {
+            clear_element(&page, frame, selector).await?;
+        }
+
+        if delay_ms > 0 {
+            type_with_delay(&page, value, Duration::from_millis(delay_ms)).await?;
+        } else if let Some(element) = element {
+            element.type_str(value).await?;
+        } else {
+            page.execute(InsertTextParams::new(value)).await?;
+        }
+
+        Ok(FillResult {
+            success: true,
+            value: value.to_string(),
+        })
+    }
+
+    /// Empty a text field or contenteditable element without typing.
+    ///
+    /// The element is focused, its content selected and deleted, and an
+    /// `input` event is dispatched so frameworks see the change.
+    pub async fn clear_field(
+        &self,
+        selector: &str,
+        frame: Option<&FrameTarget>,
+        session_id: Option<&str>,
+    ) -> Result<()> {
+        let page = self.get_page(session_id).await?;
+        clear_element(&page, frame, selector).await
+    }
+
+    /// Press a key.
+    ///
+    /// With a `frame`, that frame is focused first so the key reaches it.
+    pub async fn press(
+        &self,
+        key: &str,
+        frame: Option<&FrameTarget>,
+        session_id: Option<&str>,
+    ) -> Result<()> {
+        let page = self.get_page(session_id).await?;
+
+        if frame.is_some() {
+            evaluate_in(&page, frame, "window.focus()".to_string()).await?;
+        }
+
+        page.execute(
+            DispatchKeyEventParams::builder()
+                .r#type(DispatchKeyEventType::KeyDown)
+                .key(key)
+                .build()
+                .map_err(|e| anyhow::anyhow!("Failed to build key event: {:?}", e))?,
+        )
+        .await?;
+
+        page.execute(
+            DispatchKeyEventParams::builder()
+                .r#type(DispatchKeyEventType::KeyUp)
+                .key(key)
+                .build()
+                .map_err(|e| anyhow::anyhow!("Failed to build key event: {:?}", e))?,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    // =========================================================================
+    // NEW METHODS FOR FEATURE PARITY
+    // =========================================================================
+
+    /// Select options in a dropdown by value or, failing that, visible
+    /// label, returning the options selected afterwards.
+    ///
+    /// A `multiple` select gets every option in `values`, replacing the
+    /// current selection unless `add` is set. Single selects and other
+    /// elements take exactly one value.
+    pub async fn select(
+        &self,
+        selector: &str,
+        values: &[String],
+        add: bool,
+        frame: Option<&FrameTarget>,
+        session_id: Option<&str>,
+    ) -> Result<Vec<SelectedOption>> {
+        let page = self.get_page(session_id).await?;
+        let css_selector = resolve_selector(selector);
+
+        // Use JSON encoding for safe string escaping
+        let selector_json = serde_json::to_string(&css_selector)?;
+        let values_json = serde_json::to_string(values)?;
+
+        // Options are matched by value, then by visible text (exact, then
+        // ignoring case and surrounding whitespace)
+        let script = format!(
+            r#"(() => {{
+                const sel = {};
+                const vals = {};
+                const add = {};
+                const el = document.querySelector(sel);
+                if (!el) return null;
+                const changed = () => {{
+                    el.dispatchEvent(new Event('input', {{ bubbles: true }}));
+                    el.dispatchEvent(new Event('change', {{ bubbles: true }}));
+                }};
+                const multiple = el instanceof HTMLSelectElement && el.multiple;
+                if (!multiple && vals.length !== 1) return {{ single: true }};
+                if (!(el instanceof HTMLSelectElement)) {{
+                    el.value = vals[0];
+                    changed();
+                    return {{ selected: [{{ value: String(el.value), label: String(el.value) }}] }};
+                }}
+                const options = Array.from(el.options);
+                const label = o => o.text.trim();
+                const find = val => {{
+                    const wanted = val.trim().toLowerCase();
+                    return options.find(o => o.value === val)
+                        || options.find(o => o.text === val)
+                        || options.find(o => label(o).toLowerCase() === wanted);
+                }};
+                const matches = vals.map(find);
+                const missing = vals.filter((_, i) => !matches[i]);
+                if (missing.length) return {{ missing: missing[0], labels: options.map(label) }};
+                if (multiple) {{
+                    if (!add) options.forEach(o => {{ o.selected = false; }});
+                    matches.forEach(o => {{ o.selected = true; }});
+                }} else {{
+                    el.selectedIndex = matches[0].index;
+                }}
+                changed();
+                const selected = Array.from(el.selectedOptions);
+                return {{ selected: selected.map(o => ({{ value: o.value, label: label(o) }})) }};
+            }})()"#,
+            selector_json, values_json, add
+        );
+
+        let result = evaluate_in(&page, frame, script)
+            .await
+            .context("Failed to select option")?
+            .filter(|v| !v.is_null())
+            .ok_or_else(|| element_not_found(selector))?;
+        if result.get("single").is_some() {
+            anyhow::bail!(
+                "{} is not a multi-select; pass exactly one value (got {})",
+                selector,
+                values.len()
+            );
+        }
+        if let (Some(missing), Some(labels)) = (result.get("missing"), result.get("labels")) {
+            let labels: Vec<String> = serde_json::from_value(labels.clone())?;
+            return Err(option_not_found(
+                selector,
+                missing.as_str().unwrap_or(""),
+                &labels,
+            ));
+        }
+
+        serde_json::from_value(result["selected"].clone())
+            .context("Failed to parse selected options")
+    }
+
+    /// Set checkbox/radio state, returning the state read back afterwards.
+    ///
+    /// Disabled elements are refused. An indeterminate checkbox is cleared
+    /// first. The element is clicked so page handlers run; if a handler
+    /// swallows the click, `checked` is set directly and `change` fired.
+    pub async fn check(
+        &self,
+        selector: &str,
+        checked: bool,
+        frame: Option<&FrameTarget>,
+        session_id: Option<&str>,
+    ) -> Result<bool> {
+        let page = self.get_page(session_id).await?;
+        let css_selector = resolve_selector(selector);
+
+        let selector_json = serde_json::to_string(&css_selector)?;
+
+        // Custom checkboxes (role="checkbox") report through aria-checked
+        let script = format!(
+            r#"(() => {{
+                const sel = {};
+                const want = {};
+                const el = document.querySelector(sel);
+                if (!el) return null;
+                if (el.matches(':disabled') || el.getAttribute('aria-disabled') === 'true') {{
+                    return {{ disabled: true }};
+                }}
+                const native = 'checked' in el;
+                const isChecked = () => native ? el.checked : el.getAttribute('aria-checked') === 'true';
+                if (el.indeterminate) el.indeterminate = false;
+                if (isChecked() !== want) el.click();
+                if (native && el.checked !== want) {{
+                    el.checked = want;
+                    el.dispatchEvent(new Event('input', {{ bubbles: true }}));
+                    el.dispatchEvent(new Event('change', {{ bubbles: true }}));
+                }}
+                return {{ checked: isChecked() }};
+            }})()"#,
+            selector_json, checked
+        );
+
+        let state = evaluate_in(&page, frame, script)
+            .await
+            .context("Failed to set checkbox state")?
+            .filter(|v| !v.is_null())
+            .ok_or_else(|| element_not_found(selector))?;
+        if state.get("disabled").is_some() {
+            anyhow::bail!("ELEMENT_DISABLED: {} is disabled", selector);
+        }
+
+        Ok(state["checked"].as_bool().unwrap_or(false))
+    }
+
+    /// Hover over an element.
+    pub async fn hover(
+        &self,
+        selector: &str,
+        frame: Option<&FrameTarget>,
+        session_id: Option<&str>,
+    ) -> Result<()> {
+        let page = self.get_page(session_id).await?;
+        let css_selector = resolve_selector(selector);
+
+        if let Some(frame) = frame {
+            let point = element_center(&page, frame, &css_selector).await?;
+            page.move_mouse(point).await?;
+        } else {
+            let element = page
+                .find_element(&css_selector)
+                .await
+                .map_err(|_| element_not_found(selector))?;
+
+            element.hover().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Select characters `start..end` of an element's text, e.g. before
+    /// pressing Ctrl+C.
+    ///
+    /// Inputs and textareas use `setSelectionRange`; other elements
+    /// (contenteditable or plain text) get a document range over their text
+    /// nodes. Offsets past the end of the text are clamped.
+    pub async fn select_text_in(
+        &self,
+        selector: &str,
+        start: usize,
+        end: usize,
+        session_id: Option<&str>,
+    ) -> Result<()> {
+        if start > end {
+            anyhow::bail!("Selection start ({}) is after its end ({})", start, end);
+        }
+        self.select_text(selector, Some((start, end)), session_id)
+            .await
+    }
+
+    /// Select all of an element's text.
+    pub async fn select_all_text(&self, selector: &str, session_id: Option<&str>) -> Result<()> {
+        self.select_text(selector, None, session_id).await
+    }
+
+    async fn select_text(
+        &self,
+        selector: &str,
+        range: Option<(usize, usize)>,
+        session_id: Option<&str>,
+    ) -> Result<()> {
+        let page = self.get_page(session_id).await?;
+        let selector_json = serde_json::to_string(&resolve_selector(selector))?;
+        let range_json = serde_json::to_string(&range)?;
+
+        let script = format!(
+            r#"(() => {{
+                const el = document.querySelector({});
+                if (!el) return null;
+                const range = {};
+                if (el instanceof HTMLInputElement || el instanceof HTMLTextAreaElement) {{
+                    const [start, end] = range || [0, el.value.length];
+                    el.focus();
+                    el.setSelectionRange(start, end);
+                    return true;
+                }}
+
+                // Map a character offset to a position in el's text nodes
+                const position = (offset) => {{
+                    const walker = document.createTreeWalker(el, NodeFilter.SHOW_TEXT);
+                    let node, last = null;
+                    while ((node = walker.nextNode())) {{
+                        if (offset <= node.data.length) return [node, offset];
+                        offset -= node.data.length;
+                        last = node;
+                    }}
+                    return last ? [last, last.data.length] : [el, 0];
+                }};
+                const selected = document.createRange();
+                if (range) {{
+                    selected.setStart(...position(range[0]));
+                    selected.setEnd(...position(range[1]));
+                }} else {{
+                    selected.selectNodeContents(el);
+                }}
+                if (el.isContentEditable) el.focus();
+                const selection = window.getSelection();
+                selection.removeAllRanges();
+                selection.addRange(selected);
+                return true;
+            }})()"#,
+            selector_json, range_json
+        );
+
+        let selected = page
+            .evaluate(script)
+            .await
+            .context("Failed to select text")?;
+        if selected.value().is_none_or(|v| v.is_null()) {
+            return Err(element_not_found(selector));
+        }
+
+        Ok(())
+    }
+
+    /// Drag `source` onto `target` with the left mouse button.
+    ///
+    /// Presses at the source's center, moves through `waypoints` (viewport
+    /// coordinates) in small steps, and releases at the target's center.
+    /// Pages using HTML5 `draggable` don't react to synthetic mouse drags;
+    /// this is for pages that track the mouse themselves.
+    pub async fn drag(
+        &self,
+        source: &str,
+        target: &str,
+        waypoints: &[Point],
+        session_id: Option<&str>,
+    ) -> Result<()> {
+        use DispatchMouseEventType::{MouseMoved, MousePressed, MouseReleased};
+        let page = self.get_page(session_id).await?;
+
+        let source_element = page
+            .find_element(resolve_selector(source))
+            .await
+            .map_err(|_| element_not_found(source))?;
+        source_element.scroll_into_view().await?;
+        let start = box_center(&source_element).await?;
+        let target_element = page
+            .find_element(resolve_selector(target))
+            .await
+            .map_err(|_| element_not_found(target))?;
+        let end = box_center(&target_element).await?;
+
+        dispatch_mouse(&page, MouseMoved, start, MouseButton::None, 0).await?;
+        dispatch_mouse(&page, MousePressed, start, MouseButton::Left, 1).await?;
+        for point in drag_path(start, waypoints, end, DRAG_MOVES_PER_SEGMENT) {
+            tokio::time::sleep(DRAG_MOVE_INTERVAL).await;
+            dispatch_mouse(&page, MouseMoved, point, MouseButton::Left, 1).await?;
+        }
+        dispatch_mouse(&page, MouseReleased, end, MouseButton::Left, 0).await?;
+
+        Ok(())
+    }
+
+    /// Drag the left mouse button along a polyline of viewport points, e.g.
+    /// to draw a stroke on a canvas.
+    ///
+    /// Presses at the first point, moves to each following point in turn and
+    /// releases at the last, pausing `delay_between_points_ms` between points.
+    /// Only the given points are sent; add more for smoother curves.
+    pub async fn drag_along_path(
+        &self,
+        points: &[(f64, f64)],
+        delay_between_points_ms: u64,
+        session_id: Option<&str>,
+    ) -> Result<()> {
+        use DispatchMouseEventType::{MouseMoved, MousePressed, MouseReleased};
+        if points.len() < 2 {
+            anyhow::bail!("A drag path needs at least two points");
+        }
+        let page = self.get_page(session_id).await?;
+        let delay = Duration::from_millis(delay_between_points_ms);
+        let point = |&(x, y): &(f64, f64)| Point { x, y };
+        let (start, end) = (point(&points[0]), point(&points[points.len() - 1]));
+
+        dispatch_mouse(&page, MouseMoved, start, MouseButton::None, 0).await?;
+        dispatch_mouse(&page, MousePressed, start, MouseButton::Left, 1).await?;
+        for next in &points[1..] {
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            dispatch_mouse(&page, MouseMoved, point(next), MouseButton::Left, 1).await?;
+        }
+        dispatch_mouse(&page, MouseReleased, end, MouseButton::Left, 0).await
+    }
+
+    /// Move the mouse to viewport coordinates (CSS pixels from the top-left
+    /// of the visible area, not the document).
+    pub async fn mouse_move(&self, x: f64, y: f64, session_id: Option<&str>) -> Result<()> {
+        let page = self.get_page(session_id).await?;
+        dispatch_mouse(
+            &page,
+            DispatchMouseEventType::MouseMoved,
+            Point { x, y },
+            MouseButton::None,
+            0,
+        )
+        .await
+    }
+
+    /// Right-click an element, and report the `contextmenu` event it caused
+    /// (None if the page never saw one).
+    pub async fn right_click(
+        &self,
+        selector: &str,
+        session_id: Option<&str>,
+    ) -> Result<ClickResult> {
+        use DispatchMouseEventType::{MouseMoved, MousePressed, MouseReleased};
+        let page = self.get_page(session_id).await?;
+        let element = page
+            .find_element(resolve_selector(selector))
+            .await
+            .map_err(|_| element_not_found(selector))?;
+        element.scroll_into_view().await?;
+        let point = box_center(&element).await?;
+
+        // Keep the event itself, so defaultPrevented reflects the page's handlers
+        page.evaluate(
+            "window.addEventListener('contextmenu', (e) => { window.__fgpContextMenu = e; }, \
+             { capture: true, once: true })",
+        )
+        .await
+        .context("Failed to watch for contextmenu events")?;
+
+        dispatch_mouse(&page, MouseMoved, point, MouseButton::None, 0).await?;
+        dispatch_mouse(&page, MousePressed, point, MouseButton::Right, 2).await?;
+        dispatch_mouse(&page, MouseReleased, point, MouseButton::Right, 0).await?;
+
+        let script = r#"(() => {
+            const e = window.__fgpContextMenu;
+            delete window.__fgpContextMenu;
+            if (!e) return null;
+            const el = e.target;
+            let target = el.tagName ? el.tagName.toLowerCase() : el.nodeName;
+            if (el.id) target += '#' + el.id;
+            if (el.classList && el.classList.length) target += '.' + [...el.classList].join('.');
+            return { target, default_prevented: e.defaultPrevented };
+        })()"#;
+        let context_menu = evaluate_in(&page, None, script.to_string())
+            .await
+            .context("Failed to read the contextmenu event")?
+            .filter(|v| !v.is_null())
+            .map(serde_json::from_value)
+            .transpose()?;
+
+        Ok(ClickResult {
+            success: true,
+            element: Some(selector.to_string()),
+            context_menu,
+        })
+    }
+
+    /// Click an item of an open custom (JavaScript) context menu by its text.
+    ///
+    /// Prefers visible ARIA menu items with exactly `text`, then ones
+    /// containing it (case-insensitive), then the innermost visible element
+    /// whose text is exactly `text`, for menus without roles.
+    pub async fn select_context_menu_item(
+        &self,
+        text: &str,
+        session_id: Option<&str>,
+    ) -> Result<ClickResult> {
+        use DispatchMouseEventType::{MouseMoved, MousePressed, MouseReleased};
+        let page = self.get_page(session_id).await?;
+
+        let script = format!(
+            r#"(() => {{
+                const text = {};
+                const visible = (el) => {{
+                    const r = el.getBoundingClientRect();
+                    return r.width > 0 && r.height > 0
+                        && getComputedStyle(el).visibility !== 'hidden';
+                }};
+                const label = (el) => (el.innerText || '').trim();
+                const items = [...document.querySelectorAll(
+                    '[role=menuitem], [role=menuitemcheckbox], [role=menuitemradio]'
+                )].filter(visible);
+                let el = items.find((e) => label(e) === text)
+                    || items.find((e) => label(e).toLowerCase().includes(text.toLowerCase()));
+                if (!el) {{
+                    const exact = [...document.querySelectorAll('body *')]
+                        .filter((e) => label(e) === text && visible(e));
+                    el = exact.find((e) => !exact.some((o) => o !== e && e.contains(o)));
+                }}
+                if (!el) return null;
+                el.scrollIntoView({{ block: 'nearest' }});
+                const r = el.getBoundingClientRect();
+                return {{ x: r.left + r.width / 2, y: r.top + r.height / 2 }};
+            }})()"#,
+            serde_json::to_string(text)?
+        );
+
+        let center = evaluate_in(&page, None, script)
+            .await
+            .context("Failed to find context menu item")?
+            .filter(|v| !v.is_null())
+            .ok_or_else(|| element_not_found(&format!("menu item '{}'", text)))?;
+        let point = Point {
+            x: center["x"].as_f64().unwrap_or_default(),
+            y: center["y"].as_f64().unwrap_or_default(),
+        };
+
+        dispatch_mouse(&page, MouseMoved, point, MouseButton::None, 0).await?;
+        dispatch_mouse(&page, MousePressed, point, MouseButton::Left, 1).await?;
+        dispatch_mouse(&page, MouseReleased, point, MouseButton::Left, 0).await?;
+
+        Ok(ClickResult {
+            success: true,
+            element: Some(text.to_string()),
+            context_menu: None,
+        })
+    }
+
+    /// Click `button` at viewport coordinates, whatever element is there.
+    pub async fn mouse_click(
+        &self,
+        x: f64,
+        y: f64,
+        button: MouseButton,
+        session_id: Option<&str>,
+    ) -> Result<()> {
+        use DispatchMouseEventType::{MouseMoved, MousePressed, MouseReleased};
+        let page = self.get_page(session_id).await?;
+        let point = Point { x, y };
+        let mask = button_mask(&button);
+        dispatch_mouse(&page, MouseMoved, point, MouseButton::None, 0).await?;
+        dispatch_mouse(&page, MousePressed, point, button.clone(), mask).await?;
+        dispatch_mouse(&page, MouseReleased, point, button, 0).await
+    }
+
+    /// Scroll the wheel by `delta_x`/`delta_y` pixels with the mouse at
+    /// viewport coordinates `x`, `y`.
+    pub async fn mouse_wheel(
+        &self,
+        delta_x: f64,
+        delta_y: f64,
+        x: f64,
+        y: f64,
+        session_id: Option<&str>,
+    ) -> Result<()> {
+        let page = self.get_page(session_id).await?;
+        page.execute(
+            DispatchMouseEventParams::builder()
+                .r#type(DispatchMouseEventType::MouseWheel)
+                .x(x)
+                .y(y)
+                .delta_x(delta_x)
+                .delta_y(delta_y)
+                .build()
+                .map_err(|e| anyhow::anyhow!("Failed to build mouse event: {}", e))?,
+        )
+        .await
+        .context("Failed to dispatch mouse wheel event")?;
+        Ok(())
+    }
+
+    /// Focus an element without clicking it.
+    pub async fn focus(
+        &self,
+        selector: &str,
+        frame: Option<&FrameTarget>,
+        session_id: Option<&str>,
+    ) -> Result<()> {
+        let page = self.get_page(session_id).await?;
+        let css_selector = resolve_selector(selector);
+        let selector_json = serde_json::to_string(&css_selector)?;
+
+        let script = format!(
+            r#"(() => {{
+                const el = document.querySelector({});
+                if (!el) return false;
+                el.focus();
+                return true;
+            }})()"#,
+            selector_json
+        );
+
+        let found = evaluate_in(&page, frame, script)
+            .await?
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if !found {
+            return Err(element_not_found(selector));
+        }
+
+        Ok(())
+    }
+
+    /// Get the visible text of an element, or of the whole document.
+    pub async fn get_text(
+        &self,
+        selector: Option<&str>,
+        frame: Option<&FrameTarget>,
+        session_id: Option<&str>,
+    ) -> Result<String> {
+        let page = self.get_page(session_id).await?;
+
+        let script = match selector {
+            Some(sel) => {
+                let selector_json = serde_json::to_string(&resolve_selector(sel))?;
+                format!(
+                    r#"(() => {{
+                        const el = document.querySelector({});
+                        return el ? el.innerText : null;
+                    }})()"#,
+                    selector_json
+                )
+            }
+            None => "document.body ? document.body.innerText : ''".to_string(),
+        };
+
+        match evaluate_in(&page, frame, script).await? {
+            Some(serde_json::Value::String(text)) => Ok(text),
+            _ => Err(element_not_found(selector.unwrap_or("body"))),
+        }
+    }
+
+    /// Get an element's bounding box.
+    pub async fn get_element_bounds(
+        &self,
         selector: &str,
-        file_path: &str,
+        session_id: Option<&str>,
+    ) -> Result<ElementBounds> {
+        let page = self.get_page(session_id).await?;
+        let css_selector = resolve_selector(selector);
+        let selector_json = serde_json::to_string(&css_selector)?;
+
+        let script = format!(
+            r#"(() => {{
+                const el = document.querySelector({});
+                if (!el) return null;
+                const r = el.getBoundingClientRect();
+                const inViewport = r.width > 0 && r.height > 0 &&
+                    r.bottom > 0 && r.right > 0 &&
+                    r.top < window.innerHeight && r.left < window.innerWidth;
+                return {{
+                    x: r.x, y: r.y, width: r.width, height: r.height,
+                    top: r.top, right: r.right, bottom: r.bottom, left: r.left,
+                    in_viewport: inViewport,
+                }};
+            }})()"#,
+            selector_json
+        );
+
+        let bounds: Option<ElementBounds> = page.evaluate(script).await?.into_value()?;
+        bounds.ok_or_else(|| element_not_found(selector))
+    }
+
+    /// Remove focus from the currently focused element.
+    pub async fn blur(&self, session_id: Option<&str>) -> Result<()> {
+        let page = self.get_page(session_id).await?;
+
+        page.evaluate("document.activeElement && document.activeElement.blur()")
+            .await?;
+
+        Ok(())
+    }
+
+    /// Scroll to element or by amount.
+    pub async fn scroll(
+        &self,
+        selector: Option<&str>,
+        x: i32,
+        y: i32,
+        frame: Option<&FrameTarget>,
         session_id: Option<&str>,
     ) -> Result<()> {
         let page = self.get_page(session_id).await?;
-        let css_selector = resolve_selector(selector);
 
-        // Resolve to absolute path
-        let path = std::path::Path::new(file_path);
-        let absolute_path = if path.is_absolute() {
-            path.to_path_buf()
+        let script = if let Some(sel) = selector {
+            let css_selector = resolve_selector(sel);
+            let selector_json = serde_json::to_string(&css_selector)?;
+            format!(
+                r#"(() => {{
+                    const sel = {};
+                    const el = document.querySelector(sel);
+                    if (el) el.scrollIntoView({{ behavior: 'instant', block: 'center' }});
+                }})()"#,
+                selector_json
+            )
         } else {
-            std::env::current_dir()?.join(path)
+            format!("window.scrollBy({}, {})", x, y)
         };
 
-        // Verify file exists
-        if !absolute_path.exists() {
-            anyhow::bail!("File not found: {}", absolute_path.display());
+        evaluate_in(&page, frame, script).await?;
+
+        Ok(())
+    }
+
+    /// Scroll to the top of the page.
+    pub async fn scroll_to_top(&self, smooth: bool, session_id: Option<&str>) -> Result<()> {
+        let page = self.get_page(session_id).await?;
+        scroll_window_to(&page, "0", smooth).await
+    }
+
+    /// Scroll to the bottom of the page once. Unlike `scroll_to_bottom`, this
+    /// doesn't wait for more content to load.
+    pub async fn jump_to_bottom(&self, smooth: bool, session_id: Option<&str>) -> Result<()> {
+        let page = self.get_page(session_id).await?;
+        scroll_window_to(&page, DOCUMENT_HEIGHT_SCRIPT, smooth).await
+    }
+
+    /// Scroll to the bottom again and again until the page stops growing,
+    /// for feeds that load more content as you scroll.
+    ///
+    /// After each scroll the document height is polled for up to `settle`;
+    /// if it hasn't grown by then, the feed counts as exhausted. Scrolling
+    /// also stops after `max_scrolls` scrolls or once `timeout` has passed.
+    pub async fn scroll_to_bottom(
+        &self,
+        max_scrolls: u32,
+        settle: Duration,
+        timeout: Duration,
+        session_id: Option<&str>,
+    ) -> Result<ScrollToBottomResult> {
+        let page = self.get_page(session_id).await?;
+        let deadline = Instant::now() + timeout;
+        let document_height = || async {
+            let height: f64 = page.evaluate(DOCUMENT_HEIGHT_SCRIPT).await?.into_value()?;
+            Ok::<_, anyhow::Error>(height as u64)
+        };
+
+        let mut scrolls = 0;
+        let mut height = document_height().await?;
+        let reached_end = loop {
+            if scrolls >= max_scrolls || Instant::now() >= deadline {
+                break false;
+            }
+            page.evaluate(format!("window.scrollTo(0, {})", DOCUMENT_HEIGHT_SCRIPT))
+                .await?;
+            scrolls += 1;
+
+            // A settle window cut short by the deadline proves nothing
+            let settled_at = (Instant::now() + settle).min(deadline);
+            let grew = loop {
+                let current = document_height().await?;
+                if current > height {
+                    height = current;
+                    break true;
+                }
+                if Instant::now() >= settled_at {
+                    break false;
+                }
+                tokio::time::sleep(SCROLL_POLL_INTERVAL).await;
+            };
+            if !grew {
+                break settled_at < deadline;
+            }
+        };
+
+        Ok(ScrollToBottomResult {
+            scrolls,
+            height,
+            reached_end,
+        })
+    }
+
+    /// Press a key with modifiers (Ctrl, Shift, Alt, Meta).
+    pub async fn press_combo(
+        &self,
+        modifiers: &[&str],
+        key: &str,
+        session_id: Option<&str>,
+    ) -> Result<()> {
+        let flags = modifier_flags(modifiers)?;
+        let page = self.get_page(session_id).await?;
+        press_with_modifiers(&page, key, flags).await
+    }
+
+    /// Press keys one after another, pausing after each as it asks.
+    ///
+    /// Stops at the first key that fails to send; earlier keys have already
+    /// reached the page.
+    pub async fn key_sequence(&self, keys: &[KeyPress], session_id: Option<&str>) -> Result<()> {
+        // Check every press first, so a typo doesn't leave the sequence half sent
+        let flags = keys
+            .iter()
+            .map(|press| {
+                let modifiers: Vec<&str> = press.modifiers.iter().map(|m| m.as_str()).collect();
+                modifier_flags(&modifiers)
+            })
+            .collect::<Result<Vec<i64>>>()?;
+        let page = self.get_page(session_id).await?;
+
+        for (press, flags) in keys.iter().zip(flags) {
+            press_with_modifiers(&page, &press.key, flags)
+                .await
+                .with_context(|| format!("Failed to press '{}'", press.key))?;
+            if press.delay_after_ms > 0 {
+                tokio::time::sleep(Duration::from_millis(press.delay_after_ms)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Upload a file to an input element.
+    pub async fn upload(
+        &self,
+        selector: &str,
+        file_paths: &[String],
+        session_id: Option<&str>,
+    ) -> Result<Vec<String>> {
+        let page = self.get_page(session_id).await?;
+        let css_selector = resolve_selector(selector);
+
+        if file_paths.is_empty() {
+            anyhow::bail!("No files to upload");
+        }
+
+        // Resolve to absolute paths and verify each file exists
+        let mut absolute_paths = Vec::with_capacity(file_paths.len());
+        for file_path in file_paths {
+            let path = std::path::Path::new(file_path);
+            let absolute_path = if path.is_absolute() {
+                path.to_path_buf()
+            } else {
+                std::env::current_dir()?.join(path)
+            };
+            if !absolute_path.exists() {
+                anyhow::bail!("File not found: {}", absolute_path.display());
+            }
+            absolute_paths.push(absolute_path);
         }
 
         // Validate element exists and is a file input
@@ -710,129 +4211,818 @@ impl BrowserClient {
                 if (el.tagName !== 'INPUT' || el.type !== 'file') {{
                     throw new Error('Element is not a file input: ' + sel);
                 }}
-                return true;
+                return el.multiple;
             }})()"#,
             selector_json
         );
 
-        page.evaluate(script)
-            .await
-            .context("Element validation failed")?;
+        let multiple: bool = page
+            .evaluate(script)
+            .await
+            .context("Element validation failed")?
+            .into_value()?;
+        if absolute_paths.len() > 1 && !multiple {
+            anyhow::bail!(
+                "MULTIPLE_NOT_ALLOWED: {} takes one file (no `multiple` attribute), got {}",
+                selector,
+                absolute_paths.len()
+            );
+        }
+
+        // Use CDP DOM.setFileInputFiles via element
+        // chromiumoxide Element doesn't expose this directly, so we use Page.evaluate with file input
+        // Instead, we'll focus the element and use CDP Input domain
+
+        // Get the element's node id and use DOM.setFileInputFiles
+        use chromiumoxide::cdp::browser_protocol::dom::{
+            GetDocumentParams, QuerySelectorParams, SetFileInputFilesParams,
+        };
+
+        // Get document root
+        let doc = page.execute(GetDocumentParams::default()).await?;
+        let root_node_id = doc.root.node_id;
+
+        // Query for the element
+        let query_result = page
+            .execute(QuerySelectorParams::new(root_node_id, &css_selector))
+            .await?;
+        let node_id = query_result.node_id;
+
+        // Set the files
+        page.execute(
+            SetFileInputFilesParams::builder()
+                .files(
+                    absolute_paths
+                        .iter()
+                        .map(|path| path.to_string_lossy().to_string())
+                        .collect::<Vec<_>>(),
+                )
+                .node_id(node_id)
+                .build()
+                .map_err(|e| anyhow::anyhow!("Failed to build SetFileInputFilesParams: {:?}", e))?,
+        )
+        .await?;
+
+        tracing::debug!("Uploaded {} file(s) to {}", absolute_paths.len(), selector);
+
+        Ok(absolute_paths
+            .iter()
+            .map(|path| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            })
+            .collect())
+    }
+
+    /// Upload `contents` as a file called `filename`. The bytes go to a
+    /// temporary file that stays while the page shows the same document
+    /// (see `UploadDirs`), since the page reads the file when the form is
+    /// submitted, not now.
+    pub async fn upload_bytes(
+        &self,
+        selector: &str,
+        filename: &str,
+        contents: &[u8],
+        session_id: Option<&str>,
+    ) -> Result<String> {
+        if filename.is_empty()
+            || std::path::Path::new(filename).file_name() != Some(std::ffi::OsStr::new(filename))
+        {
+            anyhow::bail!("Invalid filename '{}': must be a plain file name", filename);
+        }
+
+        let sid = session_id.unwrap_or(&self.default_session_id);
+        let (pages, uploads) = {
+            let sessions = self.sessions.read().await;
+            let session = sessions
+                .get(sid)
+                .ok_or_else(|| self.session_not_found(sid))?;
+            (session.pages.clone(), session.uploads.clone())
+        };
+        uploads.prune(&pages).await;
+
+        // A directory per upload, so the page sees the name it was given
+        let page = self.get_page(session_id).await?;
+        let dir = uploads.create(&page).await?;
+        let path = dir.join(filename);
+        let uploaded = async {
+            tokio::fs::write(&path, contents)
+                .await
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            let path = path.to_string_lossy().to_string();
+            self.upload(selector, std::slice::from_ref(&path), session_id)
+                .await?;
+            Ok(path)
+        }
+        .await;
+        if uploaded.is_err() {
+            uploads.remove(&dir).await;
+        }
+        uploaded
+    }
+
+    /// Health check - verify browser is responsive. False while the health
+    /// task is reconnecting.
+    pub async fn health_check(&self) -> Result<bool> {
+        if self.is_reconnecting() {
+            return Ok(false);
+        }
+        Ok(self.is_connected(true).await)
+    }
+
+    /// Browser product string, e.g. "HeadlessChrome/131.0.6778.85".
+    pub async fn browser_version(&self) -> Result<String> {
+        let version = tokio::time::timeout(HEALTH_CHECK_TIMEOUT, self.browser.version())
+            .await
+            .context("Timed out getting browser version")?
+            .context("Failed to get browser version")?;
+        Ok(version.product)
+    }
+
+    /// Number of open sessions, including the default one.
+    pub async fn session_count(&self) -> usize {
+        self.sessions.read().await.len()
+    }
+
+    /// Close the browser.
+    #[allow(dead_code)]
+    pub async fn close(mut self) -> Result<()> {
+        // Dispose all non-default sessions
+        let sessions = self.sessions.read().await;
+        for (id, session) in sessions.iter() {
+            if id != &self.default_session_id {
+                if let Some(ref context_id) = session.context_id {
+                    let _ = self
+                        .browser
+                        .dispose_browser_context(context_id.clone())
+                        .await;
+                }
+            }
+        }
+        drop(sessions);
+
+        // The health task only holds a weak reference
+        if let Some(browser) = Arc::get_mut(&mut self.browser) {
+            browser.close().await?;
+        }
+        Ok(())
+    }
+
+    /// Find the installed binary for a browser channel. Fails rather than
+    /// falling back to another browser.
+    pub fn find_channel_executable(channel: BrowserChannel) -> Result<PathBuf> {
+        let paths = channel_executable_paths(channel);
+        if let Some(path) = paths.iter().map(PathBuf::from).find(|p| p.exists()) {
+            tracing::info!("Using {} at: {:?}", channel.name(), path);
+            return Ok(path);
+        }
+
+        anyhow::bail!(
+            "Browser channel '{}' is not installed (looked in: {})",
+            channel.name(),
+            paths.join(", ")
+        )
+    }
+
+    /// Find Chrome executable on the system.
+    ///
+    /// `FGP_CHROME` or `CHROME_PATH` skips discovery. Otherwise Playwright's
+    /// chrome-headless-shell is preferred, then system Chrome, Chromium or Edge.
+    fn find_chrome_executable() -> Result<PathBuf> {
+        if let Some(path) = chrome_path_override()? {
+            return Ok(path);
+        }
+
+        if let Some(binary) = find_headless_shell() {
+            tracing::info!("Using chrome-headless-shell at: {:?}", binary);
+            return Ok(binary);
+        }
+
+        for p in system_browser_paths() {
+            if p.exists() {
+                tracing::info!("Found Chrome at: {:?}", p);
+                return Ok(p);
+            }
+        }
+
+        anyhow::bail!("Chrome/Chromium not found. {}", install_hint())
+    }
+}
+
+/// Choose a tab by URL substring (first match) or by index.
+fn pick_tab<'a>(
+    tabs: &'a [TabInfo],
+    url: Option<&str>,
+    index: Option<usize>,
+) -> Result<&'a TabInfo> {
+    match (url, index) {
+        (Some(pattern), _) => tabs
+            .iter()
+            .find(|t| t.url.contains(pattern))
+            .ok_or_else(|| anyhow::anyhow!("No tab URL contains '{}'", pattern)),
+        (None, Some(i)) => tabs.get(i).ok_or_else(|| {
+            anyhow::anyhow!("Tab index {} out of range ({} tabs open)", i, tabs.len())
+        }),
+        (None, None) => anyhow::bail!("Pass a URL substring or a tab index"),
+    }
+}
+
+/// How `attach_session` picks a tab.
+#[derive(Debug, Clone, Copy)]
+pub enum TabQuery<'a> {
+    TargetId(&'a str),
+    /// URL substring
+    Url(&'a str),
+    /// Title substring
+    Title(&'a str),
+}
+
+/// Find a tab to attach to. URL and title matches skip tabs a session
+/// already drives.
+fn match_tab<'a>(tabs: &'a [TabInfo], query: &TabQuery<'_>) -> Result<&'a TabInfo> {
+    let free = || tabs.iter().filter(|t| t.session_id.is_none());
+    let tab = match *query {
+        TabQuery::TargetId(id) => tabs.iter().find(|t| t.target_id == id),
+        TabQuery::Url(pattern) => free().find(|t| t.url.contains(pattern)),
+        TabQuery::Title(text) => {
+            let text = text.to_lowercase();
+            free().find(|t| t.title.to_lowercase().contains(&text))
+        }
+    };
+    tab.ok_or_else(|| anyhow::anyhow!("TAB_NOT_FOUND: no free tab matches {:?}", query))
+}
+
+/// Fill in a session's URL and title as of now from its active `page`.
+async fn with_location(mut info: SessionInfo, page: &Page) -> SessionInfo {
+    info.url = page.url().await.ok().flatten();
+    info.title = page.get_title().await.ok().flatten();
+    info
+}
+
+/// Stop a session's listeners on a tab we're leaving, so it's left as we
+/// found it.
+async fn release_tab(session: &BrowserSession) -> Result<()> {
+    session.network.write().await.disable();
+    session.capture.write().await.stop();
+    session.har.write().await.finish();
+    session.intercept.write().await.reset();
+    sync_interception(session.main_page(), &session.intercept).await?;
+    if session.network_conditions.read().await.is_some() {
+        apply_network_conditions(session.main_page(), None).await?;
+    }
+    if session.cpu_throttling.read().await.is_some() {
+        apply_cpu_throttling(session.main_page(), 1.0).await?;
+    }
+    if session.media.read().await.is_some() {
+        apply_media(session.main_page(), &MediaEmulation::default()).await?;
+    }
+    Ok(())
+}
+
+/// Wrap a Clipboard API call, failing clearly where the API is missing.
+fn clipboard_script(call: &str) -> String {
+    format!(
+        "(async () => {{ \
+           if (!navigator.clipboard) throw new Error('The Clipboard API needs a secure page (https or localhost)'); \
+           return await {}; \
+         }})()",
+        call
+    )
+}
+
+/// Open IndexedDB database `db_name` in the page and return what `run`, a
+/// JS function taking the database (and maybe returning a promise), returns.
+///
+/// `indexedDB.open()` creates missing databases, so the upgrade that would
+/// create one is aborted and reported as `DATABASE_NOT_FOUND`. Results go
+/// through `JSON.stringify`, with binary data, Maps, Sets and BigInts
+/// converted first, since `returnByValue` can't carry them.
+async fn with_indexeddb(page: &Page, db_name: &str, run: &str) -> Result<serde_json::Value> {
+    let script = format!(
+        r#"(() => new Promise((resolve) => {{
+            const name = {};
+            const run = {};
+            const done = (outcome) => resolve(JSON.stringify(outcome, (_, v) => {{
+                if (typeof v === 'bigint') return v.toString();
+                if (v instanceof ArrayBuffer) return Array.from(new Uint8Array(v));
+                if (ArrayBuffer.isView(v)) return Array.from(new Uint8Array(v.buffer, v.byteOffset, v.byteLength));
+                if (v instanceof Map) return Object.fromEntries(v);
+                if (v instanceof Set) return Array.from(v);
+                return v;
+            }}));
+            const fail = (e) => done({{ error: String((e && e.message) || e) }});
+            let missing = false;
+            let request;
+            try {{ request = indexedDB.open(name); }} catch (e) {{ return fail(e); }}
+            request.onupgradeneeded = () => {{ missing = true; request.transaction.abort(); }};
+            request.onerror = () => fail(missing ? 'DATABASE_NOT_FOUND: ' + name : request.error);
+            request.onsuccess = () => {{
+                const db = request.result;
+                Promise.resolve()
+                    .then(() => run(db))
+                    .then((ok) => done({{ ok }}), fail)
+                    .finally(() => db.close());
+            }};
+        }}))()"#,
+        serde_json::to_string(db_name)?,
+        run
+    );
+
+    let outcome: String = page
+        .evaluate(script)
+        .await
+        .context("Failed to read IndexedDB")?
+        .into_value()?;
+    let mut outcome: serde_json::Value =
+        serde_json::from_str(&outcome).context("Failed to parse IndexedDB result")?;
+    if let Some(error) = outcome.get("error").and_then(|e| e.as_str()) {
+        anyhow::bail!("{}", error);
+    }
+    Ok(outcome
+        .get_mut("ok")
+        .map(serde_json::Value::take)
+        .unwrap_or_default())
+}
+
+/// Close a session removed from the map. While the pool is short, its
+/// context is cleared and put back (see `pool::recycle`); otherwise, or if
+/// that fails, it's disposed with `dispose_session`.
+pub(super) async fn retire_session(
+    browser: &Browser,
+    pool: &SessionPool,
+    session: BrowserSession,
+) -> Result<()> {
+    let recyclable = session
+        .context_id
+        .clone()
+        .filter(|_| !session.attached && pool.wants());
+    if let Some(context_id) = recyclable {
+        match recycle(browser, context_id, &session.visited).await {
+            Ok(pooled) => {
+                if pool.give_back(pooled).is_none() {
+                    tracing::info!(
+                        "Closed session {}; its context is back in the pool",
+                        session.id
+                    );
+                    return Ok(());
+                }
+            }
+            Err(e) => tracing::warn!("Failed to recycle session {}: {:#}", session.id, e),
+        }
+    }
+    dispose_session(browser, session).await
+}
+
+/// Close a session removed from the map: dispose its context or tab, or for
+/// an attached session, detach and leave the user's tab open.
+pub(super) async fn dispose_session(browser: &Browser, session: BrowserSession) -> Result<()> {
+    if session.attached {
+        release_tab(&session).await?;
+        tracing::info!("Detached session {} from its tab", session.id);
+        return Ok(());
+    }
+    match session.context_id {
+        Some(context_id) => {
+            browser
+                .dispose_browser_context(context_id)
+                .await
+                .context("Failed to dispose browser context")?;
+        }
+        // Tab sessions live in the default context; close just the tab
+        None => {
+            session
+                .main_page()
+                .clone()
+                .close()
+                .await
+                .context("Failed to close tab")?;
+        }
+    }
+    tracing::info!("Closed session: {}", session.id);
+    Ok(())
+}
+
+/// A cookie as read from one context, ready to set in another.
+fn cookie_param(cookie: Cookie) -> CookieParam {
+    let mut param = CookieParam::new(cookie.name, cookie.value);
+    param.domain = Some(cookie.domain);
+    param.path = Some(cookie.path);
+    param.secure = Some(cookie.secure);
+    param.http_only = Some(cookie.http_only);
+    param.same_site = cookie.same_site;
+    param.expires = (!cookie.session).then(|| TimeSinceEpoch::new(cookie.expires));
+    param.priority = Some(cookie.priority);
+    param.source_scheme = Some(cookie.source_scheme);
+    param.source_port = Some(cookie.source_port);
+    param.partition_key = cookie.partition_key;
+    param
+}
+
+/// Error for a `<select>` with no option whose value or label is `value`.
+fn option_not_found(selector: &str, value: &str, labels: &[String]) -> anyhow::Error {
+    let labels: Vec<String> = labels.iter().map(|l| format!("{:?}", l)).collect();
+    anyhow::anyhow!(
+        "OPTION_NOT_FOUND: no option in {} has value or label {:?} (available: {})",
+        selector,
+        value,
+        labels.join(", ")
+    )
+}
+
+/// Error for a selector that matched nothing.
+fn element_not_found(selector: &str) -> anyhow::Error {
+    anyhow::anyhow!("ELEMENT_NOT_FOUND: {}", selector)
+}
+
+/// Center of an element's border box, in viewport coordinates.
+async fn box_center(element: &Element) -> Result<Point> {
+    let bounds = element
+        .bounding_box()
+        .await
+        .context("Failed to get element box model")?;
+    Ok(Point {
+        x: bounds.x + bounds.width / 2.0,
+        y: bounds.y + bounds.height / 2.0,
+    })
+}
+
+/// Send a mouse event at `point` (viewport coordinates). `button` is the
+/// one pressed, released or held, and `buttons` the mask of held buttons.
+async fn dispatch_mouse(
+    page: &Page,
+    event_type: DispatchMouseEventType,
+    point: Point,
+    button: MouseButton,
+    buttons: i64,
+) -> Result<()> {
+    let is_move = event_type == DispatchMouseEventType::MouseMoved;
+    let mut builder = DispatchMouseEventParams::builder()
+        .r#type(event_type)
+        .x(point.x)
+        .y(point.y)
+        .button(button)
+        .buttons(buttons);
+    if !is_move {
+        builder = builder.click_count(1);
+    }
+    page.execute(
+        builder
+            .build()
+            .map_err(|e| anyhow::anyhow!("Failed to build mouse event: {}", e))?,
+    )
+    .await
+    .context("Failed to dispatch mouse event")?;
+    Ok(())
+}
+
+/// Bit for `button` in a mouse event's `buttons` mask.
+fn button_mask(button: &MouseButton) -> i64 {
+    match button {
+        MouseButton::Left => 1,
+        MouseButton::Right => 2,
+        MouseButton::Middle => 4,
+        MouseButton::Back => 8,
+        MouseButton::Forward => 16,
+        MouseButton::None => 0,
+    }
+}
+
+/// Points a drag moves through after pressing at `start`: `moves` evenly
+/// spaced steps along each segment of start, waypoints..., end.
+fn drag_path(start: Point, waypoints: &[Point], end: Point, moves: usize) -> Vec<Point> {
+    let mut path = Vec::new();
+    let mut from = start;
+    for &to in waypoints.iter().chain(std::iter::once(&end)) {
+        for i in 1..=moves {
+            let t = i as f64 / moves as f64;
+            path.push(Point {
+                x: from.x + (to.x - from.x) * t,
+                y: from.y + (to.y - from.y) * t,
+            });
+        }
+        from = to;
+    }
+    path
+}
+
+/// Focus an editable element, select its content and delete it.
+async fn clear_element(page: &Page, frame: Option<&FrameTarget>, selector: &str) -> Result<()> {
+    let selector_json = serde_json::to_string(&resolve_selector(selector))?;
+    let script = format!(
+        r#"(() => {{
+            const el = document.querySelector({});
+            if (!el) return 'missing';
+            const nonText = ['checkbox', 'radio', 'button', 'submit', 'reset', 'file', 'image', 'range', 'color', 'hidden'];
+            const field = (el instanceof HTMLInputElement && !nonText.includes(el.type))
+                || el instanceof HTMLTextAreaElement;
+            if (field ? el.disabled || el.readOnly : !el.isContentEditable) return 'not_editable';
+
+            el.focus();
+            if (field) el.select(); else document.execCommand('selectAll');
+            // execCommand fires its own input event; fall back for fields it can't edit
+            const deleted = document.execCommand('delete');
+            if (!deleted || (field ? el.value : el.textContent) !== '') {{
+                if (field) {{
+                    // The native setter keeps frameworks' value tracking in sync
+                    const proto = Object.getPrototypeOf(el);
+                    Object.getOwnPropertyDescriptor(proto, 'value').set.call(el, '');
+                }} else {{
+                    el.textContent = '';
+                }}
+                el.dispatchEvent(new Event('input', {{ bubbles: true }}));
+            }}
+            return 'cleared';
+        }})()"#,
+        selector_json
+    );
+
+    match evaluate_in(page, frame, script).await? {
+        Some(serde_json::Value::String(status)) if status == "cleared" => Ok(()),
+        Some(serde_json::Value::String(status)) if status == "not_editable" => {
+            Err(anyhow::anyhow!(
+                "ELEMENT_NOT_EDITABLE: {} is not a text field or contenteditable element",
+                selector
+            ))
+        }
+        _ => Err(element_not_found(selector)),
+    }
+}
+
+/// Screenshot params for an image format and its quality.
+fn screenshot_params(
+    format: ScreenshotFormat,
+) -> Result<chromiumoxide::page::ScreenshotParamsBuilder> {
+    let params = chromiumoxide::page::ScreenshotParams::builder();
+    let mut params = match format {
+        ScreenshotFormat::Png => params.format(CaptureScreenshotFormat::Png),
+        ScreenshotFormat::Jpeg { .. } => params.format(CaptureScreenshotFormat::Jpeg),
+        ScreenshotFormat::Webp { .. } => params.format(CaptureScreenshotFormat::Webp),
+    };
+    if let Some(quality) = format.quality() {
+        if quality > 100 {
+            anyhow::bail!(
+                "Screenshot quality must be between 0 and 100, got {}",
+                quality
+            );
+        }
+        params = params.quality(quality as i64);
+    }
+    Ok(params)
+}
+
+/// Save a captured image to `path`, or return it base64-encoded.
+async fn screenshot_result(
+    data: Vec<u8>,
+    path: Option<&str>,
+    format: ScreenshotFormat,
+    width: u32,
+    height: u32,
+) -> Result<ScreenshotResult> {
+    let (data, path) = match path {
+        Some(file_path) => {
+            tokio::fs::write(file_path, &data).await?;
+            (None, Some(file_path.to_string()))
+        }
+        None => {
+            let encoded = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &data);
+            (Some(encoded), None)
+        }
+    };
+    Ok(ScreenshotResult {
+        data,
+        path,
+        format: format.content_type().to_string(),
+        width,
+        height,
+    })
+}
 
-        // Use CDP DOM.setFileInputFiles via element
-        // chromiumoxide Element doesn't expose this directly, so we use Page.evaluate with file input
-        // Instead, we'll focus the element and use CDP Input domain
+/// Scroll the window to the vertical position `top` (a JS expression).
+async fn scroll_window_to(page: &Page, top: &str, smooth: bool) -> Result<()> {
+    let behavior = if smooth { "smooth" } else { "instant" };
+    page.evaluate(format!(
+        "window.scrollTo({{ left: 0, top: {}, behavior: '{}' }})",
+        top, behavior
+    ))
+    .await?;
+    Ok(())
+}
 
-        // Get the element's node id and use DOM.setFileInputFiles
-        use chromiumoxide::cdp::browser_protocol::dom::{
-            GetDocumentParams, QuerySelectorParams, SetFileInputFilesParams,
+/// CDP modifier bit mask for modifier names.
+fn modifier_flags(modifiers: &[&str]) -> Result<i64> {
+    modifiers.iter().try_fold(0, |acc, m| {
+        let flag = match m.to_lowercase().as_str() {
+            "ctrl" | "control" => 1,
+            "shift" => 2,
+            "alt" => 4,
+            "meta" | "cmd" | "command" => 8,
+            _ => anyhow::bail!(
+                "INVALID_MODIFIER: unknown modifier '{}' (expected ctrl, shift, alt or meta)",
+                m
+            ),
         };
+        Ok(acc | flag)
+    })
+}
 
-        // Get document root
-        let doc = page.execute(GetDocumentParams::default()).await?;
-        let root_node_id = doc.root.node_id;
+/// Send keyDown and keyUp for `key` with the `modifiers` mask held.
+async fn press_with_modifiers(page: &Page, key: &str, modifiers: i64) -> Result<()> {
+    page.execute(
+        DispatchKeyEventParams::builder()
+            .r#type(DispatchKeyEventType::KeyDown)
+            .key(key)
+            .modifiers(modifiers)
+            .build()
+            .map_err(|e| anyhow::anyhow!("Failed to build key event: {:?}", e))?,
+    )
+    .await?;
+
+    page.execute(
+        DispatchKeyEventParams::builder()
+            .r#type(DispatchKeyEventType::KeyUp)
+            .key(key)
+            .modifiers(modifiers)
+            .build()
+            .map_err(|e| anyhow::anyhow!("Failed to build key event: {:?}", e))?,
+    )
+    .await?;
 
-        // Query for the element
-        let query_result = page
-            .execute(QuerySelectorParams::new(root_node_id, &css_selector))
-            .await?;
-        let node_id = query_result.node_id;
+    Ok(())
+}
+
+/// Type `text` into the focused element one key event per character.
+async fn type_with_delay(page: &Page, text: &str, delay: Duration) -> Result<()> {
+    for (i, ch) in text.chars().enumerate() {
+        if i > 0 {
+            tokio::time::sleep(delay).await;
+        }
+        let (key, text) = match ch {
+            '\n' => ("Enter".to_string(), "\r".to_string()),
+            _ => (ch.to_string(), ch.to_string()),
+        };
 
-        // Set the file
         page.execute(
-            SetFileInputFilesParams::builder()
-                .files(vec![absolute_path.to_string_lossy().to_string()])
-                .node_id(node_id)
+            DispatchKeyEventParams::builder()
+                .r#type(DispatchKeyEventType::KeyDown)
+                .key(key.clone())
+                .text(text)
                 .build()
-                .map_err(|e| anyhow::anyhow!("Failed to build SetFileInputFilesParams: {:?}", e))?,
+                .map_err(|e| anyhow::anyhow!("Failed to build key event: {:?}", e))?,
+        )
+        .await?;
+        page.execute(
+            DispatchKeyEventParams::builder()
+                .r#type(DispatchKeyEventType::KeyUp)
+                .key(key)
+                .build()
+                .map_err(|e| anyhow::anyhow!("Failed to build key event: {:?}", e))?,
         )
         .await?;
+    }
+    Ok(())
+}
 
-        tracing::debug!("Uploaded file {} to {}", file_path, selector);
+/// Drain CDP events on a background task. The returned flag is cleared
+/// once the connection errors or closes.
+fn spawn_handler(mut handler: Handler) -> Arc<AtomicBool> {
+    let connected = Arc::new(AtomicBool::new(true));
+    let flag = connected.clone();
+
+    tokio::spawn(async move {
+        while let Some(event) = handler.next().await {
+            if let Err(CdpError::Ws(e)) = event {
+                tracing::warn!("Chrome connection failed: {}", e);
+                flag.store(false, Ordering::Relaxed);
+            }
+        }
+        flag.store(false, Ordering::Relaxed);
+    });
 
-        Ok(())
+    connected
+}
+
+/// Where `--connect` reaches Chrome.
+#[derive(Debug, PartialEq)]
+pub enum ConnectEndpoint {
+    /// HTTP debugging endpoint; the WebSocket URL comes from `/json/version`
+    Http(String),
+    /// DevTools WebSocket URL, used as-is
+    WebSocket(String),
+}
+
+impl ConnectEndpoint {
+    pub fn url(&self) -> &str {
+        match self {
+            Self::Http(url) | Self::WebSocket(url) => url,
+        }
     }
+}
 
-    /// Health check - verify browser is responsive.
-    pub async fn health_check(&self) -> Result<bool> {
-        let _version = self.browser.version().await?;
-        Ok(true)
+/// Classify a `--connect` URL by scheme. A bare `host:port` means HTTP.
+pub fn parse_connect_endpoint(url: &str) -> Result<ConnectEndpoint> {
+    let url = url.trim();
+    let Some((scheme, _)) = url.split_once("://") else {
+        return Ok(ConnectEndpoint::Http(format!("http://{}", url)));
+    };
+
+    match scheme.to_ascii_lowercase().as_str() {
+        "http" => Ok(ConnectEndpoint::Http(url.to_string())),
+        "ws" => Ok(ConnectEndpoint::WebSocket(url.to_string())),
+        // chromiumoxide is built without TLS support
+        "https" | "wss" => anyhow::bail!(
+            "TLS endpoints are not supported ({}): use http:// or ws://, \
+             e.g. through an SSH tunnel or port forward",
+            url
+        ),
+        other => anyhow::bail!(
+            "Unsupported --connect scheme '{}': expected http://host:port or ws://.../devtools/browser/<id>",
+            other
+        ),
     }
+}
 
-    /// Close the browser.
-    #[allow(dead_code)]
-    pub async fn close(mut self) -> Result<()> {
-        // Dispose all non-default sessions
-        let sessions = self.sessions.read().await;
-        for (id, session) in sessions.iter() {
-            if id != &self.default_session_id {
-                if let Some(ref context_id) = session.context_id {
-                    let _ = self
-                        .browser
-                        .dispose_browser_context(context_id.clone())
-                        .await;
-                }
+/// Environment variables that override Chrome discovery, in priority order.
+const CHROME_PATH_VARS: &[&str] = &["FGP_CHROME", "CHROME_PATH"];
+
+/// Chrome set by `FGP_CHROME` or `CHROME_PATH`, if any.
+pub(super) fn chrome_path_override() -> Result<Option<PathBuf>> {
+    for var in CHROME_PATH_VARS {
+        if let Some(path) = std::env::var_os(var).filter(|v| !v.is_empty()) {
+            let path = PathBuf::from(path);
+            if !path.exists() {
+                anyhow::bail!("{} points to {:?}, which does not exist", var, path);
             }
+            tracing::info!("Using Chrome from {}: {:?}", var, path);
+            return Ok(Some(path));
         }
-        drop(sessions);
-
-        self.browser.close().await?;
-        Ok(())
     }
+    Ok(None)
+}
 
-    /// Find Chrome executable on the system.
-    fn find_chrome_executable() -> Result<PathBuf> {
-        let (subdir_name, alt_subdir) = if cfg!(target_os = "macos") {
-            if cfg!(target_arch = "aarch64") {
-                (
-                    "chrome-headless-shell-mac-arm64",
-                    "chrome-headless-shell-mac-x64",
-                )
-            } else {
-                (
-                    "chrome-headless-shell-mac-x64",
-                    "chrome-headless-shell-mac-arm64",
-                )
-            }
+/// Newest chrome-headless-shell in the Playwright browser cache, if any.
+fn find_headless_shell() -> Option<PathBuf> {
+    let (subdirs, binary_name): (&[&str], &str) = if cfg!(windows) {
+        (&["chrome-headless-shell-win64"], "chrome-headless-shell.exe")
+    } else if cfg!(target_os = "macos") {
+        if cfg!(target_arch = "aarch64") {
+            (
+                &["chrome-headless-shell-mac-arm64", "chrome-headless-shell-mac-x64"],
+                "chrome-headless-shell",
+            )
         } else {
-            ("chrome-headless-shell-linux", "chrome-headless-shell-linux")
-        };
-
-        if let Some(home) = dirs::home_dir() {
-            let playwright_cache = home.join("Library/Caches/ms-playwright");
-            if playwright_cache.exists() {
-                if let Ok(entries) = std::fs::read_dir(&playwright_cache) {
-                    let mut headless_dirs: Vec<_> = entries
-                        .filter_map(|e| e.ok())
-                        .filter(|e| {
-                            e.file_name()
-                                .to_string_lossy()
-                                .starts_with("chromium_headless_shell")
-                        })
-                        .collect();
-
-                    headless_dirs.sort_by_key(|b| std::cmp::Reverse(b.file_name()));
-
-                    for dir in headless_dirs {
-                        let binary = dir.path().join(subdir_name).join("chrome-headless-shell");
-
-                        if binary.exists() {
-                            tracing::info!("Using chrome-headless-shell at: {:?}", binary);
-                            return Ok(binary);
-                        }
+            (
+                &["chrome-headless-shell-mac-x64", "chrome-headless-shell-mac-arm64"],
+                "chrome-headless-shell",
+            )
+        }
+    } else {
+        (
+            &["chrome-headless-shell-linux64", "chrome-headless-shell-linux"],
+            "chrome-headless-shell",
+        )
+    };
+
+    // ~/Library/Caches, ~/.cache or %LOCALAPPDATA%
+    let playwright_cache = dirs::cache_dir()?.join("ms-playwright");
+    let mut headless_dirs: Vec<_> = std::fs::read_dir(&playwright_cache)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            e.file_name()
+                .to_string_lossy()
+                .starts_with("chromium_headless_shell")
+        })
+        .collect();
 
-                        let alt_binary = dir.path().join(alt_subdir).join("chrome-headless-shell");
+    headless_dirs.sort_by_key(|b| std::cmp::Reverse(b.file_name()));
 
-                        if alt_binary.exists() {
-                            tracing::info!("Using chrome-headless-shell at: {:?}", alt_binary);
-                            return Ok(alt_binary);
-                        }
-                    }
-                }
-            }
-        }
+    headless_dirs.iter().find_map(|dir| {
+        subdirs
+            .iter()
+            .map(|subdir| dir.path().join(subdir).join(binary_name))
+            .find(|binary| binary.exists())
+    })
+}
 
-        let paths = [
+/// Well-known install locations of Chrome, Chromium and Edge on this platform.
+pub(super) fn system_browser_paths() -> Vec<PathBuf> {
+    if cfg!(windows) {
+        let roots = ["ProgramFiles", "ProgramFiles(x86)", "LOCALAPPDATA"]
+            .iter()
+            .filter_map(std::env::var_os)
+            .map(PathBuf::from)
+            .collect::<Vec<_>>();
+        // Chrome first; Edge is Chromium-based and preinstalled on Windows
+        [
+            r"Google\Chrome\Application\chrome.exe",
+            r"Microsoft\Edge\Application\msedge.exe",
+        ]
+        .iter()
+        .flat_map(|suffix| roots.iter().map(move |root| root.join(suffix)))
+        .collect()
+    } else {
+        [
             "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
             "/Applications/Chromium.app/Contents/MacOS/Chromium",
             "/Applications/Google Chrome Canary.app/Contents/MacOS/Google Chrome Canary",
@@ -840,17 +5030,69 @@ impl BrowserClient {
             "/usr/bin/google-chrome-stable",
             "/usr/bin/chromium",
             "/usr/bin/chromium-browser",
-        ];
+            "/snap/bin/chromium",
+            "/usr/bin/microsoft-edge",
+            "/usr/bin/microsoft-edge-stable",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .collect()
+    }
+}
 
-        for path in &paths {
-            let p = PathBuf::from(path);
-            if p.exists() {
-                tracing::info!("Found Chrome at: {}", path);
-                return Ok(p);
+/// How to install a browser on this platform.
+fn install_hint() -> &'static str {
+    if cfg!(windows) {
+        "Install Google Chrome (https://www.google.com/chrome/) or run `npx playwright install chromium`, or set FGP_CHROME to chrome.exe."
+    } else if cfg!(target_os = "macos") {
+        "Install Playwright (npx playwright install chromium) for best performance, or Google Chrome, or set FGP_CHROME."
+    } else {
+        "Install Playwright (npx playwright install chromium) for best performance, or your distro's chromium package, or set FGP_CHROME."
+    }
+}
+
+/// Install locations of a channel's browser binary on this platform.
+fn channel_executable_paths(channel: BrowserChannel) -> &'static [&'static str] {
+    if cfg!(target_os = "macos") {
+        match channel {
+            BrowserChannel::Chrome => {
+                &["/Applications/Google Chrome.app/Contents/MacOS/Google Chrome"]
+            }
+            BrowserChannel::ChromeBeta => {
+                &["/Applications/Google Chrome Beta.app/Contents/MacOS/Google Chrome Beta"]
             }
+            BrowserChannel::ChromeDev => {
+                &["/Applications/Google Chrome Dev.app/Contents/MacOS/Google Chrome Dev"]
+            }
+            BrowserChannel::ChromeCanary => {
+                &["/Applications/Google Chrome Canary.app/Contents/MacOS/Google Chrome Canary"]
+            }
+            BrowserChannel::Chromium => &["/Applications/Chromium.app/Contents/MacOS/Chromium"],
+        }
+    } else {
+        match channel {
+            BrowserChannel::Chrome => &[
+                "/usr/bin/google-chrome",
+                "/usr/bin/google-chrome-stable",
+                "/opt/google/chrome/chrome",
+            ],
+            BrowserChannel::ChromeBeta => {
+                &["/usr/bin/google-chrome-beta", "/opt/google/chrome-beta/chrome"]
+            }
+            BrowserChannel::ChromeDev => &[
+                "/usr/bin/google-chrome-unstable",
+                "/opt/google/chrome-unstable/chrome",
+            ],
+            BrowserChannel::ChromeCanary => &[
+                "/usr/bin/google-chrome-canary",
+                "/opt/google/chrome-canary/chrome",
+            ],
+            BrowserChannel::Chromium => &[
+                "/usr/bin/chromium",
+                "/usr/bin/chromium-browser",
+                "/snap/bin/chromium",
+            ],
         }
-
-        anyhow::bail!("Chrome/Chromium not found. Install Playwright (npx playwright install chromium) for best performance.")
     }
 }
 
@@ -867,3 +5109,640 @@ fn resolve_selector(selector: &str) -> String {
 fn count_nodes(nodes: &[crate::models::AriaNode]) -> usize {
     nodes.iter().map(|n| 1 + count_nodes(&n.children)).sum()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick_tab() {
+        let tab = |url: &str| TabInfo {
+            target_id: url.to_string(),
+            url: url.to_string(),
+            title: String::new(),
+            active: false,
+            session_id: None,
+        };
+        let tabs = vec![tab("https://mail.example.com/inbox"), tab("https://app.example.com/")];
+
+        assert_eq!(
+            pick_tab(&tabs, Some("app."), None).unwrap().url,
+            "https://app.example.com/"
+        );
+        assert_eq!(
+            pick_tab(&tabs, None, Some(0)).unwrap().url,
+            "https://mail.example.com/inbox"
+        );
+        assert!(pick_tab(&tabs, Some("bank"), None).is_err());
+        assert!(pick_tab(&tabs, None, Some(2)).is_err());
+        assert!(pick_tab(&tabs, None, None).is_err());
+    }
+
+    #[test]
+    fn test_match_tab() {
+        let tab = |id: &str, url: &str, title: &str, session: Option<&str>| TabInfo {
+            target_id: id.to_string(),
+            url: url.to_string(),
+            title: title.to_string(),
+            active: false,
+            session_id: session.map(str::to_string),
+        };
+        let tabs = vec![
+            tab("A1", "https://mail.example.com/", "Inbox", Some("default")),
+            tab("B2", "https://mail.example.com/u/1", "Inbox (2)", None),
+            tab("C3", "https://app.example.com/", "Dashboard", None),
+        ];
+
+        let matched = |query| match_tab(&tabs, &query).unwrap().target_id.as_str();
+
+        // Driven tabs are skipped for URL and title matches
+        assert_eq!(matched(TabQuery::Url("mail.")), "B2");
+        assert_eq!(matched(TabQuery::Title("dash")), "C3");
+        assert_eq!(matched(TabQuery::TargetId("A1")), "A1");
+        assert!(match_tab(&tabs, &TabQuery::Url("bank")).is_err());
+        assert!(match_tab(&tabs, &TabQuery::TargetId("Z9")).is_err());
+    }
+
+    #[test]
+    fn test_parse_connect_endpoint() {
+        assert_eq!(
+            parse_connect_endpoint("http://localhost:9222").unwrap(),
+            ConnectEndpoint::Http("http://localhost:9222".to_string())
+        );
+        assert_eq!(
+            parse_connect_endpoint("localhost:9222").unwrap(),
+            ConnectEndpoint::Http("http://localhost:9222".to_string())
+        );
+        assert_eq!(
+            parse_connect_endpoint("ws://chrome:9222/devtools/browser/abc").unwrap(),
+            ConnectEndpoint::WebSocket("ws://chrome:9222/devtools/browser/abc".to_string())
+        );
+        assert!(parse_connect_endpoint("wss://chrome.example.com/devtools/browser/abc").is_err());
+        assert!(parse_connect_endpoint("ftp://localhost:9222").is_err());
+    }
+
+    #[test]
+    fn test_modifier_flags() {
+        assert_eq!(modifier_flags(&[]).unwrap(), 0);
+        assert_eq!(modifier_flags(&["Ctrl", "shift", "cmd"]).unwrap(), 11);
+
+        let error = modifier_flags(&["shift", "ctl"]).unwrap_err().to_string();
+        assert!(error.starts_with("INVALID_MODIFIER"), "{}", error);
+        assert!(error.contains("'ctl'"), "{}", error);
+    }
+
+    #[test]
+    fn test_drag_path() {
+        let point = |x, y| Point { x, y };
+
+        let path = drag_path(point(0.0, 0.0), &[], point(100.0, 50.0), 4);
+        assert_eq!(path.len(), 4);
+        assert_eq!(path[0], point(25.0, 12.5));
+        assert_eq!(path[3], point(100.0, 50.0));
+
+        // Waypoints are visited in order, ending on the target
+        let waypoints = [point(0.0, 100.0)];
+        let path = drag_path(point(0.0, 0.0), &waypoints, point(100.0, 100.0), 2);
+        assert_eq!(
+            path,
+            vec![
+                point(0.0, 50.0),
+                point(0.0, 100.0),
+                point(50.0, 100.0),
+                point(100.0, 100.0)
+            ]
+        );
+    }
+
+    /// Run `test` on a headless client with a fresh profile, then shut
+    /// Chrome down and remove the profile, also when the test fails.
+    fn with_test_client<F, Fut>(name: &str, test: F)
+    where
+        F: FnOnce(Arc<BrowserClient>) -> Fut,
+        Fut: std::future::Future<Output = ()>,
+    {
+        let user_data_dir =
+            std::env::temp_dir().join(format!("fgp-{}-test-{}", name, std::process::id()));
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let outcome = runtime.block_on(async {
+            let viewport = ViewportSize::default();
+            let client = BrowserClient::new(user_data_dir.clone(), true, viewport, None).await?;
+            let client = Arc::new(client);
+            let outcome = std::panic::AssertUnwindSafe(test(client.clone()))
+                .catch_unwind()
+                .await;
+            client.dispose().await;
+            Ok::<_, anyhow::Error>(outcome)
+        });
+        std::fs::remove_dir_all(&user_data_dir).ok();
+        match outcome {
+            Ok(Ok(())) => {}
+            Ok(Err(panic)) => std::panic::resume_unwind(panic),
+            Err(e) => panic!("Failed to launch Chrome: {:#}", e),
+        }
+    }
+
+    #[test]
+    #[ignore = "launches Chrome"]
+    fn test_mouse_click_at_coordinates() {
+        with_test_client("mouse", |client| async move {
+            client
+                .evaluate(
+                    r#"document.body.innerHTML = '<div id="target" style="position: fixed; left: 100px; top: 100px; width: 100px; height: 50px"></div>';
+                    window.hits = [];
+                    target.addEventListener('click', e => hits.push([e.clientX, e.clientY, e.button]));"#,
+                    None,
+                )
+                .await
+                .unwrap();
+
+            client
+                .mouse_click(150.0, 125.0, MouseButton::Left, None)
+                .await
+                .unwrap();
+            // Outside the element: no handler call
+            client
+                .mouse_click(10.0, 10.0, MouseButton::Left, None)
+                .await
+                .unwrap();
+
+            let hits = client.evaluate("hits", None).await.unwrap();
+            assert_eq!(hits, serde_json::json!([[150, 125, 0]]));
+        });
+    }
+
+    #[test]
+    #[ignore = "launches Chrome"]
+    fn test_screenshot_element() {
+        with_test_client("element-shot", |client| async move {
+            // Below the fold, so it has to be scrolled to
+            client
+                .evaluate(
+                    r#"document.body.innerHTML = '<div style="height: 3000px"></div><div id="box" style="width: 120px; height: 45px; background: red"></div>'"#,
+                    None,
+                )
+                .await
+                .unwrap();
+
+            let shot = client
+                .screenshot_element("#box", None, ScreenshotFormat::Png, None)
+                .await
+                .unwrap();
+            assert_eq!((shot.width, shot.height), (120, 45));
+            assert!(shot.data.is_some());
+
+            let missing = client
+                .screenshot_element("#nope", None, ScreenshotFormat::Png, None)
+                .await;
+            assert!(missing
+                .unwrap_err()
+                .to_string()
+                .starts_with("ELEMENT_NOT_FOUND"));
+        });
+    }
+
+    #[test]
+    #[ignore = "launches Chrome"]
+    fn test_check_states() {
+        with_test_client("check", |client| async move {
+            client
+                .evaluate(
+                    r#"document.body.innerHTML = '<input type="checkbox" id="mixed"><input type="checkbox" id="off" disabled><input type="checkbox" id="stubborn">';
+                    mixed.indeterminate = true;
+                    stubborn.addEventListener('click', e => e.preventDefault());"#,
+                    None,
+                )
+                .await
+                .unwrap();
+
+            assert!(client.check("#mixed", true, None, None).await.unwrap());
+            let indeterminate = client.evaluate("mixed.indeterminate", None).await.unwrap();
+            assert_eq!(indeterminate, serde_json::json!(false));
+
+            let error = client.check("#off", true, None, None).await.unwrap_err();
+            assert!(error.to_string().starts_with("ELEMENT_DISABLED"));
+
+            // The page cancels clicks; the state is set anyway
+            assert!(client.check("#stubborn", true, None, None).await.unwrap());
+            assert!(!client.check("#stubborn", false, None, None).await.unwrap());
+        });
+    }
+
+    #[test]
+    #[ignore = "launches Chrome"]
+    fn test_emulation_stays_on_own_tab() {
+        with_test_client("own-tab", |client| async move {
+            client
+                .evaluate("window.open('about:blank'); 1", None)
+                .await
+                .unwrap();
+            for _ in 0..50 {
+                if client.list_pages(None).await.unwrap().pages.len() == 2 {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            client.switch_page(1, None).await.unwrap();
+
+            // Set while the popup is active
+            let dark = MediaEmulation {
+                color_scheme: Some("dark".to_string()),
+                ..Default::default()
+            };
+            client.emulate_media(Some(dark), None).await.unwrap();
+
+            let is_dark = "matchMedia('(prefers-color-scheme: dark)').matches";
+            let on_popup = client.evaluate(is_dark, None).await.unwrap();
+            assert_eq!(on_popup, serde_json::json!(false));
+            client.switch_page(0, None).await.unwrap();
+            let on_own_tab = client.evaluate(is_dark, None).await.unwrap();
+            assert_eq!(on_own_tab, serde_json::json!(true));
+        });
+    }
+
+    #[test]
+    #[ignore = "launches Chrome"]
+    fn test_upload_several_files() {
+        let dir = std::env::temp_dir().join(format!("fgp-upload-files-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let files: Vec<String> = ["a.txt", "b.txt"]
+            .iter()
+            .map(|name| {
+                let path = dir.join(name);
+                std::fs::write(&path, name).unwrap();
+                path.to_string_lossy().to_string()
+            })
+            .collect();
+
+        with_test_client("upload", |client| async move {
+            client
+                .evaluate(
+                    r#"document.body.innerHTML = '<input type="file" id="one"><input type="file" id="many" multiple>'"#,
+                    None,
+                )
+                .await
+                .unwrap();
+
+            let names = client.upload("#many", &files, None).await.unwrap();
+            assert_eq!(names, vec!["a.txt", "b.txt"]);
+            let count = client.evaluate("many.files.length", None).await.unwrap();
+            assert_eq!(count, serde_json::json!(2));
+
+            let error = client.upload("#one", &files, None).await.unwrap_err();
+            assert!(error.to_string().starts_with("MULTIPLE_NOT_ALLOWED"));
+            assert!(client.upload("#one", &files[..1], None).await.is_ok());
+        });
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    #[ignore = "launches Chrome"]
+    fn test_select_by_label() {
+        async fn select(
+            client: &BrowserClient,
+            values: &[&str],
+            add: bool,
+        ) -> Result<Vec<SelectedOption>> {
+            let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+            client.select("#country", &values, add, None, None).await
+        }
+
+        with_test_client("select", |client| async move {
+            client
+                .evaluate(
+                    r#"document.body.innerHTML = '<select id="country"><option value="us">United States</option><option value="ca"> Canada </option></select>'"#,
+                    None,
+                )
+                .await
+                .unwrap();
+
+            let selected = select(&client, &["ca"], false).await.unwrap();
+            assert_eq!(selected[0].label, "Canada");
+            let selected = select(&client, &["united states"], false).await.unwrap();
+            assert_eq!(selected[0].value, "us");
+            assert!(select(&client, &["us", "ca"], false).await.is_err());
+
+            // Multi-select: replace the selection, then add to it
+            client
+                .evaluate("document.querySelector('#country').multiple = true", None)
+                .await
+                .unwrap();
+            let selected = select(&client, &["us", "Canada"], false).await.unwrap();
+            assert_eq!(selected.len(), 2);
+            let selected = select(&client, &["ca"], false).await.unwrap();
+            assert_eq!(selected[0].value, "ca");
+            let selected = select(&client, &["us"], true).await.unwrap();
+            assert_eq!(selected.len(), 2);
+
+            let error = select(&client, &["Mexico"], false).await;
+            let error = format!("{:#}", error.unwrap_err());
+            assert!(error.starts_with("OPTION_NOT_FOUND"));
+            assert!(error.contains(r#""United States", "Canada""#));
+        });
+    }
+
+    /// Names of the cookies in a session's browser context.
+    async fn context_cookie_names(client: &BrowserClient, session_id: &str) -> Vec<String> {
+        use chromiumoxide::cdp::browser_protocol::storage::GetCookiesParams;
+
+        let context_id = client.get_context_id(Some(session_id)).await.unwrap();
+        let cookies = client
+            .browser
+            .execute(GetCookiesParams {
+                browser_context_id: context_id,
+            })
+            .await
+            .unwrap()
+            .result
+            .cookies;
+        cookies.into_iter().map(|c| c.name).collect()
+    }
+
+    async fn wait_for_pool(client: &BrowserClient, ready: usize) {
+        for _ in 0..100 {
+            if client.session_pool_stats().ready == ready {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        panic!("session pool never reached {} ready contexts", ready);
+    }
+
+    #[test]
+    #[ignore = "launches Chrome"]
+    fn test_navigate_reports_status() {
+        // Local server answering every request with a 404
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            use std::io::{BufRead, BufReader, Write};
+            for mut stream in listener.incoming().flatten() {
+                // Skip the request headers
+                let headers = BufReader::new(&stream).lines().map_while(Result::ok);
+                headers.take_while(|line| !line.is_empty()).for_each(drop);
+                stream
+                    .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 9\r\nConnection: close\r\n\r\nnot found")
+                    .ok();
+            }
+        });
+
+        with_test_client("status", |client| async move {
+            let url = format!("http://127.0.0.1:{}/missing", port);
+            let result = client.navigate(&url, None).await.unwrap();
+            assert_eq!(result.status, Some(404));
+
+            let result = client
+                .navigate("data:text/html,<p>hi</p>", None)
+                .await
+                .unwrap();
+            assert_eq!(result.status, None);
+        });
+    }
+
+    #[test]
+    #[ignore = "launches Chrome"]
+    fn test_scroll_to_bottom_exhausts_feed() {
+        // Loads three more screens of content, one per scroll to the bottom
+        let feed = "data:text/html,<body style='margin:0'><div style='height:2000px'></div>\
+            <script>let loaded = 0; addEventListener('scroll', () => {\
+            if (loaded < 3 && innerHeight + scrollY >= document.body.scrollHeight - 10) {\
+            loaded++; setTimeout(() => { const d = document.createElement('div');\
+            d.style.height = '2000px'; document.body.append(d); }, 200); } });</script>";
+
+        with_test_client("scroll", |client| async move {
+            let settle = Duration::from_secs(1);
+            let timeout = Duration::from_secs(30);
+
+            client.navigate(feed, None).await.unwrap();
+            let result = client
+                .scroll_to_bottom(50, settle, timeout, None)
+                .await
+                .unwrap();
+            assert_eq!((result.scrolls, result.height), (4, 8000));
+            assert!(result.reached_end);
+
+            client.navigate(feed, None).await.unwrap();
+            let result = client
+                .scroll_to_bottom(1, settle, timeout, None)
+                .await
+                .unwrap();
+            assert_eq!((result.scrolls, result.height), (1, 4000));
+            assert!(!result.reached_end);
+        });
+    }
+
+    #[test]
+    #[ignore = "launches Chrome"]
+    fn test_pooled_session_is_clean_after_close() {
+        with_test_client("pool", |client| async move {
+            client.set_session_pool(2);
+            wait_for_pool(&client, 2).await;
+
+            client.create_session("first", None, false).await.unwrap();
+            let cookie = SerializableCookie {
+                name: "token".to_string(),
+                value: "secret".to_string(),
+                domain: "example.com".to_string(),
+                path: "/".to_string(),
+                expires: None,
+                secure: false,
+                http_only: false,
+                same_site: None,
+            };
+            client.set_cookies(&[cookie], Some("first")).await.unwrap();
+            assert_eq!(context_cookie_names(&client, "first").await, vec!["token"]);
+
+            client.close_session("first").await.unwrap();
+            wait_for_pool(&client, 2).await;
+            client.create_session("second", None, false).await.unwrap();
+
+            assert_eq!(client.session_pool_stats().hits, 2);
+            assert!(context_cookie_names(&client, "second").await.is_empty());
+        });
+    }
+
+    #[test]
+    #[ignore = "launches Chrome"]
+    fn test_recycled_session_is_clean() {
+        // Local server answering every request with the same page
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            use std::io::{BufRead, BufReader, Write};
+            for mut stream in listener.incoming().flatten() {
+                let headers = BufReader::new(&stream).lines().map_while(Result::ok);
+                headers.take_while(|line| !line.is_empty()).for_each(drop);
+                stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: 9\r\nConnection: close\r\n\r\n<p>hi</p>")
+                    .ok();
+            }
+        });
+
+        with_test_client("recycle", |client| async move {
+            client.set_session_pool(1);
+            wait_for_pool(&client, 1).await;
+            client.create_session("first", None, false).await.unwrap();
+            wait_for_pool(&client, 1).await;
+            // Let the refill finish, then leave the pool one short
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            client.pool.take().unwrap();
+
+            let url = format!("http://127.0.0.1:{}/", port);
+            client.navigate(&url, Some("first")).await.unwrap();
+            client
+                .evaluate(
+                    "localStorage.setItem('token', 'secret'); document.cookie = 'sid=1'",
+                    Some("first"),
+                )
+                .await
+                .unwrap();
+            assert_eq!(context_cookie_names(&client, "first").await, vec!["sid"]);
+            let context = client.get_context_id(Some("first")).await.unwrap();
+
+            client.close_session("first").await.unwrap();
+            assert_eq!(client.session_pool_stats().recycled, 1);
+            client.create_session("second", None, false).await.unwrap();
+            assert_eq!(
+                client.get_context_id(Some("second")).await.unwrap(),
+                context
+            );
+
+            assert!(context_cookie_names(&client, "second").await.is_empty());
+            client.navigate(&url, Some("second")).await.unwrap();
+            let token = client
+                .evaluate("localStorage.getItem('token')", Some("second"))
+                .await
+                .unwrap();
+            assert_eq!(token, serde_json::Value::Null);
+        });
+    }
+
+    #[test]
+    #[ignore = "launches Chrome"]
+    fn test_session_limit() {
+        with_test_client("limit", |client| async move {
+            client.set_max_sessions(2);
+
+            client.create_session("old", None, false).await.unwrap();
+            client.create_session("recent", None, false).await.unwrap();
+            client.evaluate("1", Some("recent")).await.unwrap();
+
+            let err = client
+                .create_session("third", None, false)
+                .await
+                .unwrap_err()
+                .to_string();
+            assert!(err.starts_with("SESSION_LIMIT_REACHED"), "{}", err);
+            assert!(err.contains("'old'"), "{}", err);
+
+            client.create_session("third", None, true).await.unwrap();
+            let sessions = client.list_sessions().await;
+            assert!(!sessions.iter().any(|s| s.id == "old"));
+            assert!(sessions.iter().any(|s| s.id == "third"));
+        });
+    }
+
+    #[test]
+    #[ignore = "launches Chrome"]
+    fn test_auth_state_covers_several_origins() {
+        with_test_client("origins", |client| async move {
+            client.create_session("app", None, false).await.unwrap();
+
+            let saved: Vec<LocalStorageState> =
+                ["https://app.example.test", "https://auth.example.test"]
+                    .iter()
+                    .map(|origin| LocalStorageState {
+                        origin: origin.to_string(),
+                        items: HashMap::from([("token".to_string(), origin.to_string())]),
+                    })
+                    .collect();
+            let written = client
+                .load_local_storage(&saved, Some("app"))
+                .await
+                .unwrap();
+            assert_eq!(written.len(), 2);
+            // Written without leaving the session's page
+            let page = client.get_page(Some("app")).await.unwrap();
+            assert_eq!(page.url().await.unwrap().as_deref(), Some("about:blank"));
+
+            let origins = ["https://auth.example.test".to_string()];
+            let state = client
+                .capture_auth_state(Some(&origins), Some("app"))
+                .await
+                .unwrap();
+            assert_eq!(state.local_storage.len(), 1);
+            assert_eq!(
+                state.local_storage[0].items["token"],
+                "https://auth.example.test"
+            );
+        });
+    }
+
+    #[test]
+    #[ignore = "launches Chrome"]
+    fn test_indexeddb_records() {
+        with_test_client("indexeddb", |client| async move {
+            // IndexedDB needs a real origin
+            client
+                .network_mock(
+                    "https://app.example.test/*".to_string(),
+                    200,
+                    HashMap::from([("Content-Type".to_string(), "text/html".to_string())]),
+                    b"<html></html>".to_vec(),
+                    None,
+                )
+                .await
+                .unwrap();
+            client
+                .navigate("https://app.example.test/", None)
+                .await
+                .unwrap();
+            client
+                .evaluate(
+                    r#"new Promise((resolve) => {
+                        const request = indexedDB.open('app');
+                        request.onupgradeneeded = () => {
+                            const tokens = request.result.createObjectStore('tokens');
+                            tokens.put({ access: 'abc', bytes: new Uint8Array([1, 2]) }, 'user-1');
+                            tokens.put('def', 'user-2');
+                            request.result.createObjectStore('cache', { keyPath: 'id' });
+                        };
+                        request.onsuccess = () => { request.result.close(); resolve(); };
+                    })"#,
+                    None,
+                )
+                .await
+                .unwrap();
+
+            let mut stores = client.list_indexeddb_stores("app", None).await.unwrap();
+            stores.sort();
+            assert_eq!(stores, vec!["cache", "tokens"]);
+
+            let records = client.get_indexeddb("app", "tokens", None).await.unwrap();
+            assert_eq!(
+                records,
+                vec![
+                    serde_json::json!({"key": "user-1", "value": {"access": "abc", "bytes": [1, 2]}}),
+                    serde_json::json!({"key": "user-2", "value": "def"}),
+                ]
+            );
+
+            let error = client.get_indexeddb("app", "nope", None).await.unwrap_err();
+            assert!(error.to_string().starts_with("STORE_NOT_FOUND"));
+            let error = client
+                .list_indexeddb_stores("other", None)
+                .await
+                .unwrap_err();
+            assert!(error.to_string().starts_with("DATABASE_NOT_FOUND"));
+            // Looking didn't create it
+            let names = client
+                .evaluate(
+                    "indexedDB.databases().then((dbs) => dbs.map((db) => db.name))",
+                    None,
+                )
+                .await
+                .unwrap();
+            assert_eq!(names, serde_json::json!(["app"]));
+        });
+    }
+}
@@ -0,0 +1,221 @@
+//! Console message capture.
+//!
+//! Every session gets a background task that records `Runtime.consoleAPICalled`
+//! and `Log.entryAdded` events into a bounded ring buffer, so page errors can
+//! be inspected after the fact with `browser.console.get`.
+
+use anyhow::{Context, Result};
+use chromiumoxide::cdp::browser_protocol::log::EventEntryAdded;
+use chromiumoxide::cdp::js_protocol::runtime::{EventConsoleApiCalled, RemoteObject};
+use chromiumoxide::page::Page;
+use chrono::{TimeZone, Utc};
+use futures::stream;
+use futures::StreamExt;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::models::ConsoleEntry;
+
+/// Number of entries kept per session unless configured otherwise.
+pub const DEFAULT_CONSOLE_BUFFER: usize = 1000;
+
+/// Upper bound on the configurable buffer size.
+pub const MAX_CONSOLE_BUFFER: usize = 10_000;
+
+/// Per-session ring buffer of recent console messages.
+#[derive(Debug)]
+pub struct ConsoleState {
+    entries: VecDeque<ConsoleEntry>,
+    capacity: usize,
+}
+
+impl Default for ConsoleState {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CONSOLE_BUFFER)
+    }
+}
+
+impl ConsoleState {
+    pub fn with_capacity(capacity: usize) -> Self {
+        let capacity = clamp_capacity(capacity);
+        Self {
+            entries: VecDeque::with_capacity(capacity.min(DEFAULT_CONSOLE_BUFFER)),
+            capacity,
+        }
+    }
+
+    /// Change the buffer size, dropping the oldest entries if it shrinks.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = clamp_capacity(capacity);
+        while self.entries.len() > self.capacity {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Buffered entries, oldest first, optionally filtered by level and
+    /// limited to the most recent `limit`.
+    pub fn list(&self, level: Option<&str>, limit: Option<usize>) -> Vec<ConsoleEntry> {
+        let matched: Vec<ConsoleEntry> = self
+            .entries
+            .iter()
+            .filter(|e| level.is_none_or(|l| e.level.eq_ignore_ascii_case(l)))
+            .cloned()
+            .collect();
+
+        match limit {
+            Some(limit) if matched.len() > limit => matched[matched.len() - limit..].to_vec(),
+            _ => matched,
+        }
+    }
+
+    /// Drop all buffered entries, returning how many were removed.
+    pub fn clear(&mut self) -> usize {
+        let count = self.entries.len();
+        self.entries.clear();
+        count
+    }
+
+    fn push(&mut self, entry: ConsoleEntry) {
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+}
+
+fn clamp_capacity(capacity: usize) -> usize {
+    capacity.clamp(1, MAX_CONSOLE_BUFFER)
+}
+
+enum ConsoleEvent {
+    Api(Arc<EventConsoleApiCalled>),
+    Log(Arc<EventEntryAdded>),
+}
+
+/// Subscribe to console and log events on `page` and buffer them.
+pub async fn spawn_console_listener(
+    page: &Page,
+    capacity: usize,
+) -> Result<Arc<RwLock<ConsoleState>>> {
+    let state = Arc::new(RwLock::new(ConsoleState::with_capacity(capacity)));
+    watch_console(page, &state).await?;
+    Ok(state)
+}
+
+/// Buffer console messages from another of a session's pages into its
+/// existing state.
+pub async fn watch_console(page: &Page, state: &Arc<RwLock<ConsoleState>>) -> Result<()> {
+    // Runtime and Log domains are enabled by chromiumoxide for every page
+    let api = page
+        .event_listener::<EventConsoleApiCalled>()
+        .await
+        .context("Failed to subscribe to console events")?
+        .map(ConsoleEvent::Api);
+    let log = page
+        .event_listener::<EventEntryAdded>()
+        .await
+        .context("Failed to subscribe to log events")?
+        .map(ConsoleEvent::Log);
+    let mut events = stream::select(api, log);
+
+    let task_state = state.clone();
+    tokio::spawn(async move {
+        while let Some(event) = events.next().await {
+            let entry = match event {
+                ConsoleEvent::Api(e) => ConsoleEntry {
+                    level: e.r#type.as_ref().to_string(),
+                    text: e.args.iter().map(format_arg).collect::<Vec<_>>().join(" "),
+                    source: "console".to_string(),
+                    url: e
+                        .stack_trace
+                        .as_ref()
+                        .and_then(|t| t.call_frames.first())
+                        .map(|f| f.url.clone())
+                        .filter(|u| !u.is_empty()),
+                    timestamp: format_timestamp(*e.timestamp.inner()),
+                },
+                ConsoleEvent::Log(e) => ConsoleEntry {
+                    level: e.entry.level.as_ref().to_string(),
+                    text: e.entry.text.clone(),
+                    source: e.entry.source.as_ref().to_string(),
+                    url: e.entry.url.clone(),
+                    timestamp: format_timestamp(*e.entry.timestamp.inner()),
+                },
+            };
+            task_state.write().await.push(entry);
+        }
+    });
+
+    Ok(())
+}
+
+/// Render a console argument roughly the way DevTools prints it.
+fn format_arg(arg: &RemoteObject) -> String {
+    match &arg.value {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(value) => value.to_string(),
+        None => arg
+            .unserializable_value
+            .as_ref()
+            .map(|v| v.inner().clone())
+            .or_else(|| arg.description.clone())
+            .unwrap_or_else(|| arg.r#type.as_ref().to_string()),
+    }
+}
+
+/// CDP console timestamps are milliseconds since the epoch.
+fn format_timestamp(millis: f64) -> String {
+    Utc.timestamp_millis_opt(millis as i64)
+        .single()
+        .unwrap_or_else(Utc::now)
+        .to_rfc3339()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(level: &str, text: &str) -> ConsoleEntry {
+        ConsoleEntry {
+            level: level.to_string(),
+            text: text.to_string(),
+            source: "console".to_string(),
+            url: None,
+            timestamp: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_console_buffer_is_bounded() {
+        let mut state = ConsoleState::with_capacity(3);
+        for i in 0..5 {
+            state.push(entry("log", &i.to_string()));
+        }
+
+        let texts: Vec<String> = state.list(None, None).into_iter().map(|e| e.text).collect();
+        assert_eq!(texts, vec!["2", "3", "4"]);
+
+        state.set_capacity(1);
+        assert_eq!(state.list(None, None)[0].text, "4");
+
+        assert_eq!(ConsoleState::with_capacity(0).capacity, 1);
+        assert_eq!(
+            ConsoleState::with_capacity(usize::MAX).capacity,
+            MAX_CONSOLE_BUFFER
+        );
+    }
+
+    #[test]
+    fn test_console_list_filters() {
+        let mut state = ConsoleState::default();
+        state.push(entry("log", "a"));
+        state.push(entry("error", "b"));
+        state.push(entry("error", "c"));
+
+        assert_eq!(state.list(Some("ERROR"), None).len(), 2);
+        assert_eq!(state.list(Some("error"), Some(1))[0].text, "c");
+        assert_eq!(state.clear(), 3);
+        assert!(state.list(None, None).is_empty());
+    }
+}
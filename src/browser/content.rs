@@ -0,0 +1,218 @@
+//! Readability-style main content extraction rendered as Markdown.
+//!
+//! A heuristic script picks the element most likely to hold the article body
+//! (semantic `<article>`/`<main>` first, then paragraph density scoring),
+//! strips navigation/boilerplate and converts it to Markdown in the page.
+
+use anyhow::{Context, Result};
+use chromiumoxide::page::Page;
+use serde::Deserialize;
+
+use crate::models::ContentExtraction;
+
+/// Below this many words the extracted candidate is considered a failure.
+const MIN_CONTENT_WORDS: usize = 25;
+
+const EXTRACT_SCRIPT: &str = r#"(() => {
+    const NEGATIVE = /comment|footer|footnote|nav|sidebar|menu|share|social|promo|advert|sponsor|cookie|banner|related|newsletter|popup|modal|breadcrumb/i;
+    const POSITIVE = /article|content|main|post|entry|story|text|body/i;
+    const STRIP = 'script,style,noscript,template,iframe,svg,canvas,form,button,input,select,textarea,nav,footer,header,aside,[role=navigation],[role=banner],[role=contentinfo],[role=complementary],[aria-hidden=true],[hidden]';
+
+    const meta = (sel) => {
+        const el = document.querySelector(sel);
+        return el ? (el.getAttribute('content') || el.textContent || '').trim() : '';
+    };
+    const h1 = document.querySelector('h1');
+    const title = meta('meta[property="og:title"]') || document.title || (h1 ? h1.textContent.trim() : '');
+    const byline = meta('meta[name="author"]') || meta('[rel="author"]') || meta('[itemprop="author"]') || meta('.byline') || null;
+
+    const classId = (el) => (el.className && typeof el.className === 'string' ? el.className : '') + ' ' + (el.id || '');
+
+    const pickCandidate = () => {
+        const semantic = document.querySelector('article') || document.querySelector('main, [role=main]');
+        if (semantic && (semantic.textContent || '').trim().length > 200) return semantic;
+        const scores = new Map();
+        for (const p of document.querySelectorAll('p, pre, td')) {
+            const text = (p.textContent || '').trim();
+            if (text.length < 25) continue;
+            const score = 1 + text.split(',').length + Math.min(Math.floor(text.length / 100), 3);
+            const parent = p.parentElement;
+            if (!parent) continue;
+            scores.set(parent, (scores.get(parent) || 0) + score);
+            if (parent.parentElement) {
+                scores.set(parent.parentElement, (scores.get(parent.parentElement) || 0) + score / 2);
+            }
+        }
+        let best = null;
+        let bestScore = 0;
+        for (const [el, raw] of scores) {
+            let score = raw;
+            const ci = classId(el);
+            if (NEGATIVE.test(ci)) score *= 0.5;
+            if (POSITIVE.test(ci)) score *= 1.25;
+            if (score > bestScore) { best = el; bestScore = score; }
+        }
+        return best;
+    };
+
+    const clean = (root) => {
+        const clone = root.cloneNode(true);
+        for (const el of clone.querySelectorAll(STRIP)) el.remove();
+        for (const el of Array.from(clone.querySelectorAll('*'))) {
+            const ci = classId(el);
+            if (NEGATIVE.test(ci) && !POSITIVE.test(ci)) el.remove();
+        }
+        return clone;
+    };
+
+    const inline = (s) => s.replace(/\s+/g, ' ');
+    const toMarkdown = (node, ctx) => {
+        if (node.nodeType === Node.TEXT_NODE) return ctx.pre ? node.textContent : inline(node.textContent);
+        if (node.nodeType !== Node.ELEMENT_NODE) return '';
+        const tag = node.tagName.toLowerCase();
+        const children = (c = ctx) => Array.from(node.childNodes).map((n) => toMarkdown(n, c)).join('');
+        switch (tag) {
+            case 'h1': case 'h2': case 'h3': case 'h4': case 'h5': case 'h6':
+                return '\n\n' + '#'.repeat(Number(tag[1])) + ' ' + children().trim() + '\n\n';
+            case 'p': return '\n\n' + children().trim() + '\n\n';
+            case 'br': return '  \n';
+            case 'hr': return '\n\n---\n\n';
+            case 'strong': case 'b': { const t = children().trim(); return t ? '**' + t + '**' : ''; }
+            case 'em': case 'i': { const t = children().trim(); return t ? '*' + t + '*' : ''; }
+            case 'code': return ctx.pre ? children() : '`' + children().trim() + '`';
+            case 'pre': return '\n\n```\n' + children({ ...ctx, pre: true }).replace(/\n+$/, '') + '\n```\n\n';
+            case 'blockquote': return '\n\n' + children().trim().split('\n').map((l) => '> ' + l).join('\n') + '\n\n';
+            case 'a': {
+                const t = children().trim();
+                const href = node.getAttribute('href');
+                if (!href || href.startsWith('javascript:') || !t) return t;
+                try { return '[' + t + '](' + new URL(href, document.baseURI).href + ')'; } catch (_) { return t; }
+            }
+            case 'img': {
+                const src = node.getAttribute('src');
+                if (!src) return '';
+                try { return '![' + (node.getAttribute('alt') || '') + '](' + new URL(src, document.baseURI).href + ')'; } catch (_) { return ''; }
+            }
+            case 'ul': case 'ol': {
+                const depth = ctx.depth || 0;
+                const items = Array.from(node.children).filter((c) => c.tagName === 'LI');
+                const lines = items.map((li, i) => {
+                    const marker = tag === 'ol' ? (i + 1) + '. ' : '- ';
+                    const body = Array.from(li.childNodes).map((n) => toMarkdown(n, { ...ctx, depth: depth + 1 })).join('').trim();
+                    return '  '.repeat(depth) + marker + body;
+                });
+                return '\n\n' + lines.join('\n') + '\n\n';
+            }
+            default: return children();
+        }
+    };
+
+    const candidate = pickCandidate();
+    const cleaned = candidate ? clean(candidate) : null;
+    const markdown = cleaned ? toMarkdown(cleaned, {}).replace(/\n{3,}/g, '\n\n').trim() : '';
+    const text = cleaned ? (cleaned.textContent || '').replace(/\s+/g, ' ').trim() : '';
+    const bodyText = document.body ? (document.body.innerText || '').trim() : '';
+
+    return { title, byline, markdown, text, body_text: bodyText };
+})()"#;
+
+#[derive(Debug, Deserialize)]
+struct RawExtraction {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    byline: Option<String>,
+    #[serde(default)]
+    markdown: String,
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    body_text: String,
+}
+
+/// Extract the main content of the page as Markdown.
+///
+/// Falls back to the body's `innerText` (with `fallback: true`) when the
+/// heuristic cannot find a meaningful content block.
+pub async fn extract_main_content(page: &Page) -> Result<ContentExtraction> {
+    let raw: RawExtraction = page
+        .evaluate(EXTRACT_SCRIPT)
+        .await
+        .context("Failed to evaluate content extraction script")?
+        .into_value()
+        .context("Failed to parse content extraction result")?;
+
+    Ok(finalize(raw))
+}
+
+fn finalize(raw: RawExtraction) -> ContentExtraction {
+    let byline = raw.byline.filter(|b| !b.trim().is_empty());
+
+    if count_words(&raw.text) < MIN_CONTENT_WORDS {
+        tracing::debug!("Content extraction found no main content, falling back to body text");
+        return ContentExtraction {
+            title: raw.title,
+            byline,
+            word_count: count_words(&raw.body_text),
+            markdown: raw.body_text.clone(),
+            text: raw.body_text,
+            fallback: true,
+        };
+    }
+
+    ContentExtraction {
+        title: raw.title,
+        byline,
+        word_count: count_words(&raw.text),
+        markdown: raw.markdown,
+        text: raw.text,
+        fallback: false,
+    }
+}
+
+/// Count whitespace-separated words.
+fn count_words(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(text: &str, body_text: &str) -> RawExtraction {
+        RawExtraction {
+            title: "Title".to_string(),
+            byline: Some("  ".to_string()),
+            markdown: format!("# Title\n\n{}", text),
+            text: text.to_string(),
+            body_text: body_text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_count_words() {
+        assert_eq!(count_words(""), 0);
+        assert_eq!(count_words("  one   two\nthree\t"), 3);
+    }
+
+    #[test]
+    fn test_finalize_uses_main_content() {
+        let text = "word ".repeat(MIN_CONTENT_WORDS);
+        let result = finalize(raw(&text, "nav body footer"));
+
+        assert!(!result.fallback);
+        assert_eq!(result.word_count, MIN_CONTENT_WORDS);
+        assert!(result.markdown.starts_with("# Title"));
+        assert_eq!(result.byline, None);
+    }
+
+    #[test]
+    fn test_finalize_falls_back_to_body_text() {
+        let result = finalize(raw("too short", "Home About Contact"));
+
+        assert!(result.fallback);
+        assert_eq!(result.text, "Home About Contact");
+        assert_eq!(result.markdown, "Home About Contact");
+        assert_eq!(result.word_count, 3);
+    }
+}
@@ -0,0 +1,280 @@
+//! JavaScript dialog (alert/confirm/prompt/beforeunload) handling.
+//!
+//! Every page of a session gets a background task that answers
+//! `Page.javascriptDialogOpening` according to the session's policy, so clicks
+//! that open dialogs never deadlock.
+//! Dialogs are dismissed by default. Under the `manual` policy they stay open
+//! until the policy is switched to accept or dismiss, which answers the open
+//! dialog. `beforeunload` dialogs are always accepted so navigation can't stall.
+
+use anyhow::{Context, Result};
+use chromiumoxide::cdp::browser_protocol::page::{
+    DialogType, EventJavascriptDialogClosed, EventJavascriptDialogOpening,
+    HandleJavaScriptDialogParams,
+};
+use chromiumoxide::page::Page;
+use chrono::Utc;
+use futures::stream;
+use futures::StreamExt;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::models::{DialogAction, DialogPolicy, DialogRecord};
+
+/// Maximum number of handled dialogs kept per session.
+const MAX_DIALOG_HISTORY: usize = 100;
+
+/// Per-session dialog policy and history of handled dialogs.
+#[derive(Debug, Default)]
+pub struct DialogState {
+    pub policy: DialogPolicy,
+    pub history: VecDeque<DialogRecord>,
+    /// Page showing the dialog left open under the manual policy
+    open_on: Option<Page>,
+}
+
+impl DialogState {
+    fn record(&mut self, record: DialogRecord) {
+        if self.history.len() >= MAX_DIALOG_HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(record);
+    }
+
+    /// The dialog left open under the manual policy, if any.
+    fn pending_mut(&mut self) -> Option<&mut DialogRecord> {
+        self.history.iter_mut().rev().find(|r| r.pending)
+    }
+
+    /// Remove and return all records, keeping a dialog that is still open.
+    pub fn drain_answered(&mut self) -> Vec<DialogRecord> {
+        let records = self.history.iter().cloned().collect();
+        self.history.retain(|r| r.pending);
+        records
+    }
+}
+
+/// How to answer a new dialog: `Some(accept)`, or None to leave it open.
+fn decide(policy: &DialogPolicy, dialog_type: &DialogType) -> Option<bool> {
+    if *dialog_type == DialogType::Beforeunload {
+        return Some(true);
+    }
+    match policy.action {
+        DialogAction::Accept => Some(true),
+        DialogAction::Dismiss => Some(false),
+        DialogAction::Manual => None,
+    }
+}
+
+/// Answer the dialog open on `page`.
+async fn answer(page: &Page, accept: bool, prompt_text: Option<String>) -> Result<()> {
+    let mut params = HandleJavaScriptDialogParams::new(accept);
+    if accept {
+        params.prompt_text = prompt_text;
+    }
+    page.execute(params)
+        .await
+        .context("Failed to handle JavaScript dialog")?;
+    Ok(())
+}
+
+/// Answer a dialog left open by the manual policy, using the current policy.
+///
+/// Does nothing while the policy is still manual or no dialog is open.
+pub async fn answer_pending(state: &mut DialogState) -> Result<()> {
+    let policy = state.policy.clone();
+    let accept = match policy.action {
+        DialogAction::Accept => true,
+        DialogAction::Dismiss => false,
+        DialogAction::Manual => return Ok(()),
+    };
+    let Some(page) = state.open_on.clone() else {
+        return Ok(());
+    };
+    let Some(record) = state.pending_mut() else {
+        return Ok(());
+    };
+
+    let prompt_text = policy.prompt_text.or_else(|| record.default_prompt.clone());
+    answer(&page, accept, prompt_text).await?;
+
+    record.pending = false;
+    record.action = policy.action;
+    state.open_on = None;
+    Ok(())
+}
+
+enum DialogEvent {
+    Opening(Arc<EventJavascriptDialogOpening>),
+    Closed(Arc<EventJavascriptDialogClosed>),
+}
+
+/// Subscribe to dialog events on `page` and answer them using the shared policy.
+pub async fn spawn_dialog_handler(page: &Page) -> Result<Arc<RwLock<DialogState>>> {
+    let state = Arc::new(RwLock::new(DialogState::default()));
+    watch_dialogs(page, &state).await?;
+    Ok(state)
+}
+
+/// Answer dialogs on another of a session's pages using its existing state.
+pub async fn watch_dialogs(page: &Page, state: &Arc<RwLock<DialogState>>) -> Result<()> {
+    let opening = page
+        .event_listener::<EventJavascriptDialogOpening>()
+        .await
+        .context("Failed to subscribe to dialog events")?
+        .map(DialogEvent::Opening);
+    let closed = page
+        .event_listener::<EventJavascriptDialogClosed>()
+        .await
+        .context("Failed to subscribe to dialog events")?
+        .map(DialogEvent::Closed);
+    let mut events = stream::select(opening, closed);
+
+    let page = page.clone();
+    let task_state = state.clone();
+    tokio::spawn(async move {
+        while let Some(event) = events.next().await {
+            let event = match event {
+                DialogEvent::Opening(event) => event,
+                // A dialog left open was answered elsewhere (e.g. by the user)
+                DialogEvent::Closed(closed) => {
+                    let mut state = task_state.write().await;
+                    if state.open_on.as_ref().map(|p| p.target_id()) != Some(page.target_id()) {
+                        continue;
+                    }
+                    state.open_on = None;
+                    if let Some(record) = state.pending_mut() {
+                        record.pending = false;
+                        record.action = if closed.result {
+                            DialogAction::Accept
+                        } else {
+                            DialogAction::Dismiss
+                        };
+                    }
+                    continue;
+                }
+            };
+
+            let policy = task_state.read().await.policy.clone();
+            let decision = decide(&policy, &event.r#type);
+
+            if let Some(accept) = decision {
+                if !accept && policy.action == DialogAction::Dismiss {
+                    tracing::warn!(
+                        "Dismissed {} dialog: {:?}",
+                        event.r#type.as_ref(),
+                        event.message
+                    );
+                }
+                let prompt_text = policy
+                    .prompt_text
+                    .clone()
+                    .or_else(|| event.default_prompt.clone());
+                if let Err(e) = answer(&page, accept, prompt_text).await {
+                    tracing::warn!("{:#}", e);
+                }
+            }
+
+            let mut state = task_state.write().await;
+            if decision.is_none() {
+                state.open_on = Some(page.clone());
+            }
+            state.record(DialogRecord {
+                dialog_type: event.r#type.as_ref().to_string(),
+                message: event.message.clone(),
+                url: event.url.clone(),
+                default_prompt: event.default_prompt.clone(),
+                action: match decision {
+                    Some(true) => DialogAction::Accept,
+                    Some(false) => DialogAction::Dismiss,
+                    None => DialogAction::Manual,
+                },
+                pending: decision.is_none(),
+                timestamp: Utc::now().to_rfc3339(),
+            });
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(message: &str) -> DialogRecord {
+        DialogRecord {
+            dialog_type: "alert".to_string(),
+            message: message.to_string(),
+            url: "https://example.com".to_string(),
+            default_prompt: None,
+            action: DialogAction::Dismiss,
+            pending: false,
+            timestamp: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_dialog_history_is_bounded() {
+        let mut state = DialogState::default();
+        for i in 0..(MAX_DIALOG_HISTORY + 5) {
+            state.record(record(&i.to_string()));
+        }
+
+        assert_eq!(state.history.len(), MAX_DIALOG_HISTORY);
+        assert_eq!(state.history.front().unwrap().message, "5");
+    }
+
+    #[test]
+    fn test_default_policy_dismisses() {
+        let state = DialogState::default();
+        assert_eq!(state.policy.action, DialogAction::Dismiss);
+        assert!(state.policy.prompt_text.is_none());
+    }
+
+    #[test]
+    fn test_decide() {
+        let policy = |action| DialogPolicy {
+            action,
+            prompt_text: None,
+        };
+
+        assert_eq!(
+            decide(&policy(DialogAction::Accept), &DialogType::Confirm),
+            Some(true)
+        );
+        assert_eq!(
+            decide(&policy(DialogAction::Dismiss), &DialogType::Alert),
+            Some(false)
+        );
+        assert_eq!(
+            decide(&policy(DialogAction::Manual), &DialogType::Prompt),
+            None
+        );
+        // beforeunload never blocks navigation
+        assert_eq!(
+            decide(&policy(DialogAction::Manual), &DialogType::Beforeunload),
+            Some(true)
+        );
+        assert_eq!(
+            decide(&policy(DialogAction::Dismiss), &DialogType::Beforeunload),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_drain_keeps_pending_dialog() {
+        let mut state = DialogState::default();
+        state.record(record("answered"));
+        state.record(DialogRecord {
+            pending: true,
+            action: DialogAction::Manual,
+            ..record("open")
+        });
+
+        assert_eq!(state.drain_answered().len(), 2);
+        assert_eq!(state.history.len(), 1);
+        assert_eq!(state.pending_mut().unwrap().message, "open");
+    }
+}
@@ -0,0 +1,483 @@
+//! Download handling.
+//!
+//! Downloads are saved to a per-session directory under
+//! `~/.fgp/services/browser/downloads/<session>/`. Chrome writes each file
+//! under its download GUID (`allowAndName`); once `Browser.downloadProgress`
+//! reports completion, the file is renamed to its suggested filename.
+//! Files older than a given age can be removed with [`cleanup_downloads`].
+
+use anyhow::{Context, Result};
+use chromiumoxide::browser::Browser;
+use chromiumoxide::cdp::browser_protocol::browser::{
+    BrowserContextId, DownloadProgressState, EventDownloadProgress, EventDownloadWillBegin,
+    SetDownloadBehaviorBehavior, SetDownloadBehaviorParams,
+};
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::RwLock;
+
+use super::client::BrowserSession;
+use crate::models::{DownloadCleanup, DownloadInfo};
+
+/// How often `wait` re-checks download state.
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Root directory for all session download directories.
+pub fn downloads_root() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".fgp")
+        .join("services")
+        .join("browser")
+        .join("downloads")
+}
+
+/// Download directory for a session.
+pub fn session_downloads_dir(session_id: &str) -> PathBuf {
+    downloads_root().join(session_id)
+}
+
+/// Route downloads for a browser context into `dir`.
+pub async fn enable_downloads(
+    browser: &Browser,
+    context_id: Option<BrowserContextId>,
+    dir: &Path,
+) -> Result<()> {
+    tokio::fs::create_dir_all(dir)
+        .await
+        .with_context(|| format!("Failed to create downloads directory {}", dir.display()))?;
+
+    let mut params = SetDownloadBehaviorParams::new(SetDownloadBehaviorBehavior::AllowAndName);
+    params.browser_context_id = context_id;
+    params.download_path = Some(dir.to_string_lossy().to_string());
+    params.events_enabled = Some(true);
+
+    browser
+        .execute(params)
+        .await
+        .context("Failed to set download behavior")?;
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+struct DownloadEntry {
+    /// Start order, kept when earlier entries are pruned
+    seq: u64,
+    info: DownloadInfo,
+    dir: PathBuf,
+    /// Whether `wait` already returned this download
+    consumed: bool,
+}
+
+/// Tracks downloads across all sessions, in start order.
+#[derive(Debug, Default)]
+pub struct DownloadTracker {
+    entries: RwLock<Vec<DownloadEntry>>,
+    /// Sequence number of the next download to start
+    next_seq: AtomicU64,
+}
+
+impl DownloadTracker {
+    async fn begin(&self, session_id: String, event: &EventDownloadWillBegin) {
+        let dir = session_downloads_dir(&session_id);
+        let info = DownloadInfo {
+            guid: event.guid.clone(),
+            session_id,
+            url: event.url.clone(),
+            filename: sanitize_filename(&event.suggested_filename),
+            path: dir.join(&event.guid).to_string_lossy().to_string(),
+            state: "in_progress".to_string(),
+            received_bytes: 0,
+            total_bytes: 0,
+            size: None,
+        };
+
+        tracing::info!(
+            "Download started in session {}: {} ({})",
+            info.session_id,
+            info.filename,
+            info.url
+        );
+        let mut entries = self.entries.write().await;
+        entries.push(DownloadEntry {
+            seq: self.next_seq.fetch_add(1, Ordering::Relaxed),
+            info,
+            dir,
+            consumed: false,
+        });
+    }
+
+    async fn progress(&self, event: &EventDownloadProgress) {
+        let mut entries = self.entries.write().await;
+        let Some(entry) = entries.iter_mut().find(|e| e.info.guid == event.guid) else {
+            return;
+        };
+
+        entry.info.received_bytes = event.received_bytes as u64;
+        entry.info.total_bytes = event.total_bytes as u64;
+
+        match event.state {
+            DownloadProgressState::InProgress => {}
+            DownloadProgressState::Canceled => {
+                tracing::info!("Download canceled: {}", entry.info.url);
+                entry.info.state = "canceled".to_string();
+            }
+            DownloadProgressState::Completed => {
+                let target = unique_path(&entry.dir, &entry.info.filename);
+                match tokio::fs::rename(&entry.info.path, &target).await {
+                    Ok(()) => {
+                        entry.info.path = target.to_string_lossy().to_string();
+                        if let Some(name) = target.file_name() {
+                            entry.info.filename = name.to_string_lossy().to_string();
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to rename download {}: {}", entry.info.guid, e)
+                    }
+                }
+                entry.info.size = tokio::fs::metadata(&entry.info.path)
+                    .await
+                    .map(|m| m.len())
+                    .ok();
+                entry.info.state = "completed".to_string();
+                tracing::info!("Download completed: {}", entry.info.path);
+            }
+        }
+    }
+
+    /// List downloads for a session.
+    pub async fn list(&self, session_id: &str) -> Vec<DownloadInfo> {
+        self.entries
+            .read()
+            .await
+            .iter()
+            .filter(|e| e.info.session_id == session_id)
+            .map(|e| e.info.clone())
+            .collect()
+    }
+
+    /// Marker for downloads started from now on, to pass to `wait`.
+    pub fn mark(&self) -> u64 {
+        self.next_seq.load(Ordering::Relaxed)
+    }
+
+    /// Files of downloads still being written.
+    pub async fn in_progress_paths(&self) -> Vec<PathBuf> {
+        self.entries
+            .read()
+            .await
+            .iter()
+            .filter(|e| e.info.state == "in_progress")
+            .map(|e| PathBuf::from(&e.info.path))
+            .collect()
+    }
+
+    /// Forget finished downloads whose files no longer exist.
+    pub async fn prune_missing(&self) {
+        self.entries
+            .write()
+            .await
+            .retain(|e| e.info.state == "in_progress" || Path::new(&e.info.path).exists());
+    }
+
+    /// Wait for a finished download in the session not yet returned by `wait`,
+    /// considering only downloads started at or after the `since` marker.
+    pub async fn wait(
+        &self,
+        session_id: &str,
+        since: u64,
+        timeout: Duration,
+    ) -> Result<DownloadInfo> {
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        loop {
+            {
+                let mut entries = self.entries.write().await;
+                if let Some(entry) = entries.iter_mut().find(|e| {
+                    e.seq >= since
+                        && e.info.session_id == session_id
+                        && !e.consumed
+                        && e.info.state != "in_progress"
+                }) {
+                    entry.consumed = true;
+                    if entry.info.state == "canceled" {
+                        anyhow::bail!("Download canceled: {}", entry.info.url);
+                    }
+                    return Ok(entry.info.clone());
+                }
+            }
+
+            if tokio::time::Instant::now() >= deadline {
+                anyhow::bail!(
+                    "Timed out after {}ms waiting for download",
+                    timeout.as_millis()
+                );
+            }
+            tokio::time::sleep(WAIT_POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Listen for browser-level download events and record them in `tracker`.
+pub async fn spawn_download_listener(
+    browser: &Browser,
+    tracker: Arc<DownloadTracker>,
+    sessions: Arc<RwLock<HashMap<String, BrowserSession>>>,
+    default_session_id: String,
+) -> Result<()> {
+    let mut will_begin = browser
+        .event_listener::<EventDownloadWillBegin>()
+        .await
+        .context("Failed to subscribe to download events")?;
+    let mut progress = browser
+        .event_listener::<EventDownloadProgress>()
+        .await
+        .context("Failed to subscribe to download progress events")?;
+
+    let begin_tracker = tracker.clone();
+    tokio::spawn(async move {
+        while let Some(event) = will_begin.next().await {
+            let session_id = session_for_frame(&sessions, &event.frame_id)
+                .await
+                .unwrap_or_else(|| default_session_id.clone());
+            tracing::debug!("Download started in session {}: {}", session_id, event.url);
+            begin_tracker.begin(session_id, &event).await;
+        }
+    });
+
+    tokio::spawn(async move {
+        while let Some(event) = progress.next().await {
+            tracker.progress(&event).await;
+        }
+    });
+
+    Ok(())
+}
+
+/// Remove downloaded files older than `max_age` from every session directory,
+/// leaving the files at `in_progress` (see [`DownloadTracker::in_progress_paths`]).
+pub async fn cleanup_downloads(
+    max_age: Duration,
+    in_progress: Vec<PathBuf>,
+) -> Result<DownloadCleanup> {
+    let root = downloads_root();
+    tokio::task::spawn_blocking(move || cleanup_dir(&root, max_age, &in_progress))
+        .await
+        .context("Download cleanup task failed")?
+}
+
+/// Remove files older than `max_age` from the session directories under `root`,
+/// then any session directories left empty. Files of downloads still in
+/// progress (or Chrome's temporary files next to them) are kept however old.
+fn cleanup_dir(root: &Path, max_age: Duration, in_progress: &[PathBuf]) -> Result<DownloadCleanup> {
+    let mut result = DownloadCleanup::default();
+    if !root.exists() {
+        return Ok(result);
+    }
+
+    let cutoff = SystemTime::now()
+        .checked_sub(max_age)
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+
+    for session_dir in std::fs::read_dir(root)? {
+        let session_dir = session_dir?.path();
+        if !session_dir.is_dir() {
+            continue;
+        }
+
+        for file in std::fs::read_dir(&session_dir)? {
+            let file = file?;
+            let metadata = file.metadata()?;
+            if !metadata.is_file() || metadata.modified()? > cutoff {
+                continue;
+            }
+            let path = file.path().to_string_lossy().to_string();
+            if in_progress
+                .iter()
+                .any(|p| path.starts_with(p.to_string_lossy().as_ref()))
+            {
+                continue;
+            }
+            match std::fs::remove_file(file.path()) {
+                Ok(()) => {
+                    result.removed_files += 1;
+                    result.freed_bytes += metadata.len();
+                }
+                Err(e) => tracing::warn!("Failed to remove {}: {}", file.path().display(), e),
+            }
+        }
+
+        // Only succeeds when the directory is empty
+        let _ = std::fs::remove_dir(&session_dir);
+    }
+
+    Ok(result)
+}
+
+/// Find the session whose page (or popup) owns `frame_id`.
+async fn session_for_frame(
+    sessions: &RwLock<HashMap<String, BrowserSession>>,
+    frame_id: &chromiumoxide::cdp::browser_protocol::page::FrameId,
+) -> Option<String> {
+    let sessions = sessions.read().await;
+    for (id, session) in sessions.iter() {
+        for page in &session.pages {
+            if let Ok(frames) = page.frames().await {
+                if frames.contains(frame_id) {
+                    return Some(id.clone());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Strip path components so a suggested filename can't escape the downloads dir.
+fn sanitize_filename(name: &str) -> String {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or("").trim();
+    if base.is_empty() || base == "." || base == ".." {
+        "download".to_string()
+    } else {
+        base.to_string()
+    }
+}
+
+/// Pick `dir/name`, or `dir/stem (n).ext` if that file already exists.
+fn unique_path(dir: &Path, name: &str) -> PathBuf {
+    let candidate = dir.join(name);
+    if !candidate.exists() {
+        return candidate;
+    }
+
+    let path = Path::new(name);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| name.to_string());
+    let ext = path.extension().map(|e| e.to_string_lossy().to_string());
+
+    (1..)
+        .map(|n| match &ext {
+            Some(ext) => dir.join(format!("{} ({}).{}", stem, n, ext)),
+            None => dir.join(format!("{} ({})", stem, n)),
+        })
+        .find(|p| !p.exists())
+        .expect("unbounded range always yields a free path")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(sanitize_filename("report.pdf"), "report.pdf");
+        assert_eq!(sanitize_filename("../../etc/passwd"), "passwd");
+        assert_eq!(sanitize_filename("C:\\temp\\file.txt"), "file.txt");
+        assert_eq!(sanitize_filename(""), "download");
+        assert_eq!(sanitize_filename(".."), "download");
+    }
+
+    #[test]
+    fn test_unique_path() {
+        let dir = std::env::temp_dir().join(format!("fgp-downloads-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        assert_eq!(unique_path(&dir, "a.txt"), dir.join("a.txt"));
+        std::fs::write(dir.join("a.txt"), b"x").unwrap();
+        assert_eq!(unique_path(&dir, "a.txt"), dir.join("a (1).txt"));
+        std::fs::write(dir.join("a (1).txt"), b"x").unwrap();
+        assert_eq!(unique_path(&dir, "a.txt"), dir.join("a (2).txt"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cleanup_dir() {
+        let root = std::env::temp_dir().join(format!("fgp-downloads-{}", uuid::Uuid::new_v4()));
+        let old_dir = root.join("old");
+        let mixed_dir = root.join("mixed");
+        std::fs::create_dir_all(&old_dir).unwrap();
+        std::fs::create_dir_all(&mixed_dir).unwrap();
+
+        let two_days_ago = SystemTime::now() - Duration::from_secs(2 * 24 * 60 * 60);
+        for path in [old_dir.join("a.csv"), mixed_dir.join("b.csv")] {
+            std::fs::write(&path, b"12345").unwrap();
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(two_days_ago)
+                .unwrap();
+        }
+        std::fs::write(mixed_dir.join("new.csv"), b"x").unwrap();
+        // A slow download: its file is old but Chrome is still writing it
+        let downloading = mixed_dir.join("8d3c-guid");
+        std::fs::write(&downloading, b"123").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&downloading)
+            .unwrap()
+            .set_modified(two_days_ago)
+            .unwrap();
+
+        let in_progress = vec![downloading.clone()];
+        let result = cleanup_dir(&root, Duration::from_secs(24 * 60 * 60), &in_progress).unwrap();
+        assert_eq!(result.removed_files, 2);
+        assert_eq!(result.freed_bytes, 10);
+        assert!(!old_dir.exists());
+        assert!(mixed_dir.join("new.csv").exists());
+        assert!(!mixed_dir.join("b.csv").exists());
+        assert!(downloading.exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    fn finished(tracker: &DownloadTracker, guid: &str, path: &Path) -> DownloadEntry {
+        DownloadEntry {
+            seq: tracker.next_seq.fetch_add(1, Ordering::Relaxed),
+            info: DownloadInfo {
+                guid: guid.to_string(),
+                session_id: "default".to_string(),
+                url: format!("https://example.com/{}", guid),
+                filename: guid.to_string(),
+                path: path.to_string_lossy().to_string(),
+                state: "completed".to_string(),
+                received_bytes: 1,
+                total_bytes: 1,
+                size: Some(1),
+            },
+            dir: path.parent().unwrap().to_path_buf(),
+            consumed: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mark_survives_prune() {
+        let dir = std::env::temp_dir().join(format!("fgp-downloads-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let kept = dir.join("kept.csv");
+        std::fs::write(&kept, b"x").unwrap();
+
+        let tracker = DownloadTracker::default();
+        let removed = finished(&tracker, "removed", &dir.join("removed.csv"));
+        tracker.entries.write().await.push(removed);
+        let since = tracker.mark();
+        let after = finished(&tracker, "kept", &kept);
+        tracker.entries.write().await.push(after);
+
+        // Dropping the earlier entry mustn't hide the download after the mark
+        tracker.prune_missing().await;
+        let download = tracker
+            .wait("default", since, Duration::from_millis(10))
+            .await
+            .unwrap();
+        assert_eq!(download.guid, "kept");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
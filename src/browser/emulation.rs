@@ -0,0 +1,286 @@
+//! Device and viewport emulation.
+//!
+//! Applies `Emulation.setDeviceMetricsOverride`, touch emulation and
+//! `Emulation.setUserAgentOverride` to a session's page. Named presets live in
+//! a small table below. Permission names for `Browser.grantPermissions` are
+//! parsed here as well, and network throttling profiles for
+//! `Network.emulateNetworkConditions` live alongside the device presets.
+//! CSS media emulation goes through `Emulation.setEmulatedMedia`, and CPU
+//! throttling through `Emulation.setCPUThrottlingRate`.
+
+use anyhow::{Context, Result};
+use chromiumoxide::cdp::browser_protocol::browser::PermissionType;
+use chromiumoxide::cdp::browser_protocol::emulation::{
+    ClearDeviceMetricsOverrideParams, MediaFeature, SetCpuThrottlingRateParams,
+    SetDeviceMetricsOverrideParams, SetEmulatedMediaParams, SetTouchEmulationEnabledParams,
+    SetUserAgentOverrideParams,
+};
+use chromiumoxide::cdp::browser_protocol::network::EmulateNetworkConditionsParams;
+use chromiumoxide::page::Page;
+
+use crate::models::{EmulationSettings, MediaEmulation, NetworkConditions};
+
+/// A named device preset.
+struct DevicePreset {
+    name: &'static str,
+    width: u32,
+    height: u32,
+    device_scale_factor: f64,
+    is_mobile: bool,
+    user_agent: &'static str,
+}
+
+const PRESETS: &[DevicePreset] = &[
+    DevicePreset {
+        name: "iphone-14",
+        width: 390,
+        height: 844,
+        device_scale_factor: 3.0,
+        is_mobile: true,
+        user_agent: "Mozilla/5.0 (iPhone; CPU iPhone OS 16_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/16.0 Mobile/15E148 Safari/604.1",
+    },
+    DevicePreset {
+        name: "pixel-7",
+        width: 412,
+        height: 915,
+        device_scale_factor: 2.625,
+        is_mobile: true,
+        user_agent: "Mozilla/5.0 (Linux; Android 13; Pixel 7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/116.0.0.0 Mobile Safari/537.36",
+    },
+    DevicePreset {
+        name: "ipad",
+        width: 820,
+        height: 1180,
+        device_scale_factor: 2.0,
+        is_mobile: true,
+        user_agent: "Mozilla/5.0 (iPad; CPU OS 16_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/16.0 Mobile/15E148 Safari/604.1",
+    },
+];
+
+/// Look up a preset by name, ignoring case and treating spaces and
+/// underscores as dashes ("iPhone 14" finds `iphone-14`).
+pub fn device_preset(name: &str) -> Option<EmulationSettings> {
+    let name = name.trim().replace([' ', '_'], "-");
+    PRESETS
+        .iter()
+        .find(|p| p.name.eq_ignore_ascii_case(&name))
+        .map(|p| EmulationSettings {
+            width: p.width,
+            height: p.height,
+            device_scale_factor: p.device_scale_factor,
+            is_mobile: p.is_mobile,
+            user_agent: Some(p.user_agent.to_string()),
+        })
+}
+
+/// Names of all presets, for error messages and schemas.
+pub fn device_preset_names() -> Vec<&'static str> {
+    PRESETS.iter().map(|p| p.name).collect()
+}
+
+/// Network profiles, matching the DevTools throttling presets:
+/// (name, offline, latency ms, download kbit/s, upload kbit/s).
+const NETWORK_PROFILES: &[(&str, bool, f64, f64, f64)] = &[
+    ("slow-3g", false, 2000.0, 400.0, 400.0),
+    ("fast-3g", false, 562.5, 1440.0, 675.0),
+    ("offline", true, 0.0, 0.0, 0.0),
+];
+
+/// Look up a network profile by name, ignoring case ("Slow 3G" finds `slow-3g`).
+pub fn network_profile(name: &str) -> Option<NetworkConditions> {
+    let name = name.trim().replace([' ', '_'], "-");
+    NETWORK_PROFILES
+        .iter()
+        .find(|(profile, ..)| profile.eq_ignore_ascii_case(&name))
+        .map(
+            |&(profile, offline, latency, download, upload)| NetworkConditions {
+                profile: Some(profile.to_string()),
+                offline,
+                latency_ms: latency,
+                download_kbps: (!offline).then_some(download),
+                upload_kbps: (!offline).then_some(upload),
+            },
+        )
+}
+
+/// Names of all network profiles, for error messages and schemas.
+pub fn network_profile_names() -> Vec<&'static str> {
+    NETWORK_PROFILES.iter().map(|(name, ..)| *name).collect()
+}
+
+/// Throttle `page`'s network, or lift throttling when `conditions` is None.
+///
+/// The override belongs to the page target, so it survives navigation.
+pub async fn apply_network_conditions(
+    page: &Page,
+    conditions: Option<&NetworkConditions>,
+) -> Result<()> {
+    // CDP takes bytes per second, with -1 meaning unthrottled
+    let throughput = |kbps: Option<f64>| kbps.map_or(-1.0, |kbps| kbps * 1000.0 / 8.0);
+    let params = match conditions {
+        Some(c) => EmulateNetworkConditionsParams::new(
+            c.offline,
+            c.latency_ms,
+            throughput(c.download_kbps),
+            throughput(c.upload_kbps),
+        ),
+        None => EmulateNetworkConditionsParams::new(false, 0.0, -1.0, -1.0),
+    };
+
+    page.execute(params)
+        .await
+        .context("Failed to set network conditions")?;
+    Ok(())
+}
+
+/// Permissions API names that differ from their CDP permission type.
+const PERMISSION_ALIASES: &[(&str, PermissionType)] = &[
+    ("clipboard-read", PermissionType::ClipboardReadWrite),
+    ("clipboard-write", PermissionType::ClipboardSanitizedWrite),
+    ("camera", PermissionType::VideoCapture),
+    ("microphone", PermissionType::AudioCapture),
+    ("background-sync", PermissionType::BackgroundSync),
+    ("persistent-storage", PermissionType::DurableStorage),
+];
+
+/// Parse a permission by its Permissions API name ("clipboard-read",
+/// "camera") or CDP name ("clipboardReadWrite", "videoCapture").
+pub fn parse_permission(name: &str) -> Option<PermissionType> {
+    let name = name.trim();
+    PERMISSION_ALIASES
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(name))
+        .map(|(_, permission)| permission.clone())
+        .or_else(|| name.parse().ok())
+}
+
+/// Apply device metrics, touch support and (optionally) a user agent to `page`.
+pub async fn apply_emulation(page: &Page, settings: &EmulationSettings) -> Result<()> {
+    page.execute(SetDeviceMetricsOverrideParams::new(
+        settings.width,
+        settings.height,
+        settings.device_scale_factor,
+        settings.is_mobile,
+    ))
+    .await
+    .context("Failed to set device metrics")?;
+
+    page.execute(SetTouchEmulationEnabledParams::new(settings.is_mobile))
+        .await
+        .context("Failed to set touch emulation")?;
+
+    if let Some(user_agent) = &settings.user_agent {
+        page.execute(SetUserAgentOverrideParams::new(user_agent.clone()))
+            .await
+            .context("Failed to set user agent")?;
+    }
+
+    Ok(())
+}
+
+/// Remove all emulation overrides from `page`.
+pub async fn clear_emulation(page: &Page) -> Result<()> {
+    page.execute(ClearDeviceMetricsOverrideParams::default())
+        .await
+        .context("Failed to clear device metrics")?;
+    page.execute(SetTouchEmulationEnabledParams::new(false))
+        .await
+        .context("Failed to disable touch emulation")?;
+    // An empty override restores the browser's default user agent.
+    page.execute(SetUserAgentOverrideParams::new(String::new()))
+        .await
+        .context("Failed to clear user agent")?;
+    Ok(())
+}
+
+/// Emulate CSS media on `page`. Unset fields fall back to the browser's
+/// own values, so an empty `MediaEmulation` clears the override.
+pub async fn apply_media(page: &Page, settings: &MediaEmulation) -> Result<()> {
+    let mut features = Vec::new();
+    if let Some(scheme) = &settings.color_scheme {
+        features.push(MediaFeature::new("prefers-color-scheme", scheme.clone()));
+    }
+    if let Some(reduced) = settings.reduced_motion {
+        let value = if reduced { "reduce" } else { "no-preference" };
+        features.push(MediaFeature::new("prefers-reduced-motion", value));
+    }
+
+    let params = SetEmulatedMediaParams::builder()
+        .media(settings.media.clone().unwrap_or_default())
+        .features(features)
+        .build();
+    page.execute(params)
+        .await
+        .context("Failed to set emulated media")?;
+    Ok(())
+}
+
+/// Slow `page`'s CPU down by `rate` (1 = full speed). Like network
+/// throttling, the rate belongs to the page target and survives navigation.
+pub async fn apply_cpu_throttling(page: &Page, rate: f64) -> Result<()> {
+    page.execute(SetCpuThrottlingRateParams::new(rate))
+        .await
+        .context("Failed to set CPU throttling rate")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_lookup() {
+        let iphone = device_preset("iPhone-14").unwrap();
+        assert_eq!((iphone.width, iphone.height), (390, 844));
+        assert!(iphone.is_mobile);
+        assert!(iphone.user_agent.unwrap().contains("iPhone"));
+
+        assert_eq!(device_preset("iPhone 14"), device_preset("iphone-14"));
+        assert_eq!(device_preset("Pixel 7").unwrap().width, 412);
+        assert!(device_preset("nokia-3310").is_none());
+        assert_eq!(device_preset_names(), vec!["iphone-14", "pixel-7", "ipad"]);
+    }
+
+    #[test]
+    fn test_network_profile() {
+        let slow = network_profile("Slow 3G").unwrap();
+        assert_eq!(slow.profile.as_deref(), Some("slow-3g"));
+        assert_eq!(slow.latency_ms, 2000.0);
+        assert_eq!(slow.download_kbps, Some(400.0));
+
+        let offline = network_profile("offline").unwrap();
+        assert!(offline.offline);
+        assert_eq!(offline.download_kbps, None);
+
+        assert!(network_profile("5g").is_none());
+        assert_eq!(
+            network_profile_names(),
+            vec!["slow-3g", "fast-3g", "offline"]
+        );
+    }
+
+    #[test]
+    fn test_parse_permission() {
+        assert_eq!(
+            parse_permission("geolocation"),
+            Some(PermissionType::Geolocation)
+        );
+        assert_eq!(
+            parse_permission("notifications"),
+            Some(PermissionType::Notifications)
+        );
+        assert_eq!(
+            parse_permission("clipboard-read"),
+            Some(PermissionType::ClipboardReadWrite)
+        );
+        assert_eq!(
+            parse_permission("camera"),
+            Some(PermissionType::VideoCapture)
+        );
+        assert_eq!(
+            parse_permission("videoCapture"),
+            Some(PermissionType::VideoCapture)
+        );
+        assert_eq!(parse_permission("teleport"), None);
+    }
+}
@@ -0,0 +1,252 @@
+//! Fetching a URL with the session's cookies, without rendering it.
+//!
+//! The request first runs as `fetch()` inside the page, so it carries the
+//! page's cookies and origin and reports the final URL after redirects. When
+//! the page may not read the response (CORS, or a page with no origin), it
+//! is retried with `Network.loadNetworkResource`, which uses the browser's
+//! cookie jar directly but cannot see where redirects ended up.
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use chromiumoxide::cdp::browser_protocol::io::{CloseParams, ReadParams, StreamHandle};
+use chromiumoxide::cdp::browser_protocol::network::{
+    LoadNetworkResourceOptions, LoadNetworkResourceParams,
+};
+use chromiumoxide::cdp::js_protocol::runtime::EvaluateParams;
+use chromiumoxide::page::Page;
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+
+/// Bytes requested per `IO.read` call.
+const READ_CHUNK_BYTES: i64 = 64 * 1024;
+
+/// A fetched response body.
+#[derive(Debug)]
+pub struct FetchedBody {
+    /// URL after redirects (the requested URL for CDP-level fetches)
+    pub final_url: String,
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+    /// "page" or "network"
+    pub via: &'static str,
+}
+
+/// What the in-page `fetch()` script returns.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum PageFetch {
+    Ok {
+        status: u16,
+        url: String,
+        content_type: Option<String>,
+        data: String,
+    },
+    TooLarge {
+        too_large: u64,
+    },
+    Blocked {
+        blocked: String,
+    },
+}
+
+fn fetch_script(url: &str, max_bytes: u64) -> String {
+    format!(
+        r#"(async () => {{
+    const maxBytes = {max_bytes};
+    let response;
+    try {{
+        response = await fetch({url}, {{ credentials: 'include' }});
+    }} catch (e) {{
+        return {{ blocked: String(e) }};
+    }}
+    const length = Number(response.headers.get('content-length') || 0);
+    if (length > maxBytes) return {{ too_large: length }};
+    const bytes = new Uint8Array(await response.arrayBuffer());
+    if (bytes.length > maxBytes) return {{ too_large: bytes.length }};
+    let binary = '';
+    for (let i = 0; i < bytes.length; i += 0x8000) {{
+        binary += String.fromCharCode.apply(null, bytes.subarray(i, i + 0x8000));
+    }}
+    return {{
+        status: response.status,
+        url: response.url,
+        content_type: response.headers.get('content-type'),
+        data: btoa(binary),
+    }};
+}})()"#,
+        url = serde_json::to_string(url).unwrap_or_default(),
+    )
+}
+
+fn too_large(size: u64, max_bytes: u64) -> anyhow::Error {
+    anyhow::anyhow!(
+        "TOO_LARGE: response is {} bytes, over the {} byte limit (raise max_bytes)",
+        size,
+        max_bytes
+    )
+}
+
+/// Fetch `url` with the page's cookies, refusing bodies over `max_bytes`.
+pub async fn fetch_url(page: &Page, url: &str, max_bytes: u64) -> Result<FetchedBody> {
+    let params = EvaluateParams::builder()
+        .expression(fetch_script(url, max_bytes))
+        .await_promise(true)
+        .return_by_value(true)
+        .build()
+        .map_err(|e| anyhow::anyhow!("Failed to build evaluate params: {}", e))?;
+
+    let result: PageFetch = page
+        .evaluate(params)
+        .await
+        .context("Failed to run fetch in the page")?
+        .into_value()
+        .context("Failed to parse fetch result")?;
+
+    match result {
+        PageFetch::Ok {
+            status,
+            url: final_url,
+            content_type,
+            data,
+        } => Ok(FetchedBody {
+            final_url,
+            status,
+            content_type,
+            body: base64::engine::general_purpose::STANDARD
+                .decode(data)
+                .context("Failed to decode fetched body")?,
+            via: "page",
+        }),
+        PageFetch::TooLarge { too_large: size } => Err(too_large(size, max_bytes)),
+        PageFetch::Blocked { blocked } => {
+            tracing::debug!(
+                "In-page fetch of {} failed ({}); retrying via CDP",
+                url,
+                blocked
+            );
+            fetch_via_network(page, url, max_bytes).await
+        }
+    }
+}
+
+/// Fetch through `Network.loadNetworkResource`, which isn't subject to CORS.
+async fn fetch_via_network(page: &Page, url: &str, max_bytes: u64) -> Result<FetchedBody> {
+    let frame_id = page.mainframe().await?.context("Page has no main frame")?;
+    let mut params =
+        LoadNetworkResourceParams::new(url, LoadNetworkResourceOptions::new(false, true));
+    params.frame_id = Some(frame_id);
+    let resource = page
+        .execute(params)
+        .await
+        .context("Failed to load resource")?
+        .result
+        .resource;
+
+    if !resource.success {
+        anyhow::bail!(
+            "Fetch of {} failed: {}",
+            url,
+            resource
+                .net_error_name
+                .unwrap_or_else(|| "unknown network error".to_string())
+        );
+    }
+    let stream = resource
+        .stream
+        .context("Resource loaded without a body stream")?;
+
+    let body = read_stream(page, &stream, max_bytes).await;
+    let _ = page.execute(CloseParams::new(stream)).await;
+
+    Ok(FetchedBody {
+        final_url: url.to_string(),
+        status: resource.http_status_code.unwrap_or(0.0) as u16,
+        content_type: resource
+            .headers
+            .as_ref()
+            .and_then(|h| header_value(h.inner(), "content-type")),
+        body: body?,
+        via: "network",
+    })
+}
+
+async fn read_stream(page: &Page, stream: &StreamHandle, max_bytes: u64) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+    loop {
+        let mut params = ReadParams::new(stream.clone());
+        params.size = Some(READ_CHUNK_BYTES);
+        let chunk = page
+            .execute(params)
+            .await
+            .context("Failed to read resource body")?
+            .result;
+
+        if chunk.base64_encoded.unwrap_or(false) {
+            body.extend(
+                base64::engine::general_purpose::STANDARD
+                    .decode(&chunk.data)
+                    .context("Failed to decode resource body")?,
+            );
+        } else {
+            body.extend(chunk.data.as_bytes());
+        }
+
+        if body.len() as u64 > max_bytes {
+            return Err(too_large(body.len() as u64, max_bytes));
+        }
+        if chunk.eof {
+            return Ok(body);
+        }
+    }
+}
+
+/// Case-insensitive header lookup in a CDP `Headers` object.
+fn header_value(headers: &JsonValue, name: &str) -> Option<String> {
+    headers
+        .as_object()?
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .and_then(|(_, value)| value.as_str())
+        .map(|value| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_header_value() {
+        let headers = json!({"Content-Type": "application/pdf", "X-Other": 1});
+        assert_eq!(
+            header_value(&headers, "content-type").as_deref(),
+            Some("application/pdf")
+        );
+        assert_eq!(header_value(&headers, "x-other"), None);
+        assert_eq!(header_value(&headers, "etag"), None);
+    }
+
+    #[test]
+    fn test_page_fetch_parsing() {
+        let ok: PageFetch = serde_json::from_value(json!({
+            "status": 200, "url": "https://a.test/x.pdf", "content_type": null, "data": "eA=="
+        }))
+        .unwrap();
+        assert!(matches!(ok, PageFetch::Ok { status: 200, .. }));
+
+        let blocked: PageFetch =
+            serde_json::from_value(json!({"blocked": "TypeError: Failed to fetch"})).unwrap();
+        assert!(matches!(blocked, PageFetch::Blocked { .. }));
+
+        let large: PageFetch = serde_json::from_value(json!({"too_large": 10})).unwrap();
+        assert!(matches!(large, PageFetch::TooLarge { too_large: 10 }));
+    }
+
+    #[test]
+    fn test_fetch_script_escapes_url() {
+        let script = fetch_script("https://a.test/\"x'", 5);
+        assert!(script.contains(r#"fetch("https://a.test/\"x'""#));
+        assert!(script.contains("const maxBytes = 5;"));
+    }
+}
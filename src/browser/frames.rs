@@ -0,0 +1,301 @@
+//! Iframe targeting.
+//!
+//! Child frames are addressed by their position in document order (0 = first
+//! iframe, nested frames follow their parent) or by a URL pattern / frame name.
+//! A path such as `["checkout", "card"]` walks nested frames one level at a
+//! time.
+//! Scripts run in the frame's own execution context; mouse input is dispatched
+//! at page coordinates offset by the iframe's position.
+//!
+//! Only frames rendered in the page's process can be targeted. Cross-origin
+//! iframes usually run out of process (site isolation) and have no execution
+//! context reachable from the page, so targeting them fails with an error.
+
+use anyhow::{Context, Result};
+use chromiumoxide::cdp::browser_protocol::dom::{GetBoxModelParams, GetFrameOwnerParams};
+use chromiumoxide::cdp::browser_protocol::page::{
+    CreateIsolatedWorldParams, FrameId, FrameTree, GetFrameTreeParams,
+};
+use chromiumoxide::cdp::js_protocol::runtime::{EvaluateParams, ExecutionContextId};
+use chromiumoxide::layout::Point;
+use chromiumoxide::page::Page;
+use serde_json::Value as JsonValue;
+
+use super::network::url_matches;
+use crate::models::{FrameInfo, FrameTarget};
+
+/// A child frame of the page, in document order.
+#[derive(Debug, Clone)]
+pub struct ChildFrame {
+    pub id: FrameId,
+    /// Parent frame (None for frames directly in the top document)
+    pub parent: Option<FrameId>,
+    pub info: FrameInfo,
+}
+
+/// All child frames of the page, depth-first in document order.
+pub async fn list_frames(page: &Page) -> Result<Vec<ChildFrame>> {
+    let tree = page
+        .execute(GetFrameTreeParams::default())
+        .await
+        .context("Failed to get frame tree")?
+        .result
+        .frame_tree;
+
+    let mut frames = Vec::new();
+    collect_frames(
+        tree.child_frames.as_deref().unwrap_or_default(),
+        None,
+        &mut frames,
+    );
+    Ok(frames)
+}
+
+fn collect_frames(trees: &[FrameTree], parent: Option<&FrameId>, out: &mut Vec<ChildFrame>) {
+    for tree in trees {
+        out.push(ChildFrame {
+            id: tree.frame.id.clone(),
+            parent: parent.cloned(),
+            info: FrameInfo {
+                index: out.len(),
+                url: tree.frame.url.clone(),
+                name: tree.frame.name.clone().filter(|n| !n.is_empty()),
+            },
+        });
+        collect_frames(
+            tree.child_frames.as_deref().unwrap_or_default(),
+            Some(&tree.frame.id),
+            out,
+        );
+    }
+}
+
+/// Find the frame a target refers to.
+pub async fn resolve_frame(page: &Page, target: &FrameTarget) -> Result<ChildFrame> {
+    let frames = list_frames(page).await?;
+    find_frame(&frames, target).cloned().ok_or_else(|| {
+        anyhow::anyhow!(
+            "FRAME_NOT_FOUND: no frame matches {} ({} child frames on the page)",
+            target,
+            frames.len()
+        )
+    })
+}
+
+fn find_frame<'a>(frames: &'a [ChildFrame], target: &FrameTarget) -> Option<&'a ChildFrame> {
+    match target {
+        FrameTarget::Index(index) => frames.get(*index),
+        FrameTarget::Url(pattern) => frames.iter().find(|f| frame_matches(f, pattern)),
+        FrameTarget::Path(steps) => {
+            let mut found: Option<&ChildFrame> = None;
+            for step in steps {
+                let parent = found.map(|f| &f.id);
+                let mut children = frames.iter().filter(|f| f.parent.as_ref() == parent);
+                found = Some(match step {
+                    FrameTarget::Index(index) => children.nth(*index)?,
+                    FrameTarget::Url(pattern) => children.find(|f| frame_matches(f, pattern))?,
+                    FrameTarget::Path(_) => return None,
+                });
+            }
+            found
+        }
+    }
+}
+
+fn frame_matches(frame: &ChildFrame, pattern: &str) -> bool {
+    url_matches(pattern, &frame.info.url) || frame.info.name.as_deref() == Some(pattern)
+}
+
+/// Execution context to run scripts in a frame.
+async fn frame_context(page: &Page, frame: &ChildFrame) -> Result<ExecutionContextId> {
+    if let Some(context) = page.frame_execution_context(frame.id.clone()).await? {
+        return Ok(context);
+    }
+
+    // The default context may not be tracked yet; an isolated world shares the DOM
+    let world = CreateIsolatedWorldParams::builder()
+        .frame_id(frame.id.clone())
+        .world_name("fgp")
+        .build()
+        .map_err(|e| anyhow::anyhow!("Failed to build isolated world params: {}", e))?;
+
+    page.execute(world)
+        .await
+        .map(|r| r.result.execution_context_id)
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "Frame {} ({}) is not accessible; cross-origin iframes run out of process and cannot be targeted",
+                frame.info.index,
+                frame.info.url
+            )
+        })
+}
+
+/// Evaluate `script` in the target frame, or the top document when `frame` is None.
+///
+/// Returns the script's value, or None for `undefined`.
+pub async fn evaluate_in(
+    page: &Page,
+    frame: Option<&FrameTarget>,
+    script: String,
+) -> Result<Option<JsonValue>> {
+    let Some(target) = frame else {
+        return Ok(page.evaluate(script).await?.value().cloned());
+    };
+
+    let frame = resolve_frame(page, target).await?;
+    evaluate_in_frame(page, &frame, script).await
+}
+
+async fn evaluate_in_frame(
+    page: &Page,
+    frame: &ChildFrame,
+    script: String,
+) -> Result<Option<JsonValue>> {
+    let context = frame_context(page, frame).await?;
+
+    let params = EvaluateParams::builder()
+        .expression(script)
+        .context_id(context)
+        .return_by_value(true)
+        .await_promise(true)
+        .build()
+        .map_err(|e| anyhow::anyhow!("Failed to build evaluate params: {}", e))?;
+
+    let response = page
+        .execute(params)
+        .await
+        .context("Failed to evaluate in frame")?;
+
+    if let Some(exception) = &response.result.exception_details {
+        let message = exception
+            .exception
+            .as_ref()
+            .and_then(|e| e.description.clone())
+            .unwrap_or_else(|| exception.text.clone());
+        anyhow::bail!("{}", message);
+    }
+
+    Ok(response.result.result.value)
+}
+
+/// Scroll an element into view and return its center in page coordinates.
+pub async fn element_center(page: &Page, frame: &FrameTarget, css_selector: &str) -> Result<Point> {
+    let selector_json = serde_json::to_string(css_selector)?;
+    let script = format!(
+        r#"(() => {{
+            const el = document.querySelector({});
+            if (!el) return null;
+            el.scrollIntoView({{ block: 'center', inline: 'center' }});
+            const r = el.getBoundingClientRect();
+            return {{ x: r.left + r.width / 2, y: r.top + r.height / 2 }};
+        }})()"#,
+        selector_json
+    );
+
+    let frame = resolve_frame(page, frame).await?;
+    let center = evaluate_in_frame(page, &frame, script)
+        .await?
+        .filter(|v| !v.is_null())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "ELEMENT_NOT_FOUND: {} in frame {}",
+                css_selector,
+                frame.info.index
+            )
+        })?;
+
+    let (offset_x, offset_y) = frame_offset(page, &frame).await?;
+    Ok(Point {
+        x: offset_x + center["x"].as_f64().unwrap_or_default(),
+        y: offset_y + center["y"].as_f64().unwrap_or_default(),
+    })
+}
+
+/// Top-left corner of a frame's content box in page coordinates.
+async fn frame_offset(page: &Page, frame: &ChildFrame) -> Result<(f64, f64)> {
+    let owner = page
+        .execute(GetFrameOwnerParams::new(frame.id.clone()))
+        .await
+        .context("Failed to find the iframe element")?;
+    let box_model = page
+        .execute(
+            GetBoxModelParams::builder()
+                .backend_node_id(owner.result.backend_node_id)
+                .build(),
+        )
+        .await
+        .context("Failed to get iframe position")?;
+
+    // Quads are [x1, y1, x2, y2, ...] starting at the top-left corner
+    let quad = box_model.result.model.content.inner();
+    Ok((
+        quad.first().copied().unwrap_or_default(),
+        quad.get(1).copied().unwrap_or_default(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(index: usize, url: &str, name: Option<&str>) -> ChildFrame {
+        ChildFrame {
+            id: FrameId::new(format!("frame-{}", index)),
+            parent: None,
+            info: FrameInfo {
+                index,
+                url: url.to_string(),
+                name: name.map(|n| n.to_string()),
+            },
+        }
+    }
+
+    #[test]
+    fn test_find_frame() {
+        let frames = vec![
+            frame(0, "https://ads.example.com/banner", None),
+            frame(1, "https://pay.example.com/checkout", Some("payment")),
+        ];
+
+        let by_index = find_frame(&frames, &FrameTarget::Index(1)).unwrap();
+        assert_eq!(by_index.info.url, "https://pay.example.com/checkout");
+
+        let by_url = find_frame(&frames, &FrameTarget::Url("ads.example".to_string())).unwrap();
+        assert_eq!(by_url.info.index, 0);
+
+        let by_name = find_frame(&frames, &FrameTarget::Url("payment".to_string())).unwrap();
+        assert_eq!(by_name.info.index, 1);
+
+        assert!(find_frame(&frames, &FrameTarget::Index(2)).is_none());
+        assert!(find_frame(&frames, &FrameTarget::Url("*.org/*".to_string())).is_none());
+    }
+
+    #[test]
+    fn test_find_frame_path() {
+        let mut card = frame(2, "https://js.stripe.com/card", Some("card"));
+        card.parent = Some(FrameId::new("frame-1"));
+        let frames = vec![
+            frame(0, "https://ads.example.com/banner", None),
+            frame(1, "https://pay.example.com/checkout", Some("checkout")),
+            card,
+        ];
+
+        let path = FrameTarget::Path(vec![
+            FrameTarget::Url("checkout".to_string()),
+            FrameTarget::Url("card".to_string()),
+        ]);
+        assert_eq!(find_frame(&frames, &path).unwrap().info.index, 2);
+
+        // Indexes count only the parent's direct children
+        let path = FrameTarget::Path(vec![FrameTarget::Index(1), FrameTarget::Index(0)]);
+        assert_eq!(find_frame(&frames, &path).unwrap().info.index, 2);
+
+        // The card frame isn't a child of the top document or the ad frame
+        let path = FrameTarget::Path(vec![
+            FrameTarget::Index(0),
+            FrameTarget::Url("card".to_string()),
+        ]);
+        assert!(find_frame(&frames, &path).is_none());
+    }
+}
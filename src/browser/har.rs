@@ -0,0 +1,630 @@
+//! HAR 1.2 recording.
+//!
+//! `browser.har.start` subscribes to CDP Network events for a session and
+//! keeps one entry per request hop (redirects get their own entry).
+//! `browser.har.stop` ends the recording and serializes the entries as a HAR
+//! log that DevTools and other HAR viewers can open.
+//!
+//! Cookies are not broken out and header sizes are reported as -1 (unknown),
+//! both of which the spec allows.
+
+use anyhow::{Context, Result};
+use chromiumoxide::cdp::browser_protocol::network::{
+    EnableParams, EventDataReceived, EventLoadingFailed, EventLoadingFinished,
+    EventRequestWillBeSent, EventResponseReceived, Headers, Request, ResourceTiming, Response,
+};
+use chromiumoxide::page::Page;
+use chrono::{TimeZone, Utc};
+use futures::StreamExt;
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+
+use super::network::{post_body, prefer_left};
+
+/// Maximum number of entries recorded per session; later requests are dropped.
+const MAX_HAR_ENTRIES: usize = 10_000;
+
+/// A HAR 1.2 document.
+#[derive(Debug, Serialize)]
+pub struct Har {
+    pub log: HarLog,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HarLog {
+    pub version: String,
+    pub creator: HarCreator,
+    pub entries: Vec<HarEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HarCreator {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarEntry {
+    pub started_date_time: String,
+    /// Total time in milliseconds (sum of the non-negative timings)
+    pub time: f64,
+    pub request: HarRequest,
+    pub response: HarResponse,
+    pub cache: HarCache,
+    pub timings: HarTimings,
+    #[serde(rename = "serverIPAddress", skip_serializing_if = "Option::is_none")]
+    pub server_ip_address: Option<String>,
+    #[serde(rename = "_resourceType")]
+    pub resource_type: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarRequest {
+    pub method: String,
+    pub url: String,
+    pub http_version: String,
+    pub cookies: Vec<HarNameValue>,
+    pub headers: Vec<HarNameValue>,
+    pub query_string: Vec<HarNameValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<HarPostData>,
+    pub headers_size: i64,
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPostData {
+    pub mime_type: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarResponse {
+    pub status: i64,
+    pub status_text: String,
+    pub http_version: String,
+    pub cookies: Vec<HarNameValue>,
+    pub headers: Vec<HarNameValue>,
+    pub content: HarContent,
+    #[serde(rename = "redirectURL")]
+    pub redirect_url: String,
+    pub headers_size: i64,
+    pub body_size: i64,
+    /// Network error for failed requests (Chrome DevTools extension field)
+    #[serde(rename = "_error", skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarContent {
+    /// Decoded body size in bytes
+    pub size: i64,
+    pub mime_type: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HarCache {}
+
+/// Phase durations in milliseconds; -1 means the phase does not apply.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HarTimings {
+    pub blocked: f64,
+    pub dns: f64,
+    pub connect: f64,
+    pub send: f64,
+    pub wait: f64,
+    pub receive: f64,
+    pub ssl: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HarNameValue {
+    pub name: String,
+    pub value: String,
+}
+
+/// A request hop being recorded.
+#[derive(Debug)]
+struct PendingEntry {
+    request_id: String,
+    entry: HarEntry,
+    /// Monotonic start time (seconds)
+    start: f64,
+    /// Header bytes received with the response, subtracted from the body size
+    response_header_bytes: f64,
+    timing: Option<ResourceTiming>,
+    responded: bool,
+    done: bool,
+}
+
+/// Per-session HAR recording state.
+#[derive(Debug, Default)]
+pub struct HarState {
+    entries: Vec<PendingEntry>,
+    dropped: usize,
+    task: Option<JoinHandle<()>>,
+}
+
+impl HarState {
+    pub fn is_recording(&self) -> bool {
+        self.task.is_some()
+    }
+
+    /// Stop recording and build the HAR log. Returns the log and how many
+    /// requests were dropped because the entry limit was reached.
+    pub fn finish(&mut self) -> (Har, usize) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+
+        // Requests that never got a response have nothing useful to report
+        let entries = std::mem::take(&mut self.entries)
+            .into_iter()
+            .filter(|p| p.responded || p.done)
+            .map(|p| p.entry)
+            .collect();
+        let dropped = std::mem::take(&mut self.dropped);
+
+        let har = Har {
+            log: HarLog {
+                version: "1.2".to_string(),
+                creator: HarCreator {
+                    name: env!("CARGO_PKG_NAME").to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                },
+                entries,
+            },
+        };
+        (har, dropped)
+    }
+
+    fn find_mut(&mut self, request_id: &str) -> Option<&mut PendingEntry> {
+        self.entries
+            .iter_mut()
+            .rev()
+            .find(|p| p.request_id == request_id && !p.done)
+    }
+
+    fn on_request(&mut self, event: &EventRequestWillBeSent) {
+        let request_id = event.request_id.inner().clone();
+        let timestamp = *event.timestamp.inner();
+
+        // A redirect reuses the request ID; close out the previous hop first.
+        if let Some(redirect) = &event.redirect_response {
+            if let Some(hop) = self.find_mut(&request_id) {
+                hop.apply_response(redirect);
+                hop.entry.response.redirect_url = event.request.url.clone();
+                hop.finish(timestamp, redirect.encoded_data_length);
+            }
+        }
+
+        if self.entries.len() >= MAX_HAR_ENTRIES {
+            self.dropped += 1;
+            return;
+        }
+
+        let wall_ms = (*event.wall_time.inner() * 1000.0) as i64;
+        let started_date_time = Utc
+            .timestamp_millis_opt(wall_ms)
+            .single()
+            .unwrap_or_else(Utc::now)
+            .to_rfc3339();
+
+        self.entries.push(PendingEntry {
+            request_id,
+            entry: HarEntry {
+                started_date_time,
+                time: 0.0,
+                request: har_request(&event.request),
+                response: HarResponse {
+                    status: 0,
+                    status_text: String::new(),
+                    http_version: String::new(),
+                    cookies: Vec::new(),
+                    headers: Vec::new(),
+                    content: HarContent {
+                        size: 0,
+                        mime_type: String::new(),
+                    },
+                    redirect_url: String::new(),
+                    headers_size: -1,
+                    body_size: -1,
+                    error: None,
+                },
+                cache: HarCache::default(),
+                timings: har_timings(None, timestamp, timestamp),
+                server_ip_address: None,
+                resource_type: event
+                    .r#type
+                    .as_ref()
+                    .map(|t| t.as_ref().to_string())
+                    .unwrap_or_else(|| "Other".to_string()),
+            },
+            start: timestamp,
+            response_header_bytes: 0.0,
+            timing: None,
+            responded: false,
+            done: false,
+        });
+    }
+
+    fn on_response(&mut self, event: &EventResponseReceived) {
+        if let Some(pending) = self.find_mut(event.request_id.inner()) {
+            pending.apply_response(&event.response);
+            pending.entry.resource_type = event.r#type.as_ref().to_string();
+        }
+    }
+
+    fn on_data(&mut self, event: &EventDataReceived) {
+        if let Some(pending) = self.find_mut(event.request_id.inner()) {
+            pending.entry.response.content.size += event.data_length;
+        }
+    }
+
+    fn on_finished(&mut self, event: &EventLoadingFinished) {
+        if let Some(pending) = self.find_mut(event.request_id.inner()) {
+            pending.finish(*event.timestamp.inner(), event.encoded_data_length);
+        }
+    }
+
+    fn on_failed(&mut self, event: &EventLoadingFailed) {
+        if let Some(pending) = self.find_mut(event.request_id.inner()) {
+            pending.entry.response.error = Some(event.error_text.clone());
+            pending.finish(*event.timestamp.inner(), pending.response_header_bytes);
+        }
+    }
+}
+
+impl PendingEntry {
+    fn apply_response(&mut self, response: &Response) {
+        let http_version = http_version(response.protocol.as_deref());
+        let entry = &mut self.entry;
+
+        entry.request.http_version = http_version.clone();
+        entry.response.status = response.status;
+        entry.response.status_text = response.status_text.clone();
+        entry.response.http_version = http_version;
+        entry.response.headers = name_values(&response.headers);
+        entry.response.content.mime_type = response.mime_type.clone();
+        entry.server_ip_address = response.remote_ip_address.clone();
+
+        // Request headers as sent on the wire (including cookies), when available
+        if let Some(headers) = &response.request_headers {
+            entry.request.headers = name_values(headers);
+        }
+
+        self.response_header_bytes = response.encoded_data_length;
+        self.timing = response.timing.clone();
+        self.responded = true;
+    }
+
+    /// Close the hop at `end` with `encoded_length` bytes received in total.
+    fn finish(&mut self, end: f64, encoded_length: f64) {
+        let timings = har_timings(self.timing.as_ref(), self.start, end);
+        self.entry.time = total_time(&timings);
+        self.entry.timings = timings;
+        if self.responded {
+            self.entry.response.body_size = (encoded_length - self.response_header_bytes)
+                .max(0.0)
+                .round() as i64;
+        }
+        self.done = true;
+    }
+}
+
+/// Start recording network events from `page` into `state`. No-op if already recording.
+pub async fn start_recording(page: &Page, state: &Arc<RwLock<HarState>>) -> Result<()> {
+    let mut guard = state.write().await;
+    if guard.is_recording() {
+        return Ok(());
+    }
+
+    page.execute(EnableParams::default())
+        .await
+        .context("Failed to enable network events")?;
+
+    let requests = page
+        .event_listener::<EventRequestWillBeSent>()
+        .await
+        .context("Failed to subscribe to network events")?
+        .map(HarEvent::Request)
+        .boxed();
+    let responses = page
+        .event_listener::<EventResponseReceived>()
+        .await
+        .context("Failed to subscribe to network events")?
+        .map(HarEvent::Response)
+        .boxed();
+    let data = page
+        .event_listener::<EventDataReceived>()
+        .await
+        .context("Failed to subscribe to network events")?
+        .map(HarEvent::Data)
+        .boxed();
+    let finished = page
+        .event_listener::<EventLoadingFinished>()
+        .await
+        .context("Failed to subscribe to network events")?
+        .map(HarEvent::Finished)
+        .boxed();
+    let failed = page
+        .event_listener::<EventLoadingFailed>()
+        .await
+        .context("Failed to subscribe to network events")?
+        .map(HarEvent::Failed)
+        .boxed();
+
+    // Prefer earlier lifecycle events so a request is recorded before its response.
+    let mut events = prefer_left(
+        prefer_left(
+            prefer_left(prefer_left(requests, responses), data),
+            finished,
+        ),
+        failed,
+    );
+
+    guard.entries.clear();
+    guard.dropped = 0;
+
+    let task_state = state.clone();
+    guard.task = Some(tokio::spawn(async move {
+        while let Some(event) = events.next().await {
+            let mut state = task_state.write().await;
+            match event {
+                HarEvent::Request(e) => state.on_request(&e),
+                HarEvent::Response(e) => state.on_response(&e),
+                HarEvent::Data(e) => state.on_data(&e),
+                HarEvent::Finished(e) => state.on_finished(&e),
+                HarEvent::Failed(e) => state.on_failed(&e),
+            }
+        }
+    }));
+
+    Ok(())
+}
+
+/// Network events in the order they are applied to the recording.
+enum HarEvent {
+    Request(Arc<EventRequestWillBeSent>),
+    Response(Arc<EventResponseReceived>),
+    Data(Arc<EventDataReceived>),
+    Finished(Arc<EventLoadingFinished>),
+    Failed(Arc<EventLoadingFailed>),
+}
+
+fn har_request(request: &Request) -> HarRequest {
+    let headers = name_values(&request.headers);
+
+    let body = post_body(request);
+
+    let post_data = (!body.is_empty()).then(|| HarPostData {
+        mime_type: headers
+            .iter()
+            .find(|h| h.name.eq_ignore_ascii_case("content-type"))
+            .map(|h| h.value.clone())
+            .unwrap_or_default(),
+        text: String::from_utf8_lossy(&body).into_owned(),
+    });
+
+    HarRequest {
+        method: request.method.clone(),
+        url: request.url.clone(),
+        http_version: String::new(),
+        cookies: Vec::new(),
+        headers,
+        query_string: query_string(&request.url),
+        post_data,
+        headers_size: -1,
+        body_size: body.len() as i64,
+    }
+}
+
+/// CDP headers are a JSON object; repeated headers are joined with newlines.
+fn name_values(headers: &Headers) -> Vec<HarNameValue> {
+    let Some(map) = headers.inner().as_object() else {
+        return Vec::new();
+    };
+
+    map.iter()
+        .flat_map(|(name, value)| {
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            value
+                .split('\n')
+                .map(|v| HarNameValue {
+                    name: name.clone(),
+                    value: v.to_string(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Query parameters as they appear in the URL (not percent-decoded).
+fn query_string(url: &str) -> Vec<HarNameValue> {
+    let without_fragment = url.split('#').next().unwrap_or_default();
+    let Some((_, query)) = without_fragment.split_once('?') else {
+        return Vec::new();
+    };
+
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            HarNameValue {
+                name: name.to_string(),
+                value: value.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Map CDP's ALPN protocol names ("h2", "http/1.1") to HAR versions.
+fn http_version(protocol: Option<&str>) -> String {
+    match protocol {
+        Some("h2") => "HTTP/2".to_string(),
+        Some("h3") | Some("h3-29") => "HTTP/3".to_string(),
+        Some(p) if !p.is_empty() => p.to_ascii_uppercase(),
+        _ => String::new(),
+    }
+}
+
+/// Split a request's duration into HAR phases.
+///
+/// `start` and `end` are monotonic seconds. CDP timing offsets are
+/// milliseconds relative to `timing.request_time`, with -1 for phases that
+/// did not happen (e.g. DNS on a reused connection). Without timing data the
+/// whole duration counts as waiting.
+fn har_timings(timing: Option<&ResourceTiming>, start: f64, end: f64) -> HarTimings {
+    let phase = |from: f64, to: f64| {
+        if from >= 0.0 {
+            (to - from).max(0.0)
+        } else {
+            -1.0
+        }
+    };
+
+    let Some(t) = timing else {
+        return HarTimings {
+            blocked: -1.0,
+            dns: -1.0,
+            connect: -1.0,
+            send: 0.0,
+            wait: ((end - start) * 1000.0).max(0.0),
+            receive: 0.0,
+            ssl: -1.0,
+        };
+    };
+
+    // Queueing before the request started, plus any stall before the first phase
+    let queued = ((t.request_time - start) * 1000.0).max(0.0);
+    let first_phase = [t.dns_start, t.connect_start, t.send_start]
+        .into_iter()
+        .find(|offset| *offset >= 0.0)
+        .unwrap_or(0.0);
+
+    HarTimings {
+        blocked: queued + first_phase,
+        dns: phase(t.dns_start, t.dns_end),
+        connect: phase(t.connect_start, t.connect_end),
+        send: phase(t.send_start, t.send_end).max(0.0),
+        wait: (t.receive_headers_end - t.send_end).max(0.0),
+        receive: ((end - t.request_time) * 1000.0 - t.receive_headers_end).max(0.0),
+        ssl: phase(t.ssl_start, t.ssl_end),
+    }
+}
+
+/// Entry time is the sum of the phases; `ssl` is already part of `connect`.
+fn total_time(timings: &HarTimings) -> f64 {
+    [
+        timings.blocked,
+        timings.dns,
+        timings.connect,
+        timings.send,
+        timings.wait,
+        timings.receive,
+    ]
+    .into_iter()
+    .filter(|t| *t > 0.0)
+    .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing() -> ResourceTiming {
+        serde_json::from_value(serde_json::json!({
+            "requestTime": 10.0,
+            "proxyStart": -1.0, "proxyEnd": -1.0,
+            "dnsStart": 2.0, "dnsEnd": 7.0,
+            "connectStart": 7.0, "connectEnd": 30.0,
+            "sslStart": 15.0, "sslEnd": 30.0,
+            "workerStart": -1.0, "workerReady": -1.0,
+            "workerFetchStart": -1.0, "workerRespondWithSettled": -1.0,
+            "sendStart": 31.0, "sendEnd": 32.0,
+            "pushStart": 0.0, "pushEnd": 0.0,
+            "receiveHeadersStart": 100.0, "receiveHeadersEnd": 120.0
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_har_timings() {
+        // Request queued 5ms before the network stack picked it up, done 150ms later
+        let timings = har_timings(Some(&timing()), 9.995, 10.150);
+        assert_eq!(timings.blocked.round(), 7.0);
+        assert_eq!(timings.dns, 5.0);
+        assert_eq!(timings.connect, 23.0);
+        assert_eq!(timings.ssl, 15.0);
+        assert_eq!(timings.send, 1.0);
+        assert_eq!(timings.wait, 88.0);
+        assert_eq!(timings.receive.round(), 30.0);
+        assert_eq!(total_time(&timings).round(), 154.0);
+
+        // Reused connection: no DNS or connect phase
+        let mut reused = timing();
+        reused.dns_start = -1.0;
+        reused.dns_end = -1.0;
+        reused.connect_start = -1.0;
+        reused.connect_end = -1.0;
+        reused.ssl_start = -1.0;
+        reused.ssl_end = -1.0;
+        let timings = har_timings(Some(&reused), 10.0, 10.150);
+        assert_eq!(
+            (timings.dns, timings.connect, timings.ssl),
+            (-1.0, -1.0, -1.0)
+        );
+        assert_eq!(timings.blocked, 31.0);
+
+        let untimed = har_timings(None, 1.0, 1.25);
+        assert_eq!(untimed.wait, 250.0);
+        assert_eq!(total_time(&untimed), 250.0);
+    }
+
+    #[test]
+    fn test_query_string() {
+        let params = query_string("https://example.com/search?q=rust&page=2&flag#results");
+        let pairs: Vec<(&str, &str)> = params
+            .iter()
+            .map(|p| (p.name.as_str(), p.value.as_str()))
+            .collect();
+        assert_eq!(pairs, vec![("q", "rust"), ("page", "2"), ("flag", "")]);
+        assert!(query_string("https://example.com/").is_empty());
+    }
+
+    #[test]
+    fn test_name_values_splits_repeated_headers() {
+        let headers = Headers::new(serde_json::json!({
+            "Content-Type": "text/html",
+            "Set-Cookie": "a=1\nb=2"
+        }));
+        let values = name_values(&headers);
+        assert_eq!(values.len(), 3);
+        assert_eq!(
+            values
+                .iter()
+                .filter(|h| h.name == "Set-Cookie")
+                .map(|h| h.value.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a=1", "b=2"]
+        );
+    }
+
+    #[test]
+    fn test_http_version() {
+        assert_eq!(http_version(Some("h2")), "HTTP/2");
+        assert_eq!(http_version(Some("http/1.1")), "HTTP/1.1");
+        assert_eq!(http_version(None), "");
+    }
+}
@@ -0,0 +1,76 @@
+//! Closing sessions that callers forgot about.
+//!
+//! Every page action marks its session as used. With an idle timeout set
+//! (`session.set_idle_timeout` or `start --session-idle-timeout`), a cleanup
+//! task closes custom sessions unused for longer than that, the same way
+//! `session.close` does. The default session is never closed, and neither is
+//! one a call is using or waiting for (see [`SessionInUse`]), however long
+//! the call takes.
+
+use chromiumoxide::browser::Browser;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+use super::client::{retire_session, BrowserSession};
+use super::pool::SessionPool;
+
+/// How often the cleanup task looks for idle sessions.
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Whether a call is running or queued for a session, by session id. The
+/// service answers from its session lanes.
+pub type SessionInUse = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Close sessions idle for longer than `idle_timeout_secs` (0 = never).
+/// Stops once the client is dropped.
+pub fn spawn_idle_cleanup(
+    browser: Weak<Browser>,
+    sessions: Weak<RwLock<HashMap<String, BrowserSession>>>,
+    default_session_id: String,
+    idle_timeout_secs: Arc<AtomicU64>,
+    pool: Arc<SessionPool>,
+    in_use: Arc<Mutex<Option<SessionInUse>>>,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+            let (Some(browser), Some(sessions)) = (browser.upgrade(), sessions.upgrade()) else {
+                return;
+            };
+            let timeout = match idle_timeout_secs.load(Ordering::Relaxed) {
+                0 => continue,
+                secs => Duration::from_secs(secs),
+            };
+
+            let in_use = in_use.lock().unwrap().clone();
+            let busy = |id: &str| in_use.as_ref().is_some_and(|in_use| in_use(id));
+            let idle: Vec<BrowserSession> = {
+                let mut sessions = sessions.write().await;
+                let ids: Vec<String> = sessions
+                    .values()
+                    .filter(|s| s.id != default_session_id && s.idle_for() >= timeout)
+                    .filter(|s| !busy(&s.id))
+                    .map(|s| s.id.clone())
+                    .collect();
+                ids.iter().filter_map(|id| sessions.remove(id)).collect()
+            };
+            for session in idle {
+                tracing::info!(
+                    "Session {} idle for over {}s; closing it",
+                    session.id,
+                    timeout.as_secs()
+                );
+                let id = session.id.clone();
+                if let Err(e) = retire_session(&browser, &pool, session).await {
+                    tracing::warn!("Failed to close idle session {}: {:#}", id, e);
+                }
+            }
+        }
+    });
+}
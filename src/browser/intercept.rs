@@ -0,0 +1,434 @@
+//! Request interception via the CDP Fetch domain.
+//!
+//! Fetch is only enabled while a session has rules, and only for requests that
+//! could match them. Paused requests are re-checked against the rules: a
+//! matching mock fulfills the request, otherwise a matching block rule fails it
+//! (`BlockedByClient`), otherwise it is continued. Mocks win over blocks, so a
+//! mocked endpoint still answers while its host is blocked.
+//!
+//! When HTTP auth credentials are set, Fetch also handles auth challenges. That
+//! needs every request routed through Fetch, so credentials add a catch-all
+//! pattern; requests no rule matches are continued unchanged.
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use chromiumoxide::cdp::browser_protocol::fetch::{
+    AuthChallengeResponse, AuthChallengeResponseResponse, ContinueRequestParams,
+    ContinueWithAuthParams, DisableParams, EnableParams, EventAuthRequired, EventRequestPaused,
+    FailRequestParams, FulfillRequestParams, HeaderEntry, RequestId, RequestPattern,
+};
+use chromiumoxide::cdp::browser_protocol::network::{ErrorReason, ResourceType};
+use chromiumoxide::page::Page;
+use futures::stream;
+use futures::StreamExt;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+
+use super::network::url_matches;
+use crate::models::{BlockRules, HttpCredentials, MockRule};
+
+/// Per-session interception rules and the task answering paused requests.
+#[derive(Debug, Default)]
+pub struct InterceptState {
+    pub block: BlockRules,
+    /// Requests aborted by block rules since the session started
+    pub blocked_count: u64,
+    mocks: Vec<Mock>,
+    next_mock_id: u64,
+    /// Credentials answering HTTP auth challenges
+    pub credentials: Option<HttpCredentials>,
+    /// Requests already answered with credentials (a repeat means they were rejected)
+    auth_attempts: HashSet<RequestId>,
+    task: Option<JoinHandle<()>>,
+}
+
+/// Challenges remembered before the attempt set is reset.
+const MAX_AUTH_ATTEMPTS: usize = 1000;
+
+/// A mock rule and the body it serves.
+#[derive(Debug)]
+struct Mock {
+    rule: MockRule,
+    body: Vec<u8>,
+}
+
+/// What to do with a paused request.
+enum Decision {
+    Fulfill(FulfillRequestParams),
+    Block,
+    Continue,
+}
+
+impl InterceptState {
+    /// Drop all rules and credentials. Call `sync_interception` afterwards to
+    /// stop intercepting on the page.
+    pub fn reset(&mut self) {
+        *self = Self {
+            task: self.task.take(),
+            ..Self::default()
+        };
+    }
+
+    /// Add a mock, replacing any existing mock for the same URL pattern.
+    pub fn add_mock(
+        &mut self,
+        url_pattern: String,
+        status: u16,
+        headers: HashMap<String, String>,
+        body: Vec<u8>,
+    ) -> MockRule {
+        self.mocks.retain(|m| m.rule.url_pattern != url_pattern);
+        self.next_mock_id += 1;
+
+        let rule = MockRule {
+            id: format!("mock-{}", self.next_mock_id),
+            url_pattern,
+            status,
+            headers,
+            body_size: body.len(),
+            hit_count: 0,
+        };
+        self.mocks.push(Mock {
+            rule: rule.clone(),
+            body,
+        });
+        rule
+    }
+
+    /// Active mocks in the order they were added.
+    pub fn mocks(&self) -> Vec<MockRule> {
+        self.mocks.iter().map(|m| m.rule.clone()).collect()
+    }
+
+    /// Remove the mock with `id`, or every mock when `id` is None.
+    pub fn remove_mocks(&mut self, id: Option<&str>) -> Result<usize> {
+        let before = self.mocks.len();
+        match id {
+            Some(id) => {
+                self.mocks.retain(|m| m.rule.id != id);
+                if self.mocks.len() == before {
+                    anyhow::bail!("Mock not found: {}", id);
+                }
+            }
+            None => self.mocks.clear(),
+        }
+        Ok(before - self.mocks.len())
+    }
+
+    fn decide(&mut self, event: &EventRequestPaused) -> Decision {
+        let url = &event.request.url;
+
+        if let Some(mock) = self
+            .mocks
+            .iter_mut()
+            .find(|m| url_matches(&m.rule.url_pattern, url))
+        {
+            mock.rule.hit_count += 1;
+            let headers = mock
+                .rule
+                .headers
+                .iter()
+                .map(|(name, value)| HeaderEntry::new(name.clone(), value.clone()))
+                .collect();
+            let body = base64::engine::general_purpose::STANDARD.encode(&mock.body);
+
+            let mut params =
+                FulfillRequestParams::new(event.request_id.clone(), mock.rule.status as i64);
+            params.response_headers = Some(headers);
+            params.body = Some(body.into());
+            return Decision::Fulfill(params);
+        }
+
+        if self.should_block(url, event.resource_type.as_ref()) {
+            self.blocked_count += 1;
+            return Decision::Block;
+        }
+
+        Decision::Continue
+    }
+
+    /// Answer an auth challenge: provide credentials once per request, then
+    /// cancel so a wrong password fails instead of looping.
+    fn answer_auth(&mut self, request_id: &RequestId) -> AuthChallengeResponse {
+        let Some(credentials) = &self.credentials else {
+            return AuthChallengeResponse::new(AuthChallengeResponseResponse::Default);
+        };
+
+        if self.auth_attempts.len() >= MAX_AUTH_ATTEMPTS {
+            self.auth_attempts.clear();
+        }
+        if !self.auth_attempts.insert(request_id.clone()) {
+            return AuthChallengeResponse::new(AuthChallengeResponseResponse::CancelAuth);
+        }
+
+        AuthChallengeResponse {
+            response: AuthChallengeResponseResponse::ProvideCredentials,
+            username: Some(credentials.username.clone()),
+            password: Some(credentials.password.clone()),
+        }
+    }
+
+    fn should_block(&self, url: &str, resource_type: &str) -> bool {
+        self.block
+            .resource_types
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(resource_type))
+            || self.block.url_patterns.iter().any(|p| url_matches(p, url))
+    }
+
+    /// Fetch patterns covering every request a rule could match.
+    fn fetch_patterns(&self) -> Vec<RequestPattern> {
+        let urls = self.block.url_patterns.iter().map(|p| RequestPattern {
+            url_pattern: Some(to_fetch_pattern(p)),
+            resource_type: None,
+            request_stage: None,
+        });
+        let types = self.block.resource_types.iter().map(|t| RequestPattern {
+            url_pattern: None,
+            resource_type: ResourceType::from_str(t).ok(),
+            request_stage: None,
+        });
+        let mocks = self.mocks.iter().map(|m| RequestPattern {
+            url_pattern: Some(to_fetch_pattern(&m.rule.url_pattern)),
+            resource_type: None,
+            request_stage: None,
+        });
+        let auth = self.credentials.iter().map(|_| RequestPattern {
+            url_pattern: Some("*".to_string()),
+            resource_type: None,
+            request_stage: None,
+        });
+        urls.chain(types).chain(mocks).chain(auth).collect()
+    }
+}
+
+/// Normalize a resource type name ("images", "css", "xhr") to its CDP name.
+pub fn parse_resource_type(name: &str) -> Result<String> {
+    let lower = name.trim().to_ascii_lowercase();
+    let singular = match lower.as_str() {
+        "css" | "styles" => "stylesheet",
+        "js" => "script",
+        other => other.strip_suffix('s').unwrap_or(other),
+    };
+
+    ResourceType::from_str(singular)
+        .or_else(|_| ResourceType::from_str(&lower))
+        .map(|t| t.as_ref().to_string())
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "Unknown resource type '{}': expected e.g. image, font, media, script, stylesheet, xhr, fetch",
+                name
+            )
+        })
+}
+
+/// Fetch patterns are full-URL globs; our patterns without `*` are substrings.
+fn to_fetch_pattern(pattern: &str) -> String {
+    if pattern.contains('*') {
+        pattern.to_string()
+    } else {
+        format!("*{}*", pattern)
+    }
+}
+
+enum FetchEvent {
+    Paused(Arc<EventRequestPaused>),
+    Auth(Arc<EventAuthRequired>),
+}
+
+/// Enable or disable Fetch interception on `page` to match the current rules.
+pub async fn sync_interception(page: &Page, state: &Arc<RwLock<InterceptState>>) -> Result<()> {
+    let mut guard = state.write().await;
+    let patterns = guard.fetch_patterns();
+
+    if patterns.is_empty() {
+        if let Some(task) = guard.task.take() {
+            task.abort();
+            page.execute(DisableParams::default())
+                .await
+                .context("Failed to disable request interception")?;
+        }
+        return Ok(());
+    }
+
+    if guard.task.is_none() {
+        let paused = page
+            .event_listener::<EventRequestPaused>()
+            .await
+            .context("Failed to subscribe to paused requests")?
+            .map(FetchEvent::Paused);
+        let auth = page
+            .event_listener::<EventAuthRequired>()
+            .await
+            .context("Failed to subscribe to auth challenges")?
+            .map(FetchEvent::Auth);
+        let mut events = stream::select(paused, auth);
+
+        let page = page.clone();
+        let task_state = state.clone();
+        guard.task = Some(tokio::spawn(async move {
+            while let Some(event) = events.next().await {
+                let event = match event {
+                    FetchEvent::Paused(event) => event,
+                    FetchEvent::Auth(event) => {
+                        let response = task_state.write().await.answer_auth(&event.request_id);
+                        if let Err(e) = page
+                            .execute(ContinueWithAuthParams::new(
+                                event.request_id.clone(),
+                                response,
+                            ))
+                            .await
+                        {
+                            tracing::warn!(
+                                "Failed to answer auth challenge for {}: {}",
+                                event.request.url,
+                                e
+                            );
+                        }
+                        continue;
+                    }
+                };
+                let decision = task_state.write().await.decide(&event);
+
+                let result = match decision {
+                    Decision::Fulfill(params) => page.execute(params).await.map(|_| ()),
+                    Decision::Block => page
+                        .execute(FailRequestParams::new(
+                            event.request_id.clone(),
+                            ErrorReason::BlockedByClient,
+                        ))
+                        .await
+                        .map(|_| ()),
+                    Decision::Continue => page
+                        .execute(ContinueRequestParams::new(event.request_id.clone()))
+                        .await
+                        .map(|_| ()),
+                };
+
+                if let Err(e) = result {
+                    tracing::warn!(
+                        "Failed to answer paused request {}: {}",
+                        event.request.url,
+                        e
+                    );
+                }
+            }
+        }));
+    }
+
+    page.execute(EnableParams {
+        patterns: Some(patterns),
+        handle_auth_requests: Some(guard.credentials.is_some()),
+    })
+    .await
+    .context("Failed to enable request interception")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_resource_type() {
+        assert_eq!(parse_resource_type("images").unwrap(), "Image");
+        assert_eq!(parse_resource_type("Font").unwrap(), "Font");
+        assert_eq!(parse_resource_type("css").unwrap(), "Stylesheet");
+        assert_eq!(parse_resource_type("xhr").unwrap(), "XHR");
+        assert_eq!(parse_resource_type("media").unwrap(), "Media");
+        assert!(parse_resource_type("videos-and-stuff").is_err());
+    }
+
+    #[test]
+    fn test_should_block() {
+        let state = InterceptState {
+            block: BlockRules {
+                url_patterns: vec![
+                    "doubleclick.net".to_string(),
+                    "*/analytics/*.js".to_string(),
+                ],
+                resource_types: vec!["Image".to_string()],
+            },
+            ..Default::default()
+        };
+
+        assert!(state.should_block("https://ad.doubleclick.net/x", "Script"));
+        assert!(state.should_block("https://example.com/analytics/ga.js", "Script"));
+        assert!(state.should_block("https://example.com/logo.png", "Image"));
+        assert!(!state.should_block("https://example.com/app.js", "Script"));
+    }
+
+    #[test]
+    fn test_mocks() {
+        let mut state = InterceptState::default();
+        let first = state.add_mock(
+            "/api/users".to_string(),
+            200,
+            HashMap::new(),
+            b"[]".to_vec(),
+        );
+        let second = state.add_mock(
+            "*/api/orders/*".to_string(),
+            503,
+            HashMap::new(),
+            Vec::new(),
+        );
+        assert_eq!(first.id, "mock-1");
+        assert_eq!(first.body_size, 2);
+        assert_eq!(second.id, "mock-2");
+
+        // Same pattern replaces the existing mock
+        let replaced = state.add_mock("/api/users".to_string(), 404, HashMap::new(), Vec::new());
+        assert_eq!(replaced.id, "mock-3");
+        let ids: Vec<String> = state.mocks().into_iter().map(|m| m.id).collect();
+        assert_eq!(ids, vec!["mock-2", "mock-3"]);
+        assert_eq!(state.fetch_patterns().len(), 2);
+
+        assert!(state.remove_mocks(Some("mock-1")).is_err());
+        assert_eq!(state.remove_mocks(Some("mock-2")).unwrap(), 1);
+        assert_eq!(state.remove_mocks(None).unwrap(), 1);
+        assert!(state.mocks().is_empty());
+    }
+
+    #[test]
+    fn test_answer_auth() {
+        let mut state = InterceptState::default();
+        let request = RequestId::new("interception-1");
+        assert_eq!(
+            state.answer_auth(&request).response,
+            AuthChallengeResponseResponse::Default
+        );
+
+        state.credentials = Some(HttpCredentials {
+            username: "admin".to_string(),
+            password: "secret".to_string(),
+        });
+        assert_eq!(state.fetch_patterns().len(), 1);
+
+        let first = state.answer_auth(&request);
+        assert_eq!(
+            first.response,
+            AuthChallengeResponseResponse::ProvideCredentials
+        );
+        assert_eq!(first.username.as_deref(), Some("admin"));
+
+        // A second challenge for the same request means the credentials were rejected
+        assert_eq!(
+            state.answer_auth(&request).response,
+            AuthChallengeResponseResponse::CancelAuth
+        );
+    }
+
+    #[test]
+    fn test_to_fetch_pattern() {
+        assert_eq!(to_fetch_pattern("ads"), "*ads*");
+        assert_eq!(
+            to_fetch_pattern("https://*.ads.com/*"),
+            "https://*.ads.com/*"
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! Starting the user's own Chrome with remote debugging for `--connect-launch`.
+//!
+//! When nothing answers on the debugging port, the installed Chrome is started
+//! with `--remote-debugging-port` and the user's profile, so connect mode sees
+//! their logins. A Chrome that is already running can't open a debugging port
+//! later, and a second instance on the same profile just hands its arguments
+//! to the first, so that case fails with instructions instead.
+
+use anyhow::{Context, Result};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use super::client::{chrome_path_override, system_browser_paths, BrowserClient};
+use crate::models::BrowserChannel;
+
+/// How long Chrome gets to open its debugging port.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(20);
+const STARTUP_POLL_INTERVAL: Duration = Duration::from_millis(250);
+const PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Make sure a Chrome with remote debugging listens on `port`, launching
+/// the user's Chrome if needed. Returns the executable when one was launched.
+///
+/// `profile` defaults to the browser's own profile directory.
+pub fn ensure_debug_chrome(
+    port: u16,
+    channel: Option<BrowserChannel>,
+    profile: Option<&Path>,
+) -> Result<Option<PathBuf>> {
+    if endpoint_up(port) {
+        return Ok(None);
+    }
+
+    let executable = match channel {
+        Some(channel) => BrowserClient::find_channel_executable(channel)?,
+        None => find_user_chrome()?,
+    };
+    let profile = match profile {
+        Some(profile) => profile.to_path_buf(),
+        None => default_profile_dir(&executable, std::env::consts::OS).with_context(|| {
+            format!(
+                "Don't know the profile directory of {}; pass --connect-profile",
+                executable.display()
+            )
+        })?,
+    };
+
+    if profile_in_use(&profile) {
+        anyhow::bail!(
+            "Chrome is already running without remote debugging, and a running Chrome \
+             can't open a debugging port.\n\
+             Quit it completely and run this again, or restart it yourself with:\n  \
+             \"{}\" --remote-debugging-port={}\n\
+             then use --connect http://127.0.0.1:{}",
+            executable.display(),
+            port,
+            port
+        );
+    }
+
+    let mut command = Command::new(&executable);
+    command
+        .arg(format!("--remote-debugging-port={}", port))
+        .arg(format!("--user-data-dir={}", profile.display()))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    #[cfg(unix)]
+    {
+        // Keep Chrome running after the CLI exits or is interrupted
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    command
+        .spawn()
+        .with_context(|| format!("Failed to launch {}", executable.display()))?;
+    tracing::info!(
+        "Launched {:?} with remote debugging on port {}",
+        executable,
+        port
+    );
+
+    let deadline = Instant::now() + STARTUP_TIMEOUT;
+    while Instant::now() < deadline {
+        if endpoint_up(port) {
+            return Ok(Some(executable));
+        }
+        std::thread::sleep(STARTUP_POLL_INTERVAL);
+    }
+
+    anyhow::bail!(
+        "Launched {} but nothing answered on port {} within {}s. \
+         Chrome 136+ ignores --remote-debugging-port for its default profile; \
+         pass --connect-profile with a separate directory (log in there once)",
+        executable.display(),
+        port,
+        STARTUP_TIMEOUT.as_secs()
+    )
+}
+
+/// The user's installed browser: `FGP_CHROME`/`CHROME_PATH`, then the
+/// standard Chrome, Chromium and Edge locations (never chrome-headless-shell).
+fn find_user_chrome() -> Result<PathBuf> {
+    if let Some(path) = chrome_path_override()? {
+        return Ok(path);
+    }
+    system_browser_paths()
+        .into_iter()
+        .find(|p| p.exists())
+        .context("Chrome not found; install it, or set FGP_CHROME to its executable")
+}
+
+/// Whether a DevTools HTTP endpoint answers on localhost:`port`.
+fn endpoint_up(port: u16) -> bool {
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, PROBE_TIMEOUT) else {
+        return false;
+    };
+    let _ = stream.set_read_timeout(Some(PROBE_TIMEOUT));
+    if stream
+        .write_all(b"GET /json/version HTTP/1.0\r\nHost: 127.0.0.1\r\n\r\n")
+        .is_err()
+    {
+        return false;
+    }
+
+    let mut response = String::new();
+    let _ = stream.read_to_string(&mut response);
+    response.split_whitespace().nth(1) == Some("200")
+}
+
+/// Default profile directory of the browser at `executable` on `os`.
+fn default_profile_dir(executable: &Path, os: &str) -> Option<PathBuf> {
+    let path = executable
+        .to_string_lossy()
+        .to_lowercase()
+        .replace(' ', "-");
+    let has = |tokens: &[&str]| tokens.iter().any(|t| path.contains(t));
+    let variant = if has(&["canary"]) {
+        "canary"
+    } else if has(&["chrome-beta"]) {
+        "beta"
+    } else if has(&["chrome-dev", "chrome-unstable"]) {
+        "dev"
+    } else if has(&["chromium"]) {
+        "chromium"
+    } else if has(&["msedge", "microsoft-edge"]) {
+        "edge"
+    } else {
+        "chrome"
+    };
+
+    let relative = match (os, variant) {
+        ("macos", "chrome") => "Google/Chrome",
+        ("macos", "beta") => "Google/Chrome Beta",
+        ("macos", "dev") => "Google/Chrome Dev",
+        ("macos", "canary") => "Google/Chrome Canary",
+        ("macos", "chromium") => "Chromium",
+        ("macos", "edge") => "Microsoft Edge",
+        ("windows", "chrome") => r"Google\Chrome\User Data",
+        ("windows", "beta") => r"Google\Chrome Beta\User Data",
+        ("windows", "dev") => r"Google\Chrome Dev\User Data",
+        ("windows", "canary") => r"Google\Chrome SxS\User Data",
+        ("windows", "chromium") => r"Chromium\User Data",
+        ("windows", "edge") => r"Microsoft\Edge\User Data",
+        (_, "chrome") => "google-chrome",
+        (_, "beta") => "google-chrome-beta",
+        (_, "dev") => "google-chrome-unstable",
+        (_, "chromium") => "chromium",
+        (_, "edge") => "microsoft-edge",
+        _ => return None,
+    };
+
+    // ~/Library/Application Support, %LOCALAPPDATA% or ~/.config
+    let base = if os == "windows" {
+        dirs::data_local_dir()?
+    } else {
+        dirs::config_dir()?
+    };
+    Some(base.join(relative))
+}
+
+/// Whether a running Chrome holds the profile's singleton lock.
+fn profile_in_use(profile: &Path) -> bool {
+    if cfg!(windows) {
+        return profile.join("lockfile").exists();
+    }
+
+    // A symlink to "<hostname>-<pid>"; a crash can leave it behind
+    let Ok(target) = std::fs::read_link(profile.join("SingletonLock")) else {
+        return false;
+    };
+    match lock_pid(&target.to_string_lossy()) {
+        Some(pid) => Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .stderr(Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(true),
+        None => true,
+    }
+}
+
+/// Process ID in a `SingletonLock` target.
+fn lock_pid(target: &str) -> Option<u32> {
+    target.rsplit_once('-')?.1.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_profile_dir() {
+        let dir = |exe: &str, os| default_profile_dir(Path::new(exe), os).unwrap();
+
+        assert!(dir("/usr/bin/google-chrome-stable", "linux").ends_with("google-chrome"));
+        assert!(dir("/usr/bin/chromium-browser", "linux").ends_with("chromium"));
+        assert!(dir(
+            "/Applications/Google Chrome Canary.app/Contents/MacOS/Google Chrome Canary",
+            "macos"
+        )
+        .ends_with("Google/Chrome Canary"));
+        assert!(dir(
+            r"C:\Program Files\Microsoft\Edge\Application\msedge.exe",
+            "windows"
+        )
+        .ends_with(r"Microsoft\Edge\User Data"));
+    }
+
+    #[test]
+    fn test_lock_pid() {
+        assert_eq!(lock_pid("my-laptop.local-4242"), Some(4242));
+        assert_eq!(lock_pid("garbage"), None);
+    }
+}
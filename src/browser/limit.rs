@@ -0,0 +1,105 @@
+//! Cap on open sessions.
+//!
+//! Each session owns a browser context, and an agent creating sessions in a
+//! loop can take Chrome down. Once `--max-sessions` custom sessions are open,
+//! `session.new` fails with SESSION_LIMIT_REACHED and names the least
+//! recently used session as the one to close. With `evict_idle`, it closes
+//! that session itself to make room.
+
+use std::time::Duration;
+
+/// Custom sessions allowed unless `--max-sessions` says otherwise.
+pub const DEFAULT_MAX_SESSIONS: usize = 20;
+
+/// What creating one more session takes.
+#[derive(Debug, PartialEq)]
+pub enum SessionLimit {
+    /// Below the limit, or no limit
+    Room,
+    /// Close this (least recently used) session first
+    Evict(String),
+    /// At the limit and not allowed to evict
+    Reached {
+        count: usize,
+        max: usize,
+        least_recent: Option<String>,
+    },
+}
+
+/// Check the limit against the open custom sessions, given as id and time
+/// since last use. `max` 0 means no limit.
+pub fn check_limit(open: &[(String, Duration)], max: usize, evict_idle: bool) -> SessionLimit {
+    if max == 0 || open.len() < max {
+        return SessionLimit::Room;
+    }
+    let least_recent = open
+        .iter()
+        .max_by_key(|(_, idle)| *idle)
+        .map(|(id, _)| id.clone());
+    match least_recent {
+        Some(id) if evict_idle => SessionLimit::Evict(id),
+        least_recent => SessionLimit::Reached {
+            count: open.len(),
+            max,
+            least_recent,
+        },
+    }
+}
+
+/// Error for `session.new` at the limit.
+pub fn limit_reached(count: usize, max: usize, least_recent: Option<&str>) -> anyhow::Error {
+    let hint = match least_recent {
+        Some(id) => format!("close one first (least recently used: '{}')", id),
+        None => "close one first".to_string(),
+    };
+    anyhow::anyhow!(
+        "SESSION_LIMIT_REACHED: {} sessions open (max {}); {}, or pass evict_idle: true",
+        count,
+        max,
+        hint
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open(sessions: &[(&str, u64)]) -> Vec<(String, Duration)> {
+        sessions
+            .iter()
+            .map(|(id, idle)| (id.to_string(), Duration::from_secs(*idle)))
+            .collect()
+    }
+
+    #[test]
+    fn test_limit_reached_names_least_recent_session() {
+        let sessions = open(&[("a", 5), ("b", 90), ("c", 30)]);
+        assert_eq!(check_limit(&sessions[..2], 3, false), SessionLimit::Room);
+
+        let limit = check_limit(&sessions, 3, false);
+        assert_eq!(
+            limit,
+            SessionLimit::Reached {
+                count: 3,
+                max: 3,
+                least_recent: Some("b".to_string())
+            }
+        );
+        let message = limit_reached(3, 3, Some("b")).to_string();
+        assert!(message.starts_with("SESSION_LIMIT_REACHED: 3 sessions open"));
+        assert!(message.contains("'b'"));
+
+        // No limit
+        assert_eq!(check_limit(&sessions, 0, false), SessionLimit::Room);
+    }
+
+    #[test]
+    fn test_evict_idle_picks_least_recent_session() {
+        let sessions = open(&[("a", 5), ("b", 90)]);
+        assert_eq!(
+            check_limit(&sessions, 2, true),
+            SessionLimit::Evict("b".to_string())
+        );
+        assert_eq!(check_limit(&sessions, 3, true), SessionLimit::Room);
+    }
+}
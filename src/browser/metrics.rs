@@ -0,0 +1,56 @@
+//! Page performance numbers for `browser.metrics`.
+//!
+//! Combines CDP `Performance.getMetrics` (script duration, layout count,
+//! heap size, ...) with the page's Navigation Timing and paint entries.
+
+use anyhow::{Context, Result};
+use chromiumoxide::cdp::browser_protocol::performance::{EnableParams, GetMetricsParams};
+use chromiumoxide::page::Page;
+
+use crate::models::{NavigationTiming, PageMetrics};
+
+/// Milliseconds since navigation start, or null for events that haven't
+/// happened yet.
+const TIMING_SCRIPT: &str = r#"(() => {
+    const nav = performance.getEntriesByType('navigation')[0];
+    const fcp = performance.getEntriesByName('first-contentful-paint')[0];
+    const at = (ms) => (ms > 0 ? ms : null);
+    return {
+        dom_content_loaded_ms: nav ? at(nav.domContentLoadedEventEnd) : null,
+        load_ms: nav ? at(nav.loadEventEnd) : null,
+        first_contentful_paint_ms: fcp ? fcp.startTime : null,
+    };
+})()"#;
+
+/// Collect `page`'s runtime metrics and navigation timing.
+pub async fn collect_metrics(page: &Page) -> Result<PageMetrics> {
+    let mut metrics = page
+        .execute(GetMetricsParams::default())
+        .await
+        .context("Failed to get performance metrics")?
+        .result
+        .metrics;
+    // The domain reports nothing until enabled, which happens on first use
+    if metrics.is_empty() {
+        page.execute(EnableParams::default())
+            .await
+            .context("Failed to enable performance metrics")?;
+        metrics = page
+            .execute(GetMetricsParams::default())
+            .await?
+            .result
+            .metrics;
+    }
+
+    let timing: NavigationTiming = page
+        .evaluate(TIMING_SCRIPT)
+        .await
+        .context("Failed to read navigation timing")?
+        .into_value()?;
+
+    Ok(PageMetrics {
+        url: page.url().await?.unwrap_or_default(),
+        metrics: metrics.into_iter().map(|m| (m.name, m.value)).collect(),
+        timing,
+    })
+}
@@ -2,5 +2,33 @@
 
 mod aria;
 mod client;
+mod console;
+mod content;
+mod dialog;
+mod downloads;
+mod emulation;
+mod fetch;
+mod frames;
+mod har;
+mod idle;
+mod intercept;
+mod launch;
+mod limit;
+mod metrics;
+mod network;
+mod origin_storage;
+mod pool;
+mod popups;
+mod reconnect;
+mod uploads;
 
-pub use client::BrowserClient;
+pub use aria::{diff_aria_snapshots, render_as_markdown};
+pub use client::{parse_connect_endpoint, BrowserClient, TabQuery};
+pub use console::DEFAULT_CONSOLE_BUFFER;
+pub use emulation::{
+    device_preset, device_preset_names, network_profile, network_profile_names, parse_permission,
+};
+pub use idle::SessionInUse;
+pub use intercept::parse_resource_type;
+pub use launch::ensure_debug_chrome;
+pub use limit::DEFAULT_MAX_SESSIONS;
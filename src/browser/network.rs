@@ -0,0 +1,609 @@
+//! Network request capture.
+//!
+//! Capture is off by default. When enabled for a session, a background task
+//! records CDP Network events into a bounded ring buffer that
+//! `browser.network.list` can filter.
+//!
+//! [`NetworkCapture`] is a separate, short-lived recorder for checking which
+//! requests a page action fires: it keeps outgoing requests with their headers
+//! and bodies between `capture_start` and `capture_stop`.
+//!
+//! [`set_blocked_urls`] blocks URL patterns in Chrome's network stack. Unlike
+//! the Fetch-based block rules in `intercept`, requests are never paused, so
+//! it costs nothing per request, but it can only match URLs.
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use chromiumoxide::cdp::browser_protocol::network::{
+    EnableParams, EventLoadingFailed, EventLoadingFinished, EventRequestWillBeSent,
+    EventResponseReceived, Headers, Request, SetBlockedUrLsParams, SetBlockedUrLsReturns,
+};
+use chromiumoxide::page::Page;
+use chromiumoxide::types::{Command, Method, MethodId};
+use chrono::{TimeZone, Utc};
+use futures::stream::{self, BoxStream, PollNext};
+use futures::StreamExt;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+
+use crate::models::{CapturedRequest, NetworkBody, NetworkFilter, NetworkRequest};
+
+/// Maximum number of requests kept per session.
+const MAX_NETWORK_REQUESTS: usize = 500;
+
+/// Maximum number of requests buffered by a [`NetworkCapture`].
+const MAX_CAPTURED_REQUESTS: usize = 1000;
+
+/// Per-session capture state and ring buffer of recent requests.
+#[derive(Debug, Default)]
+pub struct NetworkState {
+    pub requests: VecDeque<NetworkRequest>,
+    /// Monotonic start time (seconds) of each buffered request, for durations
+    started: VecDeque<f64>,
+    task: Option<JoinHandle<()>>,
+}
+
+impl NetworkState {
+    pub fn is_enabled(&self) -> bool {
+        self.task.is_some()
+    }
+
+    /// Stop capturing. Already captured requests are kept.
+    pub fn disable(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+
+    /// Drop all captured requests, returning how many were removed.
+    pub fn clear(&mut self) -> usize {
+        let count = self.requests.len();
+        self.requests.clear();
+        self.started.clear();
+        count
+    }
+
+    /// Captured requests matching `filter`, oldest first.
+    pub fn list(&self, filter: &NetworkFilter) -> Vec<NetworkRequest> {
+        let matched: Vec<NetworkRequest> = self
+            .requests
+            .iter()
+            .filter(|r| matches_filter(r, filter))
+            .cloned()
+            .collect();
+
+        match filter.limit {
+            Some(limit) if matched.len() > limit => matched[matched.len() - limit..].to_vec(),
+            _ => matched,
+        }
+    }
+
+    fn push(&mut self, request: NetworkRequest, started: f64) {
+        if self.requests.len() >= MAX_NETWORK_REQUESTS {
+            self.requests.pop_front();
+            self.started.pop_front();
+        }
+        self.requests.push_back(request);
+        self.started.push_back(started);
+    }
+
+    /// Latest captured entry for a request ID.
+    pub fn get(&self, request_id: &str) -> Option<&NetworkRequest> {
+        self.requests
+            .iter()
+            .rev()
+            .find(|r| r.request_id == request_id)
+    }
+
+    /// Latest entry for a request ID (redirect hops share the ID).
+    fn find_mut(&mut self, request_id: &str) -> Option<(&mut NetworkRequest, f64)> {
+        let idx = self
+            .requests
+            .iter()
+            .rposition(|r| r.request_id == request_id)?;
+        let started = self.started[idx];
+        Some((&mut self.requests[idx], started))
+    }
+
+    fn on_request(&mut self, event: &EventRequestWillBeSent) {
+        let request_id = event.request_id.inner().clone();
+        let timestamp = *event.timestamp.inner();
+
+        // A redirect reuses the request ID; close out the previous hop first.
+        if let Some(redirect) = &event.redirect_response {
+            if let Some((hop, started)) = self.find_mut(&request_id) {
+                hop.status = Some(redirect.status);
+                hop.mime_type = Some(redirect.mime_type.clone());
+                hop.encoded_data_length = redirect.encoded_data_length.max(0.0) as u64;
+                hop.duration_ms = Some(elapsed_ms(started, timestamp));
+            }
+        }
+
+        let wall_ms = (*event.wall_time.inner() * 1000.0) as i64;
+        let started_at = Utc
+            .timestamp_millis_opt(wall_ms)
+            .single()
+            .unwrap_or_else(Utc::now)
+            .to_rfc3339();
+
+        self.push(
+            NetworkRequest {
+                request_id,
+                url: event.request.url.clone(),
+                method: event.request.method.clone(),
+                resource_type: event
+                    .r#type
+                    .as_ref()
+                    .map(|t| t.as_ref().to_string())
+                    .unwrap_or_else(|| "Other".to_string()),
+                status: None,
+                mime_type: None,
+                encoded_data_length: 0,
+                duration_ms: None,
+                error: None,
+                started_at,
+            },
+            timestamp,
+        );
+    }
+
+    fn on_response(&mut self, event: &EventResponseReceived) {
+        if let Some((request, _)) = self.find_mut(event.request_id.inner()) {
+            request.status = Some(event.response.status);
+            request.mime_type = Some(event.response.mime_type.clone());
+            request.resource_type = event.r#type.as_ref().to_string();
+        }
+    }
+
+    fn on_finished(&mut self, event: &EventLoadingFinished) {
+        if let Some((request, started)) = self.find_mut(event.request_id.inner()) {
+            request.encoded_data_length = event.encoded_data_length.max(0.0) as u64;
+            request.duration_ms = Some(elapsed_ms(started, *event.timestamp.inner()));
+        }
+    }
+
+    fn on_failed(&mut self, event: &EventLoadingFailed) {
+        if let Some((request, started)) = self.find_mut(event.request_id.inner()) {
+            request.error = Some(event.error_text.clone());
+            request.duration_ms = Some(elapsed_ms(started, *event.timestamp.inner()));
+        }
+    }
+}
+
+/// Network events in the order they are applied to the buffer.
+enum NetworkEvent {
+    Request(Arc<EventRequestWillBeSent>),
+    Response(Arc<EventResponseReceived>),
+    Finished(Arc<EventLoadingFinished>),
+    Failed(Arc<EventLoadingFailed>),
+}
+
+/// Start recording network events from `page` into `state`. No-op if already enabled.
+pub async fn enable_capture(page: &Page, state: &Arc<RwLock<NetworkState>>) -> Result<()> {
+    let mut guard = state.write().await;
+    if guard.is_enabled() {
+        return Ok(());
+    }
+
+    page.execute(EnableParams::default())
+        .await
+        .context("Failed to enable network events")?;
+
+    let requests = page
+        .event_listener::<EventRequestWillBeSent>()
+        .await
+        .context("Failed to subscribe to network events")?
+        .map(NetworkEvent::Request)
+        .boxed();
+    let responses = page
+        .event_listener::<EventResponseReceived>()
+        .await
+        .context("Failed to subscribe to network events")?
+        .map(NetworkEvent::Response)
+        .boxed();
+    let finished = page
+        .event_listener::<EventLoadingFinished>()
+        .await
+        .context("Failed to subscribe to network events")?
+        .map(NetworkEvent::Finished)
+        .boxed();
+    let failed = page
+        .event_listener::<EventLoadingFailed>()
+        .await
+        .context("Failed to subscribe to network events")?
+        .map(NetworkEvent::Failed)
+        .boxed();
+
+    // Prefer earlier lifecycle events so a request is recorded before its response.
+    let mut events = prefer_left(
+        prefer_left(prefer_left(requests, responses), finished),
+        failed,
+    );
+
+    let task_state = state.clone();
+    guard.task = Some(tokio::spawn(async move {
+        while let Some(event) = events.next().await {
+            let mut state = task_state.write().await;
+            match event {
+                NetworkEvent::Request(e) => state.on_request(&e),
+                NetworkEvent::Response(e) => state.on_response(&e),
+                NetworkEvent::Finished(e) => state.on_finished(&e),
+                NetworkEvent::Failed(e) => state.on_failed(&e),
+            }
+        }
+    }));
+
+    Ok(())
+}
+
+/// Outgoing requests recorded during a page action.
+#[derive(Debug, Default)]
+pub struct NetworkCapture {
+    requests: Vec<CapturedRequest>,
+    task: Option<JoinHandle<()>>,
+}
+
+impl NetworkCapture {
+    pub fn is_active(&self) -> bool {
+        self.task.is_some()
+    }
+
+    /// Stop recording and drain the buffer.
+    pub fn stop(&mut self) -> Vec<CapturedRequest> {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+        std::mem::take(&mut self.requests)
+    }
+
+    fn on_request(&mut self, event: &EventRequestWillBeSent) {
+        if self.requests.len() >= MAX_CAPTURED_REQUESTS {
+            return;
+        }
+
+        let body = post_body(&event.request);
+        let wall_ms = (*event.wall_time.inner() * 1000.0) as i64;
+        self.requests.push(CapturedRequest {
+            url: event.request.url.clone(),
+            method: event.request.method.clone(),
+            headers: header_map(&event.request.headers),
+            body: (!body.is_empty()).then(|| String::from_utf8_lossy(&body).into_owned()),
+            timestamp: Utc
+                .timestamp_millis_opt(wall_ms)
+                .single()
+                .unwrap_or_else(Utc::now)
+                .to_rfc3339(),
+        });
+    }
+}
+
+/// Start recording outgoing requests from `page` into `capture`, replacing
+/// anything recorded by an earlier capture.
+pub async fn start_request_capture(
+    page: &Page,
+    capture: &Arc<RwLock<NetworkCapture>>,
+) -> Result<()> {
+    let mut guard = capture.write().await;
+    guard.stop();
+
+    page.execute(EnableParams::default())
+        .await
+        .context("Failed to enable network events")?;
+
+    let mut requests = page
+        .event_listener::<EventRequestWillBeSent>()
+        .await
+        .context("Failed to subscribe to network events")?;
+
+    let task_capture = capture.clone();
+    guard.task = Some(tokio::spawn(async move {
+        while let Some(event) = requests.next().await {
+            task_capture.write().await.on_request(&event);
+        }
+    }));
+
+    Ok(())
+}
+
+/// Request body bytes from the CDP post data entries (empty if none).
+pub(crate) fn post_body(request: &Request) -> Vec<u8> {
+    request
+        .post_data_entries
+        .iter()
+        .flatten()
+        .filter_map(|entry| entry.bytes.as_ref())
+        .filter_map(|bytes| {
+            let encoded: &str = bytes.as_ref();
+            base64::engine::general_purpose::STANDARD
+                .decode(encoded)
+                .ok()
+        })
+        .flatten()
+        .collect()
+}
+
+/// CDP headers as a name → value map.
+/// `Network.setBlockedURLs` that always sends `urls`. The generated params
+/// leave out an empty list, which Chrome rejects, so unblocking needs this.
+#[derive(Debug, Serialize)]
+struct SetBlockedUrls {
+    urls: Vec<String>,
+}
+
+impl Method for SetBlockedUrls {
+    fn identifier(&self) -> MethodId {
+        SetBlockedUrLsParams::IDENTIFIER.into()
+    }
+}
+
+impl Command for SetBlockedUrls {
+    type Response = SetBlockedUrLsReturns;
+}
+
+/// Block requests from `page` whose URL matches any of `patterns` (`*`
+/// wildcards), replacing the previous patterns. An empty list unblocks.
+pub async fn set_blocked_urls(page: &Page, patterns: &[String]) -> Result<()> {
+    page.execute(EnableParams::default())
+        .await
+        .context("Failed to enable network events")?;
+    page.execute(SetBlockedUrls {
+        urls: patterns.to_vec(),
+    })
+    .await
+    .context("Failed to set blocked URLs")?;
+    Ok(())
+}
+
+fn header_map(headers: &Headers) -> HashMap<String, String> {
+    let Some(map) = headers.inner().as_object() else {
+        return HashMap::new();
+    };
+
+    map.iter()
+        .map(|(name, value)| {
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+/// Merge two event streams, draining `left` first whenever both are ready.
+pub(crate) fn prefer_left<T: Send + 'static>(
+    left: BoxStream<'static, T>,
+    right: BoxStream<'static, T>,
+) -> BoxStream<'static, T> {
+    stream::select_with_strategy(left, right, |_: &mut ()| PollNext::Left).boxed()
+}
+
+/// Build a `NetworkBody` from a `Network.getResponseBody` result.
+///
+/// Base64 bodies are decoded; if the bytes are valid UTF-8 they are returned
+/// as text, otherwise they stay base64. The body is cut at `max_bytes`
+/// (of decoded content).
+pub fn decode_body(
+    request: &NetworkRequest,
+    body: String,
+    base64_encoded: bool,
+    max_bytes: usize,
+) -> Result<NetworkBody> {
+    let bytes = if base64_encoded {
+        base64::engine::general_purpose::STANDARD
+            .decode(body.as_bytes())
+            .context("Failed to decode response body")?
+    } else {
+        body.into_bytes()
+    };
+
+    let size_bytes = bytes.len();
+    let truncated = size_bytes > max_bytes;
+
+    let (body, base64_encoded) = match String::from_utf8(bytes) {
+        Ok(mut text) => {
+            if truncated {
+                let mut end = max_bytes;
+                while !text.is_char_boundary(end) {
+                    end -= 1;
+                }
+                text.truncate(end);
+            }
+            (text, false)
+        }
+        Err(e) => {
+            let bytes = e.into_bytes();
+            let kept = &bytes[..size_bytes.min(max_bytes)];
+            (base64::engine::general_purpose::STANDARD.encode(kept), true)
+        }
+    };
+
+    Ok(NetworkBody {
+        request_id: request.request_id.clone(),
+        url: request.url.clone(),
+        mime_type: request.mime_type.clone(),
+        body,
+        base64_encoded,
+        size_bytes,
+        truncated,
+    })
+}
+
+fn elapsed_ms(start: f64, end: f64) -> f64 {
+    ((end - start) * 1000.0).max(0.0)
+}
+
+fn matches_filter(request: &NetworkRequest, filter: &NetworkFilter) -> bool {
+    if let Some(pattern) = &filter.url_pattern {
+        if !url_matches(pattern, &request.url) {
+            return false;
+        }
+    }
+    if let Some(status) = &filter.status {
+        if !status.matches(request) {
+            return false;
+        }
+    }
+    if let Some(resource_type) = &filter.resource_type {
+        if !request.resource_type.eq_ignore_ascii_case(resource_type) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Substring match, or a glob match when the pattern contains `*`.
+pub(crate) fn url_matches(pattern: &str, url: &str) -> bool {
+    if !pattern.contains('*') {
+        return url.contains(pattern);
+    }
+
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !url.starts_with(first) || url.len() < first.len() + last.len() {
+        return false;
+    }
+
+    let mut rest = &url[first.len()..];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::StatusFilter;
+
+    fn request(id: &str, url: &str, status: Option<i64>) -> NetworkRequest {
+        NetworkRequest {
+            request_id: id.to_string(),
+            url: url.to_string(),
+            method: "GET".to_string(),
+            resource_type: "XHR".to_string(),
+            status,
+            mime_type: None,
+            encoded_data_length: 0,
+            duration_ms: None,
+            error: None,
+            started_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_url_matches() {
+        assert!(url_matches(
+            "api/users",
+            "https://example.com/api/users?page=2"
+        ));
+        assert!(!url_matches("api/orders", "https://example.com/api/users"));
+        assert!(url_matches("*/api/*", "https://example.com/api/users"));
+        assert!(url_matches(
+            "https://*.example.com/*",
+            "https://cdn.example.com/app.js"
+        ));
+        assert!(!url_matches(
+            "https://*.example.com/*",
+            "http://cdn.example.com/app.js"
+        ));
+        assert!(url_matches("*.js", "https://example.com/app.js"));
+        assert!(!url_matches("*.js", "https://example.com/app.css"));
+        assert!(!url_matches("ab*ba", "aba"));
+    }
+
+    #[test]
+    fn test_decode_body() {
+        let req = request("1", "https://example.com/api", Some(200));
+
+        let text = decode_body(&req, r#"{"ok":true}"#.to_string(), false, 1024).unwrap();
+        assert_eq!(text.body, r#"{"ok":true}"#);
+        assert!(!text.base64_encoded && !text.truncated);
+
+        let encoded = base64::engine::general_purpose::STANDARD.encode("héllo");
+        let decoded = decode_body(&req, encoded, true, 2).unwrap();
+        assert_eq!(decoded.body, "h");
+        assert_eq!(decoded.size_bytes, 6);
+        assert!(decoded.truncated && !decoded.base64_encoded);
+
+        let binary = base64::engine::general_purpose::STANDARD.encode([0xff, 0x00, 0xfe]);
+        let kept = decode_body(&req, binary.clone(), true, 1024).unwrap();
+        assert_eq!(kept.body, binary);
+        assert!(kept.base64_encoded);
+    }
+
+    #[test]
+    fn test_header_map() {
+        let headers = Headers::new(serde_json::json!({
+            "Content-Type": "application/json",
+            "X-Retry": 2,
+        }));
+        let map = header_map(&headers);
+        assert_eq!(map["Content-Type"], "application/json");
+        assert_eq!(map["X-Retry"], "2");
+        assert!(header_map(&Headers::new(serde_json::Value::Null)).is_empty());
+    }
+
+    #[test]
+    fn test_buffer_is_bounded() {
+        let mut state = NetworkState::default();
+        for i in 0..(MAX_NETWORK_REQUESTS + 5) {
+            state.push(request(&i.to_string(), "https://example.com", None), 0.0);
+        }
+
+        assert_eq!(state.requests.len(), MAX_NETWORK_REQUESTS);
+        assert_eq!(state.started.len(), MAX_NETWORK_REQUESTS);
+        assert_eq!(state.requests.front().unwrap().request_id, "5");
+    }
+
+    #[test]
+    fn test_list_filters_and_limit() {
+        let mut state = NetworkState::default();
+        state.push(request("1", "https://example.com/api/a", Some(200)), 0.0);
+        state.push(request("2", "https://example.com/api/b", Some(404)), 0.0);
+        state.push(request("3", "https://example.com/app.js", Some(500)), 0.0);
+        state.push(request("4", "https://example.com/api/c", Some(503)), 0.0);
+
+        let filter = NetworkFilter {
+            url_pattern: Some("/api/".to_string()),
+            status: StatusFilter::parse("5xx"),
+            ..Default::default()
+        };
+        let ids: Vec<_> = state
+            .list(&filter)
+            .into_iter()
+            .map(|r| r.request_id)
+            .collect();
+        assert_eq!(ids, vec!["4"]);
+
+        let filter = NetworkFilter {
+            limit: Some(2),
+            ..Default::default()
+        };
+        let ids: Vec<_> = state
+            .list(&filter)
+            .into_iter()
+            .map(|r| r.request_id)
+            .collect();
+        assert_eq!(ids, vec!["3", "4"]);
+
+        assert_eq!(state.clear(), 4);
+        assert!(state.list(&NetworkFilter::default()).is_empty());
+    }
+
+    #[test]
+    fn test_set_blocked_urls_sends_empty_list() {
+        let unblock = SetBlockedUrls { urls: Vec::new() };
+        assert_eq!(unblock.identifier().as_ref(), "Network.setBlockedURLs");
+        assert_eq!(
+            serde_json::to_value(&unblock).unwrap(),
+            serde_json::json!({"urls": []})
+        );
+    }
+}
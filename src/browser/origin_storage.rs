@@ -0,0 +1,249 @@
+//! localStorage for origins the session's page isn't showing.
+//!
+//! `DOMStorage` only reaches origins loaded in some frame of the page. To
+//! read or write another origin's storage without navigating the session,
+//! a background tab in the same browser context loads the origin's root URL.
+//! Fetch answers that request with an empty document, so nothing is requested
+//! from the site and no cookies are sent.
+
+use anyhow::{Context, Result};
+use chromiumoxide::browser::Browser;
+use chromiumoxide::cdp::browser_protocol::browser::BrowserContextId;
+use chromiumoxide::cdp::browser_protocol::dom_storage::{
+    ClearParams, GetDomStorageItemsParams, SetDomStorageItemParams, StorageId,
+};
+use chromiumoxide::cdp::browser_protocol::fetch::{
+    EnableParams, EventRequestPaused, FulfillRequestParams, HeaderEntry, RequestPattern,
+};
+use chromiumoxide::cdp::browser_protocol::target::CreateTargetParams;
+use chromiumoxide::page::Page;
+use futures::StreamExt;
+use std::collections::{BTreeSet, HashMap};
+use std::net::IpAddr;
+use tokio::task::JoinHandle;
+
+/// Reads and writes localStorage by origin for one session: through the
+/// session's page when it has the origin loaded, otherwise through a
+/// background tab opened on first need. Call `close` when done.
+pub struct OriginStorage<'a> {
+    browser: &'a Browser,
+    context: Option<BrowserContextId>,
+    page: Page,
+    stand_in: Option<StandIn>,
+}
+
+/// The background tab and the task answering its requests.
+struct StandIn {
+    page: Page,
+    responder: JoinHandle<()>,
+}
+
+impl<'a> OriginStorage<'a> {
+    /// Storage of the session in `context` (None = the default context)
+    /// whose page is `page`.
+    pub fn new(browser: &'a Browser, context: Option<BrowserContextId>, page: Page) -> Self {
+        Self {
+            browser,
+            context,
+            page,
+            stand_in: None,
+        }
+    }
+
+    pub async fn read(&mut self, origin: &str) -> Result<HashMap<String, String>> {
+        if let Ok(items) = read_items(&self.page, origin).await {
+            return Ok(items);
+        }
+        let page = self.stand_in(origin).await?;
+        read_items(page, origin).await
+    }
+
+    /// Write `items` to `origin`, first clearing it if `replace`.
+    pub async fn write(
+        &mut self,
+        origin: &str,
+        items: &HashMap<String, String>,
+        replace: bool,
+    ) -> Result<()> {
+        // A read shows whether the session's page has the origin loaded
+        let page = match read_items(&self.page, origin).await {
+            Ok(_) => &self.page,
+            Err(_) => self.stand_in(origin).await?,
+        };
+        write_items(page, origin, items, replace).await
+    }
+
+    pub async fn close(self) {
+        if let Some(stand_in) = self.stand_in {
+            stand_in.responder.abort();
+            stand_in.page.close().await.ok();
+        }
+    }
+
+    /// The background tab, showing `origin`.
+    async fn stand_in(&mut self, origin: &str) -> Result<&Page> {
+        if self.stand_in.is_none() {
+            self.stand_in = Some(open_stand_in(self.browser, self.context.clone()).await?);
+        }
+        let page = &self.stand_in.as_ref().unwrap().page;
+        page.goto(format!("{}/", origin.trim_end_matches('/')))
+            .await
+            .with_context(|| format!("Failed to load {} for its localStorage", origin))?;
+        Ok(page)
+    }
+}
+
+/// Open a background tab whose every request gets an empty document.
+async fn open_stand_in(browser: &Browser, context: Option<BrowserContextId>) -> Result<StandIn> {
+    let mut params = CreateTargetParams::new("about:blank");
+    params.browser_context_id = context;
+    params.background = Some(true);
+    let page = browser
+        .new_page(params)
+        .await
+        .context("Failed to open a tab for localStorage")?;
+
+    let mut paused = page
+        .event_listener::<EventRequestPaused>()
+        .await
+        .context("Failed to subscribe to paused requests")?;
+    page.execute(EnableParams {
+        patterns: Some(vec![RequestPattern {
+            url_pattern: Some("*".to_string()),
+            ..Default::default()
+        }]),
+        handle_auth_requests: None,
+    })
+    .await
+    .context("Failed to enable request interception")?;
+
+    let responder_page = page.clone();
+    let responder = tokio::spawn(async move {
+        while let Some(event) = paused.next().await {
+            let mut params = FulfillRequestParams::new(event.request_id.clone(), 200);
+            params.response_headers = Some(vec![HeaderEntry::new("Content-Type", "text/html")]);
+            params.body = Some(String::new().into());
+            responder_page.execute(params).await.ok();
+        }
+    });
+
+    Ok(StandIn { page, responder })
+}
+
+/// `DOMStorage` id of an origin's localStorage.
+pub fn local_storage_id(origin: &str) -> StorageId {
+    let mut storage_id = StorageId::new(true);
+    storage_id.security_origin = Some(origin.trim_end_matches('/').to_string());
+    storage_id
+}
+
+/// Context for a `DOMStorage` call that failed, usually because no frame
+/// in the page has that origin.
+pub fn storage_unavailable(origin: &str) -> String {
+    format!(
+        "Failed to access localStorage for {} (is it loaded in the page or an iframe?)",
+        origin
+    )
+}
+
+/// Every localStorage item of `origin`, which must be loaded in `page`.
+pub async fn read_items(page: &Page, origin: &str) -> Result<HashMap<String, String>> {
+    let entries = page
+        .execute(GetDomStorageItemsParams::new(local_storage_id(origin)))
+        .await
+        .with_context(|| storage_unavailable(origin))?
+        .result
+        .entries;
+    Ok(entries
+        .into_iter()
+        .filter_map(|item| match item.inner().as_slice() {
+            [key, value] => Some((key.clone(), value.clone())),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Write `items` to `origin`'s localStorage, first clearing it if `replace`.
+pub async fn write_items(
+    page: &Page,
+    origin: &str,
+    items: &HashMap<String, String>,
+    replace: bool,
+) -> Result<()> {
+    if replace {
+        page.execute(ClearParams::new(local_storage_id(origin)))
+            .await
+            .with_context(|| storage_unavailable(origin))?;
+    }
+    for (key, value) in items {
+        page.execute(SetDomStorageItemParams::new(
+            local_storage_id(origin),
+            key.clone(),
+            value.clone(),
+        ))
+        .await
+        .with_context(|| storage_unavailable(origin))?;
+    }
+    Ok(())
+}
+
+/// Origins cookies were set for, from their domains and secure flags,
+/// sorted. Cookies don't record a scheme or port: secure cookies, and any on
+/// a public host, count as https on the default port; others on localhost
+/// or an IP address as http.
+pub fn cookie_origins<'a>(cookies: impl IntoIterator<Item = (&'a str, bool)>) -> Vec<String> {
+    let origins: BTreeSet<String> = cookies
+        .into_iter()
+        .filter_map(|(domain, secure)| {
+            let host = domain.trim_start_matches('.');
+            if host.is_empty() {
+                return None;
+            }
+            let local = host == "localhost" || host.parse::<IpAddr>().is_ok();
+            let scheme = if secure || !local { "https" } else { "http" };
+            Some(format!("{}://{}", scheme, host))
+        })
+        .collect();
+    origins.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SerializableCookie;
+
+    fn cookie(domain: &str, secure: bool) -> SerializableCookie {
+        SerializableCookie {
+            name: "sid".to_string(),
+            value: "1".to_string(),
+            domain: domain.to_string(),
+            path: "/".to_string(),
+            expires: None,
+            secure,
+            http_only: false,
+            same_site: None,
+        }
+    }
+
+    #[test]
+    fn test_cookie_origins() {
+        let cookies = [
+            cookie(".example.com", false),
+            cookie("auth.example.com", true),
+            cookie("app.example.com", false),
+            cookie("example.com", true),
+            cookie("localhost", false),
+            cookie("127.0.0.1", true),
+        ];
+        assert_eq!(
+            cookie_origins(cookies.iter().map(|c| (c.domain.as_str(), c.secure))),
+            vec![
+                "http://localhost",
+                "https://127.0.0.1",
+                "https://app.example.com",
+                "https://auth.example.com",
+                "https://example.com",
+            ]
+        );
+    }
+}
@@ -0,0 +1,313 @@
+//! Warm pool of browser contexts for fast `session.new`.
+//!
+//! Creating a context and its first page costs several hundred milliseconds,
+//! most of it starting the renderer. With `--session-pool N`, that many
+//! contexts are created ahead of time, each with a blank page; `session.new`
+//! takes one and a background task tops the pool back up.
+//!
+//! A closed session's context goes back into the pool when the pool is short
+//! of its target. CDP has no call that clears a whole context, so the session
+//! records every origin its pages load, and recycling clears each of them
+//! (all storage types) along with the context's cookies, HTTP cache and
+//! permissions, and replaces its pages with a fresh blank one, which also
+//! drops per-page overrides. If any of that fails, or the pool is full, the
+//! context is disposed and the refill replaces it with a fresh one.
+
+use anyhow::{Context, Result};
+use chromiumoxide::browser::Browser;
+use chromiumoxide::cdp::browser_protocol::browser::{BrowserContextId, ResetPermissionsParams};
+use chromiumoxide::cdp::browser_protocol::network::ClearBrowserCacheParams;
+use chromiumoxide::cdp::browser_protocol::page::EventFrameNavigated;
+use chromiumoxide::cdp::browser_protocol::storage::{
+    ClearCookiesParams, ClearDataForOriginParams, GetCookiesParams,
+};
+use chromiumoxide::cdp::browser_protocol::target::{
+    CloseTargetParams, CreateBrowserContextParams, CreateTargetParams, GetTargetsParams,
+};
+use chromiumoxide::page::Page;
+use futures::StreamExt;
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+use super::origin_storage::cookie_origins;
+use crate::models::SessionPoolStats;
+
+/// A context created ahead of time, with its blank page.
+pub struct PooledContext {
+    pub context_id: BrowserContextId,
+    pub page: Page,
+}
+
+/// Contexts waiting for `session.new`, and how often one was ready.
+#[derive(Default)]
+pub struct SessionPool {
+    /// Contexts to keep ready (0 = no pool)
+    target: AtomicUsize,
+    ready: Mutex<Vec<PooledContext>>,
+    /// Set while a refill task runs
+    refilling: AtomicBool,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    recycled: AtomicU64,
+}
+
+impl SessionPool {
+    /// Change how many contexts to keep ready.
+    pub fn set_target(&self, target: usize) {
+        self.target.store(target, Ordering::Relaxed);
+    }
+
+    /// A ready context, if the pool is on and not empty.
+    pub fn take(&self) -> Option<PooledContext> {
+        if self.target.load(Ordering::Relaxed) == 0 {
+            return None;
+        }
+        let pooled = self.ready.lock().unwrap().pop();
+        let counter = if pooled.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        pooled
+    }
+
+    /// Whether a closed session's context is worth recycling.
+    pub fn wants(&self) -> bool {
+        self.missing() > 0
+    }
+
+    /// Add a recycled context, unless the pool filled up meanwhile; then
+    /// it's handed back for the caller to dispose.
+    pub fn give_back(&self, pooled: PooledContext) -> Option<PooledContext> {
+        let mut ready = self.ready.lock().unwrap();
+        if ready.len() >= self.target.load(Ordering::Relaxed) {
+            return Some(pooled);
+        }
+        ready.push(pooled);
+        self.recycled.fetch_add(1, Ordering::Relaxed);
+        None
+    }
+
+    pub fn stats(&self) -> SessionPoolStats {
+        SessionPoolStats {
+            target: self.target.load(Ordering::Relaxed),
+            ready: self.ready.lock().unwrap().len(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            recycled: self.recycled.load(Ordering::Relaxed),
+        }
+    }
+
+    fn missing(&self) -> usize {
+        let ready = self.ready.lock().unwrap().len();
+        self.target.load(Ordering::Relaxed).saturating_sub(ready)
+    }
+}
+
+/// Top the pool up to its target in the background, unless a refill is
+/// already running. Surplus contexts (after the target was lowered) are
+/// disposed.
+pub fn spawn_refill(pool: Arc<SessionPool>, browser: Weak<Browser>) {
+    if pool.refilling.swap(true, Ordering::AcqRel) {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut failed = false;
+        while let Some(browser) = browser.upgrade() {
+            let surplus: Vec<PooledContext> = {
+                let mut ready = pool.ready.lock().unwrap();
+                let target = pool.target.load(Ordering::Relaxed);
+                let keep = target.min(ready.len());
+                ready.drain(keep..).collect()
+            };
+            for pooled in surplus {
+                browser
+                    .dispose_browser_context(pooled.context_id)
+                    .await
+                    .ok();
+            }
+
+            if pool.missing() == 0 {
+                break;
+            }
+            match create_context(&browser).await {
+                Ok(pooled) => pool.ready.lock().unwrap().push(pooled),
+                Err(e) => {
+                    // session.new falls back to creating its own context
+                    tracing::warn!("Failed to refill the session pool: {:#}", e);
+                    failed = true;
+                    break;
+                }
+            }
+        }
+        pool.refilling.store(false, Ordering::Release);
+        // A session taken just before the flag cleared found a refill running
+        if !failed && pool.missing() > 0 {
+            spawn_refill(pool, browser);
+        }
+    });
+}
+
+/// Create a context with a blank page.
+pub async fn create_context(browser: &Browser) -> Result<PooledContext> {
+    let context_id = browser
+        .create_browser_context(CreateBrowserContextParams::default())
+        .await
+        .context("Failed to create browser context")?;
+    let page = blank_page(browser, &context_id).await?;
+
+    Ok(PooledContext { context_id, page })
+}
+
+async fn blank_page(browser: &Browser, context_id: &BrowserContextId) -> Result<Page> {
+    browser
+        .new_page(
+            CreateTargetParams::builder()
+                .url("about:blank")
+                .browser_context_id(context_id.clone())
+                .build()
+                .map_err(|e| anyhow::anyhow!("Failed to build target params: {:?}", e))?,
+        )
+        .await
+        .context("Failed to create page in context")
+}
+
+/// Origins a session's pages have loaded, to clear before its context is
+/// reused.
+#[derive(Debug, Default)]
+pub struct VisitedOrigins {
+    origins: Mutex<BTreeSet<String>>,
+}
+
+/// Record the origin of every frame `page` navigates.
+pub async fn track_origins(page: &Page, visited: &Arc<VisitedOrigins>) -> Result<()> {
+    let mut navigated = page
+        .event_listener::<EventFrameNavigated>()
+        .await
+        .context("Failed to subscribe to navigation events")?;
+    let visited = visited.clone();
+    tokio::spawn(async move {
+        while let Some(event) = navigated.next().await {
+            let origin = &event.frame.security_origin;
+            // Opaque origins (about:blank, data:) hold no storage
+            if !origin.is_empty() && origin != "null" {
+                visited.origins.lock().unwrap().insert(origin.clone());
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Clear a closed session's context so it can go back into the pool: a new
+/// blank page replaces its pages, then its cookies, the storage of every
+/// origin it loaded (tracked, open or holding cookies), its HTTP cache and
+/// its permissions are cleared. On error the context must be disposed.
+pub async fn recycle(
+    browser: &Browser,
+    context_id: BrowserContextId,
+    visited: &VisitedOrigins,
+) -> Result<PooledContext> {
+    let page = blank_page(browser, &context_id).await?;
+    let mut origins = visited.origins.lock().unwrap().clone();
+
+    let targets = browser
+        .execute(GetTargetsParams::default())
+        .await
+        .context("Failed to list targets")?
+        .result
+        .target_infos;
+    let stale = targets.into_iter().filter(|t| {
+        t.browser_context_id.as_ref() == Some(&context_id) && t.target_id != *page.target_id()
+    });
+    for target in stale {
+        origins.extend(origin_of(&target.url));
+        browser
+            .execute(CloseTargetParams::new(target.target_id))
+            .await
+            .context("Failed to close the session's pages")?;
+    }
+
+    let cookies = browser
+        .execute(GetCookiesParams {
+            browser_context_id: Some(context_id.clone()),
+        })
+        .await
+        .context("Failed to read the context's cookies")?
+        .result
+        .cookies;
+    origins.extend(cookie_origins(
+        cookies.iter().map(|c| (c.domain.as_str(), c.secure)),
+    ));
+    browser
+        .execute(ClearCookiesParams {
+            browser_context_id: Some(context_id.clone()),
+        })
+        .await
+        .context("Failed to clear cookies")?;
+
+    // Storage commands on a page act on its context's storage
+    for origin in &origins {
+        page.execute(ClearDataForOriginParams::new(origin.clone(), "all"))
+            .await
+            .with_context(|| format!("Failed to clear storage for {}", origin))?;
+    }
+    page.execute(ClearBrowserCacheParams::default())
+        .await
+        .context("Failed to clear the HTTP cache")?;
+    browser
+        .execute(ResetPermissionsParams {
+            browser_context_id: Some(context_id.clone()),
+        })
+        .await
+        .context("Failed to reset permissions")?;
+
+    Ok(PooledContext { context_id, page })
+}
+
+/// `scheme://host[:port]` of an http(s) URL.
+fn origin_of(url: &str) -> Option<String> {
+    let (scheme, rest) = url.split_once("://")?;
+    if scheme != "http" && scheme != "https" {
+        return None;
+    }
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?;
+    (!host.is_empty()).then(|| format!("{}://{}", scheme, host))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_pool_counts_misses() {
+        let pool = SessionPool::default();
+        // Pool off: not a miss
+        assert!(pool.take().is_none());
+        assert_eq!(pool.stats().misses, 0);
+
+        pool.set_target(2);
+        assert!(pool.take().is_none());
+        let stats = pool.stats();
+        assert_eq!((stats.target, stats.ready, stats.misses), (2, 0, 1));
+        assert_eq!(pool.missing(), 2);
+        assert!(pool.wants());
+    }
+
+    #[test]
+    fn test_origin_of() {
+        assert_eq!(
+            origin_of("https://user:pw@example.com:8443/a?b#c").as_deref(),
+            Some("https://example.com:8443")
+        );
+        assert_eq!(
+            origin_of("http://127.0.0.1:9000").as_deref(),
+            Some("http://127.0.0.1:9000")
+        );
+        assert_eq!(origin_of("about:blank"), None);
+        assert_eq!(origin_of("chrome://newtab/"), None);
+    }
+}
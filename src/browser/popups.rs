@@ -0,0 +1,260 @@
+//! Popups and new windows opened by a session's pages.
+//!
+//! A browser-level listener notes page targets whose opener belongs to a
+//! session. They are attached as extra pages of that session the next time
+//! it's used, since chromiumoxide can't drive a target until it has attached
+//! to it. Commands keep targeting the active page until `pages.switch`; when
+//! the active popup closes, the session switches back to the page that
+//! opened it.
+
+use anyhow::{Context, Result};
+use chromiumoxide::browser::Browser;
+use chromiumoxide::cdp::browser_protocol::target::{
+    EventTargetCreated, EventTargetDestroyed, TargetId,
+};
+use chromiumoxide::page::Page;
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+use super::client::BrowserSession;
+use crate::models::ClosedPopup;
+
+/// How many times to look for a popup's page before giving up.
+const ATTACH_ATTEMPTS: usize = 20;
+const ATTACH_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Popups opened or closed since the session was last used.
+#[derive(Debug, Default)]
+pub struct PopupState {
+    /// Popups not attached yet, with their opener
+    opened: Vec<(TargetId, TargetId)>,
+    /// Attached popups that have closed
+    closed: Vec<TargetId>,
+    /// Opener of each attached popup
+    openers: HashMap<TargetId, TargetId>,
+    /// Active popups that closed, reported by the next `pages.list`
+    pub switched_back: Vec<ClosedPopup>,
+}
+
+impl PopupState {
+    /// Whether there are popups to attach or drop.
+    pub fn has_pending(&self) -> bool {
+        !self.opened.is_empty() || !self.closed.is_empty()
+    }
+
+    /// Opener of an attached popup.
+    pub fn opener(&self, target_id: &TargetId) -> Option<&TargetId> {
+        self.openers.get(target_id)
+    }
+
+    fn is_opening(&self, target_id: &TargetId) -> bool {
+        self.opened.iter().any(|(popup, _)| popup == target_id)
+    }
+}
+
+/// Listen for page targets opened or closed by a session's pages.
+pub async fn spawn_popup_listener(
+    browser: &Browser,
+    sessions: Arc<RwLock<HashMap<String, BrowserSession>>>,
+) -> Result<()> {
+    let mut created = browser
+        .event_listener::<EventTargetCreated>()
+        .await
+        .context("Failed to subscribe to target events")?;
+    let mut destroyed = browser
+        .event_listener::<EventTargetDestroyed>()
+        .await
+        .context("Failed to subscribe to target events")?;
+
+    let created_sessions = sessions.clone();
+    tokio::spawn(async move {
+        while let Some(event) = created.next().await {
+            let info = &event.target_info;
+            let Some(opener) = info.opener_id.as_ref().filter(|_| info.r#type == "page") else {
+                continue;
+            };
+
+            let sessions = created_sessions.read().await;
+            // A popup's own popup may open before the first is attached
+            let owner = sessions
+                .values()
+                .find(|s| s.has_page(opener) || s.popups.lock().unwrap().is_opening(opener));
+            if let Some(session) = owner {
+                tracing::info!(
+                    "Session {} opened popup {} ({})",
+                    session.id,
+                    info.target_id.inner(),
+                    info.url
+                );
+                session
+                    .popups
+                    .lock()
+                    .unwrap()
+                    .opened
+                    .push((info.target_id.clone(), opener.clone()));
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        while let Some(event) = destroyed.next().await {
+            let sessions = sessions.read().await;
+            for session in sessions.values() {
+                let mut popups = session.popups.lock().unwrap();
+                let before = popups.opened.len();
+                popups.opened.retain(|(popup, _)| *popup != event.target_id);
+                if popups.opened.len() != before {
+                    break;
+                }
+                // Any extra page, popup or tab, but never the session's own tab
+                if session.pages[1..]
+                    .iter()
+                    .any(|p| *p.target_id() == event.target_id)
+                {
+                    popups.closed.push(event.target_id.clone());
+                    break;
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Attach newly opened popups to the session and drop closed ones.
+pub async fn sync_pages(browser: &Browser, session: &mut BrowserSession) {
+    let (opened, closed) = {
+        let mut popups = session.popups.lock().unwrap();
+        (
+            std::mem::take(&mut popups.opened),
+            std::mem::take(&mut popups.closed),
+        )
+    };
+
+    for (target_id, opener) in opened {
+        match attach(browser, &target_id).await {
+            Some(page) => {
+                if let Err(e) = session.watch_page(&page).await {
+                    tracing::warn!("Popup {}: {:#}", target_id.inner(), e);
+                }
+                session.pages.push(page);
+                session
+                    .popups
+                    .lock()
+                    .unwrap()
+                    .openers
+                    .insert(target_id, opener);
+            }
+            None => tracing::warn!("Popup {} closed before it was attached", target_id.inner()),
+        }
+    }
+
+    for target_id in closed {
+        let Some(index) = session
+            .pages
+            .iter()
+            .position(|p| *p.target_id() == target_id)
+            .filter(|i| *i > 0)
+        else {
+            continue;
+        };
+
+        if remove_page(session, index) {
+            tracing::info!(
+                "Popup {} closed; session {} switched back to page {}",
+                target_id.inner(),
+                session.id,
+                session.active_page
+            );
+            session
+                .popups
+                .lock()
+                .unwrap()
+                .switched_back
+                .push(ClosedPopup {
+                    target_id: target_id.inner().clone(),
+                    switched_to: session.active_page,
+                });
+        }
+    }
+}
+
+/// Drop the page at `index` (never the session's own tab), moving commands
+/// to its opener if it was active. Returns whether it was active.
+pub fn remove_page(session: &mut BrowserSession, index: usize) -> bool {
+    let target_id = session.pages[index].target_id().clone();
+    let opener = session
+        .popups
+        .lock()
+        .unwrap()
+        .openers
+        .remove(&target_id)
+        .and_then(|opener| session.pages.iter().position(|p| *p.target_id() == opener));
+    let was_active = index == session.active_page;
+
+    session.pages.remove(index);
+    session.active_page = active_after_close(session.active_page, index, opener);
+    was_active
+}
+
+/// Find the page for a new target, waiting for chromiumoxide to attach it.
+async fn attach(browser: &Browser, target_id: &TargetId) -> Option<Page> {
+    for _ in 0..ATTACH_ATTEMPTS {
+        if let Ok(page) = browser.get_page(target_id.clone()).await {
+            return Some(page);
+        }
+        tokio::time::sleep(ATTACH_RETRY_INTERVAL).await;
+    }
+    None
+}
+
+/// Active page index after the page at `closed` is removed.
+///
+/// Closing the active page moves to its opener (indexes taken before the
+/// removal), or to the session's own tab when it has none.
+fn active_after_close(active: usize, closed: usize, opener: Option<usize>) -> usize {
+    let next = if active == closed {
+        opener.unwrap_or(0)
+    } else {
+        active
+    };
+    if next > closed {
+        next - 1
+    } else {
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_active_after_close() {
+        // Active popup closes: back to its opener
+        assert_eq!(active_after_close(2, 2, Some(1)), 1);
+        // Opener gone too: back to the session's tab
+        assert_eq!(active_after_close(2, 2, None), 0);
+        // Another page closes before the active one: index shifts down
+        assert_eq!(active_after_close(3, 1, Some(0)), 2);
+        // A later page closes: active unchanged
+        assert_eq!(active_after_close(1, 2, Some(1)), 1);
+    }
+
+    #[test]
+    fn test_popup_state_pending() {
+        let mut state = PopupState::default();
+        assert!(!state.has_pending());
+
+        let popup = TargetId::from("popup".to_string());
+        state
+            .opened
+            .push((popup.clone(), TargetId::from("tab".to_string())));
+        assert!(state.has_pending());
+        assert!(state.is_opening(&popup));
+        assert!(state.opener(&popup).is_none());
+    }
+}
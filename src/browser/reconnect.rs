@@ -0,0 +1,109 @@
+//! Reconnecting to the user's Chrome after it restarts (connect mode).
+//!
+//! A health task pings `Browser.getVersion` every 10 seconds. When the ping
+//! fails it reconnects to the same debugging endpoint, retrying a few times
+//! while Chrome starts back up. Every page of the old connection died with
+//! it, so the new client is parked until the service swaps it in on the next
+//! call, which reports the lost sessions the same way a relaunch does.
+
+use chromiumoxide::browser::Browser;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+
+use super::client::BrowserClient;
+
+/// How often the health task pings Chrome.
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+const PING_TIMEOUT: Duration = Duration::from_secs(2);
+/// Reconnect attempts before giving up until the next call.
+const RECONNECT_ATTEMPTS: u32 = 5;
+const RECONNECT_BACKOFF: Duration = Duration::from_secs(2);
+
+/// Reconnect progress shared between a client and its health task.
+#[derive(Default)]
+pub struct ReconnectState {
+    /// Set while the health task is reconnecting
+    pub is_reconnecting: AtomicBool,
+    /// Attempts made since Chrome stopped responding
+    pub attempts: AtomicU32,
+    /// Client on the new connection, until the service swaps it in
+    pub reconnected: Mutex<Option<BrowserClient>>,
+}
+
+/// Ping the browser behind `browser` until it stops answering, then
+/// reconnect to `url`. Stops once the client is dropped.
+pub fn spawn_health_monitor(
+    browser: Weak<Browser>,
+    url: String,
+    connected: Arc<AtomicBool>,
+    state: Arc<ReconnectState>,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PING_INTERVAL);
+        // The first tick completes immediately; the client just connected
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+            let Some(client_browser) = browser.upgrade() else {
+                return;
+            };
+            let alive = connected.load(Ordering::Relaxed)
+                && matches!(
+                    tokio::time::timeout(PING_TIMEOUT, client_browser.version()).await,
+                    Ok(Ok(_))
+                );
+            drop(client_browser);
+            if alive {
+                continue;
+            }
+
+            tracing::warn!("Chrome at {} stopped responding; reconnecting", url);
+            connected.store(false, Ordering::Relaxed);
+            state.is_reconnecting.store(true, Ordering::Relaxed);
+            let client = reconnect(&browser, &url, &state).await;
+            if let Some(client) = client {
+                *state.reconnected.lock().unwrap() = Some(client);
+            }
+            state.is_reconnecting.store(false, Ordering::Relaxed);
+            return;
+        }
+    });
+}
+
+async fn reconnect(
+    browser: &Weak<Browser>,
+    url: &str,
+    state: &ReconnectState,
+) -> Option<BrowserClient> {
+    for attempt in 1..=RECONNECT_ATTEMPTS {
+        tokio::time::sleep(RECONNECT_BACKOFF).await;
+        // The service already replaced the client
+        if browser.strong_count() == 0 {
+            return None;
+        }
+
+        state.attempts.store(attempt, Ordering::Relaxed);
+        match BrowserClient::connect(url).await {
+            Ok(client) => {
+                tracing::info!("Reconnected to Chrome at {} (attempt {})", url, attempt);
+                return Some(client);
+            }
+            Err(e) => tracing::warn!(
+                "Reconnect attempt {}/{} to {} failed: {:#}",
+                attempt,
+                RECONNECT_ATTEMPTS,
+                url,
+                e
+            ),
+        }
+    }
+
+    tracing::warn!(
+        "Gave up reconnecting to {} after {} attempts; the next call tries again",
+        url,
+        RECONNECT_ATTEMPTS
+    );
+    None
+}
@@ -0,0 +1,131 @@
+//! Temporary files behind `browser.upload` with `content_base64`.
+//!
+//! A page reads an uploaded file when the form is submitted, not when the
+//! input is set, so the file has to outlive the call. Each session records
+//! the directories written for it. One is removed once the document it was
+//! set on is gone (the page closed or navigated away), which the session's
+//! next upload checks; the rest go when the session is dropped, i.e. when
+//! it's closed or its browser client goes away.
+
+use anyhow::{Context, Result};
+use chromiumoxide::cdp::browser_protocol::network::LoaderId;
+use chromiumoxide::cdp::browser_protocol::page::GetFrameTreeParams;
+use chromiumoxide::cdp::browser_protocol::target::TargetId;
+use chromiumoxide::page::Page;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Directories holding a session's uploaded bytes.
+#[derive(Default)]
+pub struct UploadDirs {
+    dirs: Mutex<Vec<UploadDir>>,
+}
+
+struct UploadDir {
+    path: PathBuf,
+    /// Page the file was set on
+    page: TargetId,
+    /// That page's document at the time
+    document: LoaderId,
+}
+
+impl UploadDirs {
+    /// A new directory for one upload to `page`, kept while the page shows
+    /// its current document.
+    pub async fn create(&self, page: &Page) -> Result<PathBuf> {
+        let document = document_id(page).await?;
+        let path = std::env::temp_dir()
+            .join("fgp-uploads")
+            .join(uuid::Uuid::new_v4().simple().to_string());
+        tokio::fs::create_dir_all(&path)
+            .await
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+
+        self.dirs.lock().unwrap().push(UploadDir {
+            path: path.clone(),
+            page: page.target_id().clone(),
+            document,
+        });
+        Ok(path)
+    }
+
+    /// Remove the directory of an upload that failed.
+    pub async fn remove(&self, path: &Path) {
+        self.dirs.lock().unwrap().retain(|dir| dir.path != path);
+        tokio::fs::remove_dir_all(path).await.ok();
+    }
+
+    /// Remove directories whose document is gone: its page is no longer
+    /// one of `pages`, or has navigated since.
+    pub async fn prune(&self, pages: &[Page]) {
+        let recorded: Vec<(PathBuf, TargetId, LoaderId)> = self
+            .dirs
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|dir| (dir.path.clone(), dir.page.clone(), dir.document.clone()))
+            .collect();
+
+        let mut gone = Vec::new();
+        for (path, target, document) in recorded {
+            let current = match pages.iter().find(|page| page.target_id() == &target) {
+                Some(page) => document_id(page).await.ok(),
+                None => None,
+            };
+            if current.as_ref() != Some(&document) {
+                gone.push(path);
+            }
+        }
+
+        self.dirs
+            .lock()
+            .unwrap()
+            .retain(|dir| !gone.contains(&dir.path));
+        for path in gone {
+            tokio::fs::remove_dir_all(&path).await.ok();
+        }
+    }
+}
+
+impl Drop for UploadDirs {
+    fn drop(&mut self) {
+        if let Ok(dirs) = self.dirs.get_mut() {
+            for dir in dirs.drain(..) {
+                std::fs::remove_dir_all(&dir.path).ok();
+            }
+        }
+    }
+}
+
+/// Loader of the page's current document, which changes when it navigates.
+async fn document_id(page: &Page) -> Result<LoaderId> {
+    Ok(page
+        .execute(GetFrameTreeParams::default())
+        .await
+        .context("Failed to read the page's frame tree")?
+        .result
+        .frame_tree
+        .frame
+        .loader_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dropping_removes_dirs() {
+        let path = std::env::temp_dir().join(format!("fgp-uploads-test-{}", std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        std::fs::write(path.join("report.csv"), "a,b").unwrap();
+
+        let uploads = UploadDirs::default();
+        uploads.dirs.lock().unwrap().push(UploadDir {
+            path: path.clone(),
+            page: TargetId::new("page"),
+            document: LoaderId::new("document"),
+        });
+        drop(uploads);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,119 @@
+//! Sharing a failed browser launch with every caller waiting on it.
+//!
+//! Only one request launches (or connects to) Chrome at a time; the others
+//! queue behind the client lock. When the launch fails, each queued request
+//! would otherwise launch again in turn, which with a missing Chrome or an
+//! unreachable debugging port can take minutes. For a short cooldown after a
+//! failure, callers get that failure's error straight away instead.
+//!
+//! Failures are kept per endpoint, so one unreachable Chrome doesn't fail
+//! calls meant for another.
+
+use anyhow::Result;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long a failed launch is reported before the next attempt.
+pub const LAUNCH_COOLDOWN: Duration = Duration::from_secs(5);
+
+/// The last failed launch per endpoint, reported until the cooldown ends.
+pub struct LaunchCooldown {
+    cooldown: Duration,
+    /// Endpoint, when it failed, and the error
+    failures: Mutex<Vec<(String, Instant, String)>>,
+}
+
+impl LaunchCooldown {
+    pub const fn new(cooldown: Duration) -> Self {
+        Self {
+            cooldown,
+            failures: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Run `launch` for `endpoint`, unless one failed there within the
+    /// cooldown, in which case return its error without launching.
+    pub async fn launch<T>(
+        &self,
+        endpoint: &str,
+        launch: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        if let Some((_, at, error)) = self
+            .failures
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(failed, _, _)| failed == endpoint)
+        {
+            if at.elapsed() < self.cooldown {
+                return Err(anyhow::anyhow!("{}", error));
+            }
+        }
+
+        let result = launch.await;
+        let mut failures = self.failures.lock().unwrap();
+        failures.retain(|(failed, _, _)| failed != endpoint);
+        if let Err(e) = &result {
+            failures.push((endpoint.to_string(), Instant::now(), format!("{:#}", e)));
+        }
+        drop(failures);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const LAUNCH_TIME: Duration = Duration::from_millis(200);
+
+    /// Launcher that fails like a missing Chrome binary.
+    async fn missing_chrome(launches: &AtomicUsize) -> Result<u32> {
+        launches.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(LAUNCH_TIME).await;
+        anyhow::bail!("Chrome not found")
+    }
+
+    #[test]
+    fn test_launches_again_after_cooldown() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let cooldown = LaunchCooldown::new(Duration::ZERO);
+        let launches = AtomicUsize::new(0);
+
+        runtime.block_on(async {
+            assert!(cooldown
+                .launch("", missing_chrome(&launches))
+                .await
+                .is_err());
+            assert_eq!(cooldown.launch("", async { Ok(7) }).await.unwrap(), 7);
+            assert!(cooldown
+                .launch("", missing_chrome(&launches))
+                .await
+                .is_err());
+        });
+
+        assert_eq!(launches.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_failure_only_blocks_its_endpoint() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let cooldown = LaunchCooldown::new(LAUNCH_COOLDOWN);
+        let launches = AtomicUsize::new(0);
+
+        runtime.block_on(async {
+            let down = "http://10.0.0.1:9222";
+            assert!(cooldown
+                .launch(down, missing_chrome(&launches))
+                .await
+                .is_err());
+            assert!(cooldown.launch(down, async { Ok(7) }).await.is_err());
+            let up = "http://10.0.0.2:9222";
+            assert_eq!(cooldown.launch(up, async { Ok(7) }).await.unwrap(), 7);
+        });
+
+        assert_eq!(launches.load(Ordering::SeqCst), 1);
+    }
+}
@@ -9,22 +9,62 @@
 //! Chrome Extension <--WebSocket--> ExtensionBridge <--Channel--> BrowserService
 //! ```
 //!
+//! The extension reconnects on its own after a reload; a supervisor task
+//! watches for the drop, logs each wait with exponential backoff, and fails
+//! requests that were in flight on the old connection. With
+//! [`ExtensionBridge::with_message_queue`], requests made while the extension
+//! is away are held and sent once it reconnects.
+//!
+//! With a certificate and key in [`ExtensionBridgeConfig`], connections are
+//! TLS-wrapped and the extension connects to `wss://localhost:PORT`.
+//!
+//! With [`ExtensionBridge::with_auth_token`], an extension connection must
+//! send `{"type":"auth","token":"..."}` as its first message within
+//! [`AUTH_TIMEOUT`], or it is closed.
+//!
+//! When a [`BidiTarget`] is attached (`start --protocol bidi`), connections to
+//! `/session` are served as WebDriver BiDi sessions instead.
+//!
 //! # CHANGELOG (recent first, max 5 entries)
 //! 01/15/2026 - Added sync call_blocking() for service integration (Claude)
 //! 01/15/2026 - Initial implementation (Claude)
 
 use anyhow::{Context, Result};
+use chrono::Utc;
 use futures::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use tokio::net::{TcpListener, TcpStream};
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
 use tokio::sync::{broadcast, mpsc, RwLock};
-use tokio_tungstenite::{accept_async, tungstenite::Message};
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::TlsAcceptor;
+use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+use tokio_tungstenite::{accept_hdr_async, tungstenite::Message, WebSocketStream};
+
+use crate::bidi::BidiTarget;
 
 const DEFAULT_WS_PORT: u16 = 9223;
 
+/// First reconnect wait; doubles on each attempt up to `MAX_RECONNECT_DELAY`.
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// How long a new extension connection has to authenticate.
+const AUTH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often the supervisor checks the connection while it's up.
+const SUPERVISOR_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+type PendingRequests =
+    Arc<RwLock<HashMap<String, tokio::sync::oneshot::Sender<ExtensionResponse>>>>;
+
 /// Request from daemon to extension
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExtensionRequest {
@@ -46,6 +86,77 @@ pub struct ExtensionResponse {
     pub error: Option<String>,
 }
 
+/// Requests held while the extension is disconnected.
+struct MessageQueue {
+    requests: Mutex<VecDeque<ExtensionRequest>>,
+    capacity: usize,
+    /// Requests rejected because the queue was full
+    dropped: AtomicU64,
+}
+
+impl MessageQueue {
+    fn new(capacity: usize) -> Self {
+        Self {
+            requests: Mutex::new(VecDeque::new()),
+            capacity,
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Queue a request, or reject it when the queue is full.
+    fn push(&self, request: ExtensionRequest) -> Result<()> {
+        let mut requests = self.requests.lock().unwrap();
+        if requests.len() >= self.capacity {
+            self.dropped.fetch_add(1, Ordering::SeqCst);
+            anyhow::bail!(
+                "Extension not connected and the request queue is full ({} requests)",
+                self.capacity
+            );
+        }
+        requests.push_back(request);
+        Ok(())
+    }
+
+    /// Remove a queued request; false if it was already sent.
+    fn remove(&self, id: &str) -> bool {
+        let mut requests = self.requests.lock().unwrap();
+        let before = requests.len();
+        requests.retain(|r| r.id != id);
+        requests.len() != before
+    }
+
+    fn contains(&self, id: &str) -> bool {
+        self.requests.lock().unwrap().iter().any(|r| r.id == id)
+    }
+
+    fn take_all(&self) -> VecDeque<ExtensionRequest> {
+        std::mem::take(&mut *self.requests.lock().unwrap())
+    }
+
+    /// Put unsent requests back at the front, ahead of newer ones.
+    fn requeue_front(&self, unsent: VecDeque<ExtensionRequest>) {
+        let mut requests = self.requests.lock().unwrap();
+        for request in unsent.into_iter().rev() {
+            requests.push_front(request);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.requests.lock().unwrap().len()
+    }
+}
+
+/// Traffic counters across all extension connections.
+#[derive(Default)]
+struct BridgeStats {
+    /// Requests written to the extension
+    messages_sent: AtomicU64,
+    /// Messages read from the extension
+    messages_received: AtomicU64,
+    /// When the extension last connected (RFC 3339)
+    last_connected_at: Mutex<Option<String>>,
+}
+
 /// Extension connection state
 #[derive(Debug, Clone, PartialEq)]
 pub enum ConnectionState {
@@ -90,6 +201,56 @@ pub fn is_extension_method(method: &str) -> bool {
     EXTENSION_METHODS.iter().any(|m| *m == method)
 }
 
+/// Options for [`ExtensionBridge::start`]
+#[derive(Debug, Clone, Default)]
+pub struct ExtensionBridgeConfig {
+    /// PEM certificate chain; with `tls_key_path`, serve wss:// instead of ws://
+    pub tls_cert_path: Option<PathBuf>,
+    /// PEM private key for `tls_cert_path`
+    pub tls_key_path: Option<PathBuf>,
+}
+
+impl ExtensionBridgeConfig {
+    /// URL scheme the bridge is served on
+    pub fn scheme(&self) -> &'static str {
+        if self.tls_cert_path.is_some() && self.tls_key_path.is_some() {
+            "wss"
+        } else {
+            "ws"
+        }
+    }
+
+    /// Load the certificate and key, or None when serving plain ws://
+    pub fn tls_acceptor(&self) -> Result<Option<TlsAcceptor>> {
+        let (cert_path, key_path) = match (&self.tls_cert_path, &self.tls_key_path) {
+            (Some(cert), Some(key)) => (cert, key),
+            (None, None) => return Ok(None),
+            _ => anyhow::bail!("TLS needs both a certificate and a private key"),
+        };
+
+        let cert_file = File::open(cert_path)
+            .with_context(|| format!("Failed to open TLS certificate {}", cert_path.display()))?;
+        let certs = rustls_pemfile::certs(&mut BufReader::new(cert_file))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .with_context(|| format!("Failed to parse TLS certificate {}", cert_path.display()))?;
+        if certs.is_empty() {
+            anyhow::bail!("No certificates found in {}", cert_path.display());
+        }
+
+        let key_file = File::open(key_path)
+            .with_context(|| format!("Failed to open TLS key {}", key_path.display()))?;
+        let key = rustls_pemfile::private_key(&mut BufReader::new(key_file))
+            .with_context(|| format!("Failed to parse TLS key {}", key_path.display()))?
+            .with_context(|| format!("No private key found in {}", key_path.display()))?;
+
+        let config = ServerConfig::builder()
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .context("TLS certificate and key don't match")?;
+        Ok(Some(TlsAcceptor::from(Arc::new(config))))
+    }
+}
+
 /// Extension Bridge manages WebSocket connection to Chrome extension
 pub struct ExtensionBridge {
     /// Current connection state
@@ -102,9 +263,21 @@ pub struct ExtensionBridge {
     response_tx: mpsc::Sender<ExtensionResponse>,
     response_rx: Arc<RwLock<mpsc::Receiver<ExtensionResponse>>>,
     /// Pending requests waiting for responses
-    pending: Arc<RwLock<HashMap<String, tokio::sync::oneshot::Sender<ExtensionResponse>>>>,
+    pending: PendingRequests,
+    /// Reconnect waits since the extension last dropped (0 while connected)
+    reconnect_attempts: Arc<AtomicU32>,
+    /// Incremented per extension connection so a stale one can't mark the bridge disconnected
+    generation: Arc<AtomicU64>,
+    /// Requests held while disconnected (None = fail fast)
+    queue: Option<Arc<MessageQueue>>,
+    /// Message counters and last connection time
+    stats: Arc<BridgeStats>,
+    /// Shared secret extensions must send before anything else (None = no auth)
+    auth_token: Option<Arc<str>>,
     /// WebSocket port
     port: u16,
+    /// WebDriver BiDi handler for `/session` connections
+    bidi: Option<Arc<BidiTarget>>,
 }
 
 impl ExtensionBridge {
@@ -120,24 +293,58 @@ impl ExtensionBridge {
             response_tx,
             response_rx: Arc::new(RwLock::new(response_rx)),
             pending: Arc::new(RwLock::new(HashMap::new())),
+            reconnect_attempts: Arc::new(AtomicU32::new(0)),
+            generation: Arc::new(AtomicU64::new(0)),
+            queue: None,
+            stats: Arc::new(BridgeStats::default()),
+            auth_token: None,
             port: port.unwrap_or(DEFAULT_WS_PORT),
+            bidi: None,
         }
     }
 
+    /// Hold up to `capacity` requests while the extension is disconnected and
+    /// send them when it reconnects, instead of failing them immediately
+    pub fn with_message_queue(mut self, capacity: usize) -> Self {
+        self.queue = Some(Arc::new(MessageQueue::new(capacity)));
+        self
+    }
+
+    /// Require extensions to authenticate with `token` before they're accepted
+    pub fn with_auth_token(mut self, token: String) -> Self {
+        self.auth_token = Some(token.into());
+        self
+    }
+
+    /// Serve WebDriver BiDi on `/session` connections
+    pub fn with_bidi(mut self, target: BidiTarget) -> Self {
+        self.bidi = Some(Arc::new(target));
+        self
+    }
+
     /// Start the WebSocket server
-    pub async fn start(&self) -> Result<()> {
+    pub async fn start(&self, config: &ExtensionBridgeConfig) -> Result<()> {
+        let tls = config.tls_acceptor()?;
         let addr = format!("127.0.0.1:{}", self.port);
         let listener = TcpListener::bind(&addr)
             .await
             .with_context(|| format!("Failed to bind WebSocket server to {}", addr))?;
 
-        tracing::info!("Extension bridge listening on ws://{}", addr);
+        tracing::info!(
+            "Extension bridge listening on {}://{}",
+            config.scheme(),
+            addr
+        );
 
         let state = self.state.clone();
         let connected = self.connected.clone();
         let request_tx = self.request_tx.clone();
         let response_tx = self.response_tx.clone();
-        let pending = self.pending.clone();
+        let generation = self.generation.clone();
+        let queue = self.queue.clone();
+        let stats = self.stats.clone();
+        let auth_token = self.auth_token.clone();
+        let bidi = self.bidi.clone();
 
         // Spawn connection acceptor
         tokio::spawn(async move {
@@ -149,13 +356,51 @@ impl ExtensionBridge {
                         let connected = connected.clone();
                         let request_rx = request_tx.subscribe();
                         let response_tx = response_tx.clone();
-                        let pending = pending.clone();
+                        let generation = generation.clone();
+                        let queue = queue.clone();
+                        let stats = stats.clone();
+                        let auth_token = auth_token.clone();
+                        let bidi = bidi.clone();
+                        let tls = tls.clone();
 
                         tokio::spawn(async move {
-                            if let Err(e) =
-                                handle_connection(stream, state, connected, request_rx, response_tx, pending)
+                            let result = match tls {
+                                Some(tls) => match tls.accept(stream).await {
+                                    Ok(stream) => {
+                                        handle_connection(
+                                            stream,
+                                            state,
+                                            connected,
+                                            generation,
+                                            request_rx,
+                                            response_tx,
+                                            queue,
+                                            stats,
+                                            auth_token,
+                                            bidi,
+                                        )
+                                        .await
+                                    }
+                                    Err(e) => Err(anyhow::Error::new(e)
+                                        .context("TLS handshake with extension failed")),
+                                },
+                                None => {
+                                    handle_connection(
+                                        stream,
+                                        state,
+                                        connected,
+                                        generation,
+                                        request_rx,
+                                        response_tx,
+                                        queue,
+                                        stats,
+                                        auth_token,
+                                        bidi,
+                                    )
                                     .await
-                            {
+                                }
+                            };
+                            if let Err(e) = result {
                                 tracing::warn!("Extension connection error: {}", e);
                             }
                         });
@@ -180,6 +425,14 @@ impl ExtensionBridge {
             }
         });
 
+        // Spawn reconnection supervisor
+        tokio::spawn(supervise_reconnects(
+            self.state.clone(),
+            self.reconnect_attempts.clone(),
+            self.pending.clone(),
+            self.queue.clone(),
+        ));
+
         Ok(())
     }
 
@@ -200,7 +453,8 @@ impl ExtensionBridge {
         method: &str,
         params: HashMap<String, serde_json::Value>,
     ) -> Result<ExtensionResponse> {
-        if !self.is_connected().await {
+        let connected = self.is_connected().await;
+        if !connected && self.queue.is_none() {
             anyhow::bail!("Extension not connected");
         }
 
@@ -218,16 +472,41 @@ impl ExtensionBridge {
             pending.insert(id.clone(), tx);
         }
 
-        // Send request to extension
-        self.request_tx
-            .send(request)
-            .map_err(|_| anyhow::anyhow!("Failed to send request to extension"))?;
+        match &self.queue {
+            Some(queue) if !connected => {
+                if let Err(e) = queue.push(request.clone()) {
+                    self.pending.write().await.remove(&id);
+                    return Err(e);
+                }
+                tracing::debug!("Extension disconnected; queued {}", method);
+
+                // The extension may have reconnected (and drained the queue) in between
+                if self.is_connected().await && queue.remove(&id) {
+                    self.request_tx
+                        .send(request)
+                        .map_err(|_| anyhow::anyhow!("Failed to send request to extension"))?;
+                }
+            }
+            _ => {
+                // Send request to extension
+                self.request_tx
+                    .send(request)
+                    .map_err(|_| anyhow::anyhow!("Failed to send request to extension"))?;
+            }
+        }
 
         // Wait for response with timeout
-        let response = tokio::time::timeout(std::time::Duration::from_secs(30), rx)
-            .await
-            .context("Extension request timed out")?
-            .context("Response channel closed")?;
+        let response = match tokio::time::timeout(std::time::Duration::from_secs(30), rx).await {
+            Ok(response) => response.context("Response channel closed")?,
+            Err(_) => {
+                // Don't send a request the caller has given up on
+                self.pending.write().await.remove(&id);
+                if let Some(queue) = &self.queue {
+                    queue.remove(&id);
+                }
+                anyhow::bail!("Extension request timed out");
+            }
+        };
 
         Ok(response)
     }
@@ -242,6 +521,48 @@ impl ExtensionBridge {
         self.port
     }
 
+    /// Reconnect waits since the extension last dropped (0 while connected)
+    pub fn reconnect_attempts(&self) -> u32 {
+        self.reconnect_attempts.load(Ordering::SeqCst)
+    }
+
+    /// Whether requests are queued (rather than failed) while disconnected
+    pub fn queues_when_disconnected(&self) -> bool {
+        self.queue.is_some()
+    }
+
+    /// Requests waiting for the extension to reconnect
+    pub fn queued_count(&self) -> usize {
+        self.queue.as_ref().map_or(0, |q| q.len())
+    }
+
+    /// Requests rejected because the queue was full
+    pub fn dropped_count(&self) -> u64 {
+        self.queue
+            .as_ref()
+            .map_or(0, |q| q.dropped.load(Ordering::SeqCst))
+    }
+
+    /// Requests written to the extension since the bridge started
+    pub fn messages_sent(&self) -> u64 {
+        self.stats.messages_sent.load(Ordering::SeqCst)
+    }
+
+    /// Messages read from the extension since the bridge started
+    pub fn messages_received(&self) -> u64 {
+        self.stats.messages_received.load(Ordering::SeqCst)
+    }
+
+    /// When the extension last connected (RFC 3339), if it ever has
+    pub fn last_connected_at(&self) -> Option<String> {
+        self.stats.last_connected_at.lock().unwrap().clone()
+    }
+
+    /// Queue capacity (None when queuing is off)
+    pub fn queue_capacity(&self) -> Option<usize> {
+        self.queue.as_ref().map(|q| q.capacity)
+    }
+
     /// Blocking version of call() for use from synchronous code.
     /// Uses tokio's Handle::block_on when called from outside async context.
     pub fn call_blocking(
@@ -277,32 +598,122 @@ impl ExtensionBridge {
     }
 }
 
+/// Backoff before reconnect attempt `attempt` (0-based): 1s, 2s, 4s, ... capped at 30s.
+fn reconnect_delay(attempt: u32) -> Duration {
+    INITIAL_RECONNECT_DELAY
+        .checked_mul(1u32.checked_shl(attempt).unwrap_or(u32::MAX))
+        .unwrap_or(MAX_RECONNECT_DELAY)
+        .min(MAX_RECONNECT_DELAY)
+}
+
+/// Watch for the extension dropping and wait for it to come back with backoff.
+///
+/// The extension dials the bridge, so recovery means keeping the listener up
+/// and waiting; each wait is logged and counted in `attempts`.
+async fn supervise_reconnects(
+    state: Arc<RwLock<ConnectionState>>,
+    attempts: Arc<AtomicU32>,
+    pending: PendingRequests,
+    queue: Option<Arc<MessageQueue>>,
+) {
+    let mut was_connected = false;
+
+    loop {
+        if *state.read().await == ConnectionState::Connected {
+            let previous = attempts.swap(0, Ordering::SeqCst);
+            if previous > 0 {
+                tracing::info!("Extension reconnected after {} attempt(s)", previous);
+            }
+            was_connected = true;
+            tokio::time::sleep(SUPERVISOR_POLL_INTERVAL).await;
+            continue;
+        }
+
+        // Nothing to recover until the extension has connected once
+        if !was_connected {
+            tokio::time::sleep(SUPERVISOR_POLL_INTERVAL).await;
+            continue;
+        }
+
+        let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+        if attempt == 0 {
+            // Requests sent on the dropped connection will never be answered;
+            // queued ones are still waiting to be sent
+            let mut pending = pending.write().await;
+            let before = pending.len();
+            pending.retain(|id, _| queue.as_ref().is_some_and(|q| q.contains(id)));
+            let failed = before - pending.len();
+            if failed > 0 {
+                tracing::warn!("Failing {} in-flight extension request(s)", failed);
+            }
+        }
+
+        let delay = reconnect_delay(attempt);
+        tracing::info!(
+            "Extension disconnected; waiting {}s for it to reconnect (attempt {})",
+            delay.as_secs(),
+            attempt + 1
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
 /// Handle a single WebSocket connection from the extension
-async fn handle_connection(
-    stream: TcpStream,
+#[allow(clippy::result_large_err)] // handshake callback signature is fixed by tungstenite
+#[allow(clippy::too_many_arguments)]
+async fn handle_connection<S>(
+    stream: S,
     state: Arc<RwLock<ConnectionState>>,
     connected: Arc<AtomicBool>,
+    generation: Arc<AtomicU64>,
     mut request_rx: broadcast::Receiver<ExtensionRequest>,
     response_tx: mpsc::Sender<ExtensionResponse>,
-    _pending: Arc<RwLock<HashMap<String, tokio::sync::oneshot::Sender<ExtensionResponse>>>>,
-) -> Result<()> {
-    let ws_stream = accept_async(stream)
-        .await
-        .context("Failed to accept WebSocket connection")?;
+    queue: Option<Arc<MessageQueue>>,
+    stats: Arc<BridgeStats>,
+    auth_token: Option<Arc<str>>,
+    bidi: Option<Arc<BidiTarget>>,
+) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let mut path = String::new();
+    let ws_stream = accept_hdr_async(stream, |request: &Request, response: Response| {
+        path = request.uri().path().to_string();
+        Ok(response)
+    })
+    .await
+    .context("Failed to accept WebSocket connection")?;
+
+    if let Some(target) = bidi.filter(|_| path.starts_with("/session")) {
+        return serve_bidi(ws_stream, target).await;
+    }
+
+    let mut ws_stream = ws_stream;
+    if let Some(token) = auth_token {
+        if let Err(e) = authenticate(&mut ws_stream, &token).await {
+            let _ = ws_stream.close(None).await;
+            return Err(e);
+        }
+        tracing::info!("Extension authenticated");
+    }
 
     let (mut ws_write, mut ws_read) = ws_stream.split();
 
     // Mark as connected (both async state and atomic flag)
+    let this_generation = generation.fetch_add(1, Ordering::SeqCst) + 1;
     *state.write().await = ConnectionState::Connected;
     connected.store(true, Ordering::SeqCst);
+    *stats.last_connected_at.lock().unwrap() = Some(Utc::now().to_rfc3339());
     tracing::info!("Extension WebSocket connected");
 
     // Handle incoming messages from extension
     let response_tx_clone = response_tx.clone();
+    let read_stats = stats.clone();
     let read_handle = tokio::spawn(async move {
         while let Some(msg) = ws_read.next().await {
             match msg {
                 Ok(Message::Text(text)) => {
+                    read_stats.messages_received.fetch_add(1, Ordering::SeqCst);
                     tracing::debug!("Received from extension: {}", text);
                     match serde_json::from_str::<ExtensionResponse>(&text) {
                         Ok(response) => {
@@ -335,6 +746,24 @@ async fn handle_connection(
 
     // Forward requests to extension
     let write_handle = tokio::spawn(async move {
+        // Requests queued while disconnected go first, in order
+        if let Some(queue) = queue {
+            let mut queued = queue.take_all();
+            if !queued.is_empty() {
+                tracing::info!("Sending {} queued request(s) to extension", queued.len());
+            }
+            while let Some(request) = queued.pop_front() {
+                let json = serde_json::to_string(&request).unwrap();
+                if let Err(e) = ws_write.send(Message::Text(json)).await {
+                    tracing::error!("Failed to send to extension: {}", e);
+                    queued.push_front(request);
+                    queue.requeue_front(queued);
+                    return;
+                }
+                stats.messages_sent.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
         loop {
             match request_rx.recv().await {
                 Ok(request) => {
@@ -344,6 +773,7 @@ async fn handle_connection(
                         tracing::error!("Failed to send to extension: {}", e);
                         break;
                     }
+                    stats.messages_sent.fetch_add(1, Ordering::SeqCst);
                 }
                 Err(broadcast::error::RecvError::Closed) => break,
                 Err(broadcast::error::RecvError::Lagged(_)) => continue,
@@ -357,18 +787,143 @@ async fn handle_connection(
         _ = write_handle => {},
     }
 
-    // Mark as disconnected (both async state and atomic flag)
-    *state.write().await = ConnectionState::Disconnected;
-    connected.store(false, Ordering::SeqCst);
-    tracing::info!("Extension WebSocket disconnected");
+    // Mark as disconnected (both async state and atomic flag), unless a
+    // reloaded extension already replaced this connection
+    let mut state = state.write().await;
+    if generation.load(Ordering::SeqCst) == this_generation {
+        *state = ConnectionState::Disconnected;
+        connected.store(false, Ordering::SeqCst);
+        tracing::info!("Extension WebSocket disconnected");
+    } else {
+        tracing::info!("Stale extension WebSocket closed");
+    }
 
     Ok(())
 }
 
+/// First message an extension sends when the bridge requires a token.
+#[derive(Deserialize)]
+struct AuthMessage {
+    r#type: String,
+    token: String,
+}
+
+/// Wait for the connection's auth message and check its token.
+async fn authenticate<S>(ws_stream: &mut WebSocketStream<S>, token: &str) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let first = tokio::time::timeout(AUTH_TIMEOUT, ws_stream.next())
+        .await
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "Extension didn't authenticate within {}s",
+                AUTH_TIMEOUT.as_secs()
+            )
+        })?;
+
+    match first {
+        Some(Ok(Message::Text(text))) if is_valid_auth(&text, token) => Ok(()),
+        Some(Ok(_)) => anyhow::bail!("Extension sent an invalid auth message; closing connection"),
+        Some(Err(e)) => Err(e).context("Failed to read auth message"),
+        None => anyhow::bail!("Extension closed the connection before authenticating"),
+    }
+}
+
+/// Whether `text` is an auth message carrying `token`.
+fn is_valid_auth(text: &str, token: &str) -> bool {
+    match serde_json::from_str::<AuthMessage>(text) {
+        Ok(message) => message.r#type == "auth" && tokens_match(&message.token, token),
+        Err(_) => false,
+    }
+}
+
+/// Compare tokens without stopping at the first differing byte.
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Random token for `--extension-auth`.
+pub fn generate_auth_token() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// Serve a WebDriver BiDi session, answering commands in order
+async fn serve_bidi<S>(ws_stream: WebSocketStream<S>, target: Arc<BidiTarget>) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (mut ws_write, mut ws_read) = ws_stream.split();
+    tracing::info!("WebDriver BiDi session connected");
+
+    while let Some(msg) = ws_read.next().await {
+        match msg {
+            Ok(Message::Text(text)) => {
+                tracing::debug!("BiDi command: {}", text);
+                let response = target.handle(&text).await;
+                if let Err(e) = ws_write.send(Message::Text(response)).await {
+                    tracing::error!("Failed to send BiDi response: {}", e);
+                    break;
+                }
+            }
+            Ok(Message::Close(_)) => break,
+            Ok(_) => {}
+            Err(e) => {
+                tracing::error!("WebSocket read error: {}", e);
+                break;
+            }
+        }
+    }
+
+    tracing::info!("WebDriver BiDi session disconnected");
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_valid_auth() {
+        assert!(is_valid_auth(r#"{"type":"auth","token":"abc"}"#, "abc"));
+        assert!(!is_valid_auth(r#"{"type":"auth","token":"abd"}"#, "abc"));
+        assert!(!is_valid_auth(r#"{"type":"auth","token":"ab"}"#, "abc"));
+        assert!(!is_valid_auth(r#"{"type":"hello","token":"abc"}"#, "abc"));
+        assert!(!is_valid_auth(r#"{"type":"hello"}"#, "abc"));
+        assert!(!is_valid_auth("not json", "abc"));
+
+        let token = generate_auth_token();
+        assert_eq!(token.len(), 32);
+        assert_ne!(token, generate_auth_token());
+    }
+
+    #[test]
+    fn test_tls_config() {
+        let plain = ExtensionBridgeConfig::default();
+        assert_eq!(plain.scheme(), "ws");
+        assert!(plain.tls_acceptor().unwrap().is_none());
+
+        let cert_only = ExtensionBridgeConfig {
+            tls_cert_path: Some(PathBuf::from("/nonexistent/cert.pem")),
+            tls_key_path: None,
+        };
+        assert_eq!(cert_only.scheme(), "ws");
+        assert!(cert_only.tls_acceptor().is_err());
+
+        let missing = ExtensionBridgeConfig {
+            tls_cert_path: Some(PathBuf::from("/nonexistent/cert.pem")),
+            tls_key_path: Some(PathBuf::from("/nonexistent/key.pem")),
+        };
+        assert_eq!(missing.scheme(), "wss");
+        let err = missing.tls_acceptor().err().unwrap().to_string();
+        assert!(err.contains("Failed to open TLS certificate"), "{}", err);
+    }
+
     #[test]
     fn test_is_extension_method() {
         assert!(is_extension_method("browser.tabs.group"));
@@ -384,4 +939,42 @@ mod tests {
         assert_eq!(extension_method_name("browser.cookies.getAll"), "cookies.getAll");
         assert_eq!(extension_method_name("tabs.group"), "tabs.group"); // Already stripped
     }
+
+    fn request(id: &str) -> ExtensionRequest {
+        ExtensionRequest {
+            id: id.to_string(),
+            method: "tabs.query".to_string(),
+            params: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_message_queue() {
+        let queue = MessageQueue::new(2);
+        queue.push(request("a")).unwrap();
+        queue.push(request("b")).unwrap();
+        assert!(queue.push(request("c")).is_err());
+        assert_eq!(queue.dropped.load(Ordering::SeqCst), 1);
+
+        assert!(queue.remove("a"));
+        assert!(!queue.remove("a"));
+        queue.push(request("c")).unwrap();
+
+        let mut taken = queue.take_all();
+        assert_eq!(queue.len(), 0);
+        taken.pop_front();
+        queue.push(request("d")).unwrap();
+        queue.requeue_front(taken);
+        let order: Vec<String> = queue.take_all().into_iter().map(|r| r.id).collect();
+        assert_eq!(order, ["c", "d"]);
+    }
+
+    #[test]
+    fn test_reconnect_delay() {
+        assert_eq!(reconnect_delay(0), Duration::from_secs(1));
+        assert_eq!(reconnect_delay(1), Duration::from_secs(2));
+        assert_eq!(reconnect_delay(2), Duration::from_secs(4));
+        assert_eq!(reconnect_delay(5), Duration::from_secs(30));
+        assert_eq!(reconnect_delay(40), Duration::from_secs(30));
+    }
 }
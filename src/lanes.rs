@@ -0,0 +1,175 @@
+//! Per-session ordering of service calls.
+//!
+//! The daemon can call `dispatch` from several threads at once. Calls for
+//! different sessions run side by side on the shared runtime, while calls for
+//! the same session wait their turn in arrival order, so one agent's slow
+//! navigation doesn't hold up another agent's snapshot.
+//!
+//! A lane exists only while a call holds or waits for it, so sessions closed
+//! by any route (`session.close`, the idle timeout, eviction at the session
+//! limit, closing a tab session) leave nothing behind.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::OwnedMutexGuard;
+
+/// One FIFO lane per session key.
+#[derive(Default)]
+pub struct SessionLanes {
+    lanes: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl SessionLanes {
+    /// Wait until earlier calls in the `key` lane have finished. The lane
+    /// is held until the returned guard is dropped.
+    ///
+    /// Must not be called from inside the runtime (it blocks the thread).
+    pub fn enter(&self, key: &str) -> LaneTurn<'_> {
+        // tokio's mutex queues waiters fairly, which keeps calls in order
+        let guard = self.lane(key).blocking_lock_owned();
+        self.turn(key, guard)
+    }
+
+    /// Like `enter`, for callers running on the runtime (BiDi commands).
+    pub async fn enter_async(&self, key: &str) -> LaneTurn<'_> {
+        let guard = self.lane(key).lock_owned().await;
+        self.turn(key, guard)
+    }
+
+    /// Whether a call holds or waits for the `key` lane.
+    pub fn is_busy(&self, key: &str) -> bool {
+        self.lanes.lock().unwrap().contains_key(key)
+    }
+
+    /// Keys a call holds or waits for.
+    pub fn busy_keys(&self) -> Vec<String> {
+        self.lanes.lock().unwrap().keys().cloned().collect()
+    }
+
+    fn lane(&self, key: &str) -> Arc<tokio::sync::Mutex<()>> {
+        self.lanes
+            .lock()
+            .unwrap()
+            .entry(key.to_string())
+            .or_default()
+            .clone()
+    }
+
+    fn turn(&self, key: &str, guard: OwnedMutexGuard<()>) -> LaneTurn<'_> {
+        LaneTurn {
+            lanes: self,
+            key: key.to_string(),
+            guard: Some(guard),
+        }
+    }
+}
+
+/// A call's turn in its lane, released on drop.
+pub struct LaneTurn<'a> {
+    lanes: &'a SessionLanes,
+    key: String,
+    guard: Option<OwnedMutexGuard<()>>,
+}
+
+impl Drop for LaneTurn<'_> {
+    fn drop(&mut self) {
+        let Some(guard) = self.guard.take() else {
+            return;
+        };
+        let mut lanes = self.lanes.lanes.lock().unwrap();
+        // Only the map and this turn hold the lane: no call is waiting for it.
+        // Callers clone the lane under the map lock, so none can slip in.
+        let idle = lanes.get(&self.key).is_some_and(|lane| {
+            Arc::ptr_eq(lane, OwnedMutexGuard::mutex(&guard)) && Arc::strong_count(lane) == 2
+        });
+        if idle {
+            lanes.remove(&self.key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    const WORK: Duration = Duration::from_millis(300);
+
+    fn timed(lanes: &SessionLanes, key: &str) -> (Instant, Instant) {
+        let _turn = lanes.enter(key);
+        let start = Instant::now();
+        std::thread::sleep(WORK);
+        (start, Instant::now())
+    }
+
+    #[test]
+    fn test_sessions_run_concurrently() {
+        let lanes = SessionLanes::default();
+        let started = Instant::now();
+
+        let (a, b) = std::thread::scope(|s| {
+            let a = s.spawn(|| timed(&lanes, "agent-a"));
+            let b = s.spawn(|| timed(&lanes, "agent-b"));
+            (a.join().unwrap(), b.join().unwrap())
+        });
+
+        // Each call started before the other finished
+        assert!(a.0 < b.1 && b.0 < a.1);
+        assert!(started.elapsed() < WORK * 2);
+    }
+
+    #[test]
+    fn test_same_session_runs_in_order() {
+        let lanes = SessionLanes::default();
+        let order = Mutex::new(Vec::new());
+
+        let runs: Vec<_> = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..3)
+                .map(|i| {
+                    let (lanes, order) = (&lanes, &order);
+                    let handle = s.spawn(move || {
+                        let _turn = lanes.enter("agent-a");
+                        order.lock().unwrap().push(i);
+                        let start = Instant::now();
+                        std::thread::sleep(WORK);
+                        (start, Instant::now())
+                    });
+                    // Let each call queue up before the next arrives
+                    std::thread::sleep(Duration::from_millis(50));
+                    handle
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        assert_eq!(*order.lock().unwrap(), vec![0, 1, 2]);
+        for pair in runs.windows(2) {
+            assert!(pair[0].1 <= pair[1].0);
+        }
+    }
+
+    #[test]
+    fn test_lanes_are_forgotten_when_idle() {
+        let lanes = SessionLanes::default();
+        let turn = lanes.enter("agent-a");
+        assert!(lanes.is_busy("agent-a"));
+        assert_eq!(lanes.busy_keys(), vec!["agent-a"]);
+        drop(turn);
+        assert!(!lanes.is_busy("agent-a"));
+        assert!(lanes.lanes.lock().unwrap().is_empty());
+
+        // A waiting call keeps the lane after the first call finishes
+        let first = lanes.enter("agent-a");
+        std::thread::scope(|s| {
+            let waiter = s.spawn(|| {
+                let _turn = lanes.enter("agent-a");
+                std::thread::sleep(Duration::from_millis(50));
+            });
+            std::thread::sleep(Duration::from_millis(50));
+            drop(first);
+            assert_eq!(lanes.lanes.lock().unwrap().len(), 1);
+            waiter.join().unwrap();
+        });
+        assert!(lanes.lanes.lock().unwrap().is_empty());
+    }
+}
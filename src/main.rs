@@ -6,19 +6,23 @@
 //! 01/15/2026 - Added extension bridge WebSocket server (Claude)
 //! 01/15/2026 - Added connect mode for user's Chrome (Claude)
 
+mod bidi;
 mod browser;
+mod cooldown;
 mod extension_bridge;
+mod lanes;
 mod models;
 mod service;
 
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use fgp_daemon::{cleanup_socket, FgpServer};
 use std::io::{BufRead, BufReader, Write};
 use std::os::unix::net::UnixStream;
 use std::path::Path;
 use std::process::Command;
 
+use crate::models::{BrowserChannel, ViewportSize};
 use crate::service::BrowserService;
 
 const DEFAULT_SOCKET: &str = "~/.fgp/services/browser/daemon.sock";
@@ -52,16 +56,58 @@ enum Commands {
         #[arg(long)]
         headed: bool,
 
-        /// Use system Chrome instead of bundled Chromium
-        #[arg(long)]
-        channel: Option<String>,
+        /// System browser to launch instead of the bundled chrome-headless-shell
+        /// (chrome, chrome-beta, chrome-dev, chrome-canary, chromium)
+        #[arg(long, conflicts_with = "connect")]
+        channel: Option<BrowserChannel>,
+
+        /// Default viewport for new pages, as WIDTHxHEIGHT (ignored with --connect)
+        #[arg(long, default_value = "1280x800")]
+        viewport: ViewportSize,
+
+        /// Console messages kept per session (max 10000)
+        #[arg(long, default_value = "1000")]
+        console_buffer: usize,
+
+        /// Close sessions that get no commands for this many seconds
+        /// (0 = never; the default session is never closed)
+        #[arg(long, default_value = "0")]
+        session_idle_timeout: u64,
+
+        /// Browser contexts to keep ready for `session new` (0 = create on demand)
+        #[arg(long, default_value = "0")]
+        session_pool: usize,
+
+        /// Custom sessions allowed at once (0 = no limit)
+        #[arg(long, default_value = "20")]
+        max_sessions: usize,
 
         /// Connect to existing Chrome instance (e.g., "http://localhost:9222")
         /// Use this to access your logged-in sessions. Start Chrome with:
         /// /Applications/Google\ Chrome.app/Contents/MacOS/Google\ Chrome --remote-debugging-port=9222
+        /// A ws://.../devtools/browser/<id> URL connects directly (remote or Docker Chrome)
         #[arg(long)]
         connect: Option<String>,
 
+        /// Connect to your own Chrome on --debug-port, launching it with remote
+        /// debugging and your profile if nothing is listening yet
+        /// (--channel picks which Chrome)
+        #[arg(long, conflicts_with = "connect")]
+        connect_launch: bool,
+
+        /// Remote debugging port for --connect-launch
+        #[arg(long, default_value = "9222", requires = "connect_launch")]
+        debug_port: u16,
+
+        /// Profile directory for --connect-launch (default: the browser's own profile)
+        #[arg(long, requires = "connect_launch")]
+        connect_profile: Option<String>,
+
+        /// Let calls target other Chrome instances with an `endpoint` param,
+        /// closing each connection after this many seconds unused
+        #[arg(long)]
+        client_pool_ttl: Option<u64>,
+
         /// Enable extension bridge WebSocket server (port 9223)
         /// Allows FGP Chrome extension to provide tab groups, cookies, etc.
         #[arg(long)]
@@ -70,6 +116,32 @@ enum Commands {
         /// Extension bridge WebSocket port (default: 9223)
         #[arg(long, default_value = "9223")]
         extension_port: u16,
+
+        /// Hold up to N extension requests while the extension is disconnected
+        /// and send them when it reconnects (0 = fail immediately)
+        #[arg(long, default_value = "0")]
+        extension_queue: usize,
+
+        /// PEM certificate for the extension bridge; with --extension-tls-key,
+        /// the bridge is served as wss:// (the extension must trust the certificate)
+        #[arg(long, requires = "extension_tls_key")]
+        extension_tls_cert: Option<String>,
+
+        /// PEM private key for --extension-tls-cert
+        #[arg(long, requires = "extension_tls_cert")]
+        extension_tls_key: Option<String>,
+
+        /// Require the extension to send a secret token when it connects.
+        /// A random token is generated and printed; paste it into the extension popup
+        /// (implies --extension-bridge)
+        #[arg(long)]
+        extension_auth: bool,
+
+        /// Protocol served on the extension bridge port.
+        /// `bidi` also accepts WebDriver BiDi sessions at ws://127.0.0.1:PORT/session
+        /// (implies --extension-bridge)
+        #[arg(long, value_enum, default_value = "fgp")]
+        protocol: Protocol,
     },
 
     /// Stop the browser daemon
@@ -87,6 +159,24 @@ enum Commands {
     /// Navigate to URL
     Open {
         url: String,
+        /// Block resource types before navigating (e.g. images,fonts)
+        #[arg(long, value_delimiter = ',')]
+        block_resources: Vec<String>,
+        /// Extra request header as "Name: value" (repeatable, kept for the session)
+        #[arg(long = "header")]
+        headers: Vec<String>,
+        /// HTTP auth credentials as "user:pass"
+        #[arg(long)]
+        auth: Option<String>,
+        /// Viewport size as WIDTHxHEIGHT, set before navigating
+        #[arg(long)]
+        viewport: Option<ViewportSize>,
+        /// Device preset to emulate before navigating (e.g. "iPhone 14")
+        #[arg(long)]
+        device: Option<String>,
+        /// Network profile to throttle with (slow-3g, fast-3g, offline)
+        #[arg(long)]
+        network: Option<String>,
         #[arg(short, long, default_value = DEFAULT_SOCKET)]
         socket: String,
         /// Session ID (optional)
@@ -96,6 +186,58 @@ enum Commands {
 
     /// Get ARIA accessibility tree snapshot
     Snapshot {
+        /// Print the tree as Markdown instead of JSON
+        #[arg(long)]
+        markdown: bool,
+        /// Maximum tree depth to include (0 = top level only)
+        #[arg(long)]
+        depth: Option<u64>,
+        /// Also include same-origin iframes
+        #[arg(long)]
+        include_frames: bool,
+        /// Keep only actionable elements (buttons, links, inputs)
+        #[arg(long)]
+        interactive_only: bool,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Save the current ARIA snapshot under a name
+    SnapshotSave {
+        name: String,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Print a saved ARIA snapshot
+    SnapshotLoad {
+        name: String,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+    },
+
+    /// Show what changed since a saved snapshot
+    SnapshotDiff {
+        /// Saved snapshot to compare from
+        before: String,
+        /// Compare against this saved snapshot instead of the live page
+        #[arg(long)]
+        after: Option<String>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// List iframes on the page (index, URL, name)
+    Frames {
         #[arg(short, long, default_value = DEFAULT_SOCKET)]
         socket: String,
         /// Session ID (optional)
@@ -105,6 +247,31 @@ enum Commands {
 
     /// Click an element
     Click {
+        /// Element selector (@e5 for ARIA ref, or CSS selector)
+        selector: String,
+        /// Target an iframe by index or URL pattern (see `frames`); "a > b" for nested frames
+        #[arg(long)]
+        frame: Option<String>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Double-click an element
+    DoubleClick {
+        /// Element selector (@e5 for ARIA ref, or CSS selector)
+        selector: String,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Right-click an element (opens its context menu)
+    RightClick {
         /// Element selector (@e5 for ARIA ref, or CSS selector)
         selector: String,
         #[arg(short, long, default_value = DEFAULT_SOCKET)]
@@ -114,12 +281,27 @@ enum Commands {
         session: Option<String>,
     },
 
+    /// Custom (JavaScript) context menus
+    ContextMenu {
+        #[command(subcommand)]
+        action: ContextMenuAction,
+    },
+
     /// Fill an input field
     Fill {
         /// Element selector
         selector: String,
         /// Value to fill
         value: String,
+        /// Type one character at a time with this pause between keystrokes (ms)
+        #[arg(long, default_value = "0")]
+        delay_ms: u64,
+        /// Append to the field's current content instead of replacing it
+        #[arg(long)]
+        no_clear: bool,
+        /// Target an iframe by index or URL pattern (see `frames`); "a > b" for nested frames
+        #[arg(long)]
+        frame: Option<String>,
         #[arg(short, long, default_value = DEFAULT_SOCKET)]
         socket: String,
         /// Session ID (optional)
@@ -131,6 +313,9 @@ enum Commands {
     Press {
         /// Key to press (e.g., Enter, Tab, Escape)
         key: String,
+        /// Target an iframe by index or URL pattern (see `frames`); "a > b" for nested frames
+        #[arg(long)]
+        frame: Option<String>,
         #[arg(short, long, default_value = DEFAULT_SOCKET)]
         socket: String,
         /// Session ID (optional)
@@ -142,6 +327,149 @@ enum Commands {
     Screenshot {
         /// Output file path (optional, returns base64 if not specified)
         path: Option<String>,
+        /// Image format: png, jpeg, or webp
+        #[arg(long, default_value = "png")]
+        format: String,
+        /// Compression quality for jpeg/webp (0-100)
+        #[arg(long, value_parser = clap::value_parser!(u8).range(0..=100))]
+        quality: Option<u8>,
+        /// Crop to this element (@eN ref or CSS selector)
+        #[arg(long)]
+        element: Option<String>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Print the page to PDF (headless mode only)
+    Pdf {
+        /// Output file path (optional, returns base64 if not specified)
+        path: Option<String>,
+        /// Landscape orientation
+        #[arg(long)]
+        landscape: bool,
+        /// Print background graphics
+        #[arg(long)]
+        print_background: bool,
+        /// Rendering scale (0.1 - 2.0)
+        #[arg(long)]
+        scale: Option<f64>,
+        /// Paper width in inches
+        #[arg(long)]
+        paper_width: Option<f64>,
+        /// Paper height in inches
+        #[arg(long)]
+        paper_height: Option<f64>,
+        /// Margin in inches (all sides)
+        #[arg(long)]
+        margin: Option<f64>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Download a URL with the session's cookies without rendering it
+    Fetch {
+        /// URL to fetch
+        url: String,
+        /// Output file path (optional, returns base64 if not specified)
+        #[arg(short, long)]
+        out: Option<String>,
+        /// Refuse bodies larger than this many bytes
+        #[arg(long)]
+        max_bytes: Option<u64>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Get the HTML of an element or the full page
+    GetHtml {
+        /// Element selector (omit for the full document)
+        #[arg(long)]
+        selector: Option<String>,
+        /// Return innerHTML instead of outerHTML
+        #[arg(long)]
+        inner: bool,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Get the page's HTML with its URL and title
+    Content {
+        /// Element selector (omit for the full document)
+        #[arg(long)]
+        selector: Option<String>,
+        /// Fail above this many bytes instead of truncating (default 10 MiB)
+        #[arg(long)]
+        max_bytes: Option<u64>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Get the visible text of an element or the full page
+    GetText {
+        /// Element selector (omit for the whole body)
+        #[arg(long)]
+        selector: Option<String>,
+        /// Target an iframe by index or URL pattern (see `frames`); "a > b" for nested frames
+        #[arg(long)]
+        frame: Option<String>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Extract the main article content (readability-style)
+    Extract {
+        /// Print only the Markdown content
+        #[arg(long)]
+        markdown: bool,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// List all links on the page
+    #[command(alias = "get-links")]
+    Links {
+        /// Scope extraction to elements matching this selector
+        #[arg(long)]
+        selector: Option<String>,
+        /// Only include links on the page's origin
+        #[arg(long)]
+        same_origin_only: bool,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// List all images on the page
+    Images {
+        /// Scope extraction to elements matching this selector
+        #[arg(long)]
+        selector: Option<String>,
+        /// Only include images on the page's origin
+        #[arg(long)]
+        same_origin_only: bool,
         #[arg(short, long, default_value = DEFAULT_SOCKET)]
         socket: String,
         /// Session ID (optional)
@@ -153,8 +481,15 @@ enum Commands {
     Select {
         /// Element selector
         selector: String,
-        /// Option value to select
-        value: String,
+        /// Option value or label to select (several for a multi-select)
+        #[arg(required = true, num_args = 1..)]
+        values: Vec<String>,
+        /// Multi-select: add to the current selection instead of replacing it
+        #[arg(long)]
+        add: bool,
+        /// Target an iframe by index or URL pattern (see `frames`); "a > b" for nested frames
+        #[arg(long)]
+        frame: Option<String>,
         #[arg(short, long, default_value = DEFAULT_SOCKET)]
         socket: String,
         /// Session ID (optional)
@@ -169,6 +504,9 @@ enum Commands {
         /// Whether to check (true) or uncheck (false)
         #[arg(long, default_value = "true")]
         checked: bool,
+        /// Target an iframe by index or URL pattern (see `frames`); "a > b" for nested frames
+        #[arg(long)]
+        frame: Option<String>,
         #[arg(short, long, default_value = DEFAULT_SOCKET)]
         socket: String,
         /// Session ID (optional)
@@ -180,6 +518,9 @@ enum Commands {
     Hover {
         /// Element selector
         selector: String,
+        /// Target an iframe by index or URL pattern (see `frames`); "a > b" for nested frames
+        #[arg(long)]
+        frame: Option<String>,
         #[arg(short, long, default_value = DEFAULT_SOCKET)]
         socket: String,
         /// Session ID (optional)
@@ -187,16 +528,16 @@ enum Commands {
         session: Option<String>,
     },
 
-    /// Scroll to element or by amount
-    Scroll {
-        /// Element selector to scroll to (optional)
-        selector: Option<String>,
-        /// Horizontal scroll amount
-        #[arg(long, default_value = "0")]
-        x: i32,
-        /// Vertical scroll amount
-        #[arg(long, default_value = "0")]
-        y: i32,
+    /// Select text in an element (all of it unless --start/--end are given)
+    SelectText {
+        /// Element selector
+        selector: String,
+        /// First character to select
+        #[arg(long, requires = "end")]
+        start: Option<usize>,
+        /// Character after the last one selected
+        #[arg(long, requires = "start")]
+        end: Option<usize>,
         #[arg(short, long, default_value = DEFAULT_SOCKET)]
         socket: String,
         /// Session ID (optional)
@@ -204,13 +545,15 @@ enum Commands {
         session: Option<String>,
     },
 
-    /// Press key with modifiers (Ctrl+A, Shift+Tab, etc.)
-    PressCombo {
-        /// Key to press
-        key: String,
-        /// Modifiers (Ctrl, Shift, Alt, Meta)
-        #[arg(short, long, value_delimiter = ',')]
-        modifiers: Vec<String>,
+    /// Drag an element onto another with the mouse
+    Drag {
+        /// Selector of the element to drag
+        source: String,
+        /// Selector of the element to drop on
+        target: String,
+        /// Viewport point "x,y" to pass through on the way (repeatable)
+        #[arg(long = "step")]
+        steps: Vec<String>,
         #[arg(short, long, default_value = DEFAULT_SOCKET)]
         socket: String,
         /// Session ID (optional)
@@ -218,12 +561,14 @@ enum Commands {
         session: Option<String>,
     },
 
-    /// Upload a file to a file input
-    Upload {
-        /// Element selector
-        selector: String,
-        /// File path to upload
-        path: String,
+    /// Drag the mouse through viewport points "x,y" (e.g. draw on a canvas)
+    DragPath {
+        /// Points to press at, move through and release at (at least two)
+        #[arg(required = true, num_args = 2..)]
+        points: Vec<String>,
+        /// Pause before each move, in milliseconds
+        #[arg(long, default_value = "0")]
+        delay_ms: u64,
         #[arg(short, long, default_value = DEFAULT_SOCKET)]
         socket: String,
         /// Session ID (optional)
@@ -231,74 +576,1088 @@ enum Commands {
         session: Option<String>,
     },
 
-    /// Auth state management
-    State {
-        #[command(subcommand)]
-        action: StateAction,
-    },
-
-    /// Session management for parallel requests
-    Session {
-        #[command(subcommand)]
-        action: SessionAction,
-    },
-
-    /// Chrome extension features (tab groups, cookies, notifications)
-    Extension {
+    /// Raw mouse events at viewport coordinates (canvas apps, maps)
+    Mouse {
         #[command(subcommand)]
-        action: ExtensionAction,
+        action: MouseAction,
     },
-}
 
-#[derive(Subcommand)]
-enum StateAction {
-    /// Save current auth state
-    Save {
-        name: String,
+    /// Get an element's bounding box
+    GetBounds {
+        /// Element selector
+        selector: String,
         #[arg(short, long, default_value = DEFAULT_SOCKET)]
         socket: String,
         /// Session ID (optional)
         #[arg(long)]
         session: Option<String>,
     },
-    /// Load saved auth state
-    Load {
-        name: String,
+
+    /// Focus element without clicking
+    Focus {
+        /// Element selector
+        selector: String,
+        /// Target an iframe by index or URL pattern (see `frames`); "a > b" for nested frames
+        #[arg(long)]
+        frame: Option<String>,
         #[arg(short, long, default_value = DEFAULT_SOCKET)]
         socket: String,
         /// Session ID (optional)
         #[arg(long)]
         session: Option<String>,
     },
-    /// List saved auth states
-    List {
-        #[arg(short, long, default_value = DEFAULT_SOCKET)]
-        socket: String,
-    },
-}
 
-#[derive(Subcommand)]
-enum SessionAction {
-    /// Create a new isolated session
-    New {
-        /// Session ID
+    /// Empty a text field without typing
+    ClearField {
+        /// Element selector
+        selector: String,
+        /// Target an iframe by index or URL pattern (see `frames`); "a > b" for nested frames
         #[arg(long)]
-        id: String,
+        frame: Option<String>,
         #[arg(short, long, default_value = DEFAULT_SOCKET)]
         socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
     },
-    /// List active sessions
-    List {
+
+    /// Remove focus from the active element
+    Blur {
         #[arg(short, long, default_value = DEFAULT_SOCKET)]
         socket: String,
-    },
-    /// Close a session
-    Close {
-        /// Session ID to close
+        /// Session ID (optional)
         #[arg(long)]
-        id: String,
-        #[arg(short, long, default_value = DEFAULT_SOCKET)]
-        socket: String,
+        session: Option<String>,
+    },
+
+    /// Scroll to element or by amount
+    Scroll {
+        /// Element selector to scroll to (optional)
+        selector: Option<String>,
+        /// Horizontal scroll amount
+        #[arg(long, default_value = "0")]
+        x: i32,
+        /// Vertical scroll amount
+        #[arg(long, default_value = "0")]
+        y: i32,
+        /// Target an iframe by index or URL pattern (see `frames`); "a > b" for nested frames
+        #[arg(long)]
+        frame: Option<String>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Scroll to the top of the page
+    ScrollTop {
+        /// Animate the scroll instead of jumping
+        #[arg(long)]
+        smooth: bool,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Scroll to the bottom of the page once
+    ScrollBottom {
+        /// Animate the scroll instead of jumping
+        #[arg(long)]
+        smooth: bool,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Keep scrolling to the bottom until an infinite-scroll feed stops growing
+    ScrollToBottom {
+        /// Stop after this many scrolls (default 50)
+        #[arg(long)]
+        max_scrolls: Option<u32>,
+        /// Wait this long for more content after each scroll (default 1000)
+        #[arg(long)]
+        settle_ms: Option<u64>,
+        /// Stop scrolling after this long overall (default 30000)
+        #[arg(long)]
+        timeout_ms: Option<u64>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Press key with modifiers (Ctrl+A, Shift+Tab, etc.)
+    PressCombo {
+        /// Key to press
+        key: String,
+        /// Modifiers (Ctrl, Shift, Alt, Meta)
+        #[arg(short, long, value_delimiter = ',')]
+        modifiers: Vec<String>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Press a series of keys in order (e.g. vim or spreadsheet shortcuts)
+    KeySequence {
+        /// JSON array of key presses: `{"key", "modifiers", "delay_after_ms"}`
+        /// objects, or plain key names, e.g. '["Escape", "d", "d"]'
+        #[arg(long)]
+        keys: String,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Upload a file to a file input
+    Upload {
+        /// Element selector
+        selector: String,
+        /// File path to upload (several for an input with `multiple`)
+        #[arg(required = true, num_args = 1..)]
+        paths: Vec<String>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Resize the viewport, e.g. `set-viewport 1366x768` or `set-viewport --device "Pixel 7"`
+    SetViewport {
+        /// Viewport size as WIDTHxHEIGHT
+        #[arg(required_unless_present = "device")]
+        size: Option<ViewportSize>,
+        /// Device preset (iphone-14, pixel-7, ipad); a size overrides its viewport
+        #[arg(long)]
+        device: Option<String>,
+        /// Device pixel ratio
+        #[arg(long)]
+        scale: Option<f64>,
+        /// Mobile viewport with touch events
+        #[arg(long)]
+        mobile: bool,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Override geolocation (or clear it with --clear)
+    Geolocation {
+        /// Latitude in degrees
+        #[arg(long, allow_hyphen_values = true, required_unless_present = "clear")]
+        latitude: Option<f64>,
+        /// Longitude in degrees
+        #[arg(long, allow_hyphen_values = true, required_unless_present = "clear")]
+        longitude: Option<f64>,
+        /// Accuracy in meters
+        #[arg(long)]
+        accuracy: Option<f64>,
+        /// Remove the override
+        #[arg(long, conflicts_with_all = ["latitude", "longitude", "accuracy"])]
+        clear: bool,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Emulate a device viewport (iphone-14, pixel-7, ipad, or explicit size),
+    /// or geolocation and permissions via subcommands
+    #[command(args_conflicts_with_subcommands = true)]
+    Emulate {
+        #[command(subcommand)]
+        action: Option<EmulateAction>,
+        /// Device preset name
+        preset: Option<String>,
+        /// Viewport width in CSS pixels
+        #[arg(long)]
+        width: Option<u32>,
+        /// Viewport height in CSS pixels
+        #[arg(long)]
+        height: Option<u32>,
+        /// Device pixel ratio
+        #[arg(long)]
+        scale: Option<f64>,
+        /// Mobile viewport with touch events
+        #[arg(long)]
+        mobile: bool,
+        /// User agent override
+        #[arg(long)]
+        user_agent: Option<String>,
+        /// Clear all emulation overrides
+        #[arg(long)]
+        reset: bool,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Configure JavaScript dialog handling and show handled dialogs
+    Dialog {
+        /// Dialog action: accept, dismiss, or manual to leave dialogs open
+        /// (omit to keep current policy)
+        #[arg(long, value_parser = ["accept", "dismiss", "manual"])]
+        action: Option<String>,
+        /// Text to enter into prompt() dialogs when accepting
+        #[arg(long)]
+        prompt_text: Option<String>,
+        /// Clear the dialog buffer after showing it
+        #[arg(long)]
+        clear: bool,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Show console messages and browser log entries
+    Console {
+        /// Only show entries at this level (e.g. error, warning)
+        #[arg(long)]
+        level: Option<String>,
+        /// Show only the most recent N entries
+        #[arg(long)]
+        limit: Option<u64>,
+        /// Clear the console buffer after showing it
+        #[arg(long)]
+        clear: bool,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Network request capture
+    Network {
+        #[command(subcommand)]
+        action: NetworkAction,
+    },
+
+    /// Performance metrics and load timing for the current page
+    Metrics {
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// CPU throttling for performance testing
+    Cpu {
+        #[command(subcommand)]
+        action: CpuAction,
+    },
+
+    /// Read or write the clipboard (for copy-paste flows)
+    Clipboard {
+        #[command(subcommand)]
+        action: ClipboardAction,
+    },
+
+    /// Downloaded files
+    Downloads {
+        #[command(subcommand)]
+        action: DownloadsAction,
+    },
+
+    /// Record network traffic as a HAR file
+    Har {
+        #[command(subcommand)]
+        action: HarAction,
+    },
+
+    /// Auth state management
+    State {
+        #[command(subcommand)]
+        action: StateAction,
+    },
+
+    /// Export or import cookies as a JSON array
+    Cookies {
+        #[command(subcommand)]
+        action: CookiesAction,
+    },
+
+    /// Export or import one origin's localStorage
+    Storage {
+        #[command(subcommand)]
+        action: StorageAction,
+    },
+
+    /// Read IndexedDB for the current page's origin
+    Indexeddb {
+        /// Database name
+        #[arg(long)]
+        db: String,
+        /// Object store to read every record of (default: list the stores)
+        #[arg(long)]
+        store: Option<String>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Session management for parallel requests
+    Session {
+        #[command(subcommand)]
+        action: SessionAction,
+    },
+
+    /// List tabs or switch the default session to another tab
+    Tabs {
+        #[command(subcommand)]
+        action: TabsAction,
+    },
+
+    /// A session's pages: its own tab and popups opened from it
+    Pages {
+        #[command(subcommand)]
+        action: PagesAction,
+    },
+
+    /// Tabs within a session's browser context
+    Tab {
+        #[command(subcommand)]
+        action: TabAction,
+    },
+
+    /// Chrome extension features (tab groups, cookies, notifications)
+    Extension {
+        #[command(subcommand)]
+        action: ExtensionAction,
+    },
+}
+
+/// `start --connect-launch` settings.
+struct ConnectLaunch {
+    port: u16,
+    profile: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Protocol {
+    /// Extension bridge only
+    Fgp,
+    /// Extension bridge plus a WebDriver BiDi subset
+    Bidi,
+}
+
+#[derive(Subcommand)]
+enum CookiesAction {
+    /// Write the session's cookies to a JSON file
+    Export {
+        /// Output file path
+        path: String,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Set cookies from a JSON file (ours or a cookie-export extension's)
+    Import {
+        /// JSON array of cookies
+        path: String,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum StorageAction {
+    /// Write localStorage to a JSON file
+    Export {
+        /// Output file path
+        path: String,
+        /// Origin to export (default: the current page's)
+        #[arg(long)]
+        origin: Option<String>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Add localStorage items from a `storage export` file
+    Import {
+        /// File written by `storage export`
+        path: String,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum StateAction {
+    /// Save current auth state
+    Save {
+        name: String,
+        /// Origin whose localStorage to save (repeatable; default: the page's
+        /// origin and every origin with cookies)
+        #[arg(long = "origin")]
+        origins: Vec<String>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Load saved auth state
+    Load {
+        name: String,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// List saved auth states
+    List {
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+    },
+    /// Delete a saved auth state
+    Delete {
+        name: String,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+    },
+    /// Rename a saved auth state
+    Rename {
+        name: String,
+        new_name: String,
+        /// Replace an existing state called NEW_NAME
+        #[arg(long)]
+        overwrite: bool,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum NetworkAction {
+    /// Start capturing network requests
+    Enable {
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Stop capturing network requests
+    Disable {
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// List captured requests
+    List {
+        /// URL substring, or glob with * wildcards
+        #[arg(long)]
+        url: Option<String>,
+        /// Status code (404), class (4xx), or "failed"
+        #[arg(long)]
+        status: Option<String>,
+        /// Resource type (e.g. XHR, Fetch, Document)
+        #[arg(long = "type")]
+        resource_type: Option<String>,
+        /// Show only the most recent N requests
+        #[arg(long)]
+        limit: Option<u64>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Print the response body of a captured request
+    Body {
+        /// Request ID from `network list`
+        request_id: String,
+        /// Truncate the body after this many bytes
+        #[arg(long)]
+        max_bytes: Option<u64>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Block requests by URL pattern and/or resource type
+    Block {
+        /// URL substring or glob (repeatable)
+        #[arg(long = "url")]
+        urls: Vec<String>,
+        /// Resource types, comma-separated (e.g. images,fonts,media)
+        #[arg(long = "type", value_delimiter = ',')]
+        resource_types: Vec<String>,
+        /// URL pattern with * wildcards, blocked by Chrome without interception (repeatable)
+        #[arg(long = "pattern", conflicts_with_all = ["urls", "resource_types"])]
+        patterns: Vec<String>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Serve a canned response for matching requests (wins over block rules)
+    Mock {
+        /// URL substring, or glob with * wildcards
+        url_pattern: String,
+        /// HTTP status code
+        #[arg(long, default_value = "200")]
+        status: u16,
+        /// Response header as "Name: value" (repeatable)
+        #[arg(long = "header")]
+        headers: Vec<String>,
+        /// Inline response body
+        #[arg(long, conflicts_with = "body_file")]
+        body: Option<String>,
+        /// Read the response body from a file
+        #[arg(long)]
+        body_file: Option<String>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// List active mocks and their hit counts
+    Mocks {
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Remove a mock by ID, or all mocks
+    Unmock {
+        /// Mock ID (omit to remove all)
+        id: Option<String>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Remove all block rules and blocked URL patterns
+    Unblock {
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Show active block rules
+    Rules {
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Clear captured requests
+    Clear {
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Record outgoing requests (headers and bodies) for a page action
+    CaptureStart {
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Stop recording and print the captured requests
+    CaptureStop {
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Throttle the network with a profile or explicit numbers
+    Throttle {
+        /// Profile: slow-3g, fast-3g, offline
+        #[arg(required_unless_present_any = ["latency", "download", "upload", "reset"])]
+        profile: Option<String>,
+        /// Added latency in milliseconds
+        #[arg(long)]
+        latency: Option<f64>,
+        /// Download throughput in kbit/s
+        #[arg(long)]
+        download: Option<f64>,
+        /// Upload throughput in kbit/s
+        #[arg(long)]
+        upload: Option<f64>,
+        /// Lift throttling
+        #[arg(long, conflicts_with_all = ["profile", "latency", "download", "upload"])]
+        reset: bool,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ContextMenuAction {
+    /// Click the menu item with this text (after `right-click`)
+    Select {
+        text: String,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum CpuAction {
+    /// Slow the session's CPU down (e.g. 4 = 4x slower; 1 = full speed)
+    Throttle {
+        rate: f64,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ClipboardAction {
+    /// Print the clipboard's text
+    Read {
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Put text on the clipboard
+    Write {
+        text: String,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum EmulateAction {
+    /// Override geolocation (or clear it with --clear)
+    Geolocation {
+        /// Latitude in degrees
+        #[arg(long, allow_hyphen_values = true, required_unless_present = "clear")]
+        latitude: Option<f64>,
+        /// Longitude in degrees
+        #[arg(long, allow_hyphen_values = true, required_unless_present = "clear")]
+        longitude: Option<f64>,
+        /// Accuracy in meters
+        #[arg(long)]
+        accuracy: Option<f64>,
+        /// Remove the override
+        #[arg(long, conflicts_with_all = ["latitude", "longitude", "accuracy"])]
+        clear: bool,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Grant permissions (e.g. geolocation,notifications,clipboard-read,camera)
+    Permissions {
+        /// Comma-separated permission names
+        #[arg(value_delimiter = ',', required_unless_present = "reset")]
+        permissions: Vec<String>,
+        /// Origin to grant for (default: the page's current origin)
+        #[arg(long)]
+        origin: Option<String>,
+        /// Reset all permission grants instead
+        #[arg(long, conflicts_with_all = ["permissions", "origin"])]
+        reset: bool,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Emulate CSS media (dark mode, reduced motion, print)
+    Media {
+        /// prefers-color-scheme
+        #[arg(long, value_parser = ["dark", "light"])]
+        color_scheme: Option<String>,
+        /// prefers-reduced-motion: reduce (pass false to turn it off)
+        #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+        reduced_motion: Option<bool>,
+        /// CSS media type
+        #[arg(long, value_parser = ["print", "screen"])]
+        media: Option<String>,
+        /// Clear all media emulation
+        #[arg(long, conflicts_with_all = ["color_scheme", "reduced_motion", "media"])]
+        reset: bool,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DownloadsAction {
+    /// List downloaded files
+    List {
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Wait for the next download to finish
+    Wait {
+        /// Timeout in milliseconds
+        #[arg(long, default_value = "30000")]
+        timeout: u64,
+        /// Ignore downloads that started before this command
+        #[arg(long)]
+        new: bool,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Delete downloaded files older than a given age
+    Clean {
+        /// Maximum age in hours
+        #[arg(long, default_value = "168")]
+        older_than: u64,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum HarAction {
+    /// Start recording requests and responses
+    Start {
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Stop recording and write the HAR file
+    Stop {
+        /// Output file path
+        path: String,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SessionAction {
+    /// Create a new isolated session
+    New {
+        /// Session ID
+        #[arg(long)]
+        id: String,
+        /// Human-readable name shown by `session list`
+        #[arg(long)]
+        name: Option<String>,
+        /// At the session limit, close the least recently used session to make room
+        #[arg(long)]
+        evict_idle: bool,
+        /// Saved auth state to start with (see `state save`)
+        #[arg(long)]
+        state: Option<String>,
+        /// Page to open once --state is restored
+        #[arg(long)]
+        url: Option<String>,
+        /// Viewport size as WIDTHxHEIGHT
+        #[arg(long)]
+        viewport: Option<ViewportSize>,
+        /// Device preset to emulate (e.g. "iPhone 14")
+        #[arg(long)]
+        device: Option<String>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+    },
+    /// Create a session with copies of another session's cookies and localStorage
+    Clone {
+        /// Session to copy ("default" for the default session)
+        #[arg(long)]
+        source: String,
+        /// ID for the new session (generated if omitted)
+        #[arg(long)]
+        id: Option<String>,
+        /// Human-readable name shown by `session list`
+        #[arg(long)]
+        name: Option<String>,
+        /// Leave the new session on the source's current URL
+        #[arg(long)]
+        navigate: bool,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+    },
+    /// List active sessions
+    List {
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+    },
+    /// Show one session's details
+    Info {
+        /// Session ID (default session if omitted)
+        #[arg(long)]
+        id: Option<String>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+    },
+    /// Close a session
+    Close {
+        /// Session ID to close
+        #[arg(long)]
+        id: String,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+    },
+    /// Create a session that drives an already-open tab (closing it leaves the tab)
+    Attach {
+        /// Session ID
+        #[arg(long)]
+        id: String,
+        /// Target ID (from `session targets`)
+        #[arg(long, required_unless_present_any = ["url", "title"], conflicts_with_all = ["url", "title"])]
+        target_id: Option<String>,
+        /// URL substring (first tab no session drives)
+        #[arg(long, conflicts_with = "title")]
+        url: Option<String>,
+        /// Title substring (first tab no session drives)
+        #[arg(long)]
+        title: Option<String>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+    },
+    /// Label a session with a key/value pair (shown by `session list`)
+    SetMetadata {
+        key: String,
+        value: String,
+        /// Session ID (default session if omitted)
+        #[arg(long)]
+        id: Option<String>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+    },
+    /// Show a session's metadata
+    GetMetadata {
+        /// Session ID (default session if omitted)
+        #[arg(long)]
+        id: Option<String>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+    },
+    /// List tabs a session can attach to
+    Targets {
+        /// Include tabs a session already drives
+        #[arg(long)]
+        all: bool,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+    },
+    /// Open a URL in a new tab (shares logins) and print its session ID
+    TabsOpenNew {
+        url: String,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum TabsAction {
+    /// List open tabs
+    List {
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+    },
+    /// Drive another tab from the default session
+    Switch {
+        /// Target ID (from `tabs list`)
+        target_id: String,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+    },
+    /// Drive an existing tab chosen by URL substring or index
+    Attach {
+        /// URL substring (first matching tab)
+        #[arg(long, required_unless_present = "index", conflicts_with = "index")]
+        url: Option<String>,
+        /// Position in `tabs list`
+        #[arg(long)]
+        index: Option<u64>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+    },
+    /// Close a session's tab, or a tab by target ID
+    Close {
+        /// Session ID, or target ID (from `tabs list`) of a tab no session drives
+        id: String,
+        /// Allow closing a tab the daemon didn't open
+        #[arg(long)]
+        force: bool,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum PagesAction {
+    /// List the session's pages and report popups that closed
+    List {
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Send subsequent commands to another page (e.g. a popup)
+    Switch {
+        /// Page index (from `pages list`; 0 = the session's own tab)
+        index: u64,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum TabAction {
+    /// Open a tab in the session's context and send subsequent commands to it
+    New {
+        /// URL to open (default about:blank)
+        url: Option<String>,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// List the tabs in the session's context
+    List {
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Send subsequent commands to another tab
+    Switch {
+        /// Target ID (from `tab list`)
+        target_id: String,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Close a tab in the session's context
+    Close {
+        /// Target ID (from `tab list`)
+        target_id: String,
+        /// Allow closing a tab the session didn't open or switch to
+        #[arg(long)]
+        force: bool,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum MouseAction {
+    /// Move the mouse to x,y (CSS pixels from the viewport's top-left)
+    Move {
+        x: f64,
+        y: f64,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Click at x,y, whatever element is there
+    Click {
+        x: f64,
+        y: f64,
+        #[arg(long, default_value = "left", value_parser = ["left", "right", "middle"])]
+        button: String,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Scroll the wheel (positive delta-y scrolls down)
+    Wheel {
+        #[arg(allow_negative_numbers = true)]
+        delta_x: f64,
+        #[arg(allow_negative_numbers = true)]
+        delta_y: f64,
+        /// Mouse position while scrolling
+        #[arg(long, default_value = "0")]
+        x: f64,
+        #[arg(long, default_value = "0")]
+        y: f64,
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+        /// Session ID (optional)
+        #[arg(long)]
+        session: Option<String>,
     },
 }
 
@@ -347,6 +1706,11 @@ enum ExtensionAction {
         #[arg(short, long, default_value = DEFAULT_SOCKET)]
         socket: String,
     },
+    /// Show bridge connection and queued requests
+    Status {
+        #[arg(short, long, default_value = DEFAULT_SOCKET)]
+        socket: String,
+    },
 }
 
 /// Build params with optional session_id
@@ -361,9 +1725,54 @@ fn with_session(mut params: serde_json::Value, session: Option<String>) -> serde
     params
 }
 
+/// `browser.set_viewport` params for --viewport/--device flags, if either is set
+fn viewport_params(
+    viewport: Option<ViewportSize>,
+    device: Option<String>,
+) -> Option<serde_json::Value> {
+    if viewport.is_none() && device.is_none() {
+        return None;
+    }
+    let mut params = serde_json::json!({});
+    let obj = params.as_object_mut().unwrap();
+    if let Some(size) = viewport {
+        obj.insert("width".to_string(), serde_json::json!(size.width));
+        obj.insert("height".to_string(), serde_json::json!(size.height));
+    }
+    if let Some(device) = device {
+        obj.insert("device".to_string(), serde_json::Value::String(device));
+    }
+    Some(params)
+}
+
+/// Parse repeated "Name: value" header flags into a JSON object
+fn parse_headers(headers: Vec<String>) -> Result<serde_json::Map<String, serde_json::Value>> {
+    let mut header_map = serde_json::Map::new();
+    for header in headers {
+        let (name, value) = header.split_once(':').ok_or_else(|| {
+            anyhow::anyhow!("Invalid header '{}': expected \"Name: value\"", header)
+        })?;
+        header_map.insert(
+            name.trim().to_string(),
+            serde_json::Value::String(value.trim().to_string()),
+        );
+    }
+    Ok(header_map)
+}
+
+/// Parse an "x,y" point flag into `{x, y}`
+fn parse_point(point: &str) -> Result<serde_json::Value> {
+    let invalid = || anyhow::anyhow!("Invalid point '{}': expected \"x,y\"", point);
+    let (x, y) = point.split_once(',').ok_or_else(invalid)?;
+    let x: f64 = x.trim().parse().map_err(|_| invalid())?;
+    let y: f64 = y.trim().parse().map_err(|_| invalid())?;
+    Ok(serde_json::json!({"x": x, "y": y}))
+}
+
 #[cfg(test)]
 mod tests {
-    use super::with_session;
+    use super::{parse_headers, parse_point, viewport_params, with_session};
+    use crate::models::ViewportSize;
     use serde_json::json;
 
     #[test]
@@ -387,6 +1796,43 @@ mod tests {
         let updated = with_session(params.clone(), None);
         assert_eq!(updated, params);
     }
+
+    #[test]
+    fn parse_headers_splits_on_first_colon() {
+        let headers = parse_headers(vec![
+            "X-Api-Key: abc123".to_string(),
+            "Referer:https://example.com/".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(headers["X-Api-Key"], "abc123");
+        assert_eq!(headers["Referer"], "https://example.com/");
+        assert!(parse_headers(vec!["no-colon".to_string()]).is_err());
+    }
+
+    #[test]
+    fn parse_point_reads_x_and_y() {
+        assert_eq!(
+            parse_point("300, 120.5").unwrap(),
+            json!({"x": 300.0, "y": 120.5})
+        );
+        assert!(parse_point("300").is_err());
+        assert!(parse_point("a,b").is_err());
+    }
+
+    #[test]
+    fn viewport_params_only_when_flagged() {
+        assert!(viewport_params(None, None).is_none());
+
+        let size = ViewportSize {
+            width: 390,
+            height: 844,
+        };
+        let params = viewport_params(Some(size), Some("iPhone 14".to_string())).unwrap();
+        assert_eq!(
+            params,
+            json!({"width": 390, "height": 844, "device": "iPhone 14"})
+        );
+    }
 }
 
 fn main() -> Result<()> {
@@ -397,73 +1843,411 @@ fn main() -> Result<()> {
             socket,
             foreground,
             headed,
-            channel: _,
+            channel,
+            viewport,
+            console_buffer,
+            session_idle_timeout,
+            session_pool,
+            max_sessions,
             connect,
+            connect_launch,
+            debug_port,
+            connect_profile,
+            client_pool_ttl,
             extension_bridge,
             extension_port,
-        } => cmd_start(socket, foreground, !headed, connect, extension_bridge, extension_port),
+            extension_queue,
+            extension_tls_cert,
+            extension_tls_key,
+            extension_auth,
+            protocol,
+        } => cmd_start(
+            socket,
+            foreground,
+            !headed,
+            viewport,
+            channel,
+            console_buffer,
+            session_idle_timeout,
+            session_pool,
+            max_sessions,
+            connect,
+            connect_launch.then_some(ConnectLaunch {
+                port: debug_port,
+                profile: connect_profile,
+            }),
+            client_pool_ttl,
+            extension_bridge,
+            extension_port,
+            extension_queue,
+            extension_tls_cert,
+            extension_tls_key,
+            extension_auth,
+            protocol,
+        ),
         Commands::Stop { socket } => cmd_stop(socket),
         Commands::Status { socket } => cmd_status(socket),
         Commands::Open {
             url,
+            block_resources,
+            headers,
+            auth,
+            viewport,
+            device,
+            network,
             socket,
             session,
         } => {
+            if let Some(base) = viewport_params(viewport, device) {
+                let params = with_session(base, session.clone());
+                call_daemon_raw(&socket, "browser.set_viewport", params)?;
+            }
+            if let Some(profile) = network {
+                let params = with_session(serde_json::json!({"profile": profile}), session.clone());
+                call_daemon_raw(&socket, "browser.set_network_conditions", params)?;
+            }
+            if !block_resources.is_empty() {
+                let params = with_session(
+                    serde_json::json!({"resource_types": block_resources}),
+                    session.clone(),
+                );
+                call_daemon_raw(&socket, "browser.network.block", params)?;
+            }
+            if !headers.is_empty() || auth.is_some() {
+                let mut base = serde_json::json!({});
+                let obj = base.as_object_mut().unwrap();
+                if !headers.is_empty() {
+                    obj.insert(
+                        "headers".to_string(),
+                        serde_json::Value::Object(parse_headers(headers)?),
+                    );
+                }
+                if let Some(auth) = auth {
+                    let (username, password) = auth.split_once(':').ok_or_else(|| {
+                        anyhow::anyhow!("Invalid --auth value: expected \"user:pass\"")
+                    })?;
+                    obj.insert(
+                        "credentials".to_string(),
+                        serde_json::json!({"username": username, "password": password}),
+                    );
+                }
+                let params = with_session(base, session.clone());
+                call_daemon_raw(&socket, "browser.set_headers", params)?;
+            }
             let params = with_session(serde_json::json!({"url": url}), session);
             cmd_call_daemon(&socket, "browser.open", params, cli.json)
         }
-        Commands::Snapshot { socket, session } => {
+        Commands::Snapshot {
+            markdown,
+            depth,
+            include_frames,
+            interactive_only,
+            socket,
+            session,
+        } => {
+            let mut base = serde_json::json!({
+                "include_frames": include_frames,
+                "interactive_only": interactive_only,
+            });
+            if let Some(d) = depth {
+                base["snapshot_depth"] = serde_json::json!(d);
+            }
+            let params = with_session(base, session);
+            if markdown && !cli.json {
+                let response = call_daemon_raw(&socket, "browser.snapshot_markdown", params)?;
+                let content = response
+                    .get("result")
+                    .and_then(|r| r.get("markdown"))
+                    .and_then(|m| m.as_str())
+                    .unwrap_or_default();
+                print!("{}", content);
+                Ok(())
+            } else if markdown {
+                cmd_call_daemon(&socket, "browser.snapshot_markdown", params, cli.json)
+            } else {
+                cmd_call_daemon(&socket, "browser.snapshot", params, cli.json)
+            }
+        }
+        Commands::SnapshotSave {
+            name,
+            socket,
+            session,
+        } => {
+            let params = with_session(serde_json::json!({"name": name}), session);
+            cmd_call_daemon(&socket, "browser.snapshot.save", params, cli.json)
+        }
+        Commands::SnapshotLoad { name, socket } => cmd_call_daemon(
+            &socket,
+            "browser.snapshot.load",
+            serde_json::json!({"name": name}),
+            cli.json,
+        ),
+        Commands::SnapshotDiff {
+            before,
+            after,
+            socket,
+            session,
+        } => {
+            let params = with_session(
+                serde_json::json!({"before_snapshot": before, "after_snapshot": after}),
+                session,
+            );
+            cmd_call_daemon(&socket, "browser.snapshot_diff", params, cli.json)
+        }
+        Commands::Frames { socket, session } => {
             let params = with_session(serde_json::json!({}), session);
-            cmd_call_daemon(&socket, "browser.snapshot", params, cli.json)
+            cmd_call_daemon(&socket, "browser.frames", params, cli.json)
+        }
+        Commands::Click {
+            selector,
+            frame,
+            socket,
+            session,
+        } => {
+            let params = with_session(
+                serde_json::json!({"selector": selector, "frame": frame}),
+                session,
+            );
+            cmd_call_daemon(&socket, "browser.click", params, cli.json)
+        }
+        Commands::DoubleClick {
+            selector,
+            socket,
+            session,
+        } => {
+            let params = with_session(serde_json::json!({"selector": selector}), session);
+            cmd_call_daemon(&socket, "browser.double_click", params, cli.json)
         }
-        Commands::Click {
+        Commands::RightClick {
             selector,
             socket,
             session,
         } => {
             let params = with_session(serde_json::json!({"selector": selector}), session);
-            cmd_call_daemon(&socket, "browser.click", params, cli.json)
+            cmd_call_daemon(&socket, "browser.right_click", params, cli.json)
         }
+        Commands::ContextMenu { action } => match action {
+            ContextMenuAction::Select {
+                text,
+                socket,
+                session,
+            } => {
+                let params = with_session(serde_json::json!({"item_text": text}), session);
+                cmd_call_daemon(&socket, "browser.context_menu.select", params, cli.json)
+            }
+        },
         Commands::Fill {
             selector,
             value,
+            delay_ms,
+            no_clear,
+            frame,
             socket,
             session,
         } => {
             let params = with_session(
-                serde_json::json!({"selector": selector, "value": value}),
+                serde_json::json!({
+                    "selector": selector,
+                    "value": value,
+                    "clear": !no_clear,
+                    "delay_ms": delay_ms,
+                    "frame": frame,
+                }),
                 session,
             );
             cmd_call_daemon(&socket, "browser.fill", params, cli.json)
         }
         Commands::Press {
             key,
+            frame,
             socket,
             session,
         } => {
-            let params = with_session(serde_json::json!({"key": key}), session);
+            let params = with_session(serde_json::json!({"key": key, "frame": frame}), session);
             cmd_call_daemon(&socket, "browser.press", params, cli.json)
         }
         Commands::Screenshot {
             path,
+            format,
+            quality,
+            element,
             socket,
             session,
         } => {
-            let base = match path {
-                Some(p) => serde_json::json!({"path": p}),
-                None => serde_json::json!({}),
+            let mut base = serde_json::json!({"format": format});
+            let obj = base.as_object_mut().unwrap();
+            if let Some(p) = path {
+                obj.insert("path".to_string(), serde_json::json!(p));
+            }
+            if let Some(q) = quality {
+                obj.insert("quality".to_string(), serde_json::json!(q));
+            }
+            let method = match element {
+                Some(selector) => {
+                    obj.insert("selector".to_string(), serde_json::json!(selector));
+                    "browser.screenshot_element"
+                }
+                None => "browser.screenshot",
             };
             let params = with_session(base, session);
-            cmd_call_daemon(&socket, "browser.screenshot", params, cli.json)
+            cmd_call_daemon(&socket, method, params, cli.json)
+        }
+        Commands::Pdf {
+            path,
+            landscape,
+            print_background,
+            scale,
+            paper_width,
+            paper_height,
+            margin,
+            socket,
+            session,
+        } => {
+            let mut base = serde_json::json!({
+                "landscape": landscape,
+                "print_background": print_background,
+            });
+            let obj = base.as_object_mut().unwrap();
+            if let Some(p) = path {
+                obj.insert("path".to_string(), serde_json::json!(p));
+            }
+            if let Some(s) = scale {
+                obj.insert("scale".to_string(), serde_json::json!(s));
+            }
+            if let Some(w) = paper_width {
+                obj.insert("paper_width".to_string(), serde_json::json!(w));
+            }
+            if let Some(h) = paper_height {
+                obj.insert("paper_height".to_string(), serde_json::json!(h));
+            }
+            if let Some(m) = margin {
+                obj.insert("margin".to_string(), serde_json::json!(m));
+            }
+            let params = with_session(base, session);
+            cmd_call_daemon(&socket, "browser.pdf", params, cli.json)
+        }
+        Commands::Fetch {
+            url,
+            out,
+            max_bytes,
+            socket,
+            session,
+        } => {
+            let mut params = serde_json::json!({"url": url});
+            let obj = params.as_object_mut().unwrap();
+            if let Some(out) = out {
+                // The daemon may run in another directory
+                let path = std::env::current_dir()?.join(out);
+                obj.insert("path".to_string(), serde_json::json!(path.to_string_lossy()));
+            }
+            if let Some(max) = max_bytes {
+                obj.insert("max_bytes".to_string(), serde_json::json!(max));
+            }
+            let params = with_session(params, session);
+            cmd_call_daemon(&socket, "browser.fetch", params, cli.json)
+        }
+        Commands::GetHtml {
+            selector,
+            inner,
+            socket,
+            session,
+        } => {
+            let mut base = serde_json::json!({"inner": inner});
+            if let Some(sel) = selector {
+                base.as_object_mut()
+                    .unwrap()
+                    .insert("selector".to_string(), serde_json::Value::String(sel));
+            }
+            let params = with_session(base, session);
+            cmd_call_daemon(&socket, "browser.get_html", params, cli.json)
+        }
+        Commands::Content {
+            selector,
+            max_bytes,
+            socket,
+            session,
+        } => {
+            let params = with_session(
+                serde_json::json!({"selector": selector, "max_bytes": max_bytes}),
+                session,
+            );
+            cmd_call_daemon(&socket, "browser.content", params, cli.json)
+        }
+        Commands::GetText {
+            selector,
+            frame,
+            socket,
+            session,
+        } => {
+            let params = with_session(
+                serde_json::json!({"selector": selector, "frame": frame}),
+                session,
+            );
+            cmd_call_daemon(&socket, "browser.get_text", params, cli.json)
+        }
+        Commands::Extract {
+            markdown,
+            socket,
+            session,
+        } => {
+            let params = with_session(serde_json::json!({}), session);
+            if markdown && !cli.json {
+                let response = call_daemon_raw(&socket, "browser.extract_content", params)?;
+                let content = response
+                    .get("result")
+                    .and_then(|r| r.get("markdown"))
+                    .and_then(|m| m.as_str())
+                    .unwrap_or_default();
+                println!("{}", content);
+                Ok(())
+            } else {
+                cmd_call_daemon(&socket, "browser.extract_content", params, cli.json)
+            }
+        }
+        Commands::Links {
+            selector,
+            same_origin_only,
+            socket,
+            session,
+        } => {
+            let mut base = serde_json::json!({"same_origin_only": same_origin_only});
+            if let Some(sel) = selector {
+                base.as_object_mut()
+                    .unwrap()
+                    .insert("selector".to_string(), serde_json::Value::String(sel));
+            }
+            let params = with_session(base, session);
+            cmd_call_daemon(&socket, "browser.extract_links", params, cli.json)
+        }
+        Commands::Images {
+            selector,
+            same_origin_only,
+            socket,
+            session,
+        } => {
+            let mut base = serde_json::json!({"same_origin_only": same_origin_only});
+            if let Some(sel) = selector {
+                base.as_object_mut()
+                    .unwrap()
+                    .insert("selector".to_string(), serde_json::Value::String(sel));
+            }
+            let params = with_session(base, session);
+            cmd_call_daemon(&socket, "browser.extract_images", params, cli.json)
         }
         Commands::Select {
             selector,
-            value,
+            values,
+            add,
+            frame,
             socket,
             session,
         } => {
+            let value = match values.as_slice() {
+                [value] => serde_json::json!(value),
+                _ => serde_json::json!(values),
+            };
             let params = with_session(
-                serde_json::json!({"selector": selector, "value": value}),
+                serde_json::json!({"selector": selector, "value": value, "add": add, "frame": frame}),
                 session,
             );
             cmd_call_daemon(&socket, "browser.select", params, cli.json)
@@ -471,70 +2255,605 @@ fn main() -> Result<()> {
         Commands::Check {
             selector,
             checked,
+            frame,
             socket,
             session,
         } => {
             let params = with_session(
-                serde_json::json!({"selector": selector, "checked": checked}),
+                serde_json::json!({"selector": selector, "checked": checked, "frame": frame}),
                 session,
             );
             cmd_call_daemon(&socket, "browser.check", params, cli.json)
         }
         Commands::Hover {
             selector,
+            frame,
             socket,
             session,
         } => {
-            let params = with_session(serde_json::json!({"selector": selector}), session);
+            let params = with_session(
+                serde_json::json!({"selector": selector, "frame": frame}),
+                session,
+            );
             cmd_call_daemon(&socket, "browser.hover", params, cli.json)
         }
+        Commands::SelectText {
+            selector,
+            start,
+            end,
+            socket,
+            session,
+        } => {
+            let params = with_session(
+                serde_json::json!({"selector": selector, "start": start, "end": end}),
+                session,
+            );
+            cmd_call_daemon(&socket, "browser.select_text", params, cli.json)
+        }
+        Commands::Drag {
+            source,
+            target,
+            steps,
+            socket,
+            session,
+        } => {
+            let steps = steps
+                .iter()
+                .map(|s| parse_point(s))
+                .collect::<Result<Vec<_>>>()?;
+            let params = with_session(
+                serde_json::json!({
+                    "source_selector": source,
+                    "target_selector": target,
+                    "steps": steps,
+                }),
+                session,
+            );
+            cmd_call_daemon(&socket, "browser.drag", params, cli.json)
+        }
+        Commands::DragPath {
+            points,
+            delay_ms,
+            socket,
+            session,
+        } => {
+            let points = points
+                .iter()
+                .map(|p| parse_point(p))
+                .collect::<Result<Vec<_>>>()?;
+            let params = with_session(
+                serde_json::json!({"points": points, "delay_between_points_ms": delay_ms}),
+                session,
+            );
+            cmd_call_daemon(&socket, "browser.drag_path", params, cli.json)
+        }
+        Commands::GetBounds {
+            selector,
+            socket,
+            session,
+        } => {
+            let params = with_session(serde_json::json!({"selector": selector}), session);
+            cmd_call_daemon(&socket, "browser.get_element_bounds", params, cli.json)
+        }
+        Commands::Focus {
+            selector,
+            frame,
+            socket,
+            session,
+        } => {
+            let params = with_session(
+                serde_json::json!({"selector": selector, "frame": frame}),
+                session,
+            );
+            cmd_call_daemon(&socket, "browser.focus", params, cli.json)
+        }
+        Commands::ClearField {
+            selector,
+            frame,
+            socket,
+            session,
+        } => {
+            let params = with_session(
+                serde_json::json!({"selector": selector, "frame": frame}),
+                session,
+            );
+            cmd_call_daemon(&socket, "browser.clear_field", params, cli.json)
+        }
+        Commands::Blur { socket, session } => {
+            let params = with_session(serde_json::json!({}), session);
+            cmd_call_daemon(&socket, "browser.blur", params, cli.json)
+        }
         Commands::Scroll {
             selector,
             x,
             y,
+            frame,
             socket,
             session,
         } => {
-            let mut base = serde_json::json!({"x": x, "y": y});
+            let mut base = serde_json::json!({"x": x, "y": y, "frame": frame});
             if let Some(sel) = selector {
                 base.as_object_mut()
                     .unwrap()
                     .insert("selector".to_string(), serde_json::Value::String(sel));
             }
-            let params = with_session(base, session);
-            cmd_call_daemon(&socket, "browser.scroll", params, cli.json)
-        }
-        Commands::PressCombo {
-            key,
-            modifiers,
-            socket,
-            session,
-        } => {
-            let params = with_session(
-                serde_json::json!({"key": key, "modifiers": modifiers}),
+            let params = with_session(base, session);
+            cmd_call_daemon(&socket, "browser.scroll", params, cli.json)
+        }
+        Commands::ScrollTop {
+            smooth,
+            socket,
+            session,
+        } => {
+            let params = with_session(serde_json::json!({"smooth": smooth}), session);
+            cmd_call_daemon(&socket, "browser.scroll.top", params, cli.json)
+        }
+        Commands::ScrollBottom {
+            smooth,
+            socket,
+            session,
+        } => {
+            let params = with_session(serde_json::json!({"smooth": smooth}), session);
+            cmd_call_daemon(&socket, "browser.scroll.bottom", params, cli.json)
+        }
+        Commands::ScrollToBottom {
+            max_scrolls,
+            settle_ms,
+            timeout_ms,
+            socket,
+            session,
+        } => {
+            let params = with_session(
+                serde_json::json!({
+                    "max_scrolls": max_scrolls,
+                    "settle_ms": settle_ms,
+                    "timeout_ms": timeout_ms
+                }),
+                session,
+            );
+            cmd_call_daemon(&socket, "browser.scroll_to_bottom", params, cli.json)
+        }
+        Commands::PressCombo {
+            key,
+            modifiers,
+            socket,
+            session,
+        } => {
+            let params = with_session(
+                serde_json::json!({"key": key, "modifiers": modifiers}),
+                session,
+            );
+            cmd_call_daemon(&socket, "browser.press_combo", params, cli.json)
+        }
+        Commands::KeySequence {
+            keys,
+            socket,
+            session,
+        } => {
+            let keys: serde_json::Value = serde_json::from_str(&keys)
+                .map_err(|e| anyhow::anyhow!("Invalid --keys (expected a JSON array): {}", e))?;
+            let params = with_session(serde_json::json!({"keys": keys}), session);
+            cmd_call_daemon(&socket, "browser.key_sequence", params, cli.json)
+        }
+        Commands::Upload {
+            selector,
+            paths,
+            socket,
+            session,
+        } => {
+            let params = with_session(
+                serde_json::json!({"selector": selector, "paths": paths}),
+                session,
+            );
+            cmd_call_daemon(&socket, "browser.upload", params, cli.json)
+        }
+        Commands::SetViewport {
+            size,
+            device,
+            scale,
+            mobile,
+            socket,
+            session,
+        } => {
+            let mut base = viewport_params(size, device).unwrap_or_else(|| serde_json::json!({}));
+            let obj = base.as_object_mut().unwrap();
+            if let Some(s) = scale {
+                obj.insert("device_scale_factor".to_string(), serde_json::json!(s));
+            }
+            if mobile {
+                obj.insert("mobile".to_string(), serde_json::Value::Bool(true));
+            }
+            let params = with_session(base, session);
+            cmd_call_daemon(&socket, "browser.set_viewport", params, cli.json)
+        }
+        Commands::Geolocation {
+            latitude,
+            longitude,
+            accuracy,
+            clear,
+            socket,
+            session,
+        }
+        | Commands::Emulate {
+            action:
+                Some(EmulateAction::Geolocation {
+                    latitude,
+                    longitude,
+                    accuracy,
+                    clear,
+                    socket,
+                    session,
+                }),
+            ..
+        } => {
+            if clear {
+                let params = with_session(serde_json::json!({}), session);
+                cmd_call_daemon(&socket, "browser.clear_geolocation", params, cli.json)
+            } else {
+                let mut base = serde_json::json!({"latitude": latitude, "longitude": longitude});
+                if let Some(a) = accuracy {
+                    base.as_object_mut()
+                        .unwrap()
+                        .insert("accuracy".to_string(), serde_json::json!(a));
+                }
+                let params = with_session(base, session);
+                cmd_call_daemon(&socket, "browser.set_geolocation", params, cli.json)
+            }
+        }
+        Commands::Emulate {
+            action:
+                Some(EmulateAction::Permissions {
+                    permissions,
+                    origin,
+                    reset,
+                    socket,
+                    session,
+                }),
+            ..
+        } => {
+            if reset {
+                let params = with_session(serde_json::json!({}), session);
+                cmd_call_daemon(&socket, "browser.reset_permissions", params, cli.json)
+            } else {
+                let params = with_session(
+                    serde_json::json!({"permissions": permissions, "origin": origin}),
+                    session,
+                );
+                cmd_call_daemon(&socket, "browser.grant_permissions", params, cli.json)
+            }
+        }
+        Commands::Emulate {
+            action:
+                Some(EmulateAction::Media {
+                    color_scheme,
+                    reduced_motion,
+                    media,
+                    reset,
+                    socket,
+                    session,
+                }),
+            ..
+        } => {
+            let params = with_session(
+                serde_json::json!({
+                    "color_scheme": color_scheme,
+                    "reduced_motion": reduced_motion,
+                    "media": media,
+                    "reset": reset,
+                }),
+                session,
+            );
+            cmd_call_daemon(&socket, "browser.emulate_media", params, cli.json)
+        }
+        Commands::Emulate {
+            action: None,
+            preset,
+            width,
+            height,
+            scale,
+            mobile,
+            user_agent,
+            reset,
+            socket,
+            session,
+        } => {
+            let mut base = serde_json::json!({"reset": reset});
+            let obj = base.as_object_mut().unwrap();
+            if let Some(p) = preset {
+                obj.insert("preset".to_string(), serde_json::Value::String(p));
+            }
+            if let Some(w) = width {
+                obj.insert("width".to_string(), serde_json::json!(w));
+            }
+            if let Some(h) = height {
+                obj.insert("height".to_string(), serde_json::json!(h));
+            }
+            if let Some(s) = scale {
+                obj.insert("device_scale_factor".to_string(), serde_json::json!(s));
+            }
+            if mobile {
+                obj.insert("is_mobile".to_string(), serde_json::Value::Bool(true));
+            }
+            if let Some(ua) = user_agent {
+                obj.insert("user_agent".to_string(), serde_json::Value::String(ua));
+            }
+            let params = with_session(base, session);
+            cmd_call_daemon(&socket, "browser.emulate", params, cli.json)
+        }
+        Commands::Dialog {
+            action,
+            prompt_text,
+            clear,
+            socket,
+            session,
+        } => {
+            let mut base = serde_json::json!({"clear": clear});
+            let obj = base.as_object_mut().unwrap();
+            if let Some(a) = action {
+                obj.insert("action".to_string(), serde_json::Value::String(a));
+            }
+            if let Some(t) = prompt_text {
+                obj.insert("prompt_text".to_string(), serde_json::Value::String(t));
+            }
+            let params = with_session(base, session);
+            cmd_call_daemon(&socket, "browser.dialog", params, cli.json)
+        }
+        Commands::Console {
+            level,
+            limit,
+            clear,
+            socket,
+            session,
+        } => {
+            let mut base = serde_json::json!({"clear": clear});
+            let obj = base.as_object_mut().unwrap();
+            if let Some(l) = level {
+                obj.insert("level".to_string(), serde_json::Value::String(l));
+            }
+            if let Some(l) = limit {
+                obj.insert("limit".to_string(), serde_json::json!(l));
+            }
+            let params = with_session(base, session);
+            cmd_call_daemon(&socket, "browser.console.get", params, cli.json)
+        }
+        Commands::Network { action } => match action {
+            NetworkAction::Enable { socket, session } => {
+                let params = with_session(serde_json::json!({}), session);
+                cmd_call_daemon(&socket, "browser.network.enable", params, cli.json)
+            }
+            NetworkAction::Disable { socket, session } => {
+                let params = with_session(serde_json::json!({}), session);
+                cmd_call_daemon(&socket, "browser.network.disable", params, cli.json)
+            }
+            NetworkAction::List {
+                url,
+                status,
+                resource_type,
+                limit,
+                socket,
+                session,
+            } => {
+                let mut base = serde_json::json!({});
+                let obj = base.as_object_mut().unwrap();
+                if let Some(u) = url {
+                    obj.insert("url_pattern".to_string(), serde_json::Value::String(u));
+                }
+                if let Some(s) = status {
+                    obj.insert("status".to_string(), serde_json::Value::String(s));
+                }
+                if let Some(t) = resource_type {
+                    obj.insert("resource_type".to_string(), serde_json::Value::String(t));
+                }
+                if let Some(l) = limit {
+                    obj.insert("limit".to_string(), serde_json::json!(l));
+                }
+                let params = with_session(base, session);
+                if cli.json {
+                    cmd_call_daemon(&socket, "browser.network.list", params, true)
+                } else {
+                    let response = call_daemon_raw(&socket, "browser.network.list", params)?;
+                    print_network_table(response.get("result").unwrap_or(&serde_json::Value::Null));
+                    Ok(())
+                }
+            }
+            NetworkAction::Body {
+                request_id,
+                max_bytes,
+                socket,
+                session,
+            } => {
+                let mut base = serde_json::json!({"request_id": request_id});
+                if let Some(m) = max_bytes {
+                    base.as_object_mut()
+                        .unwrap()
+                        .insert("max_bytes".to_string(), serde_json::json!(m));
+                }
+                let params = with_session(base, session);
+                cmd_call_daemon(&socket, "browser.network.body", params, cli.json)
+            }
+            NetworkAction::Block {
+                urls,
+                resource_types,
+                patterns,
+                socket,
+                session,
+            } => {
+                if patterns.is_empty() {
+                    let params = with_session(
+                        serde_json::json!({"url_patterns": urls, "resource_types": resource_types}),
+                        session,
+                    );
+                    cmd_call_daemon(&socket, "browser.network.block", params, cli.json)
+                } else {
+                    let params = with_session(serde_json::json!({"patterns": patterns}), session);
+                    cmd_call_daemon(&socket, "browser.network.block_urls", params, cli.json)
+                }
+            }
+            NetworkAction::Mock {
+                url_pattern,
+                status,
+                headers,
+                body,
+                body_file,
+                socket,
                 session,
-            );
-            cmd_call_daemon(&socket, "browser.press_combo", params, cli.json)
-        }
-        Commands::Upload {
-            selector,
-            path,
-            socket,
-            session,
-        } => {
-            let params = with_session(
-                serde_json::json!({"selector": selector, "path": path}),
+            } => {
+                let mut base = serde_json::json!({
+                    "url_pattern": url_pattern,
+                    "status": status,
+                    "headers": parse_headers(headers)?,
+                });
+                let obj = base.as_object_mut().unwrap();
+                if let Some(b) = body {
+                    obj.insert("body".to_string(), serde_json::Value::String(b));
+                }
+                if let Some(f) = body_file {
+                    // The daemon may run in another directory
+                    let path = std::fs::canonicalize(&f)
+                        .with_context(|| format!("File not found: {}", f))?;
+                    obj.insert(
+                        "body_path".to_string(),
+                        serde_json::Value::String(path.to_string_lossy().to_string()),
+                    );
+                }
+                let params = with_session(base, session);
+                cmd_call_daemon(&socket, "browser.network.mock", params, cli.json)
+            }
+            NetworkAction::Mocks { socket, session } => {
+                let params = with_session(serde_json::json!({}), session);
+                cmd_call_daemon(&socket, "browser.network.mocks", params, cli.json)
+            }
+            NetworkAction::Unmock { id, socket, session } => {
+                let mut base = serde_json::json!({});
+                if let Some(i) = id {
+                    base["id"] = serde_json::Value::String(i);
+                }
+                let params = with_session(base, session);
+                cmd_call_daemon(&socket, "browser.network.unmock", params, cli.json)
+            }
+            NetworkAction::Unblock { socket, session } => {
+                let params = with_session(serde_json::json!({}), session);
+                cmd_call_daemon(&socket, "browser.network.unblock", params, cli.json)
+            }
+            NetworkAction::Rules { socket, session } => {
+                let params = with_session(serde_json::json!({}), session);
+                cmd_call_daemon(&socket, "browser.network.rules", params, cli.json)
+            }
+            NetworkAction::Clear { socket, session } => {
+                let params = with_session(serde_json::json!({}), session);
+                cmd_call_daemon(&socket, "browser.network.clear", params, cli.json)
+            }
+            NetworkAction::CaptureStart { socket, session } => {
+                let params = with_session(serde_json::json!({}), session);
+                cmd_call_daemon(&socket, "browser.network.capture_start", params, cli.json)
+            }
+            NetworkAction::CaptureStop { socket, session } => {
+                let params = with_session(serde_json::json!({}), session);
+                cmd_call_daemon(&socket, "browser.network.capture_stop", params, cli.json)
+            }
+            NetworkAction::Throttle {
+                profile,
+                latency,
+                download,
+                upload,
+                reset,
+                socket,
                 session,
-            );
-            cmd_call_daemon(&socket, "browser.upload", params, cli.json)
+            } => {
+                let params = with_session(
+                    serde_json::json!({
+                        "profile": profile,
+                        "latency_ms": latency,
+                        "download_kbps": download,
+                        "upload_kbps": upload,
+                        "reset": reset,
+                    }),
+                    session,
+                );
+                cmd_call_daemon(&socket, "browser.set_network_conditions", params, cli.json)
+            }
+        },
+        Commands::Metrics { socket, session } => {
+            let params = with_session(serde_json::json!({}), session);
+            cmd_call_daemon(&socket, "browser.metrics", params, cli.json)
         }
+        Commands::Cpu { action } => match action {
+            CpuAction::Throttle {
+                rate,
+                socket,
+                session,
+            } => {
+                let params = with_session(serde_json::json!({"rate": rate}), session);
+                cmd_call_daemon(&socket, "browser.cpu.throttle", params, cli.json)
+            }
+        },
+        Commands::Clipboard { action } => match action {
+            ClipboardAction::Read { socket, session } => {
+                let params = with_session(serde_json::json!({}), session);
+                cmd_call_daemon(&socket, "browser.clipboard.read", params, cli.json)
+            }
+            ClipboardAction::Write {
+                text,
+                socket,
+                session,
+            } => {
+                let params = with_session(serde_json::json!({"text": text}), session);
+                cmd_call_daemon(&socket, "browser.clipboard.write", params, cli.json)
+            }
+        },
+        Commands::Downloads { action } => match action {
+            DownloadsAction::List { socket, session } => {
+                let params = with_session(serde_json::json!({}), session);
+                cmd_call_daemon(&socket, "browser.downloads.list", params, cli.json)
+            }
+            DownloadsAction::Wait {
+                timeout,
+                new,
+                socket,
+                session,
+            } => {
+                let params = with_session(serde_json::json!({"timeout_ms": timeout}), session);
+                let method = if new {
+                    "browser.wait_for_download"
+                } else {
+                    "browser.downloads.wait"
+                };
+                cmd_call_daemon(&socket, method, params, cli.json)
+            }
+            DownloadsAction::Clean { older_than, socket } => {
+                let params = serde_json::json!({"max_age_hours": older_than});
+                cmd_call_daemon(&socket, "browser.downloads.cleanup", params, cli.json)
+            }
+        },
+        Commands::Har { action } => match action {
+            HarAction::Start { socket, session } => {
+                let params = with_session(serde_json::json!({}), session);
+                cmd_call_daemon(&socket, "browser.har.start", params, cli.json)
+            }
+            HarAction::Stop {
+                path,
+                socket,
+                session,
+            } => {
+                // The daemon may run in another directory
+                let path = std::env::current_dir()?.join(path);
+                let params =
+                    with_session(serde_json::json!({"path": path.to_string_lossy()}), session);
+                cmd_call_daemon(&socket, "browser.har.stop", params, cli.json)
+            }
+        },
         Commands::State { action } => match action {
             StateAction::Save {
                 name,
+                origins,
                 socket,
                 session,
             } => {
-                let params = with_session(serde_json::json!({"name": name}), session);
+                let mut params = serde_json::json!({"name": name});
+                if !origins.is_empty() {
+                    params["origins"] = serde_json::json!(origins);
+                }
+                let params = with_session(params, session);
                 cmd_call_daemon(&socket, "browser.state.save", params, cli.json)
             }
             StateAction::Load {
@@ -551,24 +2870,318 @@ fn main() -> Result<()> {
                 serde_json::json!({}),
                 cli.json,
             ),
+            StateAction::Delete { name, socket } => cmd_call_daemon(
+                &socket,
+                "browser.state.delete",
+                serde_json::json!({"name": name}),
+                cli.json,
+            ),
+            StateAction::Rename {
+                name,
+                new_name,
+                overwrite,
+                socket,
+            } => cmd_call_daemon(
+                &socket,
+                "browser.state.rename",
+                serde_json::json!({"name": name, "new_name": new_name, "overwrite": overwrite}),
+                cli.json,
+            ),
+        },
+        Commands::Cookies { action } => match action {
+            CookiesAction::Export {
+                path,
+                socket,
+                session,
+            } => {
+                // The daemon may run in another directory
+                let path = std::env::current_dir()?.join(path);
+                let params =
+                    with_session(serde_json::json!({"path": path.to_string_lossy()}), session);
+                cmd_call_daemon(&socket, "browser.cookies.export", params, cli.json)
+            }
+            CookiesAction::Import {
+                path,
+                socket,
+                session,
+            } => {
+                let path = std::env::current_dir()?.join(path);
+                let params =
+                    with_session(serde_json::json!({"path": path.to_string_lossy()}), session);
+                cmd_call_daemon(&socket, "browser.cookies.import", params, cli.json)
+            }
+        },
+        Commands::Storage { action } => match action {
+            StorageAction::Export {
+                path,
+                origin,
+                socket,
+                session,
+            } => {
+                // The daemon may run in another directory
+                let path = std::env::current_dir()?.join(path);
+                let params = with_session(
+                    serde_json::json!({"path": path.to_string_lossy(), "origin": origin}),
+                    session,
+                );
+                cmd_call_daemon(&socket, "browser.storage.export", params, cli.json)
+            }
+            StorageAction::Import {
+                path,
+                socket,
+                session,
+            } => {
+                let path = std::env::current_dir()?.join(path);
+                let params =
+                    with_session(serde_json::json!({"path": path.to_string_lossy()}), session);
+                cmd_call_daemon(&socket, "browser.storage.import", params, cli.json)
+            }
+        },
+        Commands::Indexeddb {
+            db,
+            store,
+            socket,
+            session,
+        } => match store {
+            Some(store) => {
+                let params = with_session(serde_json::json!({"db": db, "store": store}), session);
+                cmd_call_daemon(&socket, "browser.indexeddb.get_all", params, cli.json)
+            }
+            None => {
+                let params = with_session(serde_json::json!({"db": db}), session);
+                cmd_call_daemon(&socket, "browser.indexeddb.list_stores", params, cli.json)
+            }
         },
         Commands::Session { action } => match action {
-            SessionAction::New { id, socket } => cmd_call_daemon(
+            SessionAction::New {
+                id,
+                name,
+                evict_idle,
+                state,
+                url,
+                viewport,
+                device,
+                socket,
+            } => {
+                let params = serde_json::json!({
+                    "id": id,
+                    "name": name,
+                    "evict_idle": evict_idle,
+                    "state": state,
+                    "url": url
+                });
+                match viewport_params(viewport, device) {
+                    Some(base) => {
+                        call_daemon_raw(&socket, "browser.session.new", params)?;
+                        let params = with_session(base, Some(id));
+                        cmd_call_daemon(&socket, "browser.set_viewport", params, cli.json)
+                    }
+                    None => cmd_call_daemon(&socket, "browser.session.new", params, cli.json),
+                }
+            }
+            SessionAction::List { socket } => {
+                if cli.json {
+                    cmd_call_daemon(&socket, "browser.session.list", serde_json::json!({}), true)
+                } else {
+                    let response =
+                        call_daemon_raw(&socket, "browser.session.list", serde_json::json!({}))?;
+                    print_session_table(response.get("result").unwrap_or(&serde_json::Value::Null));
+                    Ok(())
+                }
+            }
+            SessionAction::Info { id, socket } => cmd_call_daemon(
+                &socket,
+                "browser.session.info",
+                with_session(serde_json::json!({}), id),
+                cli.json,
+            ),
+            SessionAction::Close { id, socket } => cmd_call_daemon(
                 &socket,
-                "browser.session.new",
+                "browser.session.close",
                 serde_json::json!({"id": id}),
                 cli.json,
             ),
-            SessionAction::List { socket } => cmd_call_daemon(
+            SessionAction::Clone {
+                source,
+                id,
+                name,
+                navigate,
+                socket,
+            } => cmd_call_daemon(
+                &socket,
+                "browser.session.clone",
+                serde_json::json!({"source_id": source, "id": id, "name": name, "navigate": navigate}),
+                cli.json,
+            ),
+            SessionAction::Attach {
+                id,
+                target_id,
+                url,
+                title,
+                socket,
+            } => cmd_call_daemon(
+                &socket,
+                "browser.session.attach",
+                serde_json::json!({"id": id, "target_id": target_id, "url": url, "title": title}),
+                cli.json,
+            ),
+            SessionAction::SetMetadata {
+                key,
+                value,
+                id,
+                socket,
+            } => cmd_call_daemon(
+                &socket,
+                "browser.session.set_metadata",
+                with_session(serde_json::json!({"key": key, "value": value}), id),
+                cli.json,
+            ),
+            SessionAction::GetMetadata { id, socket } => cmd_call_daemon(
+                &socket,
+                "browser.session.get_metadata",
+                with_session(serde_json::json!({}), id),
+                cli.json,
+            ),
+            SessionAction::Targets { all, socket } => cmd_call_daemon(
+                &socket,
+                "browser.targets",
+                serde_json::json!({"all": all}),
+                cli.json,
+            ),
+            SessionAction::TabsOpenNew { url, socket } => cmd_call_daemon(
+                &socket,
+                "browser.tabs.open_new",
+                serde_json::json!({"url": url}),
+                cli.json,
+            ),
+        },
+        Commands::Tabs { action } => match action {
+            TabsAction::List { socket } => cmd_call_daemon(
                 &socket,
-                "browser.session.list",
+                "browser.tabs.list",
                 serde_json::json!({}),
                 cli.json,
             ),
-            SessionAction::Close { id, socket } => cmd_call_daemon(
+            TabsAction::Switch { target_id, socket } => cmd_call_daemon(
                 &socket,
-                "browser.session.close",
-                serde_json::json!({"id": id}),
+                "browser.tabs.switch",
+                serde_json::json!({"target_id": target_id}),
+                cli.json,
+            ),
+            TabsAction::Attach { url, index, socket } => cmd_call_daemon(
+                &socket,
+                "browser.tabs.attach",
+                serde_json::json!({"url": url, "index": index}),
+                cli.json,
+            ),
+            TabsAction::Close { id, force, socket } => cmd_call_daemon(
+                &socket,
+                "browser.tabs.close",
+                serde_json::json!({"session_id": id, "force": force}),
+                cli.json,
+            ),
+        },
+        Commands::Pages { action } => match action {
+            PagesAction::List { socket, session } => cmd_call_daemon(
+                &socket,
+                "browser.pages.list",
+                with_session(serde_json::json!({}), session),
+                cli.json,
+            ),
+            PagesAction::Switch {
+                index,
+                socket,
+                session,
+            } => cmd_call_daemon(
+                &socket,
+                "browser.pages.switch",
+                with_session(serde_json::json!({"index": index}), session),
+                cli.json,
+            ),
+        },
+        Commands::Tab { action } => match action {
+            TabAction::New {
+                url,
+                socket,
+                session,
+            } => cmd_call_daemon(
+                &socket,
+                "browser.tab.new",
+                with_session(serde_json::json!({"url": url}), session),
+                cli.json,
+            ),
+            TabAction::List { socket, session } => cmd_call_daemon(
+                &socket,
+                "browser.tab.list",
+                with_session(serde_json::json!({}), session),
+                cli.json,
+            ),
+            TabAction::Switch {
+                target_id,
+                socket,
+                session,
+            } => cmd_call_daemon(
+                &socket,
+                "browser.tab.switch",
+                with_session(serde_json::json!({"target_id": target_id}), session),
+                cli.json,
+            ),
+            TabAction::Close {
+                target_id,
+                force,
+                socket,
+                session,
+            } => cmd_call_daemon(
+                &socket,
+                "browser.tab.close",
+                with_session(
+                    serde_json::json!({"target_id": target_id, "force": force}),
+                    session,
+                ),
+                cli.json,
+            ),
+        },
+        Commands::Mouse { action } => match action {
+            MouseAction::Move {
+                x,
+                y,
+                socket,
+                session,
+            } => cmd_call_daemon(
+                &socket,
+                "browser.mouse.move",
+                with_session(serde_json::json!({"x": x, "y": y}), session),
+                cli.json,
+            ),
+            MouseAction::Click {
+                x,
+                y,
+                button,
+                socket,
+                session,
+            } => cmd_call_daemon(
+                &socket,
+                "browser.mouse.click",
+                with_session(
+                    serde_json::json!({"x": x, "y": y, "button": button}),
+                    session,
+                ),
+                cli.json,
+            ),
+            MouseAction::Wheel {
+                delta_x,
+                delta_y,
+                x,
+                y,
+                socket,
+                session,
+            } => cmd_call_daemon(
+                &socket,
+                "browser.mouse.wheel",
+                with_session(
+                    serde_json::json!({"delta_x": delta_x, "delta_y": delta_y, "x": x, "y": y}),
+                    session,
+                ),
                 cli.json,
             ),
         },
@@ -645,18 +3258,39 @@ fn main() -> Result<()> {
                 serde_json::json!({"title": title, "message": message}),
                 cli.json,
             ),
+            ExtensionAction::Status { socket } => cmd_call_daemon(
+                &socket,
+                "browser.extension.status",
+                serde_json::json!({}),
+                cli.json,
+            ),
         },
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn cmd_start(
     socket: String,
     foreground: bool,
     headless: bool,
+    viewport: ViewportSize,
+    channel: Option<BrowserChannel>,
+    console_buffer: usize,
+    session_idle_timeout: u64,
+    session_pool: usize,
+    max_sessions: usize,
     connect: Option<String>,
+    connect_launch: Option<ConnectLaunch>,
+    client_pool_ttl: Option<u64>,
     extension_bridge: bool,
     extension_port: u16,
+    extension_queue: usize,
+    extension_tls_cert: Option<String>,
+    extension_tls_key: Option<String>,
+    extension_auth: bool,
+    protocol: Protocol,
 ) -> Result<()> {
+    let extension_bridge = extension_bridge || extension_auth || protocol == Protocol::Bidi;
     let socket_path = shellexpand::tilde(&socket).to_string();
 
     // Create parent directory
@@ -669,57 +3303,117 @@ fn cmd_start(
     println!("Starting browser-gateway daemon...");
     println!("Socket: {}", socket_path);
 
+    // Launch before daemonizing so errors and instructions reach the user
+    let connect = match connect_launch {
+        Some(launch) => {
+            let profile = match launch.profile {
+                Some(dir) => Some(std::env::current_dir()?.join(shellexpand::tilde(&dir).as_ref())),
+                None => None,
+            };
+            if let Some(path) =
+                browser::ensure_debug_chrome(launch.port, channel, profile.as_deref())?
+            {
+                println!(
+                    "Launched {} with remote debugging on port {}",
+                    path.display(),
+                    launch.port
+                );
+            }
+            Some(format!("http://127.0.0.1:{}", launch.port))
+        }
+        None => connect,
+    };
+
     if let Some(ref url) = connect {
-        println!("Mode: CONNECT (attaching to user's Chrome at {})", url);
+        // Reject unsupported schemes before daemonizing
+        let endpoint = browser::parse_connect_endpoint(url)?;
+        println!("Mode: CONNECT (attaching to user's Chrome at {})", endpoint.url());
     } else {
         println!("Mode: {}", if headless { "headless" } else { "headed" });
+        if let Some(channel) = channel {
+            // Fail here, not after daemonizing where the error would be lost
+            let path = browser::BrowserClient::find_channel_executable(channel)?;
+            println!("Browser: {} ({})", channel.name(), path.display());
+        }
+        println!("Viewport: {}x{}", viewport.width, viewport.height);
     }
 
+    // The daemon runs from /tmp, so resolve certificate paths now
+    let cwd = std::env::current_dir()?;
+    let bridge_config = extension_bridge::ExtensionBridgeConfig {
+        tls_cert_path: extension_tls_cert.map(|path| cwd.join(path)),
+        tls_key_path: extension_tls_key.map(|path| cwd.join(path)),
+    };
+    let scheme = bridge_config.scheme();
+
     if extension_bridge {
-        println!("Extension bridge: ws://127.0.0.1:{}", extension_port);
+        // Fail here, not after daemonizing where the error would be lost
+        bridge_config.tls_acceptor()?;
+        println!("Extension bridge: {}://127.0.0.1:{}", scheme, extension_port);
+    }
+    if protocol == Protocol::Bidi {
+        println!("WebDriver BiDi: {}://127.0.0.1:{}/session", scheme, extension_port);
+    }
+    let auth_token = extension_auth.then(extension_bridge::generate_auth_token);
+    if let Some(ref token) = auth_token {
+        println!("Extension auth token: {} (paste into the extension popup)", token);
     }
 
-    // Create extension bridge if enabled (shared across threads)
-    let bridge: Option<std::sync::Arc<extension_bridge::ExtensionBridge>> = if extension_bridge {
-        Some(std::sync::Arc::new(extension_bridge::ExtensionBridge::new(Some(extension_port))))
-    } else {
-        None
-    };
-
-    // Helper to create the service based on mode, with optional extension bridge
-    let create_service = |connect_url: &Option<String>, bridge: Option<std::sync::Arc<extension_bridge::ExtensionBridge>>| -> Result<BrowserService> {
+    // Helper to create the service based on mode
+    let create_service = |connect_url: &Option<String>| -> Result<BrowserService> {
         let service = if let Some(url) = connect_url {
             BrowserService::new_connect(url)?
         } else {
-            BrowserService::new(headless)?
+            BrowserService::new(headless, viewport, channel)?
         };
-
-        Ok(if let Some(b) = bridge {
-            service.with_extension_bridge(b)
-        } else {
-            service
+        let service = service
+            .with_console_buffer(console_buffer)
+            .with_session_idle_timeout(session_idle_timeout)
+            .with_session_pool(session_pool)
+            .with_max_sessions(max_sessions);
+        Ok(match client_pool_ttl {
+            Some(secs) => service.with_client_pool(std::time::Duration::from_secs(secs)),
+            None => service,
         })
     };
 
-    // Helper to start extension bridge WebSocket server
-    let start_extension_bridge = |bridge: Option<std::sync::Arc<extension_bridge::ExtensionBridge>>| {
-        if let Some(b) = bridge {
-            let bridge_clone = b.clone();
-            // Start extension bridge in a separate thread with its own tokio runtime
-            std::thread::spawn(move || {
-                let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
-                rt.block_on(async {
-                    if let Err(e) = bridge_clone.start().await {
-                        tracing::error!("Extension bridge error: {}", e);
-                    }
-                    // Keep runtime alive
-                    loop {
-                        tokio::time::sleep(std::time::Duration::from_secs(3600)).await;
-                    }
-                });
-            });
-            tracing::info!("Extension bridge started on port {}", b.port());
+    // Helper to start the extension bridge WebSocket server and attach it to the service
+    let start_extension_bridge = |service: BrowserService| -> BrowserService {
+        if !extension_bridge {
+            return service;
+        }
+
+        let mut bridge = extension_bridge::ExtensionBridge::new(Some(extension_port));
+        if extension_queue > 0 {
+            bridge = bridge.with_message_queue(extension_queue);
+        }
+        if let Some(ref token) = auth_token {
+            bridge = bridge.with_auth_token(token.clone());
+        }
+        if protocol == Protocol::Bidi {
+            // BiDi commands run against the service's browser
+            bridge = bridge.with_bidi(service.bidi_target());
         }
+        let bridge = std::sync::Arc::new(bridge);
+
+        let bridge_clone = bridge.clone();
+        let bridge_config = bridge_config.clone();
+        // Start extension bridge in a separate thread with its own tokio runtime
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+            rt.block_on(async {
+                if let Err(e) = bridge_clone.start(&bridge_config).await {
+                    tracing::error!("Extension bridge error: {}", e);
+                }
+                // Keep runtime alive
+                loop {
+                    tokio::time::sleep(std::time::Duration::from_secs(3600)).await;
+                }
+            });
+        });
+        tracing::info!("Extension bridge started on port {}", bridge.port());
+
+        service.with_extension_bridge(bridge)
     };
 
     if foreground {
@@ -727,9 +3421,8 @@ fn cmd_start(
             .with_env_filter("fgp_browser=debug,fgp_daemon=debug,chromiumoxide=warn")
             .init();
 
-        start_extension_bridge(bridge.clone());
-
-        let service = create_service(&connect, bridge).context("Failed to create BrowserService")?;
+        let service = create_service(&connect).context("Failed to create BrowserService")?;
+        let service = start_extension_bridge(service);
         let server =
             FgpServer::new(service, &socket_path).context("Failed to create FGP server")?;
         server.serve().context("Server error")?;
@@ -746,9 +3439,9 @@ fn cmd_start(
                     .with_env_filter("fgp_browser=debug,fgp_daemon=debug,chromiumoxide=warn")
                     .init();
 
-                start_extension_bridge(bridge.clone());
-
-                let service = create_service(&connect, bridge).context("Failed to create BrowserService")?;
+                let service =
+                    create_service(&connect).context("Failed to create BrowserService")?;
+                let service = start_extension_bridge(service);
                 let server =
                     FgpServer::new(service, &socket_path).context("Failed to create FGP server")?;
                 server.serve().context("Server error")?;
@@ -847,6 +3540,112 @@ fn cmd_status(socket: String) -> Result<()> {
     Ok(())
 }
 
+/// Print `browser.network.list` results as a compact table.
+fn print_network_table(result: &serde_json::Value) {
+    let requests = result
+        .get("requests")
+        .and_then(|r| r.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    if requests.is_empty() {
+        if result.get("enabled").and_then(|e| e.as_bool()) == Some(false) {
+            println!("No requests captured. Enable capture with: browser-gateway network enable");
+        } else {
+            println!("No requests captured.");
+        }
+        return;
+    }
+
+    println!(
+        "{:<14} {:<7} {:<6} {:<10} {:>9} {:>8}  URL",
+        "ID", "METHOD", "STATUS", "TYPE", "SIZE", "TIME"
+    );
+    for req in &requests {
+        let status = match (req.get("status").and_then(|s| s.as_i64()), req.get("error")) {
+            (Some(code), _) => code.to_string(),
+            (None, Some(e)) if !e.is_null() => "ERR".to_string(),
+            _ => "-".to_string(),
+        };
+        let size = req
+            .get("encoded_data_length")
+            .and_then(|s| s.as_u64())
+            .map(format_bytes)
+            .unwrap_or_default();
+        let time = req
+            .get("duration_ms")
+            .and_then(|d| d.as_f64())
+            .map(|d| format!("{:.0}ms", d))
+            .unwrap_or_else(|| "-".to_string());
+
+        println!(
+            "{:<14} {:<7} {:<6} {:<10} {:>9} {:>8}  {}",
+            req.get("request_id").and_then(|i| i.as_str()).unwrap_or("-"),
+            req.get("method").and_then(|m| m.as_str()).unwrap_or("-"),
+            status,
+            req.get("resource_type").and_then(|t| t.as_str()).unwrap_or("-"),
+            size,
+            time,
+            req.get("url").and_then(|u| u.as_str()).unwrap_or(""),
+        );
+    }
+    println!("{} request(s)", requests.len());
+}
+
+/// Print `browser.session.list` results as a table; `*` marks the default
+/// session.
+fn print_session_table(result: &serde_json::Value) {
+    let sessions = result
+        .get("sessions")
+        .and_then(|s| s.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    if sessions.is_empty() {
+        println!("No sessions. The browser starts with the first command.");
+        return;
+    }
+
+    let field = |s: &serde_json::Value, key: &str| {
+        s.get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or("-")
+            .to_string()
+    };
+    println!(
+        "  {:<20} {:<16} {:<19} {:<30}  URL",
+        "ID", "NAME", "CREATED", "TITLE"
+    );
+    for session in &sessions {
+        let active = session.get("active").and_then(|a| a.as_bool()) == Some(true);
+        let created = chrono::DateTime::parse_from_rfc3339(&field(session, "created_at"))
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|_| "-".to_string());
+        let title: String = field(session, "title").chars().take(30).collect();
+
+        println!(
+            "{} {:<20} {:<16} {:<19} {:<30}  {}",
+            if active { "*" } else { " " },
+            field(session, "id"),
+            field(session, "name"),
+            created,
+            title,
+            field(session, "url"),
+        );
+    }
+    println!("{} session(s)", sessions.len());
+}
+
+fn format_bytes(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1}MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{:.1}KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{}B", bytes)
+    }
+}
+
 /// Call daemon and return parsed JSON response
 fn call_daemon_raw(
     socket: &str,
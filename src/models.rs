@@ -1,7 +1,7 @@
 //! Data models for browser automation.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use chromiumoxide::cdp::browser_protocol::network::CookieSameSite;
 
@@ -27,6 +27,81 @@ pub struct AriaNode {
     /// Child nodes
     #[serde(default)]
     pub children: Vec<AriaNode>,
+    /// Whether descendants were dropped by the snapshot depth limit
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+    /// Index of the iframe this node belongs to (None = top document)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frame: Option<usize>,
+    /// Whether the element lives inside a shadow root. CSS selectors can't
+    /// reach it from the document, so interact via `evaluate`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub in_shadow_dom: bool,
+}
+
+/// Which iframe an interaction or script targets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameTarget {
+    /// Position among child frames in document order (0 = first iframe)
+    Index(usize),
+    /// URL substring or glob, or the frame's name
+    Url(String),
+    /// Nested frames, outermost first; each step matches among the previous
+    /// frame's direct children (an index counts only those children)
+    Path(Vec<FrameTarget>),
+}
+
+impl FrameTarget {
+    /// Parse `"2"` as an index, `"checkout > card"` as a path, anything else
+    /// as a URL pattern or name.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if s.contains('>') {
+            let steps: Vec<_> = s.split('>').filter_map(Self::parse).collect();
+            return Self::path(steps);
+        }
+
+        if s.is_empty() {
+            None
+        } else if let Ok(index) = s.parse() {
+            Some(FrameTarget::Index(index))
+        } else {
+            Some(FrameTarget::Url(s.to_string()))
+        }
+    }
+
+    /// A path of steps; a single step is returned as-is.
+    pub fn path(mut steps: Vec<FrameTarget>) -> Option<Self> {
+        match steps.len() {
+            0 => None,
+            1 => steps.pop(),
+            _ => Some(FrameTarget::Path(steps)),
+        }
+    }
+}
+
+impl std::fmt::Display for FrameTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrameTarget::Index(index) => write!(f, "#{}", index),
+            FrameTarget::Url(pattern) => write!(f, "'{}'", pattern),
+            FrameTarget::Path(steps) => {
+                let steps: Vec<String> = steps.iter().map(|s| s.to_string()).collect();
+                write!(f, "{}", steps.join(" > "))
+            }
+        }
+    }
+}
+
+/// A child frame of the page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrameInfo {
+    /// Position in document order, usable as the `frame` parameter
+    pub index: usize,
+    pub url: String,
+    /// Frame name attribute
+    #[serde(default)]
+    pub name: Option<String>,
 }
 
 /// ARIA tree snapshot response.
@@ -42,20 +117,147 @@ pub struct AriaSnapshot {
     pub element_count: usize,
 }
 
+/// An ARIA snapshot stored by `browser.snapshot.save`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSnapshot {
+    /// Name the snapshot was saved under
+    pub name: String,
+    /// When the snapshot was taken (RFC 3339)
+    pub saved_at: String,
+    #[serde(flatten)]
+    pub snapshot: AriaSnapshot,
+}
+
+/// A node present in both snapshots whose state changed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AriaNodeChange {
+    pub before: AriaNode,
+    pub after: AriaNode,
+    /// Fields that differ ("value", "focused", ...)
+    pub fields: Vec<String>,
+}
+
+/// Difference between two ARIA snapshots.
+///
+/// Nodes are matched by frame, role and name (in document order), since `@eN`
+/// refs are renumbered on every snapshot. A renamed node shows up as removed
+/// and added.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    pub added: Vec<AriaNode>,
+    pub removed: Vec<AriaNode>,
+    pub changed: Vec<AriaNodeChange>,
+}
+
+/// Screenshot image format.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum ScreenshotFormat {
+    #[default]
+    Png,
+    Jpeg {
+        /// Compression quality (0-100)
+        quality: u8,
+    },
+    Webp {
+        /// Compression quality (0-100)
+        quality: u8,
+    },
+}
+
+impl ScreenshotFormat {
+    /// MIME content type of the encoded image.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ScreenshotFormat::Png => "image/png",
+            ScreenshotFormat::Jpeg { .. } => "image/jpeg",
+            ScreenshotFormat::Webp { .. } => "image/webp",
+        }
+    }
+
+    /// Compression quality, if the format is lossy.
+    pub fn quality(&self) -> Option<u8> {
+        match self {
+            ScreenshotFormat::Png => None,
+            ScreenshotFormat::Jpeg { quality } | ScreenshotFormat::Webp { quality } => {
+                Some(*quality)
+            }
+        }
+    }
+}
+
 /// Screenshot response.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScreenshotResult {
-    /// Base64-encoded PNG data (if no path specified)
+    /// Base64-encoded image data (if no path specified)
     #[serde(default)]
     pub data: Option<String>,
     /// File path (if path was specified)
     #[serde(default)]
     pub path: Option<String>,
+    /// Content type of the image (e.g., "image/png")
+    #[serde(default)]
+    pub format: String,
     /// Image dimensions
     pub width: u32,
     pub height: u32,
 }
 
+/// PDF print options (dimensions in inches, matching CDP `Page.printToPDF`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PdfOptions {
+    /// Landscape orientation
+    #[serde(default)]
+    pub landscape: bool,
+    /// Print background graphics
+    #[serde(default)]
+    pub print_background: bool,
+    /// Scale of the webpage rendering (0.1 - 2.0)
+    #[serde(default)]
+    pub scale: Option<f64>,
+    /// Paper width in inches
+    #[serde(default)]
+    pub paper_width: Option<f64>,
+    /// Paper height in inches
+    #[serde(default)]
+    pub paper_height: Option<f64>,
+    /// Margin in inches, applied to all sides
+    #[serde(default)]
+    pub margin: Option<f64>,
+}
+
+/// PDF response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PdfResult {
+    /// Base64-encoded PDF data (if no path specified)
+    #[serde(default)]
+    pub data: Option<String>,
+    /// File path (if path was specified)
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Size of the PDF in bytes
+    pub size_bytes: usize,
+}
+
+/// Response fetched by `browser.fetch`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FetchResult {
+    /// URL after redirects
+    pub url: String,
+    pub status: u16,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    /// Base64-encoded body (if no path specified)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+    /// File path (if path was specified)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub size_bytes: usize,
+    /// "page" (in-page fetch) or "network" (CDP fallback for cross-origin URLs)
+    pub via: String,
+}
+
 /// Navigation result.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NavigationResult {
@@ -66,19 +268,659 @@ pub struct NavigationResult {
     /// HTTP status code
     #[serde(default)]
     pub status: Option<u16>,
+    /// Throttling in effect for the session (timings are slower than real)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network_conditions: Option<NetworkConditions>,
+    /// CPU slowdown factor in effect for the session (`browser.cpu.throttle`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_throttling_rate: Option<f64>,
+    /// Wall-clock time from starting the navigation until it finished
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub load_time_ms: Option<u64>,
+}
+
+/// Performance numbers for the current page (`browser.metrics`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageMetrics {
+    pub url: String,
+    /// CDP `Performance.getMetrics` values by name (e.g. `JSHeapUsedSize`)
+    pub metrics: BTreeMap<String, f64>,
+    pub timing: NavigationTiming,
+}
+
+/// Navigation Timing milestones, in milliseconds since navigation start.
+/// None for events that haven't happened (yet).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NavigationTiming {
+    pub dom_content_loaded_ms: Option<f64>,
+    pub load_ms: Option<f64>,
+    pub first_contentful_paint_ms: Option<f64>,
+}
+
+/// HTML retrieval result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HtmlResult {
+    /// Outer or inner HTML
+    pub html: String,
+    /// Length of `html` in bytes
+    pub length: usize,
+}
+
+/// Serialized document (or one subtree) with the page it came from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageContent {
+    /// `outerHTML` of the document element or the selected element
+    pub html: String,
+    pub url: String,
+    pub title: String,
+    /// Length of `html` in bytes
+    pub length: usize,
+}
+
+/// Anchor extracted from a page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkInfo {
+    /// Visible link text
+    #[serde(default)]
+    pub text: String,
+    /// Absolute URL (resolved against the document base)
+    pub href: String,
+    /// `rel` attribute (empty if absent)
+    #[serde(default)]
+    pub rel: String,
+    /// `target` attribute (empty if absent)
+    #[serde(default)]
+    pub target: String,
+}
+
+/// Image extracted from a page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageInfo {
+    /// Absolute image URL
+    pub src: String,
+    /// Alt text (empty if absent)
+    #[serde(default)]
+    pub alt: String,
+    /// Intrinsic width in pixels (0 if not loaded)
+    #[serde(default)]
+    pub width: u32,
+    /// Intrinsic height in pixels (0 if not loaded)
+    #[serde(default)]
+    pub height: u32,
+}
+
+/// Main content extracted from a page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentExtraction {
+    /// Article or page title
+    pub title: String,
+    /// Author byline, if found
+    #[serde(default)]
+    pub byline: Option<String>,
+    /// Main content rendered as Markdown
+    pub markdown: String,
+    /// Main content as plain text
+    pub text: String,
+    /// Number of words in `text`
+    pub word_count: usize,
+    /// True if extraction failed and the body text was returned instead
+    #[serde(default)]
+    pub fallback: bool,
+}
+
+/// How JavaScript dialogs are answered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DialogAction {
+    /// Accept (OK) the dialog
+    Accept,
+    /// Dismiss (Cancel) the dialog
+    #[default]
+    Dismiss,
+    /// Leave the dialog open until the policy changes
+    Manual,
+}
+
+/// Per-session dialog handling policy.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DialogPolicy {
+    /// Action taken for every dialog
+    #[serde(default)]
+    pub action: DialogAction,
+    /// Text entered into `prompt()` dialogs when accepting
+    #[serde(default)]
+    pub prompt_text: Option<String>,
+}
+
+/// A JavaScript dialog opened by a page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DialogRecord {
+    /// Dialog type: alert, confirm, prompt, or beforeunload
+    pub dialog_type: String,
+    /// Dialog message
+    pub message: String,
+    /// URL of the frame that opened the dialog
+    pub url: String,
+    /// Default prompt value (prompt dialogs only)
+    #[serde(default)]
+    pub default_prompt: Option<String>,
+    /// Action that was taken (`manual` while the dialog is still open)
+    pub action: DialogAction,
+    /// Whether the dialog is still open, waiting for an answer
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pending: bool,
+    /// When the dialog opened
+    pub timestamp: String,
+}
+
+/// A console message or browser log entry captured from a page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsoleEntry {
+    /// Level: log, info, debug, warning, error, ...
+    pub level: String,
+    /// Message text (console arguments joined with spaces)
+    pub text: String,
+    /// Origin: "console" for console.* calls, otherwise the Log domain source
+    /// (javascript, network, security, ...)
+    pub source: String,
+    /// Script or resource URL, when known
+    #[serde(default)]
+    pub url: Option<String>,
+    /// When the message was logged
+    pub timestamp: String,
+}
+
+/// Dialog policy and handled dialogs for a session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DialogStatus {
+    pub policy: DialogPolicy,
+    pub dialogs: Vec<DialogRecord>,
+}
+
+/// A file downloaded by the browser.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadInfo {
+    /// Chrome download GUID
+    pub guid: String,
+    /// Session that started the download
+    pub session_id: String,
+    /// Source URL
+    pub url: String,
+    /// Filename on disk
+    pub filename: String,
+    /// Absolute path on disk
+    pub path: String,
+    /// "in_progress", "completed" or "canceled"
+    pub state: String,
+    pub received_bytes: u64,
+    /// Total size (0 if unknown)
+    pub total_bytes: u64,
+    /// Size on disk once completed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+}
+
+/// Result of removing old downloaded files.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DownloadCleanup {
+    pub removed_files: u64,
+    pub freed_bytes: u64,
+}
+
+/// Geolocation override applied to a session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Geolocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Accuracy in meters
+    pub accuracy: f64,
+    /// Origin the geolocation permission was granted for (None = all origins)
+    #[serde(default)]
+    pub origin: Option<String>,
+}
+
+/// Viewport size in CSS pixels, written as `WIDTHxHEIGHT` (e.g. `1280x800`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ViewportSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Default for ViewportSize {
+    fn default() -> Self {
+        Self {
+            width: 1280,
+            height: 800,
+        }
+    }
+}
+
+impl std::str::FromStr for ViewportSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid viewport '{}': expected WIDTHxHEIGHT, e.g. 1280x800", s);
+        let (w, h) = s.trim().split_once(['x', 'X']).ok_or_else(invalid)?;
+        let width: u32 = w.trim().parse().map_err(|_| invalid())?;
+        let height: u32 = h.trim().parse().map_err(|_| invalid())?;
+        if width == 0 || height == 0 {
+            return Err(invalid());
+        }
+        Ok(Self { width, height })
+    }
+}
+
+/// System browser selected with `start --channel`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrowserChannel {
+    Chrome,
+    ChromeBeta,
+    ChromeDev,
+    ChromeCanary,
+    Chromium,
+}
+
+impl BrowserChannel {
+    pub const NAMES: &'static [&'static str] =
+        &["chrome", "chrome-beta", "chrome-dev", "chrome-canary", "chromium"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BrowserChannel::Chrome => "chrome",
+            BrowserChannel::ChromeBeta => "chrome-beta",
+            BrowserChannel::ChromeDev => "chrome-dev",
+            BrowserChannel::ChromeCanary => "chrome-canary",
+            BrowserChannel::Chromium => "chromium",
+        }
+    }
+}
+
+impl std::str::FromStr for BrowserChannel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "chrome" | "chrome-stable" => Ok(BrowserChannel::Chrome),
+            "chrome-beta" => Ok(BrowserChannel::ChromeBeta),
+            "chrome-dev" => Ok(BrowserChannel::ChromeDev),
+            "chrome-canary" => Ok(BrowserChannel::ChromeCanary),
+            "chromium" => Ok(BrowserChannel::Chromium),
+            _ => Err(format!(
+                "Unknown channel '{}': expected one of {}",
+                s,
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// Device metrics applied by `browser.emulate`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmulationSettings {
+    /// Viewport width in CSS pixels
+    pub width: u32,
+    /// Viewport height in CSS pixels
+    pub height: u32,
+    pub device_scale_factor: f64,
+    /// Mobile viewport and touch events
+    pub is_mobile: bool,
+    /// User agent override (None keeps the current one)
+    #[serde(default)]
+    pub user_agent: Option<String>,
+}
+
+/// CSS media emulation applied by `browser.emulate_media`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MediaEmulation {
+    /// CSS media type ("print" or "screen")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media: Option<String>,
+    /// `prefers-color-scheme` ("dark" or "light")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color_scheme: Option<String>,
+    /// `prefers-reduced-motion: reduce` when true
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reduced_motion: Option<bool>,
+}
+
+impl MediaEmulation {
+    /// Overlay the fields set in `update`, keeping the rest.
+    pub fn merged(self, update: MediaEmulation) -> Self {
+        Self {
+            media: update.media.or(self.media),
+            color_scheme: update.color_scheme.or(self.color_scheme),
+            reduced_motion: update.reduced_motion.or(self.reduced_motion),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Network throttling applied by `browser.set_network_conditions`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkConditions {
+    /// Named profile ("slow-3g", "fast-3g", "offline"), None for custom numbers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Fail every request as if the connection were down
+    #[serde(default)]
+    pub offline: bool,
+    /// Minimum latency added to each request, in milliseconds
+    #[serde(default)]
+    pub latency_ms: f64,
+    /// Download throughput in kbit/s (None = unthrottled)
+    #[serde(default)]
+    pub download_kbps: Option<f64>,
+    /// Upload throughput in kbit/s (None = unthrottled)
+    #[serde(default)]
+    pub upload_kbps: Option<f64>,
+}
+
+/// A network request captured from a session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkRequest {
+    /// CDP request ID (shared by all hops of a redirect chain)
+    pub request_id: String,
+    pub url: String,
+    pub method: String,
+    /// CDP resource type (Document, XHR, Fetch, Script, ...)
+    pub resource_type: String,
+    /// HTTP status (None until a response arrives)
+    #[serde(default)]
+    pub status: Option<i64>,
+    #[serde(default)]
+    pub mime_type: Option<String>,
+    /// Bytes received over the network, including headers
+    #[serde(default)]
+    pub encoded_data_length: u64,
+    /// Time from request to completion or failure
+    #[serde(default)]
+    pub duration_ms: Option<f64>,
+    /// Failure reason (e.g. "net::ERR_CONNECTION_REFUSED")
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Wall-clock time the request was sent (RFC 3339)
+    pub started_at: String,
+}
+
+/// An outgoing request recorded by `browser.network.capture_start`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapturedRequest {
+    pub url: String,
+    pub method: String,
+    /// Request headers as sent by the page
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Request body (lossy UTF-8), if any
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Wall-clock time the request was sent (RFC 3339)
+    pub timestamp: String,
+}
+
+/// Response body of a captured request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkBody {
+    pub request_id: String,
+    pub url: String,
+    #[serde(default)]
+    pub mime_type: Option<String>,
+    /// Body text, or base64 when the content is not valid UTF-8
+    pub body: String,
+    pub base64_encoded: bool,
+    /// Full decoded size before truncation
+    pub size_bytes: usize,
+    pub truncated: bool,
+}
+
+/// Request blocking rules for a session.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BlockRules {
+    /// URL substrings, or globs with `*` wildcards
+    #[serde(default)]
+    pub url_patterns: Vec<String>,
+    /// CDP resource types (Image, Font, Media, Script, ...)
+    #[serde(default)]
+    pub resource_types: Vec<String>,
+}
+
+/// Active block rules and their hit count.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockStatus {
+    #[serde(flatten)]
+    pub rules: BlockRules,
+    /// Requests blocked since the session started
+    pub blocked_count: u64,
+    /// URL patterns blocked by Chrome itself (`browser.network.block_urls`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocked_urls: Vec<String>,
+}
+
+/// A HAR file written by `browser.har.stop`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarResult {
+    /// File path the HAR was written to
+    pub path: String,
+    /// Number of entries (request hops) in the log
+    pub entry_count: usize,
+    /// Requests not recorded because the entry limit was reached
+    #[serde(default)]
+    pub dropped: usize,
+    /// Size of the HAR file in bytes
+    pub size_bytes: usize,
+}
+
+/// A canned response served for requests matching a URL pattern.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MockRule {
+    /// Mock ID for `browser.network.unmock` (e.g. "mock-1")
+    pub id: String,
+    /// URL substring, or glob with `*` wildcards
+    pub url_pattern: String,
+    /// HTTP status code
+    pub status: u16,
+    /// Response headers
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Response body size in bytes
+    pub body_size: usize,
+    /// Requests fulfilled by this mock
+    pub hit_count: u64,
+}
+
+/// HTTP status filter for `browser.network.list`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusFilter {
+    /// Exact status code, e.g. `404`
+    Exact(i64),
+    /// Status class, e.g. `4` for `4xx`
+    Class(i64),
+    /// Requests that failed without a response
+    Failed,
+}
+
+impl StatusFilter {
+    /// Parse `"404"`, `"4xx"` or `"failed"`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_ascii_lowercase();
+        if s == "failed" {
+            return Some(StatusFilter::Failed);
+        }
+        if let Some(class) = s.strip_suffix("xx") {
+            return match class.parse::<i64>() {
+                Ok(c) if (1..=5).contains(&c) => Some(StatusFilter::Class(c)),
+                _ => None,
+            };
+        }
+        s.parse::<i64>().ok().map(StatusFilter::Exact)
+    }
+
+    pub fn matches(&self, request: &NetworkRequest) -> bool {
+        match self {
+            StatusFilter::Exact(code) => request.status == Some(*code),
+            StatusFilter::Class(class) => request.status.is_some_and(|s| s / 100 == *class),
+            StatusFilter::Failed => request.error.is_some(),
+        }
+    }
+}
+
+/// Filters for `browser.network.list`.
+#[derive(Debug, Clone, Default)]
+pub struct NetworkFilter {
+    /// URL substring, or glob with `*` wildcards
+    pub url_pattern: Option<String>,
+    pub status: Option<StatusFilter>,
+    /// Resource type, case-insensitive (e.g. "xhr", "fetch")
+    pub resource_type: Option<String>,
+    /// Return only the most recent N matches
+    pub limit: Option<usize>,
+}
+
+/// Result of `browser.session.clone`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionCloneResult {
+    /// ID of the new session
+    pub session_id: String,
+    /// Session the state was copied from
+    pub source_session_id: String,
+    /// Cookies copied (all domains in the source's context)
+    pub cookies: usize,
+    /// localStorage items copied for the source's current origin
+    pub local_storage_items: usize,
+    /// Page the clone was left on, when it navigated to the source's URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
 }
 
 /// Browser session info.
-#[allow(dead_code)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionInfo {
     /// Session ID
     pub id: String,
+    /// Human-readable label given to `session.new`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// When the session was created (RFC 3339)
+    pub created_at: String,
     /// Current URL
     #[serde(default)]
     pub url: Option<String>,
+    /// Current page title
+    #[serde(default)]
+    pub title: Option<String>,
     /// Whether this is the active session
     pub active: bool,
+    /// Extra HTTP headers sent with every request
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// Username answering HTTP auth challenges
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_username: Option<String>,
+    /// Emulated CSS media (color scheme, reduced motion, print)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media: Option<MediaEmulation>,
+    /// Driving a tab that was already open (`session.attach`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub attached: bool,
+    /// Caller-defined labels (`session.set_metadata`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, String>,
+}
+
+/// Warm session pool counters, reported by `health`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionPoolStats {
+    /// Contexts kept ready (`--session-pool`)
+    pub target: usize,
+    /// Contexts ready now
+    pub ready: usize,
+    /// `session.new` calls served from the pool
+    pub hits: u64,
+    /// `session.new` calls that found the pool empty
+    pub misses: u64,
+    /// Closed sessions' contexts cleared and put back into the pool
+    pub recycled: u64,
+}
+
+/// A browser tab (page target).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TabInfo {
+    /// CDP target ID
+    pub target_id: String,
+    pub url: String,
+    pub title: String,
+    /// Whether the session's commands use this tab (for `tabs.list`, the
+    /// default session's)
+    #[serde(default)]
+    pub active: bool,
+    /// Session driving this tab, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+}
+
+/// A page a session drives: its own tab or a popup opened from it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageInfo {
+    /// Position in the session's pages (0 = the session's own tab)
+    pub index: usize,
+    /// CDP target ID
+    pub target_id: String,
+    pub url: String,
+    pub title: String,
+    /// Index of the page that opened this one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opener: Option<usize>,
+    /// Whether commands target this page
+    #[serde(default)]
+    pub active: bool,
+}
+
+/// An active popup that closed, moving commands back to its opener.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClosedPopup {
+    /// CDP target ID of the closed popup
+    pub target_id: String,
+    /// Page index commands target now
+    pub switched_to: usize,
+}
+
+/// Pages of a session, from `browser.pages.list`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageList {
+    pub pages: Vec<PageInfo>,
+    /// Index of the page commands target
+    pub active: usize,
+    /// Active popups that closed since the last listing
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub closed: Vec<ClosedPopup>,
+}
+
+/// HTTP auth credentials used to answer `401`/`407` challenges.
+#[derive(Clone, Deserialize)]
+pub struct HttpCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+// Keep the password out of logs
+impl std::fmt::Debug for HttpCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpCredentials")
+            .field("username", &self.username)
+            .field("password", &"***")
+            .finish()
+    }
+}
+
+/// Extra headers and auth applied to a session's requests.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HeaderSettings {
+    pub headers: HashMap<String, String>,
+    /// Username answering HTTP auth challenges (the password is never returned)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_username: Option<String>,
 }
 
 /// Saved auth state info.
@@ -92,23 +934,47 @@ pub struct SavedState {
     pub saved_at: String,
 }
 
-/// Serializable cookie for auth state.
+/// Serializable cookie for auth state and cookie files.
+///
+/// Also reads the `chrome.cookies` format that cookie-export extensions
+/// write (`expirationDate`, `httpOnly`, `sameSite: "no_restriction"`).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SerializableCookie {
     pub name: String,
     pub value: String,
     pub domain: String,
     pub path: String,
-    #[serde(default)]
+    #[serde(default, alias = "expirationDate")]
     pub expires: Option<f64>,
     #[serde(default)]
     pub secure: bool,
-    #[serde(default)]
+    #[serde(default, alias = "httpOnly")]
     pub http_only: bool,
-    #[serde(default)]
+    #[serde(
+        default,
+        alias = "sameSite",
+        deserialize_with = "deserialize_same_site"
+    )]
     pub same_site: Option<CookieSameSite>,
 }
 
+/// Accept CDP (`Lax`) and `chrome.cookies` (`lax`, `no_restriction`,
+/// `unspecified`) spellings of SameSite.
+fn deserialize_same_site<'de, D>(deserializer: D) -> Result<Option<CookieSameSite>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value: Option<String> = Option::deserialize(deserializer)?;
+    Ok(
+        match value.as_deref().map(str::to_ascii_lowercase).as_deref() {
+            Some("strict") => Some(CookieSameSite::Strict),
+            Some("lax") => Some(CookieSameSite::Lax),
+            Some("none") | Some("no_restriction") => Some(CookieSameSite::None),
+            _ => None,
+        },
+    )
+}
+
 /// Local storage snapshot for a single origin.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct LocalStorageState {
@@ -119,16 +985,52 @@ pub struct LocalStorageState {
 }
 
 /// Auth state snapshot with cookies and localStorage.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AuthState {
     #[serde(default)]
     pub cookies: Vec<SerializableCookie>,
-    #[serde(default)]
-    pub local_storage: LocalStorageState,
+    /// One entry per origin (files saved before multi-origin support hold
+    /// a single object)
+    #[serde(default, deserialize_with = "deserialize_local_storage")]
+    pub local_storage: Vec<LocalStorageState>,
     #[serde(default)]
     pub saved_at: String,
 }
 
+/// Accept a list of origins' localStorage, or a single origin's.
+fn deserialize_local_storage<'de, D>(deserializer: D) -> Result<Vec<LocalStorageState>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        Many(Vec<LocalStorageState>),
+        One(LocalStorageState),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::Many(states) => states,
+        OneOrMany::One(state) if state.items.is_empty() => Vec::new(),
+        OneOrMany::One(state) => vec![state],
+    })
+}
+
+/// What `browser.session.new` restored from a saved auth state.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RestoredState {
+    /// Domains the restored cookies belong to
+    pub cookie_domains: Vec<String>,
+    /// localStorage items written, across origins
+    pub local_storage_items: usize,
+    /// Origins whose localStorage was written
+    #[serde(default)]
+    pub local_storage_origins: Vec<String>,
+    /// Page the session was left on, if it navigated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
 /// Click result.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClickResult {
@@ -137,6 +1039,69 @@ pub struct ClickResult {
     /// Element that was clicked (for debugging)
     #[serde(default)]
     pub element: Option<String>,
+    /// The `contextmenu` event a right-click caused
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_menu: Option<ContextMenuEvent>,
+}
+
+/// A `contextmenu` event seen during `browser.right_click`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextMenuEvent {
+    /// Element the event fired on, as `tag#id.class`
+    pub target: String,
+    /// The page called `preventDefault()`, usually to show its own menu
+    /// instead of the browser's
+    pub default_prevented: bool,
+}
+
+/// Element bounding box from `getBoundingClientRect()`, in CSS pixels
+/// relative to the viewport (the coordinate space mouse events use).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ElementBounds {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub left: f64,
+    /// Whether any part of the element is inside the viewport
+    pub in_viewport: bool,
+}
+
+/// Result of `browser.scroll_to_bottom`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScrollToBottomResult {
+    /// Times the page was scrolled to the bottom
+    pub scrolls: u32,
+    /// Document height after the last scroll, in CSS pixels
+    pub height: u64,
+    /// The height stopped growing; false if `max_scrolls` or the timeout
+    /// ended the scrolling first, so more content may be left
+    pub reached_end: bool,
+}
+
+/// One key press in a `browser.key_sequence`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyPress {
+    /// Key name (e.g. "j", "Escape", "ArrowDown")
+    pub key: String,
+    /// Modifiers held during the press (ctrl, shift, alt, meta)
+    #[serde(default)]
+    pub modifiers: Vec<String>,
+    /// Pause after this press, in milliseconds
+    #[serde(default)]
+    pub delay_after_ms: u64,
+}
+
+/// Option chosen by `browser.select`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SelectedOption {
+    /// The option's `value`
+    pub value: String,
+    /// The option's visible text
+    pub label: String,
 }
 
 /// Fill result.
@@ -162,12 +1127,16 @@ mod tests {
             focusable: true,
             focused: false,
             children: vec![],
+            truncated: false,
+            frame: None,
+            in_shadow_dom: false,
         };
 
         let json = serde_json::to_string(&node).unwrap();
         assert!(json.contains("@e1"));
         assert!(json.contains("button"));
         assert!(json.contains("Submit"));
+        assert!(!json.contains("in_shadow_dom"));
 
         let parsed: AriaNode = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed.ref_id, "@e1");
@@ -195,6 +1164,9 @@ mod tests {
             url: "https://example.com/page".to_string(),
             title: "Example Page".to_string(),
             status: Some(200),
+            network_conditions: None,
+            cpu_throttling_rate: None,
+            load_time_ms: Some(412),
         };
 
         let json = serde_json::to_string(&result).unwrap();
@@ -203,6 +1175,21 @@ mod tests {
         assert_eq!(parsed.url, "https://example.com/page");
         assert_eq!(parsed.title, "Example Page");
         assert_eq!(parsed.status, Some(200));
+        assert_eq!(parsed.load_time_ms, Some(412));
+        assert!(!json.contains("network_conditions"));
+    }
+
+    #[test]
+    fn test_navigation_timing_before_load() {
+        let timing: NavigationTiming = serde_json::from_value(serde_json::json!({
+            "dom_content_loaded_ms": 120.5,
+            "load_ms": null,
+            "first_contentful_paint_ms": null
+        }))
+        .unwrap();
+
+        assert_eq!(timing.dom_content_loaded_ms, Some(120.5));
+        assert!(timing.load_ms.is_none());
     }
 
     #[test]
@@ -210,6 +1197,7 @@ mod tests {
         let result = ScreenshotResult {
             data: None,
             path: Some("/tmp/screenshot.png".to_string()),
+            format: "image/png".to_string(),
             width: 1920,
             height: 1080,
         };
@@ -225,6 +1213,7 @@ mod tests {
         let result = ScreenshotResult {
             data: Some("iVBORw0KGgo...".to_string()),
             path: None,
+            format: "image/png".to_string(),
             width: 800,
             height: 600,
         };
@@ -236,19 +1225,120 @@ mod tests {
         assert!(parsed.path.is_none());
     }
 
+    #[test]
+    fn test_pdf_options_defaults() {
+        let options: PdfOptions = serde_json::from_str("{}").unwrap();
+
+        assert!(!options.landscape);
+        assert!(!options.print_background);
+        assert_eq!(options.scale, None);
+        assert_eq!(options.margin, None);
+    }
+
+    #[test]
+    fn test_link_info_deserialization_with_defaults() {
+        let json = r#"{"href": "https://example.com/about"}"#;
+        let link: LinkInfo = serde_json::from_str(json).unwrap();
+
+        assert_eq!(link.href, "https://example.com/about");
+        assert!(link.text.is_empty());
+        assert!(link.rel.is_empty());
+        assert!(link.target.is_empty());
+    }
+
+    #[test]
+    fn test_dialog_policy_deserialization() {
+        let policy: DialogPolicy =
+            serde_json::from_str(r#"{"action": "accept", "prompt_text": "yes"}"#).unwrap();
+        assert_eq!(policy.action, DialogAction::Accept);
+        assert_eq!(policy.prompt_text, Some("yes".to_string()));
+
+        let default: DialogPolicy = serde_json::from_str("{}").unwrap();
+        assert_eq!(default.action, DialogAction::Dismiss);
+    }
+
+    #[test]
+    fn test_screenshot_format_content_type() {
+        assert_eq!(ScreenshotFormat::Png.content_type(), "image/png");
+        assert_eq!(ScreenshotFormat::Png.quality(), None);
+
+        let jpeg = ScreenshotFormat::Jpeg { quality: 70 };
+        assert_eq!(jpeg.content_type(), "image/jpeg");
+        assert_eq!(jpeg.quality(), Some(70));
+
+        let webp = ScreenshotFormat::Webp { quality: 90 };
+        assert_eq!(webp.content_type(), "image/webp");
+        assert_eq!(webp.quality(), Some(90));
+    }
+
+    #[test]
+    fn test_saved_snapshot_is_flat() {
+        let saved = SavedSnapshot {
+            name: "before".to_string(),
+            saved_at: "2026-01-01T00:00:00Z".to_string(),
+            snapshot: AriaSnapshot {
+                url: "https://example.com".to_string(),
+                title: "Example".to_string(),
+                nodes: vec![],
+                element_count: 0,
+            },
+        };
+
+        let value = serde_json::to_value(&saved).unwrap();
+        assert_eq!(value["url"], "https://example.com");
+        assert_eq!(value["name"], "before");
+
+        let parsed: SavedSnapshot = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed.snapshot.title, "Example");
+    }
+
+    #[test]
+    fn test_media_emulation_merge() {
+        let dark = MediaEmulation {
+            color_scheme: Some("dark".to_string()),
+            ..Default::default()
+        };
+        let merged = dark.merged(MediaEmulation {
+            reduced_motion: Some(true),
+            ..Default::default()
+        });
+
+        assert_eq!(merged.color_scheme.as_deref(), Some("dark"));
+        assert_eq!(merged.reduced_motion, Some(true));
+        assert!(!merged.is_empty());
+        assert!(MediaEmulation::default().is_empty());
+        assert_eq!(
+            serde_json::to_value(&merged).unwrap(),
+            serde_json::json!({"color_scheme": "dark", "reduced_motion": true})
+        );
+    }
+
     #[test]
     fn test_session_info() {
         let session = SessionInfo {
             id: "session-abc".to_string(),
+            name: Some("Checkout".to_string()),
+            created_at: "2026-01-15T10:00:00+00:00".to_string(),
             url: Some("https://example.com".to_string()),
+            title: Some("Example Domain".to_string()),
             active: true,
+            headers: HashMap::new(),
+            auth_username: Some("admin".to_string()),
+            media: None,
+            attached: false,
+            metadata: HashMap::from([("owner".to_string(), "checkout-tests".to_string())]),
         };
 
         let json = serde_json::to_string(&session).unwrap();
         let parsed: SessionInfo = serde_json::from_str(&json).unwrap();
 
         assert_eq!(parsed.id, "session-abc");
+        assert_eq!(parsed.name.as_deref(), Some("Checkout"));
+        assert_eq!(parsed.title.as_deref(), Some("Example Domain"));
         assert!(parsed.active);
+        assert_eq!(parsed.auth_username.as_deref(), Some("admin"));
+        assert!(!json.contains("headers"));
+        assert_eq!(parsed.metadata["owner"], "checkout-tests");
     }
 
     #[test]
@@ -264,14 +1354,14 @@ mod tests {
                 http_only: true,
                 same_site: None,
             }],
-            local_storage: LocalStorageState {
+            local_storage: vec![LocalStorageState {
                 origin: "https://example.com".to_string(),
                 items: {
                     let mut map = HashMap::new();
                     map.insert("token".to_string(), "xyz".to_string());
                     map
                 },
-            },
+            }],
             saved_at: "2024-01-01T00:00:00Z".to_string(),
         };
 
@@ -281,16 +1371,54 @@ mod tests {
         assert_eq!(parsed.cookies.len(), 1);
         assert_eq!(parsed.cookies[0].name, "session");
         assert_eq!(
-            parsed.local_storage.items.get("token"),
+            parsed.local_storage[0].items.get("token"),
             Some(&"xyz".to_string())
         );
     }
 
+    #[test]
+    fn test_auth_state_single_origin_file() {
+        let json = r#"{"cookies": [], "local_storage": {"origin": "https://app.example.com", "items": {"token": "xyz"}}}"#;
+        let parsed: AuthState = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.local_storage.len(), 1);
+        assert_eq!(parsed.local_storage[0].origin, "https://app.example.com");
+
+        // An empty single origin (saved from about:blank) is no origin
+        let json = r#"{"cookies": [], "local_storage": {"origin": "null", "items": {}}}"#;
+        let parsed: AuthState = serde_json::from_str(json).unwrap();
+        assert!(parsed.local_storage.is_empty());
+    }
+
+    #[test]
+    fn test_cookie_from_extension_export() {
+        let json = r#"[{
+            "name": "sid", "value": "abc", "domain": ".x.com", "path": "/",
+            "expirationDate": 1700000000.5, "httpOnly": true, "secure": true,
+            "sameSite": "no_restriction", "hostOnly": false, "session": false
+        }, {
+            "name": "pref", "value": "1", "domain": "x.com", "path": "/",
+            "sameSite": "unspecified", "session": true
+        }]"#;
+        let cookies: Vec<SerializableCookie> = serde_json::from_str(json).unwrap();
+
+        assert_eq!(cookies[0].expires, Some(1700000000.5));
+        assert!(cookies[0].http_only);
+        assert_eq!(cookies[0].same_site, Some(CookieSameSite::None));
+        assert_eq!(cookies[1].expires, None);
+        assert_eq!(cookies[1].same_site, None);
+
+        // Our own export round-trips
+        let json = serde_json::to_string(&cookies).unwrap();
+        let parsed: Vec<SerializableCookie> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed[0].same_site, Some(CookieSameSite::None));
+    }
+
     #[test]
     fn test_click_result() {
         let result = ClickResult {
             success: true,
             element: Some("button#submit".to_string()),
+            context_menu: None,
         };
 
         let json = serde_json::to_string(&result).unwrap();
@@ -298,6 +1426,7 @@ mod tests {
 
         assert!(parsed.success);
         assert_eq!(parsed.element, Some("button#submit".to_string()));
+        assert!(!json.contains("context_menu"));
     }
 
     #[test]
@@ -313,4 +1442,76 @@ mod tests {
         assert!(parsed.success);
         assert_eq!(parsed.value, "test@example.com");
     }
+
+    #[test]
+    fn test_status_filter_parse() {
+        assert_eq!(StatusFilter::parse("404"), Some(StatusFilter::Exact(404)));
+        assert_eq!(StatusFilter::parse("5xx"), Some(StatusFilter::Class(5)));
+        assert_eq!(StatusFilter::parse("4XX"), Some(StatusFilter::Class(4)));
+        assert_eq!(StatusFilter::parse("failed"), Some(StatusFilter::Failed));
+        assert_eq!(StatusFilter::parse("9xx"), None);
+        assert_eq!(StatusFilter::parse("ok"), None);
+    }
+
+    #[test]
+    fn test_element_bounds_from_js() {
+        let json = r#"{"x":10,"y":20.5,"width":100,"height":40,"top":20.5,"right":110,"bottom":60.5,"left":10,"in_viewport":true}"#;
+        let bounds: ElementBounds = serde_json::from_str(json).unwrap();
+
+        assert_eq!(bounds.y, 20.5);
+        assert_eq!(bounds.right, bounds.x + bounds.width);
+        assert!(bounds.in_viewport);
+    }
+
+    #[test]
+    fn test_viewport_size_parse() {
+        assert_eq!(
+            "1280x800".parse::<ViewportSize>().unwrap(),
+            ViewportSize::default()
+        );
+        assert_eq!(
+            "390X844".parse::<ViewportSize>().unwrap(),
+            ViewportSize {
+                width: 390,
+                height: 844
+            }
+        );
+        assert!("1280".parse::<ViewportSize>().is_err());
+        assert!("0x800".parse::<ViewportSize>().is_err());
+        assert!("widexhigh".parse::<ViewportSize>().is_err());
+    }
+
+    #[test]
+    fn test_browser_channel_parse() {
+        for name in BrowserChannel::NAMES {
+            assert_eq!(name.parse::<BrowserChannel>().unwrap().name(), *name);
+        }
+        assert_eq!(
+            "Chrome-Stable".parse::<BrowserChannel>().unwrap(),
+            BrowserChannel::Chrome
+        );
+        assert!("firefox".parse::<BrowserChannel>().is_err());
+    }
+
+    #[test]
+    fn test_frame_target_parse() {
+        assert_eq!(FrameTarget::parse("0"), Some(FrameTarget::Index(0)));
+        assert_eq!(FrameTarget::parse(" 2 "), Some(FrameTarget::Index(2)));
+        assert_eq!(
+            FrameTarget::parse("checkout"),
+            Some(FrameTarget::Url("checkout".to_string()))
+        );
+        assert_eq!(FrameTarget::parse(""), None);
+        assert_eq!(
+            FrameTarget::parse("checkout > 0"),
+            Some(FrameTarget::Path(vec![
+                FrameTarget::Url("checkout".to_string()),
+                FrameTarget::Index(0),
+            ]))
+        );
+        assert_eq!(
+            FrameTarget::parse("checkout >"),
+            Some(FrameTarget::Url("checkout".to_string()))
+        );
+    }
 }
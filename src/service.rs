@@ -7,6 +7,7 @@
 //!
 //! - **Launch mode** (`new()`): Spawns a new Chrome instance with isolated profile
 //! - **Connect mode** (`new_connect()`): Attaches to existing Chrome with user's sessions
+//! - **Client pool** (`with_client_pool()`): Calls with `endpoint` run against another Chrome
 //!
 //! # CHANGELOG (recent first, max 5 entries)
 //! 01/15/2026 - Added extension bridge routing for Chrome Extension API methods (Claude)
@@ -15,7 +16,10 @@
 //! 01/14/2026 - Initial implementation (Claude)
 
 use anyhow::{Context, Result};
+use chromiumoxide::cdp::browser_protocol::input::MouseButton;
+use chromiumoxide::layout::Point;
 use chrono::Utc;
+use futures::future::{BoxFuture, FutureExt};
 use fgp_daemon::schema::SchemaBuilder;
 use fgp_daemon::service::MethodInfo;
 use fgp_daemon::FgpService;
@@ -23,30 +27,360 @@ use serde_json::json;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::runtime::Runtime;
 use tokio::sync::RwLock;
 
-use crate::browser::BrowserClient;
+use crate::bidi::BidiTarget;
+use crate::cooldown::{LaunchCooldown, LAUNCH_COOLDOWN};
+use crate::browser::{
+    device_preset, device_preset_names, diff_aria_snapshots, network_profile,
+    network_profile_names, parse_connect_endpoint, parse_permission, parse_resource_type,
+    render_as_markdown, BrowserClient, SessionInUse, TabQuery, DEFAULT_CONSOLE_BUFFER,
+    DEFAULT_MAX_SESSIONS,
+};
 use crate::extension_bridge::{extension_method_name, is_extension_method, ExtensionBridge};
+use crate::lanes::SessionLanes;
 use crate::models::*;
 
+/// Last failed launch or connect, shared by the callers queued behind it.
+static LAUNCHES: LaunchCooldown = LaunchCooldown::new(LAUNCH_COOLDOWN);
+
+/// Default cap on bodies returned by `browser.network.body` (1 MiB).
+const DEFAULT_BODY_MAX_BYTES: u64 = 1024 * 1024;
+
+/// Default cap on bodies fetched by `browser.fetch` (50 MiB).
+const DEFAULT_FETCH_MAX_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Default cap on HTML returned by `browser.content` (10 MiB).
+const DEFAULT_CONTENT_MAX_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Id of the session calls use when they name none.
+const DEFAULT_SESSION: &str = "default";
+
+/// Default timeout for `browser.downloads.wait`.
+const DEFAULT_DOWNLOAD_TIMEOUT_MS: u64 = 30_000;
+
+/// Default limits for `browser.scroll_to_bottom`.
+const DEFAULT_MAX_SCROLLS: u32 = 50;
+const DEFAULT_SCROLL_SETTLE_MS: u64 = 1_000;
+const DEFAULT_SCROLL_TIMEOUT_MS: u64 = 30_000;
+
+/// Default age after which `browser.downloads.cleanup` removes files (7 days).
+const DEFAULT_DOWNLOAD_MAX_AGE_HOURS: u64 = 7 * 24;
+
+/// Settings every browser client the service creates starts with.
+#[derive(Debug, Clone, Copy)]
+struct ClientConfig {
+    /// Custom sessions allowed at once (0 = no limit)
+    max_sessions: usize,
+    /// Custom sessions idle this long are closed (0 = never)
+    idle_timeout_secs: u64,
+    /// Contexts kept ready for `session.new`
+    session_pool: usize,
+    /// Console entries each session keeps
+    console_buffer: usize,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            max_sessions: DEFAULT_MAX_SESSIONS,
+            idle_timeout_secs: 0,
+            session_pool: 0,
+            console_buffer: DEFAULT_CONSOLE_BUFFER,
+        }
+    }
+}
+
+impl ClientConfig {
+    async fn apply(&self, client: &BrowserClient) {
+        client.set_max_sessions(self.max_sessions);
+        client.set_idle_timeout(self.idle_timeout_secs);
+        client.set_session_pool(self.session_pool);
+        client.set_console_buffer(self.console_buffer).await;
+    }
+}
+
+/// Starts the browser clients a slot hands out.
+trait Launcher: Send + Sync {
+    /// Launch Chrome with the service's settings.
+    fn launch(
+        &self,
+        user_data_dir: PathBuf,
+        headless: bool,
+        viewport: ViewportSize,
+        channel: Option<BrowserChannel>,
+    ) -> BoxFuture<'static, Result<BrowserClient>>;
+
+    /// Connect to the Chrome at `url`.
+    fn connect(&self, url: &str) -> BoxFuture<'static, Result<BrowserClient>>;
+}
+
+/// Launches or connects to a real Chrome.
+struct ChromeLauncher;
+
+impl Launcher for ChromeLauncher {
+    fn launch(
+        &self,
+        user_data_dir: PathBuf,
+        headless: bool,
+        viewport: ViewportSize,
+        channel: Option<BrowserChannel>,
+    ) -> BoxFuture<'static, Result<BrowserClient>> {
+        BrowserClient::new(user_data_dir, headless, viewport, channel).boxed()
+    }
+
+    fn connect(&self, url: &str) -> BoxFuture<'static, Result<BrowserClient>> {
+        let url = url.to_string();
+        async move { BrowserClient::connect(&url).await }.boxed()
+    }
+}
+
+/// A browser client shared by calls (empty until the first call connects),
+/// and what a client that replaces it takes over. Kept here rather than on
+/// the client, so a failed relaunch doesn't lose it.
+pub struct ClientSlot {
+    client: RwLock<Option<Arc<BrowserClient>>>,
+    /// Starts the next client when there is none
+    launcher: Arc<dyn Launcher>,
+    /// Settings, including changes calls made since
+    config: Mutex<ClientConfig>,
+    restarts: Mutex<RestartHistory>,
+    /// The service's lanes for this client's sessions
+    in_use: Option<SessionInUse>,
+}
+
+impl Default for ClientSlot {
+    fn default() -> Self {
+        Self {
+            client: RwLock::default(),
+            launcher: Arc::new(ChromeLauncher),
+            config: Mutex::default(),
+            restarts: Mutex::default(),
+            in_use: None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct RestartHistory {
+    /// Times the browser was relaunched or reconnected
+    count: u32,
+    /// Custom sessions that died with the browser, until a new client
+    /// takes them over
+    lost_sessions: Option<Vec<String>>,
+}
+
+impl ClientSlot {
+    fn new(config: ClientConfig, in_use: Option<SessionInUse>) -> Self {
+        Self {
+            config: Mutex::new(config),
+            in_use,
+            ..Default::default()
+        }
+    }
+
+    /// A slot holding a client created up front, which already has the
+    /// default settings.
+    fn holding(client: BrowserClient, in_use: SessionInUse) -> Self {
+        client.set_session_in_use(in_use.clone());
+        Self {
+            client: RwLock::new(Some(Arc::new(client))),
+            in_use: Some(in_use),
+            ..Default::default()
+        }
+    }
+
+    /// Record that the client's browser died with `lost_sessions` open.
+    fn browser_died(&self, lost_sessions: Vec<String>) {
+        let mut restarts = self.restarts.lock().unwrap();
+        restarts.count += 1;
+        restarts
+            .lost_sessions
+            .get_or_insert_with(Vec::new)
+            .extend(lost_sessions);
+    }
+
+    /// Set up a client the slot is about to hold: the slot's settings, and
+    /// if it replaces one whose browser died, the restart and lost sessions.
+    async fn install(&self, client: BrowserClient) -> Arc<BrowserClient> {
+        let config = *self.config.lock().unwrap();
+        config.apply(&client).await;
+        if let Some(in_use) = &self.in_use {
+            client.set_session_in_use(in_use.clone());
+        }
+        let mut restarts = self.restarts.lock().unwrap();
+        if let Some(lost_sessions) = restarts.lost_sessions.take() {
+            client.mark_sessions_lost(lost_sessions);
+            client.mark_restarted(restarts.count);
+        }
+        Arc::new(client)
+    }
+
+    /// Browser restarts since the daemon started.
+    fn restart_count(&self) -> u32 {
+        self.restarts.lock().unwrap().count
+    }
+
+    /// The client, if one is connected or launched.
+    pub async fn current(&self) -> Option<Arc<BrowserClient>> {
+        self.client.read().await.clone()
+    }
+}
+
+/// The service's own browser for callers outside `dispatch` (BiDi): the
+/// slot, and what `get_or_init_client` needs to relaunch or reconnect it.
+#[derive(Clone)]
+pub struct ServiceClient {
+    slot: Arc<ClientSlot>,
+    user_data_dir: PathBuf,
+    headless: bool,
+    viewport: ViewportSize,
+    channel: Option<BrowserChannel>,
+    connect_url: Option<String>,
+}
+
+impl ServiceClient {
+    /// The client, launching or reconnecting Chrome like an FGP call would.
+    pub async fn get(&self) -> Result<Arc<BrowserClient>> {
+        BrowserService::get_or_init_client(
+            &self.slot,
+            &self.user_data_dir,
+            self.headless,
+            self.viewport,
+            self.channel,
+            self.connect_url.as_deref(),
+        )
+        .await
+    }
+
+    /// The client, if one is connected or launched.
+    pub async fn current(&self) -> Option<Arc<BrowserClient>> {
+        self.slot.current().await
+    }
+}
+
+/// Lane of `session` on a pooled `endpoint`, or on the service's own browser.
+fn session_lane(endpoint: Option<&str>, session: &str) -> String {
+    match endpoint {
+        Some(endpoint) => format!("{}#{}", endpoint, session),
+        None => session.to_string(),
+    }
+}
+
+/// Which sessions of the client for `endpoint` calls are using, for its
+/// idle cleanup.
+fn lanes_in_use(lanes: &Arc<SessionLanes>, endpoint: Option<&str>) -> SessionInUse {
+    let lanes = lanes.clone();
+    let endpoint = endpoint.map(str::to_string);
+    Arc::new(move |session| lanes.is_busy(&session_lane(endpoint.as_deref(), session)))
+}
+
+/// Connections to further Chrome instances, picked per call with `endpoint`.
+///
+/// Each endpoint URL gets its own client, connected on first use and
+/// reconnected like the service's own. A connection unused for longer than
+/// the TTL is closed the next time the pool is used.
+pub struct ClientPool {
+    ttl: Duration,
+    clients: Mutex<HashMap<String, PooledClient>>,
+}
+
+struct PooledClient {
+    slot: Arc<ClientSlot>,
+    last_used: Instant,
+}
+
+impl ClientPool {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The client for `endpoint` (empty if new, with `config`), plus the
+    /// clients idle past the TTL, which leave the pool. Clients a call still
+    /// holds are kept.
+    fn checkout(
+        &self,
+        endpoint: &str,
+        config: ClientConfig,
+        lanes: &Arc<SessionLanes>,
+    ) -> (Arc<ClientSlot>, Vec<(String, Arc<ClientSlot>)>) {
+        let mut clients = self.clients.lock().unwrap();
+        let now = Instant::now();
+        let stale: Vec<String> = clients
+            .iter()
+            .filter(|(url, pooled)| {
+                url.as_str() != endpoint
+                    && now.duration_since(pooled.last_used) >= self.ttl
+                    && Arc::strong_count(&pooled.slot) == 1
+            })
+            .map(|(url, _)| url.clone())
+            .collect();
+        let stale = stale
+            .into_iter()
+            .filter_map(|url| clients.remove(&url).map(|pooled| (url, pooled.slot)))
+            .collect();
+
+        let pooled = clients.entry(endpoint.to_string()).or_insert_with(|| PooledClient {
+            slot: Arc::new(ClientSlot::new(config, Some(lanes_in_use(lanes, Some(endpoint))))),
+            last_used: now,
+        });
+        pooled.last_used = now;
+        (pooled.slot.clone(), stale)
+    }
+
+    /// The client for `endpoint`, if the pool has one.
+    fn get(&self, endpoint: &str) -> Option<Arc<ClientSlot>> {
+        self.clients.lock().unwrap().get(endpoint).map(|pooled| pooled.slot.clone())
+    }
+
+    /// Endpoint URLs in the pool, sorted.
+    pub fn endpoints(&self) -> Vec<String> {
+        let mut endpoints: Vec<String> = self.clients.lock().unwrap().keys().cloned().collect();
+        endpoints.sort();
+        endpoints
+    }
+}
+
 /// Browser automation service.
 pub struct BrowserService {
     runtime: Runtime,
-    client: Arc<RwLock<Option<Arc<BrowserClient>>>>,
+    client: Arc<ClientSlot>,
+    /// Settings for clients the service creates, pooled ones included
+    config: ClientConfig,
     user_data_dir: PathBuf,
     auth_dir: PathBuf,
+    /// Named ARIA snapshots saved for `browser.snapshot_diff`
+    snapshot_dir: PathBuf,
     headless: bool,
+    /// Viewport new pages start with (launch mode only)
+    viewport: ViewportSize,
+    /// System browser to launch instead of the bundled one (launch mode only)
+    channel: Option<BrowserChannel>,
     /// If Some, connect to existing Chrome instead of launching
     connect_url: Option<String>,
     /// Optional extension bridge for Chrome Extension API methods
     extension_bridge: Option<Arc<ExtensionBridge>>,
+    /// If Some, calls with `endpoint` use a pooled connection to that Chrome
+    client_pool: Option<ClientPool>,
+    /// When the service was created, for `uptime_secs` in health
+    started_at: Instant,
+    /// Keeps each session's calls in order while sessions run concurrently
+    lanes: Arc<SessionLanes>,
 }
 
 impl BrowserService {
     /// Create a new browser service with pre-warmed browser.
-    pub fn new(headless: bool) -> Result<Self> {
+    pub fn new(
+        headless: bool,
+        viewport: ViewportSize,
+        channel: Option<BrowserChannel>,
+    ) -> Result<Self> {
         let runtime = Runtime::new().context("Failed to create tokio runtime")?;
 
         let base_dir = dirs::home_dir()
@@ -57,36 +391,104 @@ impl BrowserService {
 
         let user_data_dir = base_dir.join("user-data");
         let auth_dir = base_dir.join("auth");
+        let snapshot_dir = base_dir.join("snapshots");
 
         // Create directories
         std::fs::create_dir_all(&user_data_dir)?;
         std::fs::create_dir_all(&auth_dir)?;
+        std::fs::create_dir_all(&snapshot_dir)?;
 
         // Pre-warm browser for instant response on first request
         let client = runtime.block_on(async {
             tracing::info!("Pre-warming browser...");
-            BrowserClient::new(user_data_dir.clone(), headless).await
+            BrowserClient::new(user_data_dir.clone(), headless, viewport, channel).await
         })?;
 
         tracing::info!("Browser pre-warmed and ready");
+        let lanes = Arc::<SessionLanes>::default();
 
         Ok(Self {
             runtime,
-            client: Arc::new(RwLock::new(Some(Arc::new(client)))),
+            client: Arc::new(ClientSlot::holding(client, lanes_in_use(&lanes, None))),
+            config: ClientConfig::default(),
             user_data_dir,
             auth_dir,
+            snapshot_dir,
             headless,
+            viewport,
+            channel,
             connect_url: None,
             extension_bridge: None,
+            client_pool: None,
+            started_at: Instant::now(),
+            lanes,
         })
     }
 
+    /// Set how many console entries each session keeps.
+    pub fn with_console_buffer(mut self, size: usize) -> Self {
+        self.config.console_buffer = size;
+        self.configure()
+    }
+
+    /// Close custom sessions unused for `secs` seconds (0 = never).
+    pub fn with_session_idle_timeout(mut self, secs: u64) -> Self {
+        self.config.idle_timeout_secs = secs;
+        self.configure()
+    }
+
+    /// Allow at most `max` custom sessions at once (0 = no limit).
+    pub fn with_max_sessions(mut self, max: usize) -> Self {
+        self.config.max_sessions = max;
+        self.configure()
+    }
+
+    /// Keep `size` browser contexts ready so `session.new` doesn't wait for one.
+    pub fn with_session_pool(mut self, size: usize) -> Self {
+        self.config.session_pool = size;
+        self.configure()
+    }
+
+    /// Give the service's client `config`; clients created later (after a
+    /// restart, or for an `endpoint`) get it from their slot.
+    fn configure(self) -> Self {
+        let (slot, config) = (self.client.clone(), self.config);
+        *slot.config.lock().unwrap() = config;
+        self.runtime.block_on(async {
+            if let Some(client) = slot.client.read().await.as_ref() {
+                config.apply(client).await;
+            }
+        });
+        self
+    }
+
     /// Set the extension bridge for routing extension methods
     pub fn with_extension_bridge(mut self, bridge: Arc<ExtensionBridge>) -> Self {
         self.extension_bridge = Some(bridge);
         self
     }
 
+    /// Let calls pick another Chrome with `endpoint`, closing connections
+    /// unused for `ttl`.
+    pub fn with_client_pool(mut self, ttl: Duration) -> Self {
+        self.client_pool = Some(ClientPool::new(ttl));
+        self
+    }
+
+    /// Handle for serving WebDriver BiDi commands against this service's
+    /// browser, in the same session lanes as FGP calls
+    pub fn bidi_target(&self) -> BidiTarget {
+        let client = ServiceClient {
+            slot: self.client.clone(),
+            user_data_dir: self.user_data_dir.clone(),
+            headless: self.headless,
+            viewport: self.viewport,
+            channel: self.channel,
+            connect_url: self.connect_url.clone(),
+        };
+        BidiTarget::new(client, self.lanes.clone(), self.runtime.handle().clone())
+    }
+
     /// Create a browser service that connects to user's existing Chrome.
     ///
     /// This mode attaches to a Chrome instance running with `--remote-debugging-port`.
@@ -94,6 +496,7 @@ impl BrowserService {
     ///
     /// # Arguments
     /// * `connect_url` - Chrome debugging URL (e.g., "http://localhost:9222")
+    ///   or DevTools WebSocket URL (e.g., "ws://chrome:9222/devtools/browser/<id>")
     pub fn new_connect(connect_url: &str) -> Result<Self> {
         let runtime = Runtime::new().context("Failed to create tokio runtime")?;
 
@@ -105,9 +508,11 @@ impl BrowserService {
 
         let user_data_dir = base_dir.join("user-data");
         let auth_dir = base_dir.join("auth");
+        let snapshot_dir = base_dir.join("snapshots");
 
-        // Create directories (for auth state storage)
+        // Create directories (for auth state and snapshot storage)
         std::fs::create_dir_all(&auth_dir)?;
+        std::fs::create_dir_all(&snapshot_dir)?;
 
         // Connect to existing Chrome
         let url = connect_url.to_string();
@@ -117,15 +522,23 @@ impl BrowserService {
         })?;
 
         tracing::info!("Connected to user's Chrome - sessions available!");
+        let lanes = Arc::<SessionLanes>::default();
 
         Ok(Self {
             runtime,
-            client: Arc::new(RwLock::new(Some(Arc::new(client)))),
+            client: Arc::new(ClientSlot::holding(client, lanes_in_use(&lanes, None))),
+            config: ClientConfig::default(),
             user_data_dir,
             auth_dir,
+            snapshot_dir,
             headless: false, // User's browser is always headed
+            viewport: ViewportSize::default(),
+            channel: None,
             connect_url: Some(connect_url.to_string()),
             extension_bridge: None,
+            client_pool: None,
+            started_at: Instant::now(),
+            lanes,
         })
     }
 
@@ -141,26 +554,194 @@ impl BrowserService {
             .map(|s| s.to_string())
     }
 
+    /// Pooled endpoint a call targets: its `endpoint` URL, normalized, unless
+    /// that's the Chrome the service itself is connected to.
+    fn get_endpoint(&self, params: &HashMap<String, Value>) -> Result<Option<String>> {
+        let Some(endpoint) = params.get("endpoint").and_then(|v| v.as_str()) else {
+            return Ok(None);
+        };
+        Self::pooled_endpoint(endpoint, self.connect_url.as_deref())
+    }
+
+    /// `endpoint` as pooled and laned, or None when it's the same Chrome as
+    /// `own`, the service's connect URL.
+    fn pooled_endpoint(endpoint: &str, own: Option<&str>) -> Result<Option<String>> {
+        let url = Self::endpoint_key(endpoint)?;
+        let own = own.map(Self::endpoint_key).transpose()?;
+        Ok((own.as_deref() != Some(url.as_str())).then_some(url))
+    }
+
+    /// Parsed endpoint URL; an http one without its trailing slash, so
+    /// `http://localhost:9222` and `http://localhost:9222/` are one Chrome.
+    fn endpoint_key(endpoint: &str) -> Result<String> {
+        let url = parse_connect_endpoint(endpoint)?.url().to_string();
+        if url.starts_with("http://") || url.starts_with("https://") {
+            return Ok(url.trim_end_matches('/').to_string());
+        }
+        Ok(url)
+    }
+
+    /// Client slot and connect URL for a call: a pooled connection when it
+    /// names an `endpoint`, otherwise the service's own browser.
+    fn client_for(&self, params: &HashMap<String, Value>) -> Result<(Arc<ClientSlot>, Option<String>)> {
+        let Some(url) = self.get_endpoint(params)? else {
+            return Ok((self.client.clone(), self.connect_url.clone()));
+        };
+        let pool = self.client_pool.as_ref().context(
+            "'endpoint' needs the client pool; start the daemon with --client-pool-ttl",
+        )?;
+
+        let (slot, stale) = pool.checkout(&url, self.config, &self.lanes);
+        for (stale_url, stale_slot) in stale {
+            self.runtime.block_on(async {
+                if let Some(client) = stale_slot.client.write().await.take() {
+                    tracing::info!("Closing idle pooled connection to {}", stale_url);
+                    client.dispose().await;
+                }
+            });
+        }
+        Ok((slot, Some(url)))
+    }
+
+    /// Lane a call waits in: its session, or for `session.new`, `attach` and
+    /// `close`, the session named by `id`, and for `session.clone`, the
+    /// source session. Browser-wide reads skip the lanes. Sessions on a
+    /// pooled `endpoint` (as returned by `get_endpoint`) get lanes of their own.
+    fn lane_key(method: &str, params: &HashMap<String, Value>, endpoint: Option<&str>) -> Option<String> {
+        let key = Self::session_lane_key(method, params)?;
+        Some(session_lane(endpoint, &key))
+    }
+
+    fn session_lane_key(method: &str, params: &HashMap<String, Value>) -> Option<String> {
+        match method {
+            "health" | "browser.session.list" | "session.list" => None,
+            "browser.session.clone" | "session.clone" => {
+                params.get("source_id").and_then(|v| v.as_str()).map(|s| s.to_string())
+            }
+            "browser.session.new" | "session.new" | "browser.session.attach" | "session.attach"
+            | "browser.session.close" | "session.close" => params
+                .get("id")
+                .or_else(|| params.get("session_id"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            _ => Some(Self::get_session_id(params).unwrap_or_else(|| DEFAULT_SESSION.to_string())),
+        }
+    }
+
+    /// `same_origin_only` flag, also accepted as `same_origin`.
+    fn get_same_origin_only(params: &HashMap<String, Value>) -> bool {
+        params
+            .get("same_origin_only")
+            .or_else(|| params.get("same_origin"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// Optional iframe target: an index (number or numeric string), or a URL pattern / frame name.
+    fn get_frame(params: &HashMap<String, Value>) -> Option<FrameTarget> {
+        Self::parse_frame(params.get("frame")?)
+    }
+
+    /// A frame index, name / URL pattern, or an array of those for nested frames.
+    fn parse_frame(value: &Value) -> Option<FrameTarget> {
+        match value {
+            Value::Number(n) => n.as_u64().map(|i| FrameTarget::Index(i as usize)),
+            Value::String(s) => FrameTarget::parse(s),
+            Value::Array(steps) => {
+                FrameTarget::path(steps.iter().filter_map(Self::parse_frame).collect())
+            }
+            _ => None,
+        }
+    }
+
+    /// Optional header map (names to values); non-string values are stringified.
+    fn get_headers(
+        params: &HashMap<String, Value>,
+        key: &str,
+    ) -> Result<Option<HashMap<String, String>>> {
+        match params.get(key) {
+            Some(Value::Object(map)) => Ok(Some(
+                map.iter()
+                    .map(|(name, value)| {
+                        let value = match value {
+                            Value::String(s) => s.clone(),
+                            other => other.to_string(),
+                        };
+                        (name.clone(), value)
+                    })
+                    .collect(),
+            )),
+            Some(Value::Null) | None => Ok(None),
+            Some(_) => anyhow::bail!("'{}' must be an object of header names to values", key),
+        }
+    }
+
     async fn get_or_init_client(
-        client: &Arc<RwLock<Option<Arc<BrowserClient>>>>,
+        slot: &ClientSlot,
         user_data_dir: &Path,
         headless: bool,
+        viewport: ViewportSize,
+        channel: Option<BrowserChannel>,
         connect_url: Option<&str>,
     ) -> Result<Arc<BrowserClient>> {
-        if let Some(existing) = client.read().await.as_ref() {
-            return Ok(Arc::clone(existing));
+        // In connect mode the user may quit Chrome at any time, so probe the connection.
+        // In launch mode the handler task clears the flag if Chrome crashes.
+        if let Some(existing) = slot.client.read().await.as_ref() {
+            if existing.is_connected(connect_url.is_some()).await {
+                return Ok(Arc::clone(existing));
+            }
+        }
+
+        let mut client_lock = slot.client.write().await;
+        let mut reconnected = None;
+        let disconnected = match client_lock.as_ref() {
+            Some(existing) => !existing.is_connected(false).await,
+            None => false,
+        };
+        if disconnected {
+            tracing::warn!("Lost connection to Chrome; dropping the browser client");
+            // Custom sessions die with the browser; report them instead of recreating them
+            if let Some(existing) = client_lock.take() {
+                slot.browser_died(existing.custom_session_ids().await);
+                reconnected = existing.take_reconnected();
+                existing.dispose().await;
+            }
+            if connect_url.is_none() {
+                tracing::warn!("Chrome exited; relaunching on this request");
+            }
         }
 
-        let mut client_lock = client.write().await;
+        // Callers queued on the lock get a failed launch's error instead of
+        // launching again, until the cooldown ends
         if client_lock.is_none() {
-            let new_client = if let Some(url) = connect_url {
+            let new_client = match (reconnected, connect_url) {
+                // The health task reconnected already
+                (Some(reconnected), Some(url)) => {
+                    tracing::info!("Switching to the reconnected Chrome at {}", url);
+                    reconnected
+                }
+                // One attempt, and if Chrome is still gone, report it plainly
+                (_, Some(url)) if disconnected => {
+                    tracing::warn!("Reconnecting to Chrome at {}", url);
+                    LAUNCHES.launch(url, slot.launcher.connect(url)).await.map_err(|e| {
+                        anyhow::anyhow!(
+                            "BROWSER_DISCONNECTED: lost connection to Chrome at {} and reconnecting failed ({:#}). \
+                             Restart Chrome with --remote-debugging-port; the next call reconnects.",
+                            url,
+                            e
+                        )
+                    })?
+                }
                 // Connect mode: attach to existing Chrome
-                BrowserClient::connect(url).await?
-            } else {
+                (_, Some(url)) => LAUNCHES.launch(url, slot.launcher.connect(url)).await?,
                 // Launch mode: spawn new Chrome
-                BrowserClient::new(user_data_dir.to_path_buf(), headless).await?
+                (_, None) => {
+                    LAUNCHES
+                        .launch("", slot.launcher.launch(user_data_dir.to_path_buf(), headless, viewport, channel))
+                        .await?
+                }
             };
-            *client_lock = Some(Arc::new(new_client));
+            *client_lock = Some(slot.install(new_client).await);
         }
 
         client_lock
@@ -176,14 +757,15 @@ impl BrowserService {
             .context("Missing 'url' parameter")?;
         let session_id = Self::get_session_id(&params);
 
-        let client = self.client.clone();
+        let (client, connect_url) = self.client_for(&params)?;
         let user_data_dir = self.user_data_dir.clone();
         let headless = self.headless;
-        let connect_url = self.connect_url.clone();
+        let viewport = self.viewport;
+        let channel = self.channel;
 
         let result = self.runtime.block_on(async {
             let browser_client =
-                Self::get_or_init_client(&client, &user_data_dir, headless, connect_url.as_deref()).await?;
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
             browser_client.navigate(url, session_id.as_deref()).await
         })?;
 
@@ -191,1054 +773,7028 @@ impl BrowserService {
     }
 
     fn handle_snapshot(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let max_depth = params
+            .get("snapshot_depth")
+            .and_then(|v| v.as_u64())
+            .map(|d| d as usize);
+        let include_frames = params
+            .get("include_frames")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let interactive_only = params
+            .get("interactive_only")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
         let session_id = Self::get_session_id(&params);
-        let client = self.client.clone();
+        let (client, connect_url) = self.client_for(&params)?;
         let user_data_dir = self.user_data_dir.clone();
         let headless = self.headless;
-        let connect_url = self.connect_url.clone();
+        let viewport = self.viewport;
+        let channel = self.channel;
 
         let result = self.runtime.block_on(async {
             let browser_client =
-                Self::get_or_init_client(&client, &user_data_dir, headless, connect_url.as_deref()).await?;
-            browser_client.snapshot(session_id.as_deref()).await
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .snapshot(
+                    max_depth,
+                    include_frames,
+                    interactive_only,
+                    session_id.as_deref(),
+                )
+                .await
         })?;
 
         Ok(serde_json::to_value(result)?)
     }
 
-    fn handle_screenshot(&self, params: HashMap<String, Value>) -> Result<Value> {
-        let path = params.get("path").and_then(|v| v.as_str());
+    fn handle_snapshot_markdown(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let max_depth = params
+            .get("snapshot_depth")
+            .and_then(|v| v.as_u64())
+            .map(|d| d as usize);
+        let include_frames = params
+            .get("include_frames")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let interactive_only = params
+            .get("interactive_only")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
         let session_id = Self::get_session_id(&params);
-
-        let client = self.client.clone();
+        let (client, connect_url) = self.client_for(&params)?;
         let user_data_dir = self.user_data_dir.clone();
         let headless = self.headless;
-        let connect_url = self.connect_url.clone();
+        let viewport = self.viewport;
+        let channel = self.channel;
 
-        let result = self.runtime.block_on(async {
+        let snapshot = self.runtime.block_on(async {
             let browser_client =
-                Self::get_or_init_client(&client, &user_data_dir, headless, connect_url.as_deref()).await?;
-            browser_client.screenshot(path, session_id.as_deref()).await
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .snapshot(
+                    max_depth,
+                    include_frames,
+                    interactive_only,
+                    session_id.as_deref(),
+                )
+                .await
         })?;
 
-        Ok(serde_json::to_value(result)?)
+        Ok(serde_json::json!({
+            "url": snapshot.url,
+            "title": snapshot.title,
+            "markdown": render_as_markdown(&snapshot.nodes),
+            "element_count": snapshot.element_count,
+        }))
     }
 
-    fn handle_click(&self, params: HashMap<String, Value>) -> Result<Value> {
-        let selector = params
-            .get("selector")
+    /// File a named snapshot is stored in.
+    fn snapshot_path(&self, name: &str) -> Result<PathBuf> {
+        if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+            anyhow::bail!("Invalid snapshot name '{}'", name);
+        }
+        Ok(self.snapshot_dir.join(format!("{}.json", name)))
+    }
+
+    fn read_snapshot(&self, name: &str) -> Result<SavedSnapshot> {
+        let path = self.snapshot_path(name)?;
+        if !path.exists() {
+            anyhow::bail!("Snapshot '{}' not found", name);
+        }
+        let bytes = std::fs::read(&path)?;
+        serde_json::from_slice(&bytes).with_context(|| format!("Snapshot '{}' is corrupt", name))
+    }
+
+    fn handle_snapshot_save(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let name = params
+            .get("name")
             .and_then(|v| v.as_str())
-            .context("Missing 'selector' parameter")?;
+            .context("Missing 'name' parameter")?
+            .to_string();
+        let path = self.snapshot_path(&name)?;
         let session_id = Self::get_session_id(&params);
 
-        let client = self.client.clone();
+        let (client, connect_url) = self.client_for(&params)?;
         let user_data_dir = self.user_data_dir.clone();
         let headless = self.headless;
-        let connect_url = self.connect_url.clone();
-        let selector = selector.to_string();
+        let viewport = self.viewport;
+        let channel = self.channel;
 
-        let result = self.runtime.block_on(async {
+        let snapshot = self.runtime.block_on(async {
             let browser_client =
-                Self::get_or_init_client(&client, &user_data_dir, headless, connect_url.as_deref()).await?;
-            browser_client.click(&selector, session_id.as_deref()).await
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .snapshot(None, false, false, session_id.as_deref())
+                .await
         })?;
 
-        Ok(serde_json::to_value(result)?)
+        let saved = SavedSnapshot {
+            name,
+            saved_at: Utc::now().to_rfc3339(),
+            snapshot,
+        };
+        std::fs::write(&path, serde_json::to_vec_pretty(&saved)?)?;
+
+        Ok(serde_json::json!({
+            "name": saved.name,
+            "path": path.to_string_lossy(),
+            "url": saved.snapshot.url,
+            "element_count": saved.snapshot.element_count,
+            "saved_at": saved.saved_at,
+        }))
     }
 
-    fn handle_fill(&self, params: HashMap<String, Value>) -> Result<Value> {
-        let selector = params
-            .get("selector")
+    fn handle_snapshot_load(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let name = params
+            .get("name")
             .and_then(|v| v.as_str())
-            .context("Missing 'selector' parameter")?;
-        let value = params
-            .get("value")
+            .context("Missing 'name' parameter")?;
+
+        Ok(serde_json::to_value(self.read_snapshot(name)?)?)
+    }
+
+    fn handle_snapshot_diff(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let before_name = params
+            .get("before_snapshot")
             .and_then(|v| v.as_str())
-            .context("Missing 'value' parameter")?;
+            .context("Missing 'before_snapshot' parameter")?;
+        let before = self.read_snapshot(before_name)?;
+
+        let after = match params.get("after_snapshot").and_then(|v| v.as_str()) {
+            Some(name) => self.read_snapshot(name)?.snapshot,
+            None => {
+                let session_id = Self::get_session_id(&params);
+                let (client, connect_url) = self.client_for(&params)?;
+                let user_data_dir = self.user_data_dir.clone();
+                let headless = self.headless;
+                let viewport = self.viewport;
+                let channel = self.channel;
+
+                self.runtime.block_on(async {
+                    let browser_client =
+                        Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+                    browser_client
+                        .snapshot(None, false, false, session_id.as_deref())
+                        .await
+                })?
+            }
+        };
+
+        let diff = diff_aria_snapshots(&before.snapshot.nodes, &after.nodes);
+        Ok(serde_json::json!({
+            "before_url": before.snapshot.url,
+            "after_url": after.url,
+            "added": diff.added,
+            "removed": diff.removed,
+            "changed": diff.changed,
+        }))
+    }
+
+    fn handle_screenshot(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let path = params.get("path").and_then(|v| v.as_str());
+        let format = Self::parse_screenshot_format(
+            params.get("format").and_then(|v| v.as_str()),
+            params.get("quality").and_then(|v| v.as_i64()),
+        )?;
         let session_id = Self::get_session_id(&params);
 
-        let client = self.client.clone();
+        let (client, connect_url) = self.client_for(&params)?;
         let user_data_dir = self.user_data_dir.clone();
         let headless = self.headless;
-        let connect_url = self.connect_url.clone();
-        let selector = selector.to_string();
-        let value = value.to_string();
+        let viewport = self.viewport;
+        let channel = self.channel;
 
         let result = self.runtime.block_on(async {
             let browser_client =
-                Self::get_or_init_client(&client, &user_data_dir, headless, connect_url.as_deref()).await?;
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
             browser_client
-                .fill(&selector, &value, session_id.as_deref())
+                .screenshot(path, format, session_id.as_deref())
                 .await
         })?;
 
         Ok(serde_json::to_value(result)?)
     }
 
-    fn handle_press(&self, params: HashMap<String, Value>) -> Result<Value> {
-        let key = params
-            .get("key")
+    fn handle_screenshot_element(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let selector = params
+            .get("selector")
             .and_then(|v| v.as_str())
-            .context("Missing 'key' parameter")?;
+            .context("Missing 'selector' parameter")?;
+        let path = params.get("path").and_then(|v| v.as_str());
+        let format = Self::parse_screenshot_format(
+            params.get("format").and_then(|v| v.as_str()),
+            params.get("quality").and_then(|v| v.as_i64()),
+        )?;
         let session_id = Self::get_session_id(&params);
 
-        let client = self.client.clone();
+        let (client, connect_url) = self.client_for(&params)?;
         let user_data_dir = self.user_data_dir.clone();
         let headless = self.headless;
-        let connect_url = self.connect_url.clone();
-        let key = key.to_string();
+        let viewport = self.viewport;
+        let channel = self.channel;
 
-        self.runtime.block_on(async {
+        let result = self.runtime.block_on(async {
             let browser_client =
-                Self::get_or_init_client(&client, &user_data_dir, headless, connect_url.as_deref()).await?;
-            browser_client.press(&key, session_id.as_deref()).await
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .screenshot_element(selector, path, format, session_id.as_deref())
+                .await
         })?;
 
-        Ok(serde_json::json!({"success": true}))
+        Ok(serde_json::to_value(result)?)
     }
 
-    fn handle_state_save(&self, params: HashMap<String, Value>) -> Result<Value> {
-        let name = params
-            .get("name")
-            .and_then(|v| v.as_str())
-            .context("Missing 'name' parameter")?;
+    fn handle_frames(&self, params: HashMap<String, Value>) -> Result<Value> {
         let session_id = Self::get_session_id(&params);
 
-        let state_path = self.auth_dir.join(format!("{}.json", name));
-        let client = self.client.clone();
+        let (client, connect_url) = self.client_for(&params)?;
         let user_data_dir = self.user_data_dir.clone();
         let headless = self.headless;
-        let connect_url = self.connect_url.clone();
+        let viewport = self.viewport;
+        let channel = self.channel;
 
-        let state = self.runtime.block_on(async {
+        let frames = self.runtime.block_on(async {
             let browser_client =
-                Self::get_or_init_client(&client, &user_data_dir, headless, connect_url.as_deref()).await?;
-            let cookies = browser_client.get_cookies(session_id.as_deref()).await?;
-            let local_storage = browser_client
-                .get_local_storage(session_id.as_deref())
-                .await?;
-            Ok::<AuthState, anyhow::Error>(AuthState {
-                cookies,
-                local_storage,
-                saved_at: Utc::now().to_rfc3339(),
-            })
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.frames(session_id.as_deref()).await
         })?;
 
-        let serialized = serde_json::to_vec_pretty(&state)?;
-        std::fs::write(&state_path, serialized)?;
-
         Ok(serde_json::json!({
-            "success": true,
-            "path": state_path.to_string_lossy()
+            "count": frames.len(),
+            "frames": frames,
         }))
     }
 
-    fn handle_state_load(&self, params: HashMap<String, Value>) -> Result<Value> {
-        let name = params
-            .get("name")
+    fn handle_get_html(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let selector = params
+            .get("selector")
             .and_then(|v| v.as_str())
-            .context("Missing 'name' parameter")?;
+            .map(|s| s.to_string());
+        let inner = params
+            .get("inner")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
         let session_id = Self::get_session_id(&params);
 
-        let state_path = self.auth_dir.join(format!("{}.json", name));
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
 
-        if !state_path.exists() {
-            anyhow::bail!("State '{}' not found", name);
-        }
-
-        let state_bytes = std::fs::read(&state_path)?;
-        let state: AuthState = serde_json::from_slice(&state_bytes)?;
-
-        let client = self.client.clone();
-        let user_data_dir = self.user_data_dir.clone();
-        let headless = self.headless;
-        let connect_url = self.connect_url.clone();
-
-        self.runtime.block_on(async {
+        let html = self.runtime.block_on(async {
             let browser_client =
-                Self::get_or_init_client(&client, &user_data_dir, headless, connect_url.as_deref()).await?;
-            browser_client
-                .set_cookies(&state.cookies, session_id.as_deref())
-                .await?;
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
             browser_client
-                .set_local_storage(&state.local_storage, session_id.as_deref())
-                .await?;
-            Ok::<(), anyhow::Error>(())
+                .get_html(selector.as_deref(), inner, session_id.as_deref())
+                .await
         })?;
 
-        Ok(serde_json::json!({
-            "success": true,
-            "name": name
-        }))
+        Ok(serde_json::to_value(HtmlResult {
+            length: html.len(),
+            html,
+        })?)
     }
 
-    fn handle_state_list(&self, _params: HashMap<String, Value>) -> Result<Value> {
-        let mut states = Vec::new();
-
-        if self.auth_dir.exists() {
-            for entry in std::fs::read_dir(&self.auth_dir)? {
-                let entry = entry?;
-                let path = entry.path();
-                if path.extension().map(|e| e == "json").unwrap_or(false) {
-                    if let Some(stem) = path.file_stem() {
-                        let name = stem.to_string_lossy().to_string();
-                        let mut domains = Vec::new();
-                        let mut saved_at = String::new();
-
-                        if let Ok(contents) = std::fs::read(&path) {
-                            if let Ok(state) = serde_json::from_slice::<AuthState>(&contents) {
-                                let mut seen = std::collections::HashSet::new();
-                                for cookie in state.cookies {
-                                    if seen.insert(cookie.domain.clone()) {
-                                        domains.push(cookie.domain);
-                                    }
-                                }
-                                saved_at = state.saved_at;
-                            }
-                        }
-
-                        states.push(SavedState {
-                            name,
-                            domains,
-                            saved_at,
-                        });
-                    }
-                }
-            }
-        }
-
-        Ok(serde_json::to_value(states)?)
-    }
+    fn handle_content(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let selector = params
+            .get("selector")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let max_bytes = params
+            .get("max_bytes")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_CONTENT_MAX_BYTES) as usize;
+        let session_id = Self::get_session_id(&params);
 
-    fn handle_health(&self, _params: HashMap<String, Value>) -> Result<Value> {
-        let client = self.client.clone();
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
 
-        let healthy = self.runtime.block_on(async {
-            let client_lock = client.read().await;
-            if let Some(ref browser_client) = *client_lock {
-                browser_client.health_check().await.unwrap_or(false)
-            } else {
-                true // No browser yet is OK
-            }
-        });
+        let content = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .content(selector.as_deref(), max_bytes, session_id.as_deref())
+                .await
+        })?;
 
-        Ok(serde_json::json!({
-            "healthy": healthy,
-            "service": "browser",
-            "version": env!("CARGO_PKG_VERSION")
-        }))
+        Ok(serde_json::to_value(content)?)
     }
 
-    // =========================================================================
-    // SESSION MANAGEMENT HANDLERS
-    // =========================================================================
-
-    fn handle_session_new(&self, params: HashMap<String, Value>) -> Result<Value> {
-        let session_id = params
-            .get("id")
-            .or_else(|| params.get("session_id"))
+    fn handle_get_text(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let selector = params
+            .get("selector")
             .and_then(|v| v.as_str())
-            .context("Missing 'id' parameter")?;
+            .map(|s| s.to_string());
+        let frame = Self::get_frame(&params);
+        let session_id = Self::get_session_id(&params);
 
-        let client = self.client.clone();
+        let (client, connect_url) = self.client_for(&params)?;
         let user_data_dir = self.user_data_dir.clone();
         let headless = self.headless;
-        let connect_url = self.connect_url.clone();
+        let viewport = self.viewport;
+        let channel = self.channel;
 
-        let id = self.runtime.block_on(async {
+        let text = self.runtime.block_on(async {
             let browser_client =
-                Self::get_or_init_client(&client, &user_data_dir, headless, connect_url.as_deref()).await?;
-            browser_client.create_session(session_id).await
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .get_text(selector.as_deref(), frame.as_ref(), session_id.as_deref())
+                .await
         })?;
 
         Ok(serde_json::json!({
-            "success": true,
-            "session_id": id
-        }))
-    }
-
-    fn handle_session_list(&self, _params: HashMap<String, Value>) -> Result<Value> {
-        let client = self.client.clone();
-
-        let sessions = self.runtime.block_on(async {
-            let client_lock = client.read().await;
-            if let Some(ref browser_client) = *client_lock {
-                browser_client.list_sessions().await
-            } else {
-                vec![]
-            }
-        });
-
-        Ok(serde_json::json!({
-            "sessions": sessions
+            "length": text.chars().count(),
+            "text": text,
         }))
     }
 
-    fn handle_session_close(&self, params: HashMap<String, Value>) -> Result<Value> {
-        let session_id = params
-            .get("id")
-            .or_else(|| params.get("session_id"))
-            .and_then(|v| v.as_str())
-            .context("Missing 'id' parameter")?;
+    fn handle_extract_content(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = Self::get_session_id(&params);
 
-        let client = self.client.clone();
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
 
-        self.runtime.block_on(async {
-            let client_lock = client.read().await;
-            if let Some(ref browser_client) = *client_lock {
-                browser_client.close_session(session_id).await
-            } else {
-                Ok(())
-            }
+        let result = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.extract_content(session_id.as_deref()).await
         })?;
 
-        Ok(serde_json::json!({
-            "success": true,
-            "session_id": session_id
-        }))
+        Ok(serde_json::to_value(result)?)
     }
 
-    // =========================================================================
-    // FEATURE PARITY HANDLERS
-    // =========================================================================
-
-    fn handle_select(&self, params: HashMap<String, Value>) -> Result<Value> {
+    fn handle_extract_links(&self, params: HashMap<String, Value>) -> Result<Value> {
         let selector = params
             .get("selector")
             .and_then(|v| v.as_str())
-            .context("Missing 'selector' parameter")?;
-        let value = params
-            .get("value")
-            .and_then(|v| v.as_str())
-            .context("Missing 'value' parameter")?;
+            .map(|s| s.to_string());
+        let same_origin_only = Self::get_same_origin_only(&params);
         let session_id = Self::get_session_id(&params);
 
-        let client = self.client.clone();
+        let (client, connect_url) = self.client_for(&params)?;
         let user_data_dir = self.user_data_dir.clone();
         let headless = self.headless;
-        let connect_url = self.connect_url.clone();
-        let selector = selector.to_string();
-        let value = value.to_string();
+        let viewport = self.viewport;
+        let channel = self.channel;
 
-        self.runtime.block_on(async {
+        let links = self.runtime.block_on(async {
             let browser_client =
-                Self::get_or_init_client(&client, &user_data_dir, headless, connect_url.as_deref()).await?;
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
             browser_client
-                .select(&selector, &value, session_id.as_deref())
+                .extract_links(selector.as_deref(), same_origin_only, session_id.as_deref())
                 .await
         })?;
 
         Ok(serde_json::json!({
-            "success": true,
-            "selector": selector,
-            "value": value
+            "count": links.len(),
+            "links": links
         }))
     }
 
-    fn handle_check(&self, params: HashMap<String, Value>) -> Result<Value> {
+    fn handle_extract_images(&self, params: HashMap<String, Value>) -> Result<Value> {
         let selector = params
             .get("selector")
             .and_then(|v| v.as_str())
-            .context("Missing 'selector' parameter")?;
-        let checked = params
-            .get("checked")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(true); // Default to checking
+            .map(|s| s.to_string());
+        let same_origin_only = Self::get_same_origin_only(&params);
         let session_id = Self::get_session_id(&params);
 
-        let client = self.client.clone();
+        let (client, connect_url) = self.client_for(&params)?;
         let user_data_dir = self.user_data_dir.clone();
         let headless = self.headless;
-        let connect_url = self.connect_url.clone();
-        let selector = selector.to_string();
+        let viewport = self.viewport;
+        let channel = self.channel;
 
-        self.runtime.block_on(async {
+        let images = self.runtime.block_on(async {
             let browser_client =
-                Self::get_or_init_client(&client, &user_data_dir, headless, connect_url.as_deref()).await?;
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
             browser_client
-                .check(&selector, checked, session_id.as_deref())
+                .extract_images(selector.as_deref(), same_origin_only, session_id.as_deref())
                 .await
         })?;
 
         Ok(serde_json::json!({
-            "success": true,
-            "selector": selector,
-            "checked": checked
+            "count": images.len(),
+            "images": images
         }))
     }
 
-    fn handle_hover(&self, params: HashMap<String, Value>) -> Result<Value> {
-        let selector = params
-            .get("selector")
-            .and_then(|v| v.as_str())
-            .context("Missing 'selector' parameter")?;
+    /// Parse screenshot `format`/`quality` params (quality defaults to 80 for lossy formats).
+    fn parse_screenshot_format(format: Option<&str>, quality: Option<i64>) -> Result<ScreenshotFormat> {
+        let quality = match quality {
+            Some(q) if !(0..=100).contains(&q) => {
+                anyhow::bail!("Invalid 'quality' {}: must be between 0 and 100", q)
+            }
+            Some(q) => q as u8,
+            None => 80,
+        };
+
+        match format.unwrap_or("png").to_lowercase().as_str() {
+            "png" => Ok(ScreenshotFormat::Png),
+            "jpeg" | "jpg" => Ok(ScreenshotFormat::Jpeg { quality }),
+            "webp" => Ok(ScreenshotFormat::Webp { quality }),
+            other => anyhow::bail!(
+                "Invalid screenshot format '{}': expected png, jpeg, or webp",
+                other
+            ),
+        }
+    }
+
+    /// Build emulation settings from a `preset` and/or explicit params.
+    ///
+    /// Explicit fields override the preset's. Returns None for `reset`.
+    fn parse_emulation(params: &HashMap<String, Value>) -> Result<Option<EmulationSettings>> {
+        if params.get("reset").and_then(|v| v.as_bool()).unwrap_or(false) {
+            return Ok(None);
+        }
+
+        let preset = params.get("preset").and_then(|v| v.as_str());
+        let width = params.get("width").and_then(|v| v.as_u64());
+        let height = params.get("height").and_then(|v| v.as_u64());
+
+        let mut settings = match preset {
+            Some(name) => device_preset(name).with_context(|| {
+                format!(
+                    "Unknown device preset '{}': expected one of {}",
+                    name,
+                    device_preset_names().join(", ")
+                )
+            })?,
+            None => EmulationSettings {
+                width: width.context("Missing 'width' parameter (or use 'preset')")? as u32,
+                height: height.context("Missing 'height' parameter (or use 'preset')")? as u32,
+                device_scale_factor: 1.0,
+                is_mobile: false,
+                user_agent: None,
+            },
+        };
+
+        if let Some(w) = width {
+            settings.width = w as u32;
+        }
+        if let Some(h) = height {
+            settings.height = h as u32;
+        }
+        if let Some(scale) = params.get("device_scale_factor").and_then(|v| v.as_f64()) {
+            settings.device_scale_factor = scale;
+        }
+        if let Some(mobile) = params.get("is_mobile").and_then(|v| v.as_bool()) {
+            settings.is_mobile = mobile;
+        }
+        if let Some(ua) = params.get("user_agent").and_then(|v| v.as_str()) {
+            settings.user_agent = Some(ua.to_string());
+        }
+
+        if settings.width == 0 || settings.height == 0 {
+            anyhow::bail!("Viewport width and height must be greater than 0");
+        }
+        if settings.device_scale_factor <= 0.0 {
+            anyhow::bail!("'device_scale_factor' must be greater than 0");
+        }
+
+        Ok(Some(settings))
+    }
+
+    fn handle_emulate(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let settings = Self::parse_emulation(&params)?;
         let session_id = Self::get_session_id(&params);
 
-        let client = self.client.clone();
+        let (client, connect_url) = self.client_for(&params)?;
         let user_data_dir = self.user_data_dir.clone();
         let headless = self.headless;
-        let connect_url = self.connect_url.clone();
-        let selector = selector.to_string();
+        let viewport = self.viewport;
+        let channel = self.channel;
 
-        self.runtime.block_on(async {
+        let applied = self.runtime.block_on(async {
             let browser_client =
-                Self::get_or_init_client(&client, &user_data_dir, headless, connect_url.as_deref()).await?;
-            browser_client.hover(&selector, session_id.as_deref()).await
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.emulate(settings, session_id.as_deref()).await
         })?;
 
         Ok(serde_json::json!({
-            "success": true,
-            "selector": selector
+            "emulating": applied.is_some(),
+            "settings": applied,
         }))
     }
 
-    fn handle_scroll(&self, params: HashMap<String, Value>) -> Result<Value> {
-        let selector = params.get("selector").and_then(|v| v.as_str());
-        let x = params.get("x").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
-        let y = params.get("y").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+    fn handle_set_viewport(&self, params: HashMap<String, Value>) -> Result<Value> {
+        // A device preset, scale factor or mobile flag means full device emulation
+        let has = |key: &str| params.get(key).is_some_and(|v| !v.is_null());
+        if has("device") || has("device_scale_factor") || has("mobile") {
+            let mut emulation = params.clone();
+            if let Some(device) = params.get("device") {
+                emulation.insert("preset".to_string(), device.clone());
+            }
+            if let Some(mobile) = params.get("mobile") {
+                emulation.insert("is_mobile".to_string(), mobile.clone());
+            }
+            emulation.remove("reset");
+            return self.handle_emulate(emulation).map(|mut result| result["settings"].take());
+        }
+
+        let width = params
+            .get("width")
+            .and_then(|v| v.as_u64())
+            .context("Missing 'width' parameter")?;
+        let height = params
+            .get("height")
+            .and_then(|v| v.as_u64())
+            .context("Missing 'height' parameter")?;
+        if width == 0 || height == 0 || width > u32::MAX as u64 || height > u32::MAX as u64 {
+            anyhow::bail!("Invalid viewport {}x{}", width, height);
+        }
+        let size = ViewportSize {
+            width: width as u32,
+            height: height as u32,
+        };
         let session_id = Self::get_session_id(&params);
 
-        let client = self.client.clone();
+        let (client, connect_url) = self.client_for(&params)?;
         let user_data_dir = self.user_data_dir.clone();
         let headless = self.headless;
-        let connect_url = self.connect_url.clone();
-        let selector = selector.map(|s| s.to_string());
+        let viewport = self.viewport;
+        let channel = self.channel;
 
-        self.runtime.block_on(async {
+        let settings = self.runtime.block_on(async {
             let browser_client =
-                Self::get_or_init_client(&client, &user_data_dir, headless, connect_url.as_deref()).await?;
-            browser_client
-                .scroll(selector.as_deref(), x, y, session_id.as_deref())
-                .await
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.set_viewport(size, session_id.as_deref()).await
         })?;
 
-        Ok(serde_json::json!({
-            "success": true,
-            "x": x,
-            "y": y
-        }))
+        Ok(serde_json::to_value(settings)?)
     }
 
-    fn handle_press_combo(&self, params: HashMap<String, Value>) -> Result<Value> {
-        let key = params
-            .get("key")
-            .and_then(|v| v.as_str())
-            .context("Missing 'key' parameter")?;
-        let modifiers: Vec<String> = params
-            .get("modifiers")
-            .and_then(|v| v.as_array())
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                    .collect()
-            })
-            .unwrap_or_default();
+    /// Validate geolocation coordinates (accuracy defaults to 0 meters).
+    fn parse_geolocation(params: &HashMap<String, Value>) -> Result<(f64, f64, f64)> {
+        let latitude = params
+            .get("latitude")
+            .and_then(|v| v.as_f64())
+            .context("Missing 'latitude' parameter")?;
+        let longitude = params
+            .get("longitude")
+            .and_then(|v| v.as_f64())
+            .context("Missing 'longitude' parameter")?;
+        let accuracy = params
+            .get("accuracy")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0);
+
+        if !(-90.0..=90.0).contains(&latitude) {
+            anyhow::bail!("Invalid 'latitude' {}: must be between -90 and 90", latitude);
+        }
+        if !(-180.0..=180.0).contains(&longitude) {
+            anyhow::bail!("Invalid 'longitude' {}: must be between -180 and 180", longitude);
+        }
+        if accuracy < 0.0 {
+            anyhow::bail!("Invalid 'accuracy' {}: must be >= 0", accuracy);
+        }
+
+        Ok((latitude, longitude, accuracy))
+    }
+
+    fn handle_set_geolocation(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let (latitude, longitude, accuracy) = Self::parse_geolocation(&params)?;
         let session_id = Self::get_session_id(&params);
 
-        let client = self.client.clone();
+        let (client, connect_url) = self.client_for(&params)?;
         let user_data_dir = self.user_data_dir.clone();
         let headless = self.headless;
-        let connect_url = self.connect_url.clone();
-        let key = key.to_string();
+        let viewport = self.viewport;
+        let channel = self.channel;
 
-        self.runtime.block_on(async {
+        let result = self.runtime.block_on(async {
             let browser_client =
-                Self::get_or_init_client(&client, &user_data_dir, headless, connect_url.as_deref()).await?;
-            let mod_refs: Vec<&str> = modifiers.iter().map(|s| s.as_str()).collect();
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
             browser_client
-                .press_combo(&mod_refs, &key, session_id.as_deref())
+                .set_geolocation(latitude, longitude, accuracy, session_id.as_deref())
                 .await
         })?;
 
-        Ok(serde_json::json!({
-            "success": true,
-            "key": key,
-            "modifiers": modifiers
-        }))
+        Ok(serde_json::to_value(result)?)
     }
 
-    fn handle_upload(&self, params: HashMap<String, Value>) -> Result<Value> {
-        let selector = params
-            .get("selector")
-            .and_then(|v| v.as_str())
-            .context("Missing 'selector' parameter")?;
-        let path = params
-            .get("path")
-            .and_then(|v| v.as_str())
-            .context("Missing 'path' parameter")?;
+    fn handle_clear_geolocation(&self, params: HashMap<String, Value>) -> Result<Value> {
         let session_id = Self::get_session_id(&params);
 
-        let client = self.client.clone();
+        let (client, connect_url) = self.client_for(&params)?;
         let user_data_dir = self.user_data_dir.clone();
         let headless = self.headless;
-        let connect_url = self.connect_url.clone();
-        let selector = selector.to_string();
-        let path = path.to_string();
+        let viewport = self.viewport;
+        let channel = self.channel;
 
         self.runtime.block_on(async {
             let browser_client =
-                Self::get_or_init_client(&client, &user_data_dir, headless, connect_url.as_deref()).await?;
-            browser_client
-                .upload(&selector, &path, session_id.as_deref())
-                .await
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.clear_geolocation(session_id.as_deref()).await
         })?;
 
-        Ok(serde_json::json!({
-            "success": true,
-            "selector": selector,
-            "path": path
+        Ok(serde_json::json!({"success": true}))
+    }
+
+    fn handle_grant_permissions(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let names: Vec<&str> = params
+            .get("permissions")
+            .and_then(|v| v.as_array())
+            .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect())
+            .filter(|names: &Vec<&str>| !names.is_empty())
+            .context("Missing 'permissions' parameter")?;
+        let permissions = names
+            .iter()
+            .map(|name| {
+                parse_permission(name).with_context(|| format!("Unknown permission '{}'", name))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let granted: Vec<String> = names.iter().map(|n| n.to_string()).collect();
+        let origin = params
+            .get("origin")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let origin = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .grant_permissions(permissions, origin, session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::json!({
+            "granted": granted,
+            "origin": origin,
         }))
     }
 
-    // =========================================================================
-    // EXTENSION BRIDGE ROUTING
-    // =========================================================================
+    fn handle_reset_permissions(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = Self::get_session_id(&params);
 
-    /// Route extension-specific methods to the Chrome extension via WebSocket.
-    /// Called by dispatch() when the method is an extension-only method.
-    fn dispatch_to_extension(&self, method: &str, params: HashMap<String, Value>) -> Result<Value> {
-        let bridge = self.extension_bridge.as_ref().ok_or_else(|| {
-            anyhow::anyhow!(
-                "Extension method '{}' requires Chrome extension. \
-                 Start daemon with --extension-bridge and install the FGP extension.",
-                method
-            )
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.reset_permissions(session_id.as_deref()).await
         })?;
 
-        if !bridge.is_connected_blocking() {
-            anyhow::bail!(
-                "Extension not connected. Install the FGP Browser Bridge extension \
-                 from chrome://extensions and ensure it's enabled."
-            );
-        }
+        Ok(serde_json::json!({"success": true}))
+    }
 
-        // Strip 'browser.' prefix for the actual Chrome Extension API call
-        let ext_method = extension_method_name(method);
-        tracing::debug!("Routing '{}' to Chrome extension as '{}'", method, ext_method);
-        let response = bridge.call_blocking(ext_method, params)?;
-        ExtensionBridge::response_to_value(response)
+    fn handle_clipboard_read(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let text = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.clipboard_read(session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({"text": text}))
     }
-}
 
-impl FgpService for BrowserService {
-    fn name(&self) -> &str {
-        "browser"
+    fn handle_clipboard_write(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let text = params
+            .get("text")
+            .and_then(|v| v.as_str())
+            .context("Missing 'text' parameter")?;
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.clipboard_write(text, session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({"success": true, "length": text.chars().count()}))
     }
 
-    fn version(&self) -> &str {
-        env!("CARGO_PKG_VERSION")
+    /// Parse `browser.emulate_media` params (None = clear all media emulation).
+    fn parse_media_emulation(params: &HashMap<String, Value>) -> Result<Option<MediaEmulation>> {
+        if params.get("reset").and_then(|v| v.as_bool()).unwrap_or(false) {
+            return Ok(None);
+        }
+
+        let choice = |key: &str, allowed: &[&str]| -> Result<Option<String>> {
+            match params.get(key).and_then(|v| v.as_str()) {
+                None => Ok(None),
+                Some(value) if allowed.contains(&value) => Ok(Some(value.to_string())),
+                Some(value) => anyhow::bail!(
+                    "Invalid {} '{}': expected one of {}",
+                    key,
+                    value,
+                    allowed.join(", ")
+                ),
+            }
+        };
+
+        let update = MediaEmulation {
+            media: choice("media", &["print", "screen"])?,
+            color_scheme: choice("color_scheme", &["dark", "light"])?,
+            reduced_motion: params.get("reduced_motion").and_then(|v| v.as_bool()),
+        };
+        if update.is_empty() {
+            anyhow::bail!("Missing 'color_scheme', 'reduced_motion' or 'media' (or 'reset')");
+        }
+
+        Ok(Some(update))
     }
 
-    fn dispatch(&self, method: &str, params: HashMap<String, Value>) -> Result<Value> {
-        // Check if this is an extension-only method that should be routed to the Chrome extension
-        if is_extension_method(method) {
-            return self.dispatch_to_extension(method, params);
+    fn handle_emulate_media(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let update = Self::parse_media_emulation(&params)?;
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let media = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.emulate_media(update, session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({
+            "emulating": media.is_some(),
+            "media": media,
+        }))
+    }
+
+    /// Parse `browser.set_network_conditions` / `browser.network.emulate`
+    /// params (None = lift throttling).
+    ///
+    /// `preset` is accepted for `profile`, and CDP's `latency` and
+    /// `download_throughput`/`upload_throughput` (bytes/s) for the
+    /// millisecond and kbit/s fields.
+    fn parse_network_conditions(params: &HashMap<String, Value>) -> Result<Option<NetworkConditions>> {
+        let profile = params.get("profile").or_else(|| params.get("preset")).and_then(|v| v.as_str());
+        if params.get("reset").and_then(|v| v.as_bool()).unwrap_or(false)
+            || profile.is_some_and(|name| name.eq_ignore_ascii_case("none"))
+        {
+            return Ok(None);
         }
 
-        match method {
-            "health" => self.handle_health(params),
-            // Navigation and state
-            "browser.open" | "open" => self.handle_open(params),
-            "browser.snapshot" | "snapshot" => self.handle_snapshot(params),
-            "browser.screenshot" | "screenshot" => self.handle_screenshot(params),
-            // Interaction
-            "browser.click" | "click" => self.handle_click(params),
-            "browser.fill" | "fill" => self.handle_fill(params),
-            "browser.press" | "press" => self.handle_press(params),
-            "browser.select" | "select" => self.handle_select(params),
-            "browser.check" | "check" => self.handle_check(params),
-            "browser.hover" | "hover" => self.handle_hover(params),
-            "browser.scroll" | "scroll" => self.handle_scroll(params),
-            "browser.press_combo" | "press_combo" => self.handle_press_combo(params),
-            "browser.upload" | "upload" => self.handle_upload(params),
-            // Auth state
-            "browser.state.save" | "state.save" => self.handle_state_save(params),
-            "browser.state.load" | "state.load" => self.handle_state_load(params),
-            "browser.state.list" | "state.list" => self.handle_state_list(params),
-            // Session management
-            "browser.session.new" | "session.new" => self.handle_session_new(params),
-            "browser.session.list" | "session.list" => self.handle_session_list(params),
-            "browser.session.close" | "session.close" => self.handle_session_close(params),
-            _ => Err(anyhow::anyhow!("Unknown method: {}", method)),
+        let number = |key: &str| -> Result<Option<f64>> {
+            match params.get(key).filter(|v| !v.is_null()) {
+                None => Ok(None),
+                Some(v) => match v.as_f64() {
+                    Some(n) if n >= 0.0 => Ok(Some(n)),
+                    _ => anyhow::bail!("'{}' must be a non-negative number", key),
+                },
+            }
+        };
+        let kbps = |bytes_per_sec: f64| bytes_per_sec * 8.0 / 1000.0;
+        let latency = number("latency_ms")?.or(number("latency")?);
+        let download = number("download_kbps")?.or(number("download_throughput")?.map(kbps));
+        let upload = number("upload_kbps")?.or(number("upload_throughput")?.map(kbps));
+        let offline = params.get("offline").and_then(|v| v.as_bool());
+        let explicit = latency.is_some() || download.is_some() || upload.is_some() || offline.is_some();
+
+        let mut conditions = match profile {
+            Some(name) => network_profile(name).with_context(|| {
+                format!(
+                    "Unknown network profile '{}': expected one of {}",
+                    name,
+                    network_profile_names().join(", ")
+                )
+            })?,
+            None if !explicit => {
+                anyhow::bail!("Missing 'profile'/'preset' (or latency_ms/download_kbps/upload_kbps/offline)")
+            }
+            None => NetworkConditions {
+                profile: None,
+                offline: false,
+                latency_ms: 0.0,
+                download_kbps: None,
+                upload_kbps: None,
+            },
+        };
+
+        // Explicit numbers override the profile, which then no longer applies as named
+        if explicit {
+            conditions.profile = None;
+        }
+        if let Some(latency) = latency {
+            conditions.latency_ms = latency;
+        }
+        if download.is_some() {
+            conditions.download_kbps = download;
         }
+        if upload.is_some() {
+            conditions.upload_kbps = upload;
+        }
+        if let Some(offline) = offline {
+            conditions.offline = offline;
+        }
+
+        Ok(Some(conditions))
     }
 
-    fn method_list(&self) -> Vec<MethodInfo> {
-        // Common session parameter schema
-        let session_param = || {
-            SchemaBuilder::string()
-                .description("Session ID for isolated browser context (optional)")
+    fn handle_set_network_conditions(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let conditions = Self::parse_network_conditions(&params)?;
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let applied = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.set_network_conditions(conditions, session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({
+            "throttling": applied.is_some(),
+            "conditions": applied,
+        }))
+    }
+
+    fn handle_cpu_throttle(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let rate = params
+            .get("rate")
+            .and_then(|v| v.as_f64())
+            .context("Missing 'rate' parameter (slowdown factor, 1 = full speed)")?;
+        if rate < 1.0 {
+            anyhow::bail!("'rate' must be at least 1 (1 = full speed, 4 = 4x slower)");
+        }
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let applied = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.set_cpu_throttling(rate, session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({
+            "throttling": applied.is_some(),
+            "rate": applied.unwrap_or(1.0),
+        }))
+    }
+
+    fn handle_metrics(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let metrics = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.page_metrics(session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::to_value(metrics)?)
+    }
+
+    fn handle_pdf(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let path = params.get("path").and_then(|v| v.as_str());
+        let options = PdfOptions {
+            landscape: params
+                .get("landscape")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            print_background: params
+                .get("print_background")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            scale: params.get("scale").and_then(|v| v.as_f64()),
+            paper_width: params.get("paper_width").and_then(|v| v.as_f64()),
+            paper_height: params.get("paper_height").and_then(|v| v.as_f64()),
+            margin: params.get("margin").and_then(|v| v.as_f64()),
         };
+        let session_id = Self::get_session_id(&params);
 
-        vec![
-            // ================================================================
-            // Navigation and State
-            // ================================================================
-            MethodInfo::new("browser.open", "Navigate to a URL")
-                .schema(
-                    SchemaBuilder::object()
-                        .property(
-                            "url",
-                            SchemaBuilder::string()
-                                .format("uri")
-                                .description("URL to navigate to"),
-                        )
-                        .property(
-                            "wait_until",
-                            SchemaBuilder::string()
-                                .enum_values(&["load", "domcontentloaded", "networkidle"])
-                                .default_value(json!("load"))
-                                .description("When to consider navigation complete"),
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let result = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .print_pdf(path, &options, session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::to_value(result)?)
+    }
+
+    fn handle_fetch(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let url = params
+            .get("url")
+            .and_then(|v| v.as_str())
+            .context("Missing 'url' parameter")?;
+        let path = params.get("path").and_then(|v| v.as_str());
+        let max_bytes = params
+            .get("max_bytes")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_FETCH_MAX_BYTES);
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let result = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .fetch(url, path, max_bytes, session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::to_value(result)?)
+    }
+
+    fn handle_click(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let selector = params
+            .get("selector")
+            .and_then(|v| v.as_str())
+            .context("Missing 'selector' parameter")?;
+        let frame = Self::get_frame(&params);
+        let session_id = Self::get_session_id(&params);
+        let click_count = params.get("click_count").and_then(|v| v.as_u64()).unwrap_or(1);
+        if click_count == 2 && frame.is_none() {
+            return self.handle_double_click(params);
+        }
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+        let selector = selector.to_string();
+
+        let result = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.click(&selector, frame.as_ref(), session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::to_value(result)?)
+    }
+
+    fn handle_double_click(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let selector = params
+            .get("selector")
+            .and_then(|v| v.as_str())
+            .context("Missing 'selector' parameter")?
+            .to_string();
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let result = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.double_click(&selector, session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::to_value(result)?)
+    }
+
+    fn handle_right_click(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let selector = params
+            .get("selector")
+            .and_then(|v| v.as_str())
+            .context("Missing 'selector' parameter")?
+            .to_string();
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let result = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.right_click(&selector, session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::to_value(result)?)
+    }
+
+    fn handle_context_menu_select(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let text = params
+            .get("item_text")
+            .or_else(|| params.get("text"))
+            .and_then(|v| v.as_str())
+            .context("Missing 'item_text' parameter")?
+            .to_string();
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let result = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.select_context_menu_item(&text, session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::to_value(result)?)
+    }
+
+    fn handle_fill(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let selector = params
+            .get("selector")
+            .and_then(|v| v.as_str())
+            .context("Missing 'selector' parameter")?;
+        let value = params
+            .get("value")
+            .and_then(|v| v.as_str())
+            .context("Missing 'value' parameter")?;
+        let clear = params.get("clear").and_then(|v| v.as_bool()).unwrap_or(true);
+        let delay_ms = params.get("delay_ms").and_then(|v| v.as_u64()).unwrap_or(0);
+        let frame = Self::get_frame(&params);
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+        let selector = selector.to_string();
+        let value = value.to_string();
+
+        let result = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .fill(&selector, &value, clear, delay_ms, frame.as_ref(), session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::to_value(result)?)
+    }
+
+    fn handle_press(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let key = params
+            .get("key")
+            .and_then(|v| v.as_str())
+            .context("Missing 'key' parameter")?;
+        let frame = Self::get_frame(&params);
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+        let key = key.to_string();
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .press(&key, frame.as_ref(), session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::json!({"success": true}))
+    }
+
+    fn handle_dialog(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let action = params
+            .get("action")
+            .and_then(|v| v.as_str())
+            .map(|a| match a {
+                "accept" => Ok(DialogAction::Accept),
+                "dismiss" => Ok(DialogAction::Dismiss),
+                "manual" => Ok(DialogAction::Manual),
+                other => Err(anyhow::anyhow!(
+                    "Invalid dialog action '{}': expected 'accept', 'dismiss' or 'manual'",
+                    other
+                )),
+            })
+            .transpose()?;
+        let prompt_text = params
+            .get("prompt_text")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let clear = params
+            .get("clear")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let session_id = Self::get_session_id(&params);
+
+        let policy = action.map(|action| DialogPolicy {
+            action,
+            prompt_text,
+        });
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let result = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .dialog(policy, clear, session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::to_value(result)?)
+    }
+
+    fn handle_set_dialog_handler(&self, mut params: HashMap<String, Value>) -> Result<Value> {
+        let mode = params
+            .remove("mode")
+            .filter(|v| v.is_string())
+            .context("Missing 'mode' parameter (accept, dismiss or manual)")?;
+        params.insert("action".to_string(), mode);
+        params.remove("clear");
+
+        let mut status = self.handle_dialog(params)?;
+        Ok(serde_json::json!({"policy": status["policy"].take()}))
+    }
+
+    fn handle_dialogs_list(&self, mut params: HashMap<String, Value>) -> Result<Value> {
+        params.remove("action");
+        params.remove("prompt_text");
+
+        let mut status = self.handle_dialog(params)?;
+        let dialogs = status["dialogs"].take();
+        Ok(serde_json::json!({
+            "count": dialogs.as_array().map_or(0, |d| d.len()),
+            "dialogs": dialogs,
+        }))
+    }
+
+    fn handle_network_enable(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.network_enable(session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({"enabled": true}))
+    }
+
+    fn handle_network_disable(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.network_disable(session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({"enabled": false}))
+    }
+
+    fn handle_network_list(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let status = match params.get("status") {
+            None | Some(Value::Null) => None,
+            Some(Value::Number(n)) => Some(StatusFilter::Exact(
+                n.as_i64().context("Invalid 'status' parameter")?,
+            )),
+            Some(Value::String(s)) => Some(StatusFilter::parse(s).with_context(|| {
+                format!("Invalid status filter '{}': expected e.g. 404, '4xx' or 'failed'", s)
+            })?),
+            Some(_) => anyhow::bail!("Invalid 'status' parameter"),
+        };
+        let filter = NetworkFilter {
+            url_pattern: params
+                .get("url_pattern")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            status,
+            resource_type: params
+                .get("resource_type")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            limit: params
+                .get("limit")
+                .and_then(|v| v.as_u64())
+                .map(|n| n as usize),
+        };
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let (enabled, requests) = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .network_list(&filter, session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::json!({
+            "enabled": enabled,
+            "count": requests.len(),
+            "requests": requests,
+        }))
+    }
+
+    fn handle_network_body(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let request_id = params
+            .get("request_id")
+            .and_then(|v| v.as_str())
+            .context("Missing 'request_id' parameter")?
+            .to_string();
+        let max_bytes = params
+            .get("max_bytes")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_BODY_MAX_BYTES) as usize;
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let body = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .network_body(&request_id, max_bytes, session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::to_value(body)?)
+    }
+
+    /// Read a string-or-array-of-strings param (`"a"`, `["a", "b"]`, or `"a,b"`).
+    fn string_list_param(params: &HashMap<String, Value>, key: &str) -> Vec<String> {
+        match params.get(key) {
+            Some(Value::String(s)) => s
+                .split(',')
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect(),
+            Some(Value::Array(items)) => items
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(|s| s.to_string())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn handle_network_block(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let url_patterns = Self::string_list_param(&params, "url_patterns");
+        let resource_types = Self::string_list_param(&params, "resource_types")
+            .iter()
+            .map(|t| parse_resource_type(t))
+            .collect::<Result<Vec<_>>>()?;
+        if url_patterns.is_empty() && resource_types.is_empty() {
+            anyhow::bail!("Provide 'url_patterns' and/or 'resource_types' to block");
+        }
+        let rules = BlockRules {
+            url_patterns,
+            resource_types,
+        };
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let status = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .network_block(rules, session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::to_value(status)?)
+    }
+
+    fn handle_network_unblock(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let status = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.network_unblock(session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::to_value(status)?)
+    }
+
+    fn handle_network_block_urls(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let patterns = Self::string_list_param(&params, "patterns");
+        if patterns.is_empty() {
+            anyhow::bail!("Missing 'patterns' parameter");
+        }
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let status = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            let patterns: Vec<&str> = patterns.iter().map(String::as_str).collect();
+            browser_client.block_url_patterns(&patterns, session_id.as_deref()).await?;
+            browser_client.network_rules(session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::to_value(status)?)
+    }
+
+    fn handle_network_clear_blocked_urls(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let status = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.clear_blocked_urls(session_id.as_deref()).await?;
+            browser_client.network_rules(session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::to_value(status)?)
+    }
+
+    fn handle_network_rules(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let status = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.network_rules(session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::to_value(status)?)
+    }
+
+    fn handle_network_mock(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let url_pattern = params
+            .get("url_pattern")
+            .and_then(|v| v.as_str())
+            .filter(|p| !p.is_empty())
+            .context("Missing 'url_pattern' parameter")?
+            .to_string();
+        let status = params.get("status").and_then(|v| v.as_u64()).unwrap_or(200);
+        if !(100..=599).contains(&status) {
+            anyhow::bail!("Invalid status {}: expected 100-599", status);
+        }
+        let headers = Self::get_headers(&params, "headers")?.unwrap_or_default();
+        let body = match (
+            params.get("body").and_then(|v| v.as_str()),
+            params.get("body_path").and_then(|v| v.as_str()),
+        ) {
+            (Some(_), Some(_)) => anyhow::bail!("Provide either 'body' or 'body_path', not both"),
+            (Some(body), None) => body.as_bytes().to_vec(),
+            (None, Some(path)) => {
+                std::fs::read(path).with_context(|| format!("File not found: {}", path))?
+            }
+            (None, None) => Vec::new(),
+        };
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let mock = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .network_mock(url_pattern, status as u16, headers, body, session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::to_value(mock)?)
+    }
+
+    fn handle_set_headers(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let headers = Self::get_headers(&params, "headers")?;
+        let credentials: Option<HttpCredentials> = match params.get("credentials") {
+            Some(Value::Null) | None => None,
+            Some(value) => Some(serde_json::from_value(value.clone()).map_err(|_| {
+                anyhow::anyhow!("'credentials' must be an object with 'username' and 'password'")
+            })?),
+        };
+        let clear = params.get("clear").and_then(|v| v.as_bool()).unwrap_or(false);
+        if headers.is_none() && credentials.is_none() && !clear {
+            anyhow::bail!("Provide 'headers', 'credentials', or 'clear'");
+        }
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let settings = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .set_headers(headers, credentials, clear, session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::to_value(settings)?)
+    }
+
+    fn handle_network_mocks(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let mocks = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.network_mocks(session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({
+            "count": mocks.len(),
+            "mocks": mocks,
+        }))
+    }
+
+    fn handle_network_unmock(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let id = params.get("id").and_then(|v| v.as_str()).map(|s| s.to_string());
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let removed = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .network_unmock(id.as_deref(), session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::json!({"removed": removed}))
+    }
+
+    fn handle_network_clear(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let cleared = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.network_clear(session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({"cleared": cleared}))
+    }
+
+    fn handle_network_capture_start(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.start_network_capture(session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({"capturing": true}))
+    }
+
+    fn handle_network_capture_stop(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let requests = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.stop_network_capture(session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({
+            "count": requests.len(),
+            "requests": requests,
+        }))
+    }
+
+    fn handle_har_start(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.har_start(session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({"recording": true}))
+    }
+
+    fn handle_har_stop(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let path = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .filter(|p| !p.is_empty())
+            .context("Missing 'path' parameter")?
+            .to_string();
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let result = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.har_stop(&path, session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::to_value(result)?)
+    }
+
+    fn handle_downloads_list(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let downloads = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.list_downloads(session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({
+            "count": downloads.len(),
+            "downloads": downloads,
+        }))
+    }
+
+    fn handle_downloads_wait(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let timeout_ms = params
+            .get("timeout_ms")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_DOWNLOAD_TIMEOUT_MS);
+        let new_only = params.get("new_only").and_then(|v| v.as_bool()).unwrap_or(false);
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let download = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .wait_for_download(timeout_ms, new_only, session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::to_value(download)?)
+    }
+
+    /// `browser.wait_for_download`: wait for a download started after this call.
+    fn handle_wait_for_download(&self, mut params: HashMap<String, Value>) -> Result<Value> {
+        params.insert("new_only".to_string(), Value::Bool(true));
+        self.handle_downloads_wait(params)
+    }
+
+    fn handle_downloads_cleanup(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let max_age_hours = params
+            .get("max_age_hours")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_DOWNLOAD_MAX_AGE_HOURS);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let result = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .cleanup_downloads(std::time::Duration::from_secs(max_age_hours.saturating_mul(60 * 60)))
+                .await
+        })?;
+
+        Ok(serde_json::to_value(result)?)
+    }
+
+    fn handle_console_get(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let level = params.get("level").and_then(|v| v.as_str());
+        let limit = params
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map(|l| l as usize);
+        let clear = params
+            .get("clear")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let entries = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .console_get(level, limit, clear, session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::json!({
+            "count": entries.len(),
+            "entries": entries,
+        }))
+    }
+
+    fn handle_state_save(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let name = params
+            .get("name")
+            .and_then(|v| v.as_str())
+            .context("Missing 'name' parameter")?;
+        let session_id = Self::get_session_id(&params);
+        let origins = Some(Self::string_list_param(&params, "origins")).filter(|o| !o.is_empty());
+
+        let state_path = self.state_path(name)?;
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let state = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .capture_auth_state(origins.as_deref(), session_id.as_deref())
+                .await
+        })?;
+
+        let serialized = serde_json::to_vec_pretty(&state)?;
+        std::fs::write(&state_path, serialized)?;
+
+        Ok(serde_json::json!({
+            "success": true,
+            "path": state_path.to_string_lossy(),
+            "local_storage_origins": state.local_storage.iter().map(|s| &s.origin).collect::<Vec<_>>(),
+        }))
+    }
+
+    fn handle_state_load(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let name = params
+            .get("name")
+            .and_then(|v| v.as_str())
+            .context("Missing 'name' parameter")?;
+        let session_id = Self::get_session_id(&params);
+        let state = self.read_auth_state(name)?;
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let origins = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .set_cookies(&state.cookies, session_id.as_deref())
+                .await?;
+            browser_client
+                .load_local_storage(&state.local_storage, session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::json!({
+            "success": true,
+            "name": name,
+            "local_storage_origins": origins,
+        }))
+    }
+
+    /// A state saved by `browser.state.save`.
+    fn read_auth_state(&self, name: &str) -> Result<AuthState> {
+        let state_path = self.state_path(name)?;
+
+        if !state_path.exists() {
+            anyhow::bail!("State '{}' not found", name);
+        }
+
+        let state_bytes = std::fs::read(&state_path)?;
+        serde_json::from_slice(&state_bytes).with_context(|| format!("State '{}' is not a saved auth state", name))
+    }
+
+    fn handle_state_delete(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let name = params
+            .get("name")
+            .and_then(|v| v.as_str())
+            .context("Missing 'name' parameter")?;
+        let state_path = self.state_path(name)?;
+
+        // Deleting a state that's already gone succeeds, and says so
+        let existed = state_path.exists();
+        if existed {
+            std::fs::remove_file(&state_path)
+                .with_context(|| format!("Failed to delete state '{}'", name))?;
+        }
+
+        Ok(serde_json::json!({
+            "deleted": existed,
+            "existed": existed,
+            "name": name
+        }))
+    }
+
+    fn handle_state_rename(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let name = params
+            .get("name")
+            .and_then(|v| v.as_str())
+            .context("Missing 'name' parameter")?;
+        let new_name = params
+            .get("new_name")
+            .and_then(|v| v.as_str())
+            .context("Missing 'new_name' parameter")?;
+        let overwrite = params.get("overwrite").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        let overwritten = Self::rename_state_file(&self.auth_dir, name, new_name, overwrite)?;
+
+        Ok(serde_json::json!({
+            "renamed": true,
+            "name": new_name,
+            "previous_name": name,
+            "overwritten": overwritten
+        }))
+    }
+
+    /// Rename state `name` in `auth_dir` to `new_name`, returning whether an
+    /// existing `new_name` was replaced (only allowed with `overwrite`).
+    fn rename_state_file(auth_dir: &Path, name: &str, new_name: &str, overwrite: bool) -> Result<bool> {
+        Self::validate_state_name(name)?;
+        Self::validate_state_name(new_name)?;
+        let from = auth_dir.join(format!("{}.json", name));
+        let to = auth_dir.join(format!("{}.json", new_name));
+
+        if !from.exists() {
+            anyhow::bail!("State '{}' not found", name);
+        }
+        if name == new_name {
+            return Ok(false);
+        }
+        let exists = to.exists();
+        if exists && !overwrite {
+            anyhow::bail!("STATE_EXISTS: state '{}' already exists; pass overwrite: true to replace it", new_name);
+        }
+
+        std::fs::rename(&from, &to).with_context(|| format!("Failed to rename state '{}' to '{}'", name, new_name))?;
+        Ok(exists)
+    }
+
+    /// File for the saved state `name`, after checking the name can't
+    /// point outside the auth directory.
+    fn state_path(&self, name: &str) -> Result<PathBuf> {
+        Self::validate_state_name(name)?;
+        Ok(self.auth_dir.join(format!("{}.json", name)))
+    }
+
+    fn handle_cookies_export(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let path = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .filter(|p| !p.is_empty())
+            .context("Missing 'path' parameter")?
+            .to_string();
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let count = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.export_cookies_to_file(&path, session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({
+            "count": count,
+            "path": path
+        }))
+    }
+
+    fn handle_cookies_import(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let path = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .filter(|p| !p.is_empty())
+            .context("Missing 'path' parameter")?
+            .to_string();
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let count = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.import_cookies_from_file(&path, session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({
+            "count": count,
+            "path": path
+        }))
+    }
+
+    fn handle_storage_export(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let path = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .filter(|p| !p.is_empty())
+            .context("Missing 'path' parameter")?
+            .to_string();
+        let origin = params
+            .get("origin")
+            .and_then(|v| v.as_str())
+            .filter(|o| !o.is_empty());
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let state = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .export_local_storage_to_file(origin, &path, session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::json!({
+            "origin": state.origin,
+            "count": state.items.len(),
+            "path": path
+        }))
+    }
+
+    fn handle_storage_import(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let path = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .filter(|p| !p.is_empty())
+            .context("Missing 'path' parameter")?
+            .to_string();
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let state = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .import_local_storage_from_file(&path, session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::json!({
+            "origin": state.origin,
+            "count": state.items.len(),
+            "path": path
+        }))
+    }
+
+    fn handle_indexeddb_get_all(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let db = params
+            .get("db")
+            .and_then(|v| v.as_str())
+            .filter(|d| !d.is_empty())
+            .context("Missing 'db' parameter")?;
+        let store = params
+            .get("store")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .context("Missing 'store' parameter")?;
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let records = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.get_indexeddb(db, store, session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({
+            "db": db,
+            "store": store,
+            "count": records.len(),
+            "records": records
+        }))
+    }
+
+    fn handle_indexeddb_list_stores(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let db = params
+            .get("db")
+            .and_then(|v| v.as_str())
+            .filter(|d| !d.is_empty())
+            .context("Missing 'db' parameter")?;
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let stores = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.list_indexeddb_stores(db, session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({"db": db, "stores": stores}))
+    }
+
+    /// Whether `name` matches `^[a-zA-Z0-9_-]+$`, which also rules out path traversal.
+    fn is_plain_name(name: &str) -> bool {
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    }
+
+    /// Validate an auth state name (`^[a-zA-Z0-9_-]+$`), which also rules out path traversal.
+    fn validate_state_name(name: &str) -> Result<()> {
+        if !Self::is_plain_name(name) {
+            anyhow::bail!(
+                "Invalid state name '{}': must match ^[a-zA-Z0-9_-]+$",
+                name
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Validate a new session's id (`^[a-zA-Z0-9_-]+$`); it names the
+    /// session's downloads directory.
+    fn validate_session_id(id: &str) -> Result<()> {
+        if !Self::is_plain_name(id) {
+            anyhow::bail!("Invalid session id '{}': must match ^[a-zA-Z0-9_-]+$", id);
+        }
+
+        Ok(())
+    }
+
+    fn handle_state_list(&self, _params: HashMap<String, Value>) -> Result<Value> {
+        let mut states = Vec::new();
+
+        if self.auth_dir.exists() {
+            for entry in std::fs::read_dir(&self.auth_dir)? {
+                let entry = entry?;
+                let path = entry.path();
+                if path.extension().map(|e| e == "json").unwrap_or(false) {
+                    if let Some(stem) = path.file_stem() {
+                        let name = stem.to_string_lossy().to_string();
+                        let mut domains = Vec::new();
+                        let mut saved_at = String::new();
+
+                        if let Ok(contents) = std::fs::read(&path) {
+                            if let Ok(state) = serde_json::from_slice::<AuthState>(&contents) {
+                                let mut seen = std::collections::HashSet::new();
+                                for cookie in state.cookies {
+                                    if seen.insert(cookie.domain.clone()) {
+                                        domains.push(cookie.domain);
+                                    }
+                                }
+                                saved_at = state.saved_at;
+                            }
+                        }
+
+                        states.push(SavedState {
+                            name,
+                            domains,
+                            saved_at,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(serde_json::to_value(states)?)
+    }
+
+    fn handle_health(&self, _params: HashMap<String, Value>) -> Result<Value> {
+        let client = self.client.clone();
+
+        let (healthy, browser_version, session_count, reconnect, restarts, pool) = self.runtime.block_on(async {
+            let client_lock = client.client.read().await;
+            if let Some(ref browser_client) = *client_lock {
+                let healthy = browser_client.health_check().await.unwrap_or(false);
+                let version = if healthy {
+                    browser_client.browser_version().await.ok()
+                } else {
+                    None
+                };
+                let reconnect = serde_json::json!({
+                    "reconnecting": browser_client.is_reconnecting(),
+                    "attempts": browser_client.reconnect_attempts(),
+                    // The next call switches to the new connection
+                    "reconnected": browser_client.has_reconnected(),
+                });
+                let restarts = browser_client.restart_count();
+                let pool = browser_client.session_pool_stats();
+                (healthy, version, browser_client.session_count().await, Some(reconnect), restarts, Some(pool))
+            } else {
+                // No browser yet is OK
+                (true, None, 0, None, client.restart_count(), None)
+            }
+        });
+
+        let mut health = serde_json::json!({
+            "healthy": healthy,
+            "service": "browser",
+            "version": env!("CARGO_PKG_VERSION"),
+            "browser_version": browser_version,
+            "mode": if self.connect_url.is_some() { "connect" } else { "launch" },
+            "session_count": session_count,
+            "browser_restarts": restarts,
+            "extension_connected": self
+                .extension_bridge
+                .as_ref()
+                .is_some_and(|bridge| bridge.is_connected_blocking()),
+            "uptime_secs": self.started_at.elapsed().as_secs(),
+        });
+        if let Some(reconnect) = reconnect.filter(|_| self.connect_url.is_some()) {
+            health["reconnect"] = reconnect;
+        }
+        if let Some(pool) = pool.filter(|pool| pool.target > 0) {
+            health["session_pool"] = serde_json::to_value(pool)?;
+        }
+        if let Some(ref client_pool) = self.client_pool {
+            health["pooled_endpoints"] = json!(client_pool.endpoints());
+        }
+        if let Some(bridge) = &self.extension_bridge {
+            health["extension_bridge"] = serde_json::json!({
+                "connected": bridge.is_connected_blocking(),
+                "port": bridge.port(),
+                "reconnect_attempts": bridge.reconnect_attempts(),
+            });
+        }
+
+        Ok(health)
+    }
+
+    // =========================================================================
+    // SESSION MANAGEMENT HANDLERS
+    // =========================================================================
+
+    fn handle_session_new(&self, params: HashMap<String, Value>) -> Result<Value> {
+        // Anonymous sessions get a generated id
+        let session_id = params
+            .get("id")
+            .or_else(|| params.get("session_id"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .unwrap_or_else(Self::generate_session_id);
+        Self::validate_session_id(&session_id)?;
+        let name = params.get("name").and_then(|v| v.as_str()).map(|s| s.to_string());
+        let evict_idle = params.get("evict_idle").and_then(|v| v.as_bool()).unwrap_or(false);
+        let url = params.get("url").and_then(|v| v.as_str()).map(|s| s.to_string());
+        // Read the saved state up front so a bad name creates nothing
+        let state_name = params.get("state").and_then(|v| v.as_str());
+        let state = state_name.map(|n| self.read_auth_state(n)).transpose()?;
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let (id, restored) = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            let id = browser_client.create_session(&session_id, name.as_deref(), evict_idle).await?;
+            // A url alone restores an empty state: just the navigation
+            let restored = match (state, url.as_deref()) {
+                (None, None) => Ok(None),
+                (state, url) => {
+                    let state = state.unwrap_or_default();
+                    browser_client.restore_auth_state(&state, url, Some(&id)).await.map(Some)
+                }
+            };
+            match restored {
+                Ok(restored) => Ok((id, restored)),
+                Err(e) => {
+                    // Don't leave a half-set-up session behind
+                    browser_client.close_session(&id).await.ok();
+                    Err(e)
+                }
+            }
+        })?;
+
+        let mut result = serde_json::json!({
+            "success": true,
+            "session_id": id,
+            "name": name
+        });
+        if let Some(restored) = restored {
+            result["state"] = json!(state_name);
+            result["cookie_domains"] = json!(restored.cookie_domains);
+            result["local_storage_items"] = json!(restored.local_storage_items);
+            result["local_storage_origins"] = json!(restored.local_storage_origins);
+            result["url"] = json!(restored.url);
+        }
+        Ok(result)
+    }
+
+    /// Id for a session created without one, e.g. `session-1a2b3c4d`.
+    fn generate_session_id() -> String {
+        format!("session-{}", &uuid::Uuid::new_v4().simple().to_string()[..8])
+    }
+
+    fn handle_session_clone(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let source_id = params
+            .get("source_id")
+            .and_then(|v| v.as_str())
+            .context("Missing 'source_id' parameter")?
+            .to_string();
+        let session_id = params
+            .get("id")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .unwrap_or_else(Self::generate_session_id);
+        Self::validate_session_id(&session_id)?;
+        let name = params.get("name").and_then(|v| v.as_str()).map(|s| s.to_string());
+        let navigate = params.get("navigate").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let result = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .clone_session(&source_id, &session_id, name.as_deref(), navigate)
+                .await
+        })?;
+
+        Ok(serde_json::to_value(result)?)
+    }
+
+    fn handle_session_attach(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = params
+            .get("id")
+            .or_else(|| params.get("session_id"))
+            .and_then(|v| v.as_str())
+            .context("Missing 'id' parameter")?;
+        Self::validate_session_id(session_id)?;
+        let str_param = |name| params.get(name).and_then(|v: &Value| v.as_str());
+        let query = match (str_param("target_id"), str_param("url"), str_param("title")) {
+            (Some(id), _, _) => TabQuery::TargetId(id),
+            (None, Some(url), _) => TabQuery::Url(url),
+            (None, None, Some(title)) => TabQuery::Title(title),
+            (None, None, None) => anyhow::bail!("Missing 'target_id', 'url' or 'title' parameter"),
+        };
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let tab = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.attach_session(session_id, &query).await
+        })?;
+
+        Ok(serde_json::to_value(tab)?)
+    }
+
+    fn handle_targets(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let all = params.get("all").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let targets = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.list_targets(all).await
+        })?;
+
+        Ok(serde_json::json!({
+            "count": targets.len(),
+            "targets": targets
+        }))
+    }
+
+    fn handle_session_list(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let (client, _) = self.client_for(&params)?;
+
+        let sessions = self.runtime.block_on(async {
+            let client_lock = client.client.read().await;
+            if let Some(ref browser_client) = *client_lock {
+                browser_client.list_sessions().await
+            } else {
+                vec![]
+            }
+        });
+
+        Ok(serde_json::json!({
+            "count": sessions.len(),
+            "sessions": sessions
+        }))
+    }
+
+    fn handle_session_info(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let info = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.session_info(session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::to_value(info)?)
+    }
+
+    fn handle_session_set_metadata(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let key = params.get("key").and_then(|v| v.as_str()).context("Missing 'key' parameter")?.to_string();
+        let value = params.get("value").and_then(|v| v.as_str()).context("Missing 'value' parameter (string)")?.to_string();
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let metadata = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.set_session_metadata(&key, &value, session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({"success": true, "metadata": metadata}))
+    }
+
+    fn handle_session_get_metadata(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let metadata = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.session_metadata(session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({"metadata": metadata}))
+    }
+
+    fn handle_session_set_idle_timeout(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let secs = params
+            .get("secs")
+            .and_then(|v| v.as_u64())
+            .context("Missing 'secs' parameter (seconds, 0 = never)")?;
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.set_idle_timeout(secs);
+            Ok::<_, anyhow::Error>(())
+        })?;
+        // Clients that replace this one keep the timeout
+        client.config.lock().unwrap().idle_timeout_secs = secs;
+
+        Ok(serde_json::json!({"success": true, "idle_timeout_secs": secs}))
+    }
+
+    fn handle_session_close(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = params
+            .get("id")
+            .or_else(|| params.get("session_id"))
+            .and_then(|v| v.as_str())
+            .context("Missing 'id' parameter")?;
+
+        let (client, _) = self.client_for(&params)?;
+
+        self.runtime.block_on(async {
+            let client_lock = client.client.read().await;
+            if let Some(ref browser_client) = *client_lock {
+                browser_client.close_session(session_id).await
+            } else {
+                Ok(())
+            }
+        })?;
+
+        Ok(serde_json::json!({
+            "success": true,
+            "session_id": session_id
+        }))
+    }
+
+    fn handle_tabs_list(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let tabs = self.runtime.block_on(async {
+            let browser_client = Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.list_tabs().await
+        })?;
+
+        Ok(serde_json::json!({
+            "count": tabs.len(),
+            "tabs": tabs
+        }))
+    }
+
+    fn handle_tabs_open_new(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let url = params
+            .get("url")
+            .and_then(|v| v.as_str())
+            .context("Missing 'url' parameter")?;
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let tab = self.runtime.block_on(async {
+            let browser_client = Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.open_new_tab(url).await
+        })?;
+
+        Ok(serde_json::to_value(tab)?)
+    }
+
+    fn handle_tabs_close(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let id = params
+            .get("session_id")
+            .or_else(|| params.get("target_id"))
+            .and_then(|v| v.as_str())
+            .context("Missing 'session_id' (or 'target_id') parameter")?;
+        let force = params
+            .get("force")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        self.runtime.block_on(async {
+            let browser_client = Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.close_tab(id, force).await
+        })?;
+
+        Ok(serde_json::json!({
+            "closed": true,
+            "id": id
+        }))
+    }
+
+    fn handle_tabs_attach(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let url = params.get("url").and_then(|v| v.as_str());
+        let index = params
+            .get("index")
+            .and_then(|v| v.as_u64())
+            .map(|i| i as usize);
+        if url.is_none() && index.is_none() {
+            anyhow::bail!("Missing 'url' or 'index' parameter");
+        }
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let tab = self.runtime.block_on(async {
+            let browser_client = Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.attach_tab(url, index).await
+        })?;
+
+        Ok(serde_json::to_value(tab)?)
+    }
+
+    fn handle_tabs_switch(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let target_id = params
+            .get("target_id")
+            .and_then(|v| v.as_str())
+            .context("Missing 'target_id' parameter")?;
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        self.runtime.block_on(async {
+            let browser_client = Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.switch_tab(target_id).await
+        })?;
+
+        Ok(serde_json::json!({
+            "success": true,
+            "target_id": target_id
+        }))
+    }
+
+    fn handle_pages_list(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let pages = self.runtime.block_on(async {
+            let browser_client = Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.list_pages(session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::to_value(pages)?)
+    }
+
+    fn handle_pages_switch(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let index = params
+            .get("index")
+            .and_then(|v| v.as_u64())
+            .context("Missing 'index' parameter")? as usize;
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let page = self.runtime.block_on(async {
+            let browser_client = Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.switch_page(index, session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::to_value(page)?)
+    }
+
+    fn handle_tab_new(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let url = params.get("url").and_then(|v| v.as_str());
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let tab = self.runtime.block_on(async {
+            let browser_client = Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.new_session_tab(url, session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::to_value(tab)?)
+    }
+
+    fn handle_tab_list(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let tabs = self.runtime.block_on(async {
+            let browser_client = Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.list_session_tabs(session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({
+            "count": tabs.len(),
+            "tabs": tabs
+        }))
+    }
+
+    fn handle_tab_switch(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let target_id = params
+            .get("target_id")
+            .and_then(|v| v.as_str())
+            .context("Missing 'target_id' parameter")?;
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let tab = self.runtime.block_on(async {
+            let browser_client = Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.switch_session_tab(target_id, session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::to_value(tab)?)
+    }
+
+    fn handle_tab_close(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let target_id = params
+            .get("target_id")
+            .and_then(|v| v.as_str())
+            .context("Missing 'target_id' parameter")?;
+        let force = params
+            .get("force")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        self.runtime.block_on(async {
+            let browser_client = Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.close_session_tab(target_id, force, session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({
+            "closed": true,
+            "target_id": target_id
+        }))
+    }
+
+    // =========================================================================
+    // FEATURE PARITY HANDLERS
+    // =========================================================================
+
+    /// `value` for `browser.select`: one option, or an array for multi-selects.
+    fn parse_select_values(params: &HashMap<String, Value>) -> Result<Vec<String>> {
+        match params.get("value") {
+            Some(Value::String(value)) => Ok(vec![value.clone()]),
+            Some(Value::Array(values)) => values
+                .iter()
+                .map(|v| v.as_str().map(|s| s.to_string()).context("'value' array must contain only strings"))
+                .collect(),
+            Some(other) => anyhow::bail!("Invalid 'value': expected a string or an array of strings, got {}", other),
+            None => anyhow::bail!("Missing 'value' parameter"),
+        }
+    }
+
+    fn handle_select(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let selector = params
+            .get("selector")
+            .and_then(|v| v.as_str())
+            .context("Missing 'selector' parameter")?;
+        let values = Self::parse_select_values(&params)?;
+        let add = params.get("add").and_then(|v| v.as_bool()).unwrap_or(false);
+        let frame = Self::get_frame(&params);
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+        let selector = selector.to_string();
+
+        let selected = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .select(&selector, &values, add, frame.as_ref(), session_id.as_deref())
+                .await
+        })?;
+
+        // value/label describe the first selected option, as for single selects
+        let first = selected.first();
+        Ok(serde_json::json!({
+            "success": true,
+            "selector": selector,
+            "value": first.map(|o| &o.value),
+            "label": first.map(|o| &o.label),
+            "selected": selected
+        }))
+    }
+
+    fn handle_check(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let selector = params
+            .get("selector")
+            .and_then(|v| v.as_str())
+            .context("Missing 'selector' parameter")?;
+        let checked = params
+            .get("checked")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true); // Default to checking
+        let frame = Self::get_frame(&params);
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+        let selector = selector.to_string();
+
+        let checked = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .check(&selector, checked, frame.as_ref(), session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::json!({
+            "success": true,
+            "selector": selector,
+            "checked": checked
+        }))
+    }
+
+    fn handle_hover(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let selector = params
+            .get("selector")
+            .and_then(|v| v.as_str())
+            .context("Missing 'selector' parameter")?;
+        let frame = Self::get_frame(&params);
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+        let selector = selector.to_string();
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.hover(&selector, frame.as_ref(), session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({
+            "success": true,
+            "selector": selector
+        }))
+    }
+
+    /// Character range `start`..`end`; neither means all of the text.
+    fn parse_text_range(params: &HashMap<String, Value>) -> Result<Option<(usize, usize)>> {
+        let offset = |key: &str| -> Result<Option<usize>> {
+            match params.get(key) {
+                None | Some(Value::Null) => Ok(None),
+                Some(v) => v.as_u64().map(|n| Some(n as usize)).with_context(|| format!("'{}' must be a non-negative integer", key)),
+            }
+        };
+        match (offset("start")?, offset("end")?) {
+            (Some(start), Some(end)) if start <= end => Ok(Some((start, end))),
+            (Some(start), Some(end)) => anyhow::bail!("'start' ({}) is after 'end' ({})", start, end),
+            (None, None) => Ok(None),
+            _ => anyhow::bail!("Pass both 'start' and 'end', or neither to select all text"),
+        }
+    }
+
+    fn handle_select_text(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let selector = params
+            .get("selector")
+            .and_then(|v| v.as_str())
+            .context("Missing 'selector' parameter")?
+            .to_string();
+        let range = Self::parse_text_range(&params)?;
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            match range {
+                Some((start, end)) => browser_client.select_text_in(&selector, start, end, session_id.as_deref()).await,
+                None => browser_client.select_all_text(&selector, session_id.as_deref()).await,
+            }
+        })?;
+
+        Ok(serde_json::json!({
+            "success": true,
+            "selector": selector,
+            "start": range.map(|(start, _)| start),
+            "end": range.map(|(_, end)| end)
+        }))
+    }
+
+    fn handle_drag(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let source = params
+            .get("source_selector")
+            .and_then(|v| v.as_str())
+            .context("Missing 'source_selector' parameter")?
+            .to_string();
+        let target = params
+            .get("target_selector")
+            .and_then(|v| v.as_str())
+            .context("Missing 'target_selector' parameter")?
+            .to_string();
+        let steps = Self::parse_points(&params, "steps")?;
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .drag(&source, &target, &steps, session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::json!({
+            "success": true,
+            "source": source,
+            "target": target
+        }))
+    }
+
+    /// Optional array of `{x, y}` viewport points under `key`.
+    fn parse_points(params: &HashMap<String, Value>, key: &str) -> Result<Vec<Point>> {
+        match params.get(key) {
+            Some(Value::Array(points)) => points
+                .iter()
+                .map(|p| {
+                    let x = p.get("x").and_then(|v| v.as_f64());
+                    let y = p.get("y").and_then(|v| v.as_f64());
+                    match (x, y) {
+                        (Some(x), Some(y)) => Ok(Point { x, y }),
+                        _ => Err(anyhow::anyhow!("Invalid point {} in '{}': expected {{x, y}}", p, key)),
+                    }
+                })
+                .collect(),
+            Some(Value::Null) | None => Ok(Vec::new()),
+            Some(other) => anyhow::bail!("Invalid '{}': expected an array of {{x, y}}, got {}", key, other),
+        }
+    }
+
+    fn handle_drag_path(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let points: Vec<(f64, f64)> = Self::parse_points(&params, "points")?
+            .into_iter()
+            .map(|p| (p.x, p.y))
+            .collect();
+        if points.len() < 2 {
+            anyhow::bail!("'points' needs at least two {{x, y}} points");
+        }
+        let delay_ms = params.get("delay_between_points_ms").and_then(|v| v.as_u64()).unwrap_or(0);
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .drag_along_path(&points, delay_ms, session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::json!({
+            "success": true,
+            "points": points.len()
+        }))
+    }
+
+    /// Required viewport coordinates `x` and `y`.
+    fn parse_mouse_point(params: &HashMap<String, Value>) -> Result<(f64, f64)> {
+        let x = params
+            .get("x")
+            .and_then(|v| v.as_f64())
+            .context("Missing 'x' parameter")?;
+        let y = params
+            .get("y")
+            .and_then(|v| v.as_f64())
+            .context("Missing 'y' parameter")?;
+        Ok((x, y))
+    }
+
+    /// Mouse `button`: left (default), right or middle.
+    fn parse_mouse_button(params: &HashMap<String, Value>) -> Result<MouseButton> {
+        match params.get("button").and_then(|v| v.as_str()).unwrap_or("left") {
+            "left" => Ok(MouseButton::Left),
+            "right" => Ok(MouseButton::Right),
+            "middle" => Ok(MouseButton::Middle),
+            other => anyhow::bail!("Invalid button '{}': expected left, right or middle", other),
+        }
+    }
+
+    fn handle_mouse_move(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let (x, y) = Self::parse_mouse_point(&params)?;
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.mouse_move(x, y, session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({"success": true, "x": x, "y": y}))
+    }
+
+    fn handle_mouse_click(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let (x, y) = Self::parse_mouse_point(&params)?;
+        let button = Self::parse_mouse_button(&params)?;
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .mouse_click(x, y, button.clone(), session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::json!({
+            "success": true,
+            "x": x,
+            "y": y,
+            "button": button.as_ref()
+        }))
+    }
+
+    fn handle_mouse_wheel(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let delta_x = params.get("delta_x").and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let delta_y = params.get("delta_y").and_then(|v| v.as_f64()).unwrap_or(0.0);
+        if delta_x == 0.0 && delta_y == 0.0 {
+            anyhow::bail!("Provide 'delta_x' and/or 'delta_y' to scroll");
+        }
+        let x = params.get("x").and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let y = params.get("y").and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .mouse_wheel(delta_x, delta_y, x, y, session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::json!({"success": true, "delta_x": delta_x, "delta_y": delta_y}))
+    }
+
+    fn handle_get_element_bounds(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let selector = params
+            .get("selector")
+            .and_then(|v| v.as_str())
+            .context("Missing 'selector' parameter")?
+            .to_string();
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let bounds = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .get_element_bounds(&selector, session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::to_value(bounds)?)
+    }
+
+    fn handle_focus(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let selector = params
+            .get("selector")
+            .and_then(|v| v.as_str())
+            .context("Missing 'selector' parameter")?;
+        let frame = Self::get_frame(&params);
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+        let selector = selector.to_string();
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.focus(&selector, frame.as_ref(), session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({
+            "success": true,
+            "selector": selector
+        }))
+    }
+
+    fn handle_clear_field(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let selector = params
+            .get("selector")
+            .and_then(|v| v.as_str())
+            .context("Missing 'selector' parameter")?;
+        let frame = Self::get_frame(&params);
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+        let selector = selector.to_string();
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.clear_field(&selector, frame.as_ref(), session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({
+            "cleared": true
+        }))
+    }
+
+    fn handle_blur(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.blur(session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({"success": true}))
+    }
+    fn handle_scroll(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let selector = params.get("selector").and_then(|v| v.as_str());
+        let x = params.get("x").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+        let y = params.get("y").and_then(|v| v.as_i64()).unwrap_or(0) as i32;
+        let frame = Self::get_frame(&params);
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+        let selector = selector.map(|s| s.to_string());
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .scroll(selector.as_deref(), x, y, frame.as_ref(), session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::json!({
+            "success": true,
+            "x": x,
+            "y": y
+        }))
+    }
+
+    /// `browser.scroll.top` / `browser.scroll.bottom`: a single jump to that end.
+    fn handle_scroll_edge(&self, params: HashMap<String, Value>, bottom: bool) -> Result<Value> {
+        let smooth = params.get("smooth").and_then(|v| v.as_bool()).unwrap_or(false);
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            if bottom {
+                browser_client.jump_to_bottom(smooth, session_id.as_deref()).await
+            } else {
+                browser_client.scroll_to_top(smooth, session_id.as_deref()).await
+            }
+        })?;
+
+        Ok(serde_json::json!({
+            "success": true,
+            "smooth": smooth
+        }))
+    }
+
+    fn handle_scroll_to_bottom(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let max_scrolls = params
+            .get("max_scrolls")
+            .and_then(|v| v.as_u64())
+            .map_or(DEFAULT_MAX_SCROLLS, |n| n.min(u32::MAX as u64) as u32);
+        let settle_ms = params.get("settle_ms").and_then(|v| v.as_u64()).unwrap_or(DEFAULT_SCROLL_SETTLE_MS);
+        let timeout_ms = params.get("timeout_ms").and_then(|v| v.as_u64()).unwrap_or(DEFAULT_SCROLL_TIMEOUT_MS);
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        let result = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client
+                .scroll_to_bottom(
+                    max_scrolls,
+                    std::time::Duration::from_millis(settle_ms),
+                    std::time::Duration::from_millis(timeout_ms),
+                    session_id.as_deref(),
+                )
+                .await
+        })?;
+
+        Ok(serde_json::to_value(result)?)
+    }
+
+    fn handle_press_combo(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let key = params
+            .get("key")
+            .and_then(|v| v.as_str())
+            .context("Missing 'key' parameter")?;
+        let modifiers: Vec<String> = params
+            .get("modifiers")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+        let key = key.to_string();
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            let mod_refs: Vec<&str> = modifiers.iter().map(|s| s.as_str()).collect();
+            browser_client
+                .press_combo(&mod_refs, &key, session_id.as_deref())
+                .await
+        })?;
+
+        Ok(serde_json::json!({
+            "success": true,
+            "key": key,
+            "modifiers": modifiers
+        }))
+    }
+
+    /// The `keys` array: `{key, modifiers?, delay_after_ms?}` objects, or plain
+    /// key names as shorthand for a press without modifiers or delay.
+    fn parse_key_presses(params: &HashMap<String, Value>) -> Result<Vec<KeyPress>> {
+        let keys = params
+            .get("keys")
+            .and_then(|v| v.as_array())
+            .context("Missing 'keys' parameter (an array of key presses)")?;
+        if keys.is_empty() {
+            anyhow::bail!("'keys' must not be empty");
+        }
+        keys.iter()
+            .map(|k| match k {
+                Value::String(key) => Ok(KeyPress { key: key.clone(), modifiers: Vec::new(), delay_after_ms: 0 }),
+                _ => serde_json::from_value(k.clone()).map_err(|e| anyhow::anyhow!("Invalid key press {} in 'keys': {}", k, e)),
+            })
+            .collect()
+    }
+
+    fn handle_key_sequence(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let keys = Self::parse_key_presses(&params)?;
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+
+        self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            browser_client.key_sequence(&keys, session_id.as_deref()).await
+        })?;
+
+        Ok(serde_json::json!({
+            "success": true,
+            "pressed": keys.len()
+        }))
+    }
+
+    /// Files for `browser.upload`: a `paths` array, or a single `path`.
+    fn parse_upload_paths(params: &HashMap<String, Value>) -> Result<Vec<String>> {
+        match (params.get("paths"), params.get("path")) {
+            (Some(Value::Array(paths)), _) => paths
+                .iter()
+                .map(|v| v.as_str().map(|s| s.to_string()).context("'paths' must contain only strings"))
+                .collect(),
+            (Some(other), _) => anyhow::bail!("Invalid 'paths': expected an array of strings, got {}", other),
+            (None, Some(Value::String(path))) => Ok(vec![path.clone()]),
+            (None, Some(other)) => anyhow::bail!("Invalid 'path': expected a string, got {}", other),
+            (None, None) => anyhow::bail!("Missing 'path' or 'paths' parameter"),
+        }
+    }
+
+    /// In-memory file for `browser.upload`: `filename` and the bytes decoded
+    /// from `content_base64`, if given (instead of `path`/`paths`).
+    fn parse_upload_content(params: &HashMap<String, Value>) -> Result<Option<(String, Vec<u8>)>> {
+        let Some(encoded) = params.get("content_base64") else {
+            return Ok(None);
+        };
+        if params.contains_key("path") || params.contains_key("paths") {
+            anyhow::bail!("Pass either 'content_base64' or 'path'/'paths', not both");
+        }
+        let encoded = encoded.as_str().context("'content_base64' must be a string")?;
+        let filename = params
+            .get("filename")
+            .and_then(|v| v.as_str())
+            .context("Missing 'filename' parameter (required with 'content_base64')")?;
+        let bytes = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, encoded)
+            .context("'content_base64' is not valid base64")?;
+        Ok(Some((filename.to_string(), bytes)))
+    }
+
+    fn handle_upload(&self, params: HashMap<String, Value>) -> Result<Value> {
+        let selector = params
+            .get("selector")
+            .and_then(|v| v.as_str())
+            .context("Missing 'selector' parameter")?;
+        let content = Self::parse_upload_content(&params)?;
+        let paths = match content {
+            Some(_) => Vec::new(),
+            None => Self::parse_upload_paths(&params)?,
+        };
+        let session_id = Self::get_session_id(&params);
+
+        let (client, connect_url) = self.client_for(&params)?;
+        let user_data_dir = self.user_data_dir.clone();
+        let headless = self.headless;
+        let viewport = self.viewport;
+        let channel = self.channel;
+        let selector = selector.to_string();
+
+        let (paths, files) = self.runtime.block_on(async {
+            let browser_client =
+                Self::get_or_init_client(&client, &user_data_dir, headless, viewport, channel, connect_url.as_deref()).await?;
+            match content {
+                Some((filename, bytes)) => {
+                    let path = browser_client
+                        .upload_bytes(&selector, &filename, &bytes, session_id.as_deref())
+                        .await?;
+                    Ok::<_, anyhow::Error>((vec![path], vec![filename]))
+                }
+                None => {
+                    let files = browser_client.upload(&selector, &paths, session_id.as_deref()).await?;
+                    Ok((paths, files))
+                }
+            }
+        })?;
+
+        Ok(serde_json::json!({
+            "success": true,
+            "selector": selector,
+            "path": paths[0],
+            "paths": paths,
+            "files": files
+        }))
+    }
+
+    // =========================================================================
+    // EXTENSION BRIDGE ROUTING
+    // =========================================================================
+
+    /// Route extension-specific methods to the Chrome extension via WebSocket.
+    /// Called by dispatch() when the method is an extension-only method.
+    fn handle_extension_status(&self, _params: HashMap<String, Value>) -> Result<Value> {
+        let Some(bridge) = &self.extension_bridge else {
+            return Ok(serde_json::json!({
+                "bridge_enabled": false,
+                "connected": false,
+                "port": null,
+                "messages_sent": 0,
+                "messages_received": 0,
+                "last_connected_at": null,
+                "queued_count": 0,
+                "dropped_count": 0,
+            }));
+        };
+
+        Ok(serde_json::json!({
+            "bridge_enabled": true,
+            "connected": bridge.is_connected_blocking(),
+            "port": bridge.port(),
+            "messages_sent": bridge.messages_sent(),
+            "messages_received": bridge.messages_received(),
+            "last_connected_at": bridge.last_connected_at(),
+            "queue_capacity": bridge.queue_capacity(),
+            "queued_count": bridge.queued_count(),
+            "dropped_count": bridge.dropped_count(),
+            "reconnect_attempts": bridge.reconnect_attempts(),
+        }))
+    }
+
+    fn dispatch_to_extension(&self, method: &str, params: HashMap<String, Value>) -> Result<Value> {
+        let bridge = self.extension_bridge.as_ref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Extension method '{}' requires Chrome extension. \
+                 Start daemon with --extension-bridge and install the FGP extension.",
+                method
+            )
+        })?;
+
+        if !bridge.is_connected_blocking() && !bridge.queues_when_disconnected() {
+            anyhow::bail!(
+                "Extension not connected. Install the FGP Browser Bridge extension \
+                 from chrome://extensions and ensure it's enabled."
+            );
+        }
+
+        // Strip 'browser.' prefix for the actual Chrome Extension API call
+        let ext_method = extension_method_name(method);
+        tracing::debug!("Routing '{}' to Chrome extension as '{}'", method, ext_method);
+        let response = bridge.call_blocking(ext_method, params)?;
+        ExtensionBridge::response_to_value(response)
+    }
+
+    /// Add `browser_restarted: true` to the first response after the browser
+    /// (or the pooled one at `endpoint`) was relaunched or reconnected.
+    fn note_restart(&self, mut value: Value, endpoint: Option<&str>) -> Value {
+        let Some(object) = value.as_object_mut() else {
+            return value;
+        };
+        let client = match (endpoint, &self.client_pool) {
+            (Some(url), Some(pool)) => pool.get(url),
+            _ => Some(self.client.clone()),
+        };
+        let Some(client) = client else {
+            return value;
+        };
+        let restarted = self.runtime.block_on(async {
+            client
+                .client
+                .read()
+                .await
+                .as_ref()
+                .is_some_and(|client| client.take_restart_notice())
+        });
+        if restarted {
+            object.insert("browser_restarted".to_string(), Value::Bool(true));
+        }
+        value
+    }
+}
+
+impl FgpService for BrowserService {
+    fn name(&self) -> &str {
+        "browser"
+    }
+
+    fn version(&self) -> &str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    fn dispatch(&self, method: &str, params: HashMap<String, Value>) -> Result<Value> {
+        // Check if this is an extension-only method that should be routed to the Chrome extension
+        if is_extension_method(method) {
+            return self.dispatch_to_extension(method, params);
+        }
+
+        // Held until the call returns; calls for other sessions don't wait
+        let endpoint = self.get_endpoint(&params).ok().flatten();
+        let _turn = Self::lane_key(method, &params, endpoint.as_deref()).map(|key| self.lanes.enter(&key));
+
+        let result = match method {
+            "health" => self.handle_health(params),
+            // Navigation and state
+            "browser.open" | "open" => self.handle_open(params),
+            "browser.snapshot" | "snapshot" => self.handle_snapshot(params),
+            "browser.snapshot_markdown" | "snapshot_markdown" => self.handle_snapshot_markdown(params),
+            "browser.snapshot.save" | "snapshot.save" => self.handle_snapshot_save(params),
+            "browser.snapshot.load" | "snapshot.load" => self.handle_snapshot_load(params),
+            "browser.snapshot_diff" | "snapshot_diff" => self.handle_snapshot_diff(params),
+            "browser.screenshot" | "screenshot" => self.handle_screenshot(params),
+            "browser.screenshot_element" | "screenshot_element" => self.handle_screenshot_element(params),
+            "browser.pdf" | "pdf" => self.handle_pdf(params),
+            "browser.fetch" | "fetch" => self.handle_fetch(params),
+            "browser.get_html" | "get_html" => self.handle_get_html(params),
+            "browser.content" | "content" => self.handle_content(params),
+            "browser.get_text" | "get_text" => self.handle_get_text(params),
+            "browser.frames" | "frames" => self.handle_frames(params),
+            "browser.get_element_bounds" | "get_element_bounds" => {
+                self.handle_get_element_bounds(params)
+            }
+            "browser.extract_content" | "extract_content" => self.handle_extract_content(params),
+            "browser.extract_links" | "extract_links" | "browser.get_links" | "get_links" => self.handle_extract_links(params),
+            "browser.extract_images" | "extract_images" => self.handle_extract_images(params),
+            // Emulation
+            "browser.emulate" | "emulate" => self.handle_emulate(params),
+            "browser.set_viewport" | "set_viewport" => self.handle_set_viewport(params),
+            "browser.set_geolocation" | "set_geolocation" => self.handle_set_geolocation(params),
+            "browser.clear_geolocation" | "clear_geolocation" => self.handle_clear_geolocation(params),
+            "browser.grant_permissions" | "grant_permissions" => self.handle_grant_permissions(params),
+            "browser.reset_permissions" | "reset_permissions" => self.handle_reset_permissions(params),
+            "browser.clipboard.read" | "clipboard.read" => self.handle_clipboard_read(params),
+            "browser.clipboard.write" | "clipboard.write" => self.handle_clipboard_write(params),
+            "browser.emulate_media" | "emulate_media" => self.handle_emulate_media(params),
+            "browser.set_network_conditions" | "set_network_conditions" => self.handle_set_network_conditions(params),
+            "browser.network.emulate" | "network.emulate" => self.handle_set_network_conditions(params),
+            "browser.cpu.throttle" | "cpu.throttle" => self.handle_cpu_throttle(params),
+            "browser.metrics" | "metrics" => self.handle_metrics(params),
+            // Interaction
+            "browser.click" | "click" => self.handle_click(params),
+            "browser.double_click" | "double_click" => self.handle_double_click(params),
+            "browser.right_click" | "right_click" => self.handle_right_click(params),
+            "browser.context_menu.select" | "context_menu.select" => self.handle_context_menu_select(params),
+            "browser.fill" | "fill" => self.handle_fill(params),
+            "browser.press" | "press" => self.handle_press(params),
+            "browser.select" | "select" => self.handle_select(params),
+            "browser.check" | "check" => self.handle_check(params),
+            "browser.hover" | "hover" => self.handle_hover(params),
+            "browser.select_text" | "select_text" => self.handle_select_text(params),
+            "browser.drag" | "drag" => self.handle_drag(params),
+            "browser.drag_path" | "drag_path" => self.handle_drag_path(params),
+            "browser.mouse.move" | "mouse.move" => self.handle_mouse_move(params),
+            "browser.mouse.click" | "mouse.click" => self.handle_mouse_click(params),
+            "browser.mouse.wheel" | "mouse.wheel" => self.handle_mouse_wheel(params),
+            "browser.focus" | "focus" => self.handle_focus(params),
+            "browser.clear_field" | "clear_field" => self.handle_clear_field(params),
+            "browser.blur" | "blur" => self.handle_blur(params),
+            "browser.scroll" | "scroll" => self.handle_scroll(params),
+            "browser.scroll.top" | "scroll.top" => self.handle_scroll_edge(params, false),
+            "browser.scroll.bottom" | "scroll.bottom" => self.handle_scroll_edge(params, true),
+            "browser.scroll_to_bottom" | "scroll_to_bottom" => self.handle_scroll_to_bottom(params),
+            "browser.press_combo" | "press_combo" => self.handle_press_combo(params),
+            "browser.key_sequence" | "key_sequence" => self.handle_key_sequence(params),
+            "browser.upload" | "upload" => self.handle_upload(params),
+            "browser.dialog" | "dialog" => self.handle_dialog(params),
+            "browser.set_dialog_handler" | "set_dialog_handler" => self.handle_set_dialog_handler(params),
+            "browser.dialogs.list" | "dialogs.list" => self.handle_dialogs_list(params),
+            // Network capture
+            "browser.network.enable" | "network.enable" => self.handle_network_enable(params),
+            "browser.network.disable" | "network.disable" => self.handle_network_disable(params),
+            "browser.network.list" | "network.list" => self.handle_network_list(params),
+            "browser.network.body" | "network.body" => self.handle_network_body(params),
+            "browser.network.clear" | "network.clear" => self.handle_network_clear(params),
+            "browser.network.capture_start" | "network.capture_start" => {
+                self.handle_network_capture_start(params)
+            }
+            "browser.network.capture_stop" | "network.capture_stop" => {
+                self.handle_network_capture_stop(params)
+            }
+            "browser.network.block" | "network.block" => self.handle_network_block(params),
+            "browser.network.unblock" | "network.unblock" => self.handle_network_unblock(params),
+            "browser.network.rules" | "network.rules" => self.handle_network_rules(params),
+            "browser.network.block_urls" | "network.block_urls" => self.handle_network_block_urls(params),
+            "browser.network.clear_blocked_urls" | "network.clear_blocked_urls" => self.handle_network_clear_blocked_urls(params),
+            "browser.network.mock" | "network.mock" | "browser.mock" | "mock" => self.handle_network_mock(params),
+            "browser.network.mocks" | "network.mocks" => self.handle_network_mocks(params),
+            "browser.network.unmock" | "network.unmock" => self.handle_network_unmock(params),
+            "browser.set_headers" | "set_headers" => self.handle_set_headers(params),
+            // HAR recording
+            "browser.har.start" | "har.start" => self.handle_har_start(params),
+            "browser.har.stop" | "har.stop" => self.handle_har_stop(params),
+            // Downloads
+            "browser.downloads.list" | "downloads.list" => self.handle_downloads_list(params),
+            "browser.downloads.wait" | "downloads.wait" => self.handle_downloads_wait(params),
+            "browser.wait_for_download" | "wait_for_download" => self.handle_wait_for_download(params),
+            "browser.downloads.cleanup" | "downloads.cleanup" => self.handle_downloads_cleanup(params),
+            // Console
+            "browser.console.get" | "console.get" => self.handle_console_get(params),
+            // Auth state
+            "browser.state.save" | "state.save" => self.handle_state_save(params),
+            "browser.state.load" | "state.load" => self.handle_state_load(params),
+            "browser.state.list" | "state.list" => self.handle_state_list(params),
+            "browser.state.delete" | "state.delete" => self.handle_state_delete(params),
+            "browser.state.rename" | "state.rename" => self.handle_state_rename(params),
+            // Cookie files
+            "browser.cookies.export" | "cookies.export" => self.handle_cookies_export(params),
+            "browser.cookies.import" | "cookies.import" => self.handle_cookies_import(params),
+            "browser.storage.export" | "storage.export" => self.handle_storage_export(params),
+            "browser.storage.import" | "storage.import" => self.handle_storage_import(params),
+            "browser.indexeddb.get_all" | "indexeddb.get_all" => self.handle_indexeddb_get_all(params),
+            "browser.indexeddb.list_stores" | "indexeddb.list_stores" => self.handle_indexeddb_list_stores(params),
+            // Session management
+            "browser.session.new" | "session.new" => self.handle_session_new(params),
+            "browser.session.clone" | "session.clone" => self.handle_session_clone(params),
+            "browser.session.list" | "session.list" => self.handle_session_list(params),
+            "browser.session.info" | "session.info" => self.handle_session_info(params),
+            "browser.session.close" | "session.close" => self.handle_session_close(params),
+            "browser.session.attach" | "session.attach" => self.handle_session_attach(params),
+            "browser.session.set_metadata" | "session.set_metadata" => self.handle_session_set_metadata(params),
+            "browser.session.get_metadata" | "session.get_metadata" => self.handle_session_get_metadata(params),
+            "browser.session.set_idle_timeout" | "session.set_idle_timeout" => self.handle_session_set_idle_timeout(params),
+            "browser.targets" | "targets" => self.handle_targets(params),
+            // Tabs (default session)
+            "browser.tabs.list" | "tabs.list" => self.handle_tabs_list(params),
+            "browser.tabs.switch" | "tabs.switch" => self.handle_tabs_switch(params),
+            "browser.tabs.attach" | "tabs.attach" => self.handle_tabs_attach(params),
+            "browser.extension.status" | "extension.status" => self.handle_extension_status(params),
+            "browser.tabs.open_new" | "tabs.open_new" => self.handle_tabs_open_new(params),
+            "browser.tabs.close" | "tabs.close" => self.handle_tabs_close(params),
+            "browser.pages.list" | "pages.list" => self.handle_pages_list(params),
+            "browser.pages.switch" | "pages.switch" => self.handle_pages_switch(params),
+            "browser.tab.new" | "tab.new" => self.handle_tab_new(params),
+            "browser.tab.list" | "tab.list" => self.handle_tab_list(params),
+            "browser.tab.switch" | "tab.switch" => self.handle_tab_switch(params),
+            "browser.tab.close" | "tab.close" => self.handle_tab_close(params),
+            _ => Err(anyhow::anyhow!("Unknown method: {}", method)),
+        };
+        result.map(|value| self.note_restart(value, endpoint.as_deref()))
+    }
+
+    fn method_list(&self) -> Vec<MethodInfo> {
+        // Common session parameter schema
+        let session_param = || {
+            SchemaBuilder::string()
+                .description("Session ID for isolated browser context (optional)")
+        };
+
+        // Pooled Chrome to target instead of the daemon's own (needs --client-pool-ttl)
+        let endpoint_param = || {
+            SchemaBuilder::string()
+                .description("Chrome debugging URL to run against (optional)")
+        };
+
+        // Iframe target for interactions
+        let frame_param = || {
+            SchemaBuilder::string().description(
+                "Target an iframe by index in document order (\"0\" = first) or by URL pattern / frame name. \
+                 Nested frames: an array such as [\"checkout\", \"card\"] or \"checkout > card\". \
+                 Cross-origin iframes cannot be targeted",
+            )
+        };
+
+        let network_conditions_params = || {
+            SchemaBuilder::object()
+                .property(
+                    "profile",
+                    SchemaBuilder::string()
+                        .enum_values(&network_profile_names())
+                        .description("Named profile; explicit fields override it"),
+                )
+                .property(
+                    "preset",
+                    SchemaBuilder::string().description("Alias of 'profile'; \"none\" lifts throttling"),
+                )
+                .property(
+                    "latency_ms",
+                    SchemaBuilder::number()
+                        .minimum(0)
+                        .description("Latency added to each request"),
+                )
+                .property(
+                    "download_kbps",
+                    SchemaBuilder::number()
+                        .minimum(0)
+                        .description("Download throughput in kbit/s"),
+                )
+                .property(
+                    "upload_kbps",
+                    SchemaBuilder::number()
+                        .minimum(0)
+                        .description("Upload throughput in kbit/s"),
+                )
+                .property(
+                    "latency",
+                    SchemaBuilder::number().minimum(0).description("Alias of 'latency_ms'"),
+                )
+                .property(
+                    "download_throughput",
+                    SchemaBuilder::number()
+                        .minimum(0)
+                        .description("Download throughput in bytes/s (as in CDP); 'download_kbps' wins"),
+                )
+                .property(
+                    "upload_throughput",
+                    SchemaBuilder::number()
+                        .minimum(0)
+                        .description("Upload throughput in bytes/s (as in CDP); 'upload_kbps' wins"),
+                )
+                .property(
+                    "offline",
+                    SchemaBuilder::boolean().description("Fail every request"),
+                )
+                .property(
+                    "reset",
+                    SchemaBuilder::boolean()
+                        .default_value(json!(false))
+                        .description("Lift throttling"),
+                )
+                .property("session_id", session_param())
+                .property("endpoint", endpoint_param())
+                .build()
+        };
+
+        let network_conditions_result = || {
+            SchemaBuilder::object()
+                .property("throttling", SchemaBuilder::boolean())
+                .property(
+                    "conditions",
+                    SchemaBuilder::object()
+                        .property("profile", SchemaBuilder::string())
+                        .property("offline", SchemaBuilder::boolean())
+                        .property("latency_ms", SchemaBuilder::number())
+                        .property("download_kbps", SchemaBuilder::number())
+                        .property("upload_kbps", SchemaBuilder::number()),
+                )
+                .build()
+        };
+
+        let page_info_schema = || {
+            SchemaBuilder::object()
+                .property("index", SchemaBuilder::integer())
+                .property("target_id", SchemaBuilder::string())
+                .property("url", SchemaBuilder::string())
+                .property("title", SchemaBuilder::string())
+                .property("opener", SchemaBuilder::integer().description("Index of the page that opened this one"))
+                .property("active", SchemaBuilder::boolean())
+        };
+
+        let tab_info_schema = || {
+            SchemaBuilder::object()
+                .property("target_id", SchemaBuilder::string())
+                .property("url", SchemaBuilder::string())
+                .property("title", SchemaBuilder::string())
+                .property("active", SchemaBuilder::boolean().description("Whether the session's commands use this tab"))
+                .property("session_id", SchemaBuilder::string().description("Session driving this tab, if any"))
+        };
+
+        let block_status_schema = || {
+            SchemaBuilder::object()
+                .property("url_patterns", SchemaBuilder::array().items(SchemaBuilder::string()))
+                .property("resource_types", SchemaBuilder::array().items(SchemaBuilder::string()))
+                .property("blocked_count", SchemaBuilder::integer())
+                .property("blocked_urls", SchemaBuilder::array().items(SchemaBuilder::string()))
+                .build()
+        };
+
+        let mock_schema = || {
+            SchemaBuilder::object()
+                .property("id", SchemaBuilder::string())
+                .property("url_pattern", SchemaBuilder::string())
+                .property("status", SchemaBuilder::integer())
+                .property("headers", SchemaBuilder::object())
+                .property("body_size", SchemaBuilder::integer())
+                .property("hit_count", SchemaBuilder::integer())
+        };
+
+        let download_schema = || {
+            SchemaBuilder::object()
+                .property("guid", SchemaBuilder::string())
+                .property("session_id", SchemaBuilder::string())
+                .property("url", SchemaBuilder::string())
+                .property("filename", SchemaBuilder::string())
+                .property("path", SchemaBuilder::string())
+                .property(
+                    "state",
+                    SchemaBuilder::string().enum_values(&["in_progress", "completed", "canceled"]),
+                )
+                .property("received_bytes", SchemaBuilder::integer())
+                .property("total_bytes", SchemaBuilder::integer())
+                .property("size", SchemaBuilder::integer().description("Size on disk once completed"))
+        };
+
+        vec![
+            // ================================================================
+            // Navigation and State
+            // ================================================================
+            MethodInfo::new("browser.open", "Navigate to a URL")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "url",
+                            SchemaBuilder::string()
+                                .format("uri")
+                                .description("URL to navigate to"),
+                        )
+                        .property(
+                            "wait_until",
+                            SchemaBuilder::string()
+                                .enum_values(&["load", "domcontentloaded", "networkidle"])
+                                .default_value(json!("load"))
+                                .description("When to consider navigation complete"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["url"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("url", SchemaBuilder::string().format("uri"))
+                        .property("title", SchemaBuilder::string())
+                        .property(
+                            "load_time_ms",
+                            SchemaBuilder::integer()
+                                .description("Wall-clock time from starting navigation until the page loaded"),
+                        )
+                        .property("status", SchemaBuilder::integer())
+                        .property(
+                            "network_conditions",
+                            SchemaBuilder::object()
+                                .description("Present when the session's network is throttled"),
+                        )
+                        .build(),
+                )
+                .example("Navigate to Google", json!({"url": "https://google.com"}))
+                .example(
+                    "Wait for network idle",
+                    json!({"url": "https://example.com", "wait_until": "networkidle"}),
+                )
+                .errors(&["NAVIGATION_FAILED", "TIMEOUT"]),
+
+            MethodInfo::new("browser.snapshot", "Get ARIA accessibility tree with @eN refs for element targeting")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "snapshot_depth",
+                            SchemaBuilder::integer()
+                                .minimum(0)
+                                .description("Maximum tree depth to include (0 = top level only, default unlimited)"),
+                        )
+                        .property(
+                            "include_frames",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Also include same-origin iframes; their nodes carry a 'frame' index"),
+                        )
+                        .property(
+                            "interactive_only",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Keep only actionable elements (buttons, links, inputs, focusable nodes)"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property(
+                            "snapshot",
+                            SchemaBuilder::string()
+                                .description("ARIA tree with @eN refs for clicking/filling"),
+                        )
+                        .property("url", SchemaBuilder::string().format("uri"))
+                        .property("title", SchemaBuilder::string())
+                        .build(),
+                )
+                .example("Get page snapshot", json!({}))
+                .example("Limit snapshot depth", json!({"snapshot_depth": 2}))
+                .example("Only clickable and fillable elements", json!({"interactive_only": true})),
+
+            MethodInfo::new("browser.snapshot_markdown", "Get ARIA tree as compact Markdown with @eN refs")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "snapshot_depth",
+                            SchemaBuilder::integer()
+                                .minimum(0)
+                                .description("Maximum tree depth to include (0 = top level only, default unlimited)"),
+                        )
+                        .property(
+                            "include_frames",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Also include same-origin iframes; their nodes carry a 'frame' index"),
+                        )
+                        .property(
+                            "interactive_only",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Keep only actionable elements (buttons, links, inputs, focusable nodes)"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property(
+                            "markdown",
+                            SchemaBuilder::string()
+                                .description("One line per node: '- [role] name (value) @eN', headings as '## name'"),
+                        )
+                        .property("url", SchemaBuilder::string().format("uri"))
+                        .property("title", SchemaBuilder::string())
+                        .property("element_count", SchemaBuilder::integer())
+                        .build(),
+                )
+                .example("Get Markdown snapshot", json!({})),
+
+            MethodInfo::new("browser.snapshot.save", "Save the current ARIA snapshot under a name")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("name", SchemaBuilder::string().min_length(1))
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["name"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("name", SchemaBuilder::string())
+                        .property("path", SchemaBuilder::string())
+                        .property("url", SchemaBuilder::string().format("uri"))
+                        .property("element_count", SchemaBuilder::integer())
+                        .property("saved_at", SchemaBuilder::string().format("date-time"))
+                        .build(),
+                )
+                .example("Save before submitting a form", json!({"name": "before-submit"}))
+                .errors(&["INVALID_NAME"]),
+
+            MethodInfo::new("browser.snapshot.load", "Load a saved ARIA snapshot")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("name", SchemaBuilder::string().min_length(1))
+                        .required(&["name"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("name", SchemaBuilder::string())
+                        .property("saved_at", SchemaBuilder::string().format("date-time"))
+                        .property("url", SchemaBuilder::string().format("uri"))
+                        .property("title", SchemaBuilder::string())
+                        .property("nodes", SchemaBuilder::array())
+                        .property("element_count", SchemaBuilder::integer())
+                        .build(),
+                )
+                .example("Load a snapshot", json!({"name": "before-submit"}))
+                .errors(&["SNAPSHOT_NOT_FOUND", "INVALID_NAME"]),
+
+            MethodInfo::new("browser.snapshot_diff", "Diff a saved ARIA snapshot against the current page")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "before_snapshot",
+                            SchemaBuilder::string()
+                                .min_length(1)
+                                .description("Name from browser.snapshot.save"),
+                        )
+                        .property(
+                            "after_snapshot",
+                            SchemaBuilder::string()
+                                .description("Compare against this saved snapshot instead of the live page"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["before_snapshot"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("before_url", SchemaBuilder::string().format("uri"))
+                        .property("after_url", SchemaBuilder::string().format("uri"))
+                        .property("added", SchemaBuilder::array().description("Nodes only in the newer snapshot"))
+                        .property("removed", SchemaBuilder::array().description("Nodes only in the saved snapshot"))
+                        .property(
+                            "changed",
+                            SchemaBuilder::array().items(
+                                SchemaBuilder::object()
+                                    .property("before", SchemaBuilder::object())
+                                    .property("after", SchemaBuilder::object())
+                                    .property("fields", SchemaBuilder::array().items(SchemaBuilder::string())),
+                            ),
+                        )
+                        .build(),
+                )
+                .example("What changed after clicking", json!({"before_snapshot": "before-submit"}))
+                .errors(&["SNAPSHOT_NOT_FOUND"]),
+
+            MethodInfo::new("browser.screenshot", "Capture screenshot as base64 or save to file")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "path",
+                            SchemaBuilder::string()
+                                .description("File path to save screenshot (optional, returns base64 if omitted)"),
+                        )
+                        .property(
+                            "full_page",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Capture full scrollable page"),
+                        )
+                        .property(
+                            "format",
+                            SchemaBuilder::string()
+                                .enum_values(&["png", "jpeg", "webp"])
+                                .default_value(json!("png"))
+                                .description("Image format"),
+                        )
+                        .property(
+                            "quality",
+                            SchemaBuilder::integer()
+                                .minimum(0)
+                                .maximum(100)
+                                .default_value(json!(80))
+                                .description("Compression quality for jpeg/webp"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property(
+                            "base64",
+                            SchemaBuilder::string()
+                                .description("Base64-encoded image (if no path specified)"),
+                        )
+                        .property(
+                            "path",
+                            SchemaBuilder::string()
+                                .description("Saved file path (if path was specified)"),
+                        )
+                        .property(
+                            "format",
+                            SchemaBuilder::string()
+                                .description("Content type, e.g. image/png"),
+                        )
+                        .property("width", SchemaBuilder::integer())
+                        .property("height", SchemaBuilder::integer())
+                        .build(),
+                )
+                .example("Get base64 screenshot", json!({}))
+                .example("Save to file", json!({"path": "/tmp/screenshot.png", "full_page": true}))
+                .example("Compressed JPEG", json!({"format": "jpeg", "quality": 60}))
+                .errors(&["INVALID_FORMAT", "INVALID_QUALITY"]),
+
+            MethodInfo::new("browser.screenshot_element", "Capture a screenshot cropped to one element")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "selector",
+                            SchemaBuilder::string().description("@eN ref or CSS selector of the element to capture"),
+                        )
+                        .property(
+                            "path",
+                            SchemaBuilder::string()
+                                .description("File path to save screenshot (optional, returns base64 if omitted)"),
+                        )
+                        .property(
+                            "format",
+                            SchemaBuilder::string()
+                                .enum_values(&["png", "jpeg", "webp"])
+                                .default_value(json!("png"))
+                                .description("Image format"),
+                        )
+                        .property(
+                            "quality",
+                            SchemaBuilder::integer()
+                                .minimum(0)
+                                .maximum(100)
+                                .default_value(json!(80))
+                                .description("Compression quality for jpeg/webp"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["selector"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("data", SchemaBuilder::string().description("Base64-encoded image (if no path specified)"))
+                        .property("path", SchemaBuilder::string().description("Saved file path (if path was specified)"))
+                        .property("format", SchemaBuilder::string().description("Content type, e.g. image/png"))
+                        .property("width", SchemaBuilder::integer().description("Image width in pixels"))
+                        .property("height", SchemaBuilder::integer().description("Image height in pixels"))
+                        .build(),
+                )
+                .example("Capture a chart", json!({"selector": "#revenue-chart", "path": "/tmp/chart.png"}))
+                .example("Capture a snapshot ref", json!({"selector": "@e12"}))
+                .errors(&["ELEMENT_NOT_FOUND", "INVALID_FORMAT", "INVALID_QUALITY"]),
+
+            MethodInfo::new("browser.pdf", "Print page to PDF as base64 or save to file (headless only)")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "path",
+                            SchemaBuilder::string()
+                                .description("File path to save PDF (optional, returns base64 if omitted)"),
+                        )
+                        .property(
+                            "landscape",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Landscape orientation"),
+                        )
+                        .property(
+                            "print_background",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Print background graphics"),
+                        )
+                        .property(
+                            "scale",
+                            SchemaBuilder::number()
+                                .default_value(json!(1))
+                                .description("Rendering scale (0.1 - 2.0)"),
+                        )
+                        .property(
+                            "paper_width",
+                            SchemaBuilder::number()
+                                .default_value(json!(8.5))
+                                .description("Paper width in inches"),
+                        )
+                        .property(
+                            "paper_height",
+                            SchemaBuilder::number()
+                                .default_value(json!(11))
+                                .description("Paper height in inches"),
+                        )
+                        .property(
+                            "margin",
+                            SchemaBuilder::number()
+                                .description("Margin in inches, applied to all sides"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property(
+                            "data",
+                            SchemaBuilder::string()
+                                .description("Base64-encoded PDF (if no path specified)"),
+                        )
+                        .property(
+                            "path",
+                            SchemaBuilder::string()
+                                .description("Saved file path (if path was specified)"),
+                        )
+                        .property("size_bytes", SchemaBuilder::integer())
+                        .build(),
+                )
+                .example("Save invoice", json!({"path": "/tmp/invoice.pdf", "print_background": true}))
+                .example("A4 landscape", json!({"landscape": true, "paper_width": 8.27, "paper_height": 11.69}))
+                .errors(&["HEADLESS_REQUIRED"]),
+
+            MethodInfo::new("browser.fetch", "Download a URL with the session's cookies without rendering it")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "url",
+                            SchemaBuilder::string()
+                                .format("uri")
+                                .description("URL to fetch"),
+                        )
+                        .property(
+                            "path",
+                            SchemaBuilder::string()
+                                .description("File path to save the body (optional, returns base64 if omitted)"),
+                        )
+                        .property(
+                            "max_bytes",
+                            SchemaBuilder::integer()
+                                .minimum(0)
+                                .default_value(json!(DEFAULT_FETCH_MAX_BYTES))
+                                .description("Fail instead of fetching a larger body"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["url"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("url", SchemaBuilder::string().description("Final URL after redirects"))
+                        .property("status", SchemaBuilder::integer())
+                        .property("content_type", SchemaBuilder::string())
+                        .property(
+                            "data",
+                            SchemaBuilder::string()
+                                .description("Base64-encoded body (if no path specified)"),
+                        )
+                        .property(
+                            "path",
+                            SchemaBuilder::string()
+                                .description("Saved file path (if path was specified)"),
+                        )
+                        .property("size_bytes", SchemaBuilder::integer())
+                        .property(
+                            "via",
+                            SchemaBuilder::string()
+                                .enum_values(&["page", "network"])
+                                .description("'network' when CORS blocked the in-page fetch"),
+                        )
+                        .build(),
+                )
+                .example("Save an invoice", json!({"url": "https://example.com/invoice/42.pdf", "path": "/tmp/invoice.pdf"}))
+                .errors(&["TOO_LARGE"]),
+
+            MethodInfo::new("browser.frames", "List the page's iframes in document order")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("count", SchemaBuilder::integer())
+                        .property(
+                            "frames",
+                            SchemaBuilder::array().items(
+                                SchemaBuilder::object()
+                                    .property("index", SchemaBuilder::integer())
+                                    .property("url", SchemaBuilder::string())
+                                    .property("name", SchemaBuilder::string()),
+                            ),
+                        )
+                        .build(),
+                )
+                .example("List frames", json!({})),
+
+            MethodInfo::new("browser.get_html", "Get outer or inner HTML of an element or the full page")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "selector",
+                            SchemaBuilder::string()
+                                .description("@eN ref or CSS selector (omit for the full document)"),
+                        )
+                        .property(
+                            "inner",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Return innerHTML instead of outerHTML"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("html", SchemaBuilder::string())
+                        .property("length", SchemaBuilder::integer())
+                        .build(),
+                )
+                .example("Full page HTML", json!({}))
+                .example("Table contents", json!({"selector": "table#results", "inner": true}))
+                .errors(&["ELEMENT_NOT_FOUND"]),
+
+            MethodInfo::new("browser.content", "Get the page's serialized HTML with its URL and title")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "selector",
+                            SchemaBuilder::string()
+                                .description("@eN ref or CSS selector; returns that element's outerHTML instead of the document's"),
+                        )
+                        .property(
+                            "max_bytes",
+                            SchemaBuilder::integer()
+                                .minimum(1)
+                                .default_value(json!(DEFAULT_CONTENT_MAX_BYTES))
+                                .description("Fail with CONTENT_TOO_LARGE above this size instead of truncating"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("html", SchemaBuilder::string())
+                        .property("url", SchemaBuilder::string())
+                        .property("title", SchemaBuilder::string())
+                        .property("length", SchemaBuilder::integer().description("Bytes of HTML"))
+                        .build(),
+                )
+                .example("Whole document", json!({}))
+                .example("One subtree", json!({"selector": "main"}))
+                .errors(&["ELEMENT_NOT_FOUND", "CONTENT_TOO_LARGE"]),
+
+            MethodInfo::new("browser.get_text", "Get the visible text of an element or the full page")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "selector",
+                            SchemaBuilder::string()
+                                .description("@eN ref or CSS selector (omit for the whole body)"),
+                        )
+                        .property("frame", frame_param())
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("text", SchemaBuilder::string())
+                        .property("length", SchemaBuilder::integer())
+                        .build(),
+                )
+                .example("Order total", json!({"selector": ".total"}))
+                .example("Text inside an iframe", json!({"selector": ".card-error", "frame": "card"}))
+                .errors(&["ELEMENT_NOT_FOUND", "FRAME_NOT_FOUND"]),
+
+            MethodInfo::new("browser.get_element_bounds", "Get an element's bounding box (viewport CSS pixels)")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "selector",
+                            SchemaBuilder::string()
+                                .description("@eN ref or CSS selector"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["selector"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("x", SchemaBuilder::number())
+                        .property("y", SchemaBuilder::number())
+                        .property("width", SchemaBuilder::number())
+                        .property("height", SchemaBuilder::number())
+                        .property("top", SchemaBuilder::number())
+                        .property("right", SchemaBuilder::number())
+                        .property("bottom", SchemaBuilder::number())
+                        .property("left", SchemaBuilder::number())
+                        .property("in_viewport", SchemaBuilder::boolean())
+                        .build(),
+                )
+                .example("Bounds of a button", json!({"selector": "@e5"}))
+                .errors(&["ELEMENT_NOT_FOUND"]),
+
+            MethodInfo::new("browser.extract_content", "Extract the main article content as Markdown (readability-style)")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("title", SchemaBuilder::string())
+                        .property("byline", SchemaBuilder::string())
+                        .property(
+                            "markdown",
+                            SchemaBuilder::string().description("Main content as Markdown"),
+                        )
+                        .property("text", SchemaBuilder::string())
+                        .property("word_count", SchemaBuilder::integer())
+                        .property(
+                            "fallback",
+                            SchemaBuilder::boolean()
+                                .description("True if no main content was found and body text was returned"),
+                        )
+                        .build(),
+                )
+                .example("Extract article", json!({})),
+
+            MethodInfo::new("browser.extract_links", "Extract all links on the page as {text, href, rel, target} (alias: browser.get_links)")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "selector",
+                            SchemaBuilder::string()
+                                .description("@eN ref or CSS selector to scope extraction (optional)"),
+                        )
+                        .property(
+                            "same_origin_only",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Only include links on the page's origin (also accepted as same_origin)"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property(
+                            "links",
+                            SchemaBuilder::array().items(
+                                SchemaBuilder::object()
+                                    .property("text", SchemaBuilder::string())
+                                    .property("href", SchemaBuilder::string().format("uri"))
+                                    .property("rel", SchemaBuilder::string())
+                                    .property("target", SchemaBuilder::string()),
+                            ),
+                        )
+                        .property("count", SchemaBuilder::integer())
+                        .build(),
+                )
+                .example("All links", json!({}))
+                .example("Same-origin links in nav", json!({"selector": "nav", "same_origin_only": true}))
+                .errors(&["ELEMENT_NOT_FOUND"]),
+
+            MethodInfo::new("browser.extract_images", "Extract all images on the page as {src, alt, width, height}")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "selector",
+                            SchemaBuilder::string()
+                                .description("@eN ref or CSS selector to scope extraction (optional)"),
+                        )
+                        .property(
+                            "same_origin_only",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Only include images on the page's origin"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property(
+                            "images",
+                            SchemaBuilder::array().items(
+                                SchemaBuilder::object()
+                                    .property("src", SchemaBuilder::string().format("uri"))
+                                    .property("alt", SchemaBuilder::string())
+                                    .property("width", SchemaBuilder::integer())
+                                    .property("height", SchemaBuilder::integer()),
+                            ),
+                        )
+                        .property("count", SchemaBuilder::integer())
+                        .build(),
+                )
+                .example("All images", json!({}))
+                .example("Images in article", json!({"selector": "article"}))
+                .errors(&["ELEMENT_NOT_FOUND"]),
+
+            // ================================================================
+            // Emulation
+            // ================================================================
+            MethodInfo::new("browser.emulate", "Emulate a device viewport (preset or explicit metrics)")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "preset",
+                            SchemaBuilder::string()
+                                .enum_values(&device_preset_names())
+                                .description("Named device preset; explicit fields override it"),
+                        )
+                        .property(
+                            "width",
+                            SchemaBuilder::integer()
+                                .minimum(1)
+                                .description("Viewport width in CSS pixels (required without preset)"),
+                        )
+                        .property(
+                            "height",
+                            SchemaBuilder::integer()
+                                .minimum(1)
+                                .description("Viewport height in CSS pixels (required without preset)"),
+                        )
+                        .property(
+                            "device_scale_factor",
+                            SchemaBuilder::number()
+                                .default_value(json!(1.0))
+                                .description("Device pixel ratio"),
+                        )
+                        .property(
+                            "is_mobile",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Mobile viewport with touch events"),
+                        )
+                        .property(
+                            "user_agent",
+                            SchemaBuilder::string().description("User agent override"),
+                        )
+                        .property(
+                            "reset",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Clear all emulation overrides"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("emulating", SchemaBuilder::boolean())
+                        .property(
+                            "settings",
+                            SchemaBuilder::object()
+                                .property("width", SchemaBuilder::integer())
+                                .property("height", SchemaBuilder::integer())
+                                .property("device_scale_factor", SchemaBuilder::number())
+                                .property("is_mobile", SchemaBuilder::boolean())
+                                .property("user_agent", SchemaBuilder::string()),
+                        )
+                        .build(),
+                )
+                .example("Emulate iPhone 14", json!({"preset": "iphone-14"}))
+                .example("Custom viewport", json!({"width": 1280, "height": 720}))
+                .example("Reset", json!({"reset": true}))
+                .errors(&["UNKNOWN_PRESET"]),
+
+            MethodInfo::new("browser.set_viewport", "Resize the page viewport")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "width",
+                            SchemaBuilder::integer()
+                                .minimum(1)
+                                .description("Viewport width in CSS pixels"),
+                        )
+                        .property(
+                            "height",
+                            SchemaBuilder::integer()
+                                .minimum(1)
+                                .description("Viewport height in CSS pixels"),
+                        )
+                        .property(
+                            "device_scale_factor",
+                            SchemaBuilder::number().description("Device pixel ratio"),
+                        )
+                        .property(
+                            "mobile",
+                            SchemaBuilder::boolean()
+                                .description("Mobile viewport with touch events"),
+                        )
+                        .property(
+                            "device",
+                            SchemaBuilder::string()
+                                .enum_values(&device_preset_names())
+                                .description("Device preset (viewport, user agent and touch); width/height override it"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("width", SchemaBuilder::integer())
+                        .property("height", SchemaBuilder::integer())
+                        .property("device_scale_factor", SchemaBuilder::number())
+                        .property("is_mobile", SchemaBuilder::boolean())
+                        .property("user_agent", SchemaBuilder::string())
+                        .build(),
+                )
+                .example("Laptop viewport", json!({"width": 1366, "height": 768}))
+                .example("Phone", json!({"device": "iPhone 14"})),
+
+            MethodInfo::new("browser.set_geolocation", "Override geolocation and grant the permission")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "latitude",
+                            SchemaBuilder::number().minimum(-90).maximum(90),
+                        )
+                        .property(
+                            "longitude",
+                            SchemaBuilder::number().minimum(-180).maximum(180),
+                        )
+                        .property(
+                            "accuracy",
+                            SchemaBuilder::number()
+                                .minimum(0)
+                                .default_value(json!(0))
+                                .description("Accuracy in meters"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["latitude", "longitude"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("latitude", SchemaBuilder::number())
+                        .property("longitude", SchemaBuilder::number())
+                        .property("accuracy", SchemaBuilder::number())
+                        .property(
+                            "origin",
+                            SchemaBuilder::string()
+                                .description("Origin granted the permission (null = all origins)"),
+                        )
+                        .build(),
+                )
+                .example("Berlin", json!({"latitude": 52.52, "longitude": 13.405}))
+                .errors(&["INVALID_COORDINATES"]),
+
+            MethodInfo::new("browser.clear_geolocation", "Remove the geolocation override")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("success", SchemaBuilder::boolean())
+                        .build(),
+                )
+                .example("Clear override", json!({})),
+
+            MethodInfo::new("browser.grant_permissions", "Grant permissions in the session's browser context")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "permissions",
+                            SchemaBuilder::array()
+                                .items(SchemaBuilder::string())
+                                .description("Permission names, e.g. geolocation, notifications, clipboard-read, camera"),
+                        )
+                        .property(
+                            "origin",
+                            SchemaBuilder::string()
+                                .description("Origin to grant for (default: the page's current origin)"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["permissions"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("granted", SchemaBuilder::array().items(SchemaBuilder::string()))
+                        .property("origin", SchemaBuilder::string())
+                        .build(),
+                )
+                .example("Allow notifications", json!({"permissions": ["notifications"]})),
+
+            MethodInfo::new("browser.reset_permissions", "Reset all permission grants for the session")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("success", SchemaBuilder::boolean())
+                        .build(),
+                )
+                .example("Reset", json!({})),
+
+            MethodInfo::new("browser.clipboard.read", "Read the clipboard's text (grants clipboard permissions for the page's origin)")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("text", SchemaBuilder::string())
+                        .build(),
+                )
+                .example("Read after copying", json!({})),
+
+            MethodInfo::new("browser.clipboard.write", "Put text on the clipboard (grants clipboard permissions for the page's origin)")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("text", SchemaBuilder::string().description("Text to copy"))
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["text"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("success", SchemaBuilder::boolean())
+                        .property("length", SchemaBuilder::integer().description("Characters written"))
+                        .build(),
+                )
+                .example("Copy, then paste with press", json!({"text": "INV-2024-0042"})),
+
+            MethodInfo::new("browser.emulate_media", "Emulate CSS media: color scheme, reduced motion, print (persists across navigations)")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "color_scheme",
+                            SchemaBuilder::string()
+                                .enum_values(&["dark", "light"])
+                                .description("prefers-color-scheme"),
+                        )
+                        .property(
+                            "reduced_motion",
+                            SchemaBuilder::boolean().description("prefers-reduced-motion: reduce"),
+                        )
+                        .property(
+                            "media",
+                            SchemaBuilder::string()
+                                .enum_values(&["print", "screen"])
+                                .description("CSS media type"),
+                        )
+                        .property(
+                            "reset",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Clear all media emulation"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("emulating", SchemaBuilder::boolean())
+                        .property(
+                            "media",
+                            SchemaBuilder::object()
+                                .property("color_scheme", SchemaBuilder::string())
+                                .property("reduced_motion", SchemaBuilder::boolean())
+                                .property("media", SchemaBuilder::string()),
+                        )
+                        .build(),
+                )
+                .example("Dark mode", json!({"color_scheme": "dark"}))
+                .example("Print stylesheet", json!({"media": "print"}))
+                .example("Reset", json!({"reset": true})),
+
+            MethodInfo::new("browser.set_network_conditions", "Throttle the session's network (persists across navigations)")
+                .schema(network_conditions_params())
+                .returns(network_conditions_result())
+                .example("Slow 3G", json!({"profile": "slow-3g"}))
+                .example("Custom", json!({"latency_ms": 300, "download_kbps": 1000, "upload_kbps": 500}))
+                .example("Offline", json!({"profile": "offline"}))
+                .example("Reset", json!({"reset": true}))
+                .errors(&["UNKNOWN_PROFILE"]),
+
+            MethodInfo::new("browser.network.emulate", "Emulate slow or offline networks for the session (same as set_network_conditions)")
+                .schema(network_conditions_params())
+                .returns(network_conditions_result())
+                .example("Slow 3G", json!({"preset": "slow-3g"}))
+                .example("Offline", json!({"offline": true}))
+                .example("CDP units", json!({"download_throughput": 50000, "upload_throughput": 20000, "latency": 400}))
+                .example("Reset", json!({"preset": "none"}))
+                .errors(&["UNKNOWN_PROFILE"]),
+
+            MethodInfo::new("browser.cpu.throttle", "Slow the session's CPU down, e.g. to reproduce low-end devices (persists across navigations)")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "rate",
+                            SchemaBuilder::number()
+                                .minimum(1)
+                                .description("Slowdown factor: 4 = 4x slower, 1 = full speed"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["rate"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("throttling", SchemaBuilder::boolean())
+                        .property("rate", SchemaBuilder::number())
+                        .build(),
+                )
+                .example("4x slowdown", json!({"rate": 4}))
+                .example("Full speed", json!({"rate": 1})),
+
+            MethodInfo::new("browser.metrics", "Performance metrics and navigation timing for the current page")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("url", SchemaBuilder::string().format("uri"))
+                        .property(
+                            "metrics",
+                            SchemaBuilder::object()
+                                .description("CDP Performance.getMetrics values by name, e.g. JSHeapUsedSize, ScriptDuration, LayoutCount"),
+                        )
+                        .property(
+                            "timing",
+                            SchemaBuilder::object()
+                                .property("dom_content_loaded_ms", SchemaBuilder::number())
+                                .property("load_ms", SchemaBuilder::number())
+                                .property("first_contentful_paint_ms", SchemaBuilder::number())
+                                .description("Milliseconds since navigation start; null until the event happens"),
+                        )
+                        .build(),
+                )
+                .example("Current page", json!({})),
+
+            // ================================================================
+            // Interaction
+            // ================================================================
+            MethodInfo::new("browser.click", "Click element by @eN ref or CSS selector")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "selector",
+                            SchemaBuilder::string()
+                                .description("@eN ref from snapshot or CSS selector"),
+                        )
+                        .property(
+                            "button",
+                            SchemaBuilder::string()
+                                .enum_values(&["left", "right", "middle"])
+                                .default_value(json!("left")),
+                        )
+                        .property(
+                            "click_count",
+                            SchemaBuilder::integer()
+                                .minimum(1)
+                                .maximum(3)
+                                .default_value(json!(1))
+                                .description("2 double-clicks like browser.double_click (not inside frames)"),
+                        )
+                        .property("frame", frame_param())
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["selector"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("clicked", SchemaBuilder::boolean())
+                        .property("selector", SchemaBuilder::string())
+                        .build(),
+                )
+                .example("Click by ref", json!({"selector": "@e15"}))
+                .example("Double-click", json!({"selector": "@e20", "click_count": 2}))
+                .example("Click inside nested iframes", json!({"selector": "#pay", "frame": ["checkout", "card"]}))
+                .errors(&["ELEMENT_NOT_FOUND", "ELEMENT_NOT_VISIBLE", "FRAME_NOT_FOUND"]),
+
+            MethodInfo::new("browser.double_click", "Double-click an element, firing dblclick listeners")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "selector",
+                            SchemaBuilder::string()
+                                .description("@eN ref from snapshot or CSS selector"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["selector"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("success", SchemaBuilder::boolean())
+                        .property("element", SchemaBuilder::string())
+                        .build(),
+                )
+                .example("Open a file row", json!({"selector": "@e20"}))
+                .errors(&["ELEMENT_NOT_FOUND"]),
+
+            MethodInfo::new("browser.right_click", "Right-click an element and report the contextmenu event")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "selector",
+                            SchemaBuilder::string()
+                                .description("@eN ref from snapshot or CSS selector"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["selector"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("success", SchemaBuilder::boolean())
+                        .property("element", SchemaBuilder::string())
+                        .property(
+                            "context_menu",
+                            SchemaBuilder::object()
+                                .property("target", SchemaBuilder::string())
+                                .property("default_prevented", SchemaBuilder::boolean())
+                                .description("Absent if no contextmenu event fired; default_prevented means the page opened its own menu"),
+                        )
+                        .build(),
+                )
+                .example("Open a row's menu", json!({"selector": "@e20"}))
+                .errors(&["ELEMENT_NOT_FOUND"]),
+
+            MethodInfo::new("browser.context_menu.select", "Click an item of an open custom (JavaScript) context menu")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "item_text",
+                            SchemaBuilder::string()
+                                .description("Item text; exact match preferred, ARIA menu items may also match a substring"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["item_text"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("success", SchemaBuilder::boolean())
+                        .property("element", SchemaBuilder::string())
+                        .build(),
+                )
+                .example("Rename via the menu", json!({"item_text": "Rename"}))
+                .errors(&["ELEMENT_NOT_FOUND"]),
+
+            MethodInfo::new("browser.fill", "Fill input field with value")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "selector",
+                            SchemaBuilder::string()
+                                .description("@eN ref from snapshot or CSS selector"),
+                        )
+                        .property(
+                            "value",
+                            SchemaBuilder::string().description("Text to fill"),
+                        )
+                        .property(
+                            "clear",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(true))
+                                .description("Clear existing content before filling"),
+                        )
+                        .property(
+                            "delay_ms",
+                            SchemaBuilder::integer()
+                                .default_value(json!(0))
+                                .description("Type one character at a time with this pause between keystrokes (0 = fill at once)"),
+                        )
+                        .property("frame", frame_param())
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["selector", "value"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("filled", SchemaBuilder::boolean())
+                        .property("selector", SchemaBuilder::string())
+                        .build(),
+                )
+                .example("Fill search box", json!({"selector": "@e5", "value": "search query"}))
+                .errors(&["ELEMENT_NOT_FOUND", "ELEMENT_NOT_EDITABLE", "FRAME_NOT_FOUND"]),
+
+            MethodInfo::new("browser.press", "Press a keyboard key")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "key",
+                            SchemaBuilder::string()
+                                .description("Key name: Enter, Tab, Escape, ArrowDown, etc."),
+                        )
+                        .property("frame", frame_param())
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["key"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("pressed", SchemaBuilder::boolean())
+                        .property("key", SchemaBuilder::string())
+                        .build(),
+                )
+                .example("Press Enter", json!({"key": "Enter"}))
+                .example("Press Escape", json!({"key": "Escape"}))
+                .errors(&["FRAME_NOT_FOUND"]),
+
+            MethodInfo::new("browser.select", "Select an option from a dropdown")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "selector",
+                            SchemaBuilder::string()
+                                .description("@eN ref or CSS selector for <select> element"),
+                        )
+                        .property(
+                            "value",
+                            SchemaBuilder::string()
+                                .description("Option value or visible label (as shown in snapshots); an array of them for a multi-select"),
+                        )
+                        .property(
+                            "add",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Multi-select: add to the current selection instead of replacing it"),
+                        )
+                        .property("frame", frame_param())
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["selector", "value"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("success", SchemaBuilder::boolean())
+                        .property("value", SchemaBuilder::string().description("Value of the selected option"))
+                        .property("label", SchemaBuilder::string().description("Visible text of the selected option"))
+                        .property(
+                            "selected",
+                            SchemaBuilder::array()
+                                .items(
+                                    SchemaBuilder::object()
+                                        .property("value", SchemaBuilder::string())
+                                        .property("label", SchemaBuilder::string()),
+                                )
+                                .description("Every option selected afterwards"),
+                        )
+                        .build(),
+                )
+                .example("Select option", json!({"selector": "@e10", "value": "option2"}))
+                .example("Select by label", json!({"selector": "@e10", "value": "United States"}))
+                .example("Multi-select", json!({"selector": "#tags", "value": ["rust", "wasm"]}))
+                .errors(&["ELEMENT_NOT_FOUND", "OPTION_NOT_FOUND", "FRAME_NOT_FOUND"]),
+
+            MethodInfo::new("browser.check", "Set checkbox or radio button state")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "selector",
+                            SchemaBuilder::string()
+                                .description("@eN ref or CSS selector"),
+                        )
+                        .property(
+                            "checked",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(true))
+                                .description("Desired checked state"),
+                        )
+                        .property("frame", frame_param())
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["selector"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("checked", SchemaBuilder::boolean().description("State read back from the element"))
+                        .build(),
+                )
+                .example("Check checkbox", json!({"selector": "@e8"}))
+                .example("Uncheck", json!({"selector": "@e8", "checked": false}))
+                .errors(&["ELEMENT_NOT_FOUND", "ELEMENT_DISABLED", "FRAME_NOT_FOUND"]),
+
+            MethodInfo::new("browser.hover", "Hover over an element")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "selector",
+                            SchemaBuilder::string()
+                                .description("@eN ref or CSS selector"),
+                        )
+                        .property("frame", frame_param())
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["selector"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("hovered", SchemaBuilder::boolean())
+                        .build(),
+                )
+                .example("Hover over menu", json!({"selector": "@e12"}))
+                .errors(&["ELEMENT_NOT_FOUND", "FRAME_NOT_FOUND"]),
+
+            MethodInfo::new("browser.select_text", "Select text in an input, textarea or other element")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "selector",
+                            SchemaBuilder::string()
+                                .description("@eN ref or CSS selector"),
+                        )
+                        .property(
+                            "start",
+                            SchemaBuilder::integer()
+                                .description("First character to select (omit start and end to select all)"),
+                        )
+                        .property(
+                            "end",
+                            SchemaBuilder::integer()
+                                .description("Character after the last one selected"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["selector"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("success", SchemaBuilder::boolean())
+                        .property("start", SchemaBuilder::integer())
+                        .property("end", SchemaBuilder::integer())
+                        .build(),
+                )
+                .example("Select all", json!({"selector": "#editor"}))
+                .example("Select a word", json!({"selector": "@e4", "start": 6, "end": 11}))
+                .errors(&["ELEMENT_NOT_FOUND"]),
+
+            MethodInfo::new("browser.drag", "Drag one element onto another with the mouse")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "source_selector",
+                            SchemaBuilder::string()
+                                .description("@eN ref or CSS selector of the element to drag"),
+                        )
+                        .property(
+                            "target_selector",
+                            SchemaBuilder::string()
+                                .description("@eN ref or CSS selector of the element to drop on"),
+                        )
+                        .property(
+                            "steps",
+                            SchemaBuilder::array()
+                                .items(
+                                    SchemaBuilder::object()
+                                        .property("x", SchemaBuilder::number())
+                                        .property("y", SchemaBuilder::number()),
+                                )
+                                .description("Viewport points to pass through on the way (optional)"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["source_selector", "target_selector"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("success", SchemaBuilder::boolean())
+                        .property("source", SchemaBuilder::string())
+                        .property("target", SchemaBuilder::string())
+                        .build(),
+                )
+                .example("Move a card", json!({"source_selector": "@e4", "target_selector": "#column-done"}))
+                .example(
+                    "Drag through a point",
+                    json!({"source_selector": "@e4", "target_selector": "@e9", "steps": [{"x": 400, "y": 120}]}),
+                )
+                .errors(&["ELEMENT_NOT_FOUND"]),
+
+            MethodInfo::new("browser.drag_path", "Drag the mouse along a polyline, e.g. to draw on a canvas")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "points",
+                            SchemaBuilder::array()
+                                .items(
+                                    SchemaBuilder::object()
+                                        .property("x", SchemaBuilder::number())
+                                        .property("y", SchemaBuilder::number()),
+                                )
+                                .description("Viewport points: press at the first, move through the rest, release at the last (at least two)"),
+                        )
+                        .property(
+                            "delay_between_points_ms",
+                            SchemaBuilder::integer()
+                                .default_value(json!(0))
+                                .description("Pause before each move, for apps that sample the pointer over time"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["points"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("success", SchemaBuilder::boolean())
+                        .property("points", SchemaBuilder::integer())
+                        .build(),
+                )
+                .example(
+                    "Draw a triangle",
+                    json!({"points": [{"x": 100, "y": 100}, {"x": 200, "y": 100}, {"x": 150, "y": 20}, {"x": 100, "y": 100}], "delay_between_points_ms": 16}),
+                ),
+
+            MethodInfo::new("browser.mouse.move", "Move the mouse to viewport coordinates")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("x", SchemaBuilder::number().description("CSS pixels from the left of the viewport"))
+                        .property("y", SchemaBuilder::number().description("CSS pixels from the top of the viewport"))
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["x", "y"])
+                        .build(),
+                )
+                .example("Move to a point", json!({"x": 320, "y": 240})),
+
+            MethodInfo::new("browser.mouse.click", "Click at viewport coordinates, whatever element is there")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("x", SchemaBuilder::number().description("CSS pixels from the left of the viewport"))
+                        .property("y", SchemaBuilder::number().description("CSS pixels from the top of the viewport"))
+                        .property(
+                            "button",
+                            SchemaBuilder::string()
+                                .enum_values(&["left", "right", "middle"])
+                                .default_value(json!("left")),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["x", "y"])
+                        .build(),
+                )
+                .example("Click a canvas", json!({"x": 320, "y": 240}))
+                .example("Right-click", json!({"x": 320, "y": 240, "button": "right"})),
+
+            MethodInfo::new("browser.mouse.wheel", "Scroll the mouse wheel at viewport coordinates")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("delta_x", SchemaBuilder::number().description("Horizontal scroll in pixels"))
+                        .property("delta_y", SchemaBuilder::number().description("Vertical scroll in pixels (positive scrolls down)"))
+                        .property("x", SchemaBuilder::number().default_value(json!(0)).description("Mouse position while scrolling"))
+                        .property("y", SchemaBuilder::number().default_value(json!(0)).description("Mouse position while scrolling"))
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .example("Zoom a map out", json!({"delta_y": 300, "x": 400, "y": 300})),
+
+            MethodInfo::new("browser.focus", "Focus an element without clicking it")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "selector",
+                            SchemaBuilder::string()
+                                .description("@eN ref or CSS selector"),
+                        )
+                        .property("frame", frame_param())
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["selector"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("success", SchemaBuilder::boolean())
+                        .property("selector", SchemaBuilder::string())
+                        .build(),
+                )
+                .example("Focus search box", json!({"selector": "input[name=q]"}))
+                .errors(&["ELEMENT_NOT_FOUND", "FRAME_NOT_FOUND"]),
+
+            MethodInfo::new("browser.clear_field", "Empty an input, textarea or contenteditable element without typing")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "selector",
+                            SchemaBuilder::string()
+                                .description("@eN ref or CSS selector"),
+                        )
+                        .property("frame", frame_param())
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["selector"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("cleared", SchemaBuilder::boolean())
+                        .build(),
+                )
+                .example("Clear search box", json!({"selector": "input[name=q]"}))
+                .errors(&["ELEMENT_NOT_FOUND", "ELEMENT_NOT_EDITABLE", "FRAME_NOT_FOUND"]),
+
+            MethodInfo::new("browser.blur", "Remove focus from the active element")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("success", SchemaBuilder::boolean())
+                        .build(),
+                )
+                .example("Blur active element", json!({})),
+
+            MethodInfo::new("browser.scroll", "Scroll page or element")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "selector",
+                            SchemaBuilder::string()
+                                .description("@eN ref or CSS selector to scroll into view"),
+                        )
+                        .property(
+                            "direction",
+                            SchemaBuilder::string()
+                                .enum_values(&["up", "down", "left", "right"])
+                                .description("Scroll direction (if no selector)"),
+                        )
+                        .property(
+                            "amount",
+                            SchemaBuilder::integer()
+                                .minimum(0)
+                                .default_value(json!(500))
+                                .description("Pixels to scroll (if using direction)"),
+                        )
+                        .property("frame", frame_param())
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("scrolled", SchemaBuilder::boolean())
+                        .build(),
+                )
+                .example("Scroll to element", json!({"selector": "@e50"}))
+                .example("Scroll down", json!({"direction": "down", "amount": 1000})),
+
+            MethodInfo::new("browser.scroll.top", "Scroll to the top of the page")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "smooth",
+                            SchemaBuilder::boolean().default_value(json!(false)).description("Animate the scroll instead of jumping"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("success", SchemaBuilder::boolean())
+                        .property("smooth", SchemaBuilder::boolean())
+                        .build(),
+                )
+                .example("Back to the top", json!({})),
+
+            MethodInfo::new("browser.scroll.bottom", "Scroll to the bottom of the page once (browser.scroll_to_bottom waits for feeds to load more)")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "smooth",
+                            SchemaBuilder::boolean().default_value(json!(false)).description("Animate the scroll instead of jumping"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("success", SchemaBuilder::boolean())
+                        .property("smooth", SchemaBuilder::boolean())
+                        .build(),
+                )
+                .example("Jump to the footer", json!({}))
+                .example("Smooth scroll", json!({"smooth": true})),
+
+            MethodInfo::new("browser.scroll_to_bottom", "Keep scrolling to the bottom until an infinite-scroll feed stops loading more")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "max_scrolls",
+                            SchemaBuilder::integer()
+                                .default_value(json!(DEFAULT_MAX_SCROLLS))
+                                .description("Stop after this many scrolls"),
+                        )
+                        .property(
+                            "settle_ms",
+                            SchemaBuilder::integer()
+                                .default_value(json!(DEFAULT_SCROLL_SETTLE_MS))
+                                .description("How long to wait for the page to grow after each scroll before calling it the end"),
+                        )
+                        .property(
+                            "timeout_ms",
+                            SchemaBuilder::integer()
+                                .default_value(json!(DEFAULT_SCROLL_TIMEOUT_MS))
+                                .description("Stop scrolling after this long overall"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("scrolls", SchemaBuilder::integer().description("Times the page was scrolled to the bottom"))
+                        .property("height", SchemaBuilder::integer().description("Final document height in CSS pixels"))
+                        .property(
+                            "reached_end",
+                            SchemaBuilder::boolean()
+                                .description("The page stopped growing; false if max_scrolls or timeout_ms stopped it first"),
+                        )
+                        .build(),
+                )
+                .example("Load a whole feed", json!({}))
+                .example("At most 10 pages of results", json!({"max_scrolls": 10, "settle_ms": 2000})),
+
+            MethodInfo::new("browser.press_combo", "Press key with modifiers (Ctrl, Shift, Alt, Meta)")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "key",
+                            SchemaBuilder::string().description("Main key to press"),
+                        )
+                        .property(
+                            "modifiers",
+                            SchemaBuilder::array()
+                                .items(
+                                    SchemaBuilder::string()
+                                        .enum_values(&["ctrl", "shift", "alt", "meta"]),
+                                )
+                                .description("Modifier keys to hold"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["key", "modifiers"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("pressed", SchemaBuilder::boolean())
+                        .build(),
+                )
+                .example("Select all (Ctrl+A)", json!({"key": "a", "modifiers": ["ctrl"]}))
+                .example("Copy (Cmd+C on Mac)", json!({"key": "c", "modifiers": ["meta"]}))
+                .errors(&["INVALID_MODIFIER"]),
+
+            MethodInfo::new("browser.key_sequence", "Press a series of keys in order, e.g. editor or spreadsheet shortcuts")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "keys",
+                            SchemaBuilder::array()
+                                .items(
+                                    SchemaBuilder::object()
+                                        .property("key", SchemaBuilder::string().description("Key name (e.g. j, Escape, ArrowDown)"))
+                                        .property(
+                                            "modifiers",
+                                            SchemaBuilder::array()
+                                                .items(SchemaBuilder::string().enum_values(&["ctrl", "shift", "alt", "meta"]))
+                                                .description("Modifier keys to hold"),
+                                        )
+                                        .property(
+                                            "delay_after_ms",
+                                            SchemaBuilder::integer().default_value(json!(0)).description("Pause after this press"),
+                                        )
+                                        .required(&["key"]),
+                                )
+                                .description("Key presses, sent one after another; a plain string is a key without modifiers"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["keys"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("success", SchemaBuilder::boolean())
+                        .property("pressed", SchemaBuilder::integer().description("Number of keys pressed"))
+                        .build(),
+                )
+                .example(
+                    "Vim: delete a line and save",
+                    json!({"keys": ["Escape", "d", "d", {"key": ":"}, "w", "Enter"]}),
+                )
+                .example(
+                    "Spreadsheet: select a row, then copy",
+                    json!({"keys": [{"key": "Space", "modifiers": ["shift"], "delay_after_ms": 100}, {"key": "c", "modifiers": ["ctrl"]}]}),
+                )
+                .errors(&["INVALID_MODIFIER"]),
+
+            MethodInfo::new("browser.upload", "Upload one or more files to a file input element")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "selector",
+                            SchemaBuilder::string()
+                                .description("@eN ref or CSS selector for file input"),
+                        )
+                        .property(
+                            "path",
+                            SchemaBuilder::string()
+                                .description("Absolute path to file to upload"),
+                        )
+                        .property(
+                            "paths",
+                            SchemaBuilder::array()
+                                .items(SchemaBuilder::string())
+                                .description("Several files, for an input with the multiple attribute (instead of path)"),
+                        )
+                        .property(
+                            "content_base64",
+                            SchemaBuilder::string()
+                                .description("File contents to upload instead of a file on disk (needs filename)"),
+                        )
+                        .property(
+                            "filename",
+                            SchemaBuilder::string()
+                                .description("Name the page sees for content_base64"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["selector"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("success", SchemaBuilder::boolean())
+                        .property("paths", SchemaBuilder::array().items(SchemaBuilder::string()))
+                        .property(
+                            "files",
+                            SchemaBuilder::array()
+                                .items(SchemaBuilder::string())
+                                .description("Names of the uploaded files"),
+                        )
+                        .build(),
+                )
+                .example("Upload file", json!({"selector": "@e30", "path": "/tmp/document.pdf"}))
+                .example("Upload several files", json!({"selector": "#photos", "paths": ["/tmp/a.jpg", "/tmp/b.jpg"]}))
+                .example(
+                    "Upload generated data",
+                    json!({"selector": "#csv", "content_base64": "bmFtZSxxdHkKYXBwbGUsMwo=", "filename": "order.csv"}),
+                )
+                .errors(&["ELEMENT_NOT_FOUND", "FILE_NOT_FOUND", "MULTIPLE_NOT_ALLOWED"]),
+
+            MethodInfo::new("browser.dialog", "Configure JavaScript dialog handling and list handled dialogs")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "action",
+                            SchemaBuilder::string()
+                                .enum_values(&["accept", "dismiss", "manual"])
+                                .description("How to answer alert/confirm/prompt dialogs (omit to keep current policy; default dismiss)"),
+                        )
+                        .property(
+                            "prompt_text",
+                            SchemaBuilder::string()
+                                .description("Text to enter into prompt() dialogs when accepting"),
+                        )
+                        .property(
+                            "clear",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Clear the dialog buffer after returning it"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property(
+                            "policy",
+                            SchemaBuilder::object()
+                                .property("action", SchemaBuilder::string())
+                                .property("prompt_text", SchemaBuilder::string()),
+                        )
+                        .property(
+                            "dialogs",
+                            SchemaBuilder::array().items(
+                                SchemaBuilder::object()
+                                    .property("dialog_type", SchemaBuilder::string())
+                                    .property("message", SchemaBuilder::string())
+                                    .property("url", SchemaBuilder::string())
+                                    .property("action", SchemaBuilder::string())
+                                    .property("pending", SchemaBuilder::boolean())
+                                    .property("timestamp", SchemaBuilder::string().format("date-time")),
+                            ),
+                        )
+                        .build(),
+                )
+                .example("Auto-accept confirms", json!({"action": "accept"}))
+                .example("Answer prompts", json!({"action": "accept", "prompt_text": "42"}))
+                .example("Read and clear handled dialogs", json!({"clear": true})),
+
+            MethodInfo::new("browser.set_dialog_handler", "Set how JavaScript dialogs are answered (default dismiss)")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "mode",
+                            SchemaBuilder::string()
+                                .enum_values(&["accept", "dismiss", "manual"])
+                                .description("manual leaves dialogs open; switching back answers the open one"),
+                        )
+                        .property(
+                            "prompt_text",
+                            SchemaBuilder::string()
+                                .description("Text to enter into prompt() dialogs when accepting"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["mode"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property(
+                            "policy",
+                            SchemaBuilder::object()
+                                .property("action", SchemaBuilder::string())
+                                .property("prompt_text", SchemaBuilder::string()),
+                        )
+                        .build(),
+                )
+                .example("Accept confirms", json!({"mode": "accept"}))
+                .example("Inspect dialogs before answering", json!({"mode": "manual"})),
+
+            MethodInfo::new("browser.dialogs.list", "List dialogs opened by the page (newest last)")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "clear",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Remove returned dialogs from the buffer (an open one is kept)"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("count", SchemaBuilder::integer())
+                        .property(
+                            "dialogs",
+                            SchemaBuilder::array().items(
+                                SchemaBuilder::object()
+                                    .property("dialog_type", SchemaBuilder::string())
+                                    .property("message", SchemaBuilder::string())
+                                    .property("url", SchemaBuilder::string())
+                                    .property("action", SchemaBuilder::string())
+                                    .property("pending", SchemaBuilder::boolean())
+                                    .property("timestamp", SchemaBuilder::string().format("date-time")),
+                            ),
+                        )
+                        .build(),
+                )
+                .example("List dialogs", json!({})),
+
+            // ================================================================
+            // Network Capture
+            // ================================================================
+            MethodInfo::new("browser.network.enable", "Start capturing network requests for a session")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("enabled", SchemaBuilder::boolean())
+                        .build(),
+                )
+                .example("Enable capture", json!({})),
+
+            MethodInfo::new("browser.network.disable", "Stop capturing network requests (keeps captured requests)")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("enabled", SchemaBuilder::boolean())
+                        .build(),
+                )
+                .example("Disable capture", json!({})),
+
+            MethodInfo::new("browser.network.list", "List captured network requests (last 500 per session)")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "url_pattern",
+                            SchemaBuilder::string()
+                                .description("URL substring, or glob with * wildcards"),
+                        )
+                        .property(
+                            "status",
+                            SchemaBuilder::string()
+                                .description("Status code (404), class ('4xx'), or 'failed'"),
+                        )
+                        .property(
+                            "resource_type",
+                            SchemaBuilder::string()
+                                .description("CDP resource type, e.g. 'XHR', 'Fetch', 'Document'"),
+                        )
+                        .property(
+                            "limit",
+                            SchemaBuilder::integer()
+                                .minimum(1)
+                                .description("Return only the most recent N matches"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("enabled", SchemaBuilder::boolean())
+                        .property("count", SchemaBuilder::integer())
+                        .property(
+                            "requests",
+                            SchemaBuilder::array().items(
+                                SchemaBuilder::object()
+                                    .property("request_id", SchemaBuilder::string())
+                                    .property("url", SchemaBuilder::string())
+                                    .property("method", SchemaBuilder::string())
+                                    .property("resource_type", SchemaBuilder::string())
+                                    .property("status", SchemaBuilder::integer())
+                                    .property("mime_type", SchemaBuilder::string())
+                                    .property("encoded_data_length", SchemaBuilder::integer())
+                                    .property("duration_ms", SchemaBuilder::number())
+                                    .property("error", SchemaBuilder::string())
+                                    .property("started_at", SchemaBuilder::string().format("date-time")),
+                            ),
+                        )
+                        .build(),
+                )
+                .example("Failed API calls", json!({"url_pattern": "/api/", "status": "4xx"}))
+                .example("Last 10 XHRs", json!({"resource_type": "XHR", "limit": 10})),
+
+            MethodInfo::new("browser.network.body", "Get the response body of a captured request")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "request_id",
+                            SchemaBuilder::string()
+                                .min_length(1)
+                                .description("Request ID from browser.network.list"),
+                        )
+                        .property(
+                            "max_bytes",
+                            SchemaBuilder::integer()
+                                .minimum(0)
+                                .default_value(json!(DEFAULT_BODY_MAX_BYTES))
+                                .description("Truncate the body after this many bytes"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["request_id"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("request_id", SchemaBuilder::string())
+                        .property("url", SchemaBuilder::string())
+                        .property("mime_type", SchemaBuilder::string())
+                        .property("body", SchemaBuilder::string())
+                        .property("base64_encoded", SchemaBuilder::boolean())
+                        .property("size_bytes", SchemaBuilder::integer())
+                        .property("truncated", SchemaBuilder::boolean())
+                        .build(),
+                )
+                .example("Get API response", json!({"request_id": "1234.56"}))
+                .example("First 4 KB only", json!({"request_id": "1234.56", "max_bytes": 4096}))
+                .errors(&["NOT_AVAILABLE"]),
+
+            MethodInfo::new("browser.network.clear", "Clear captured network requests")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("cleared", SchemaBuilder::integer())
+                        .build(),
+                )
+                .example("Clear capture buffer", json!({})),
+
+            MethodInfo::new("browser.network.capture_start", "Record outgoing requests (with headers and bodies) until capture_stop")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("capturing", SchemaBuilder::boolean())
+                        .build(),
+                )
+                .example("Before clicking a button", json!({})),
+
+            MethodInfo::new("browser.network.capture_stop", "Stop recording and return the captured requests")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("count", SchemaBuilder::integer())
+                        .property(
+                            "requests",
+                            SchemaBuilder::array().items(
+                                SchemaBuilder::object()
+                                    .property("url", SchemaBuilder::string())
+                                    .property("method", SchemaBuilder::string())
+                                    .property("headers", SchemaBuilder::object())
+                                    .property("body", SchemaBuilder::string())
+                                    .property("timestamp", SchemaBuilder::string()),
+                            ),
+                        )
+                        .build(),
+                )
+                .example("After the click", json!({})),
+
+            MethodInfo::new("browser.network.block", "Block requests by URL pattern and/or resource type")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "url_patterns",
+                            SchemaBuilder::array()
+                                .items(SchemaBuilder::string())
+                                .description("URL substrings, or globs with * wildcards"),
+                        )
+                        .property(
+                            "resource_types",
+                            SchemaBuilder::array()
+                                .items(SchemaBuilder::string())
+                                .description("Resource types: image, font, media, script, stylesheet, xhr, fetch, ..."),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(block_status_schema())
+                .example("Block images and fonts", json!({"resource_types": ["image", "font"]}))
+                .example("Block trackers", json!({"url_patterns": ["doubleclick.net", "*/analytics/*"]}))
+                .errors(&["INVALID_RESOURCE_TYPE"]),
+
+            MethodInfo::new("browser.network.unblock", "Remove all block rules and blocked URL patterns")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(block_status_schema())
+                .example("Clear block rules", json!({})),
+
+            MethodInfo::new("browser.network.rules", "List active block rules")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(block_status_schema())
+                .example("Show rules", json!({})),
+
+            MethodInfo::new("browser.network.block_urls", "Block URL patterns in Chrome's network stack (no interception)")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "patterns",
+                            SchemaBuilder::array()
+                                .items(SchemaBuilder::string())
+                                .description("URL patterns with * wildcards, added to the session's patterns"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["patterns"])
+                        .build(),
+                )
+                .returns(block_status_schema())
+                .example("Block analytics", json!({"patterns": ["*google-analytics.com*", "*googletagmanager.com*"]})),
+
+            MethodInfo::new("browser.network.clear_blocked_urls", "Remove blocked URL patterns")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(block_status_schema())
+                .example("Unblock URLs", json!({})),
+
+            MethodInfo::new("browser.network.mock", "Fulfill requests matching a URL pattern with a canned response (alias: browser.mock)")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "url_pattern",
+                            SchemaBuilder::string()
+                                .min_length(1)
+                                .description("URL substring, or glob with * wildcards. Replaces an existing mock with the same pattern"),
+                        )
+                        .property(
+                            "status",
+                            SchemaBuilder::integer()
+                                .minimum(100)
+                                .maximum(599)
+                                .default_value(json!(200)),
+                        )
+                        .property(
+                            "headers",
+                            SchemaBuilder::object()
+                                .description("Response headers, e.g. {\"Content-Type\": \"application/json\"}"),
+                        )
+                        .property("body", SchemaBuilder::string().description("Inline response body"))
+                        .property(
+                            "body_path",
+                            SchemaBuilder::string()
+                                .description("Read the response body from this file instead"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["url_pattern"])
+                        .build(),
+                )
+                .returns(mock_schema().build())
+                .example(
+                    "Stub an API endpoint",
+                    json!({
+                        "url_pattern": "/api/users",
+                        "headers": {"Content-Type": "application/json"},
+                        "body": "[{\"id\": 1}]"
+                    }),
+                )
+                .example("Simulate an outage", json!({"url_pattern": "*://api.example.com/*", "status": 503}))
+                .errors(&["FILE_NOT_FOUND"]),
+
+            MethodInfo::new("browser.network.mocks", "List active mocks and how often each matched")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("count", SchemaBuilder::integer())
+                        .property("mocks", SchemaBuilder::array().items(mock_schema()))
+                        .build(),
+                )
+                .example("List mocks", json!({})),
+
+            MethodInfo::new("browser.network.unmock", "Remove a mock by ID, or all mocks")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "id",
+                            SchemaBuilder::string().description("Mock ID from browser.network.mock (omit to remove all)"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("removed", SchemaBuilder::integer())
+                        .build(),
+                )
+                .example("Remove one mock", json!({"id": "mock-1"}))
+                .example("Remove all mocks", json!({})),
+
+            MethodInfo::new("browser.set_headers", "Send extra HTTP headers and answer auth challenges for a session")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "headers",
+                            SchemaBuilder::object()
+                                .description("Headers sent with every request; replaces the current set"),
+                        )
+                        .property(
+                            "credentials",
+                            SchemaBuilder::object()
+                                .property("username", SchemaBuilder::string())
+                                .property("password", SchemaBuilder::string())
+                                .required(&["username", "password"])
+                                .description("Answer HTTP basic/digest auth challenges automatically"),
+                        )
+                        .property(
+                            "clear",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Remove headers and credentials before applying the rest"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("headers", SchemaBuilder::object())
+                        .property(
+                            "auth_username",
+                            SchemaBuilder::string().description("Present when credentials are set; the password is never returned"),
+                        )
+                        .build(),
+                )
+                .example("Add an API key header", json!({"headers": {"X-Api-Key": "abc123"}}))
+                .example(
+                    "Log in to a protected staging site",
+                    json!({"credentials": {"username": "admin", "password": "hunter2"}}),
+                )
+                .example("Remove headers and credentials", json!({"clear": true})),
+
+            MethodInfo::new("browser.har.start", "Start recording the session's network traffic as a HAR")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("recording", SchemaBuilder::boolean())
+                        .build(),
+                )
+                .example("Start recording", json!({})),
+
+            MethodInfo::new("browser.har.stop", "Stop recording and write a HAR 1.2 file")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "path",
+                            SchemaBuilder::string()
+                                .min_length(1)
+                                .description("File to write the HAR to"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["path"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("path", SchemaBuilder::string())
+                        .property("entry_count", SchemaBuilder::integer())
+                        .property(
+                            "dropped",
+                            SchemaBuilder::integer()
+                                .description("Requests not recorded after the 10,000 entry limit"),
+                        )
+                        .property("size_bytes", SchemaBuilder::integer())
+                        .build(),
+                )
+                .example("Save the recording", json!({"path": "/tmp/session.har"})),
+
+            // ================================================================
+            // Downloads
+            // ================================================================
+            MethodInfo::new("browser.downloads.list", "List files downloaded in a session")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("count", SchemaBuilder::integer())
+                        .property("downloads", SchemaBuilder::array().items(download_schema()))
+                        .build(),
+                )
+                .example("List downloads", json!({})),
+
+            MethodInfo::new("browser.downloads.wait", "Wait for the next download to finish")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "timeout_ms",
+                            SchemaBuilder::integer()
+                                .minimum(0)
+                                .default_value(json!(DEFAULT_DOWNLOAD_TIMEOUT_MS))
+                                .description("Maximum time to wait in milliseconds"),
+                        )
+                        .property(
+                            "new_only",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Ignore downloads that started before this call"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(download_schema().build())
+                .example("Wait for a download", json!({"timeout_ms": 10000}))
+                .errors(&["TIMEOUT"]),
+
+            MethodInfo::new("browser.wait_for_download", "Wait for a download started after this call to finish")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "timeout_ms",
+                            SchemaBuilder::integer()
+                                .minimum(0)
+                                .default_value(json!(DEFAULT_DOWNLOAD_TIMEOUT_MS))
+                                .description("Maximum time to wait in milliseconds"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(download_schema().build())
+                .example("Wait for an export", json!({"timeout_ms": 60000}))
+                .errors(&["TIMEOUT"]),
+
+            MethodInfo::new("browser.downloads.cleanup", "Delete downloaded files older than a given age")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "max_age_hours",
+                            SchemaBuilder::integer()
+                                .minimum(0)
+                                .default_value(json!(DEFAULT_DOWNLOAD_MAX_AGE_HOURS))
+                                .description("Remove files last modified more than this many hours ago"),
+                        )
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("removed_files", SchemaBuilder::integer())
+                        .property("freed_bytes", SchemaBuilder::integer())
+                        .build(),
+                )
+                .example("Remove files older than a day", json!({"max_age_hours": 24})),
+
+            // ================================================================
+            // Console
+            // ================================================================
+            MethodInfo::new("browser.console.get", "Get recent console messages and browser log entries")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "level",
+                            SchemaBuilder::string()
+                                .description("Only entries at this level (log, info, debug, warning, error, ...)"),
+                        )
+                        .property(
+                            "limit",
+                            SchemaBuilder::integer()
+                                .minimum(1)
+                                .description("Return only the most recent N entries"),
+                        )
+                        .property(
+                            "clear",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Clear the console buffer after returning it"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("count", SchemaBuilder::integer())
+                        .property(
+                            "entries",
+                            SchemaBuilder::array().items(
+                                SchemaBuilder::object()
+                                    .property("level", SchemaBuilder::string())
+                                    .property("text", SchemaBuilder::string())
+                                    .property("source", SchemaBuilder::string())
+                                    .property("url", SchemaBuilder::string())
+                                    .property("timestamp", SchemaBuilder::string().format("date-time")),
+                            ),
+                        )
+                        .build(),
+                )
+                .example("Get console output", json!({}))
+                .example("Read and clear errors", json!({"level": "error", "clear": true})),
+
+            // ================================================================
+            // Auth State Management
+            // ================================================================
+            MethodInfo::new("browser.state.save", "Save auth state (cookies + localStorage) for reuse")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "name",
+                            SchemaBuilder::string()
+                                .min_length(1)
+                                .max_length(64)
+                                .pattern("^[a-zA-Z0-9_-]+$")
+                                .description("Name for this auth state"),
+                        )
+                        .property(
+                            "origins",
+                            SchemaBuilder::array()
+                                .items(SchemaBuilder::string())
+                                .description("Origins whose localStorage to save (default: the page's origin and every origin with cookies)"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["name"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("saved", SchemaBuilder::boolean())
+                        .property("name", SchemaBuilder::string())
+                        .property("path", SchemaBuilder::string())
+                        .property(
+                            "local_storage_origins",
+                            SchemaBuilder::array()
+                                .items(SchemaBuilder::string())
+                                .description("Origins with localStorage in the saved state"),
+                        )
+                        .build(),
+                )
+                .example("Save GitHub auth", json!({"name": "github-prod"}))
+                .example(
+                    "Save an app and its login domain",
+                    json!({"name": "app", "origins": ["https://app.example.com", "https://auth.example.com"]}),
+                ),
+
+            MethodInfo::new("browser.state.load", "Load previously saved auth state")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "name",
+                            SchemaBuilder::string().description("Name of saved auth state"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["name"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("loaded", SchemaBuilder::boolean())
+                        .property("name", SchemaBuilder::string())
+                        .property(
+                            "local_storage_origins",
+                            SchemaBuilder::array()
+                                .items(SchemaBuilder::string())
+                                .description("Origins whose localStorage was restored"),
+                        )
+                        .build(),
+                )
+                .example("Load GitHub auth", json!({"name": "github-prod"}))
+                .errors(&["STATE_NOT_FOUND"]),
+
+            MethodInfo::new("browser.state.list", "List all saved auth states")
+                .schema(SchemaBuilder::object().build())
+                .returns(
+                    SchemaBuilder::object()
+                        .property(
+                            "states",
+                            SchemaBuilder::array().items(
+                                SchemaBuilder::object()
+                                    .property("name", SchemaBuilder::string())
+                                    .property("created_at", SchemaBuilder::string().format("date-time"))
+                                    .property("size_bytes", SchemaBuilder::integer()),
+                            ),
+                        )
+                        .property("count", SchemaBuilder::integer())
+                        .build(),
+                )
+                .example("List auth states", json!({})),
+
+            MethodInfo::new("browser.state.delete", "Delete a saved auth state")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "name",
+                            SchemaBuilder::string()
+                                .pattern("^[a-zA-Z0-9_-]+$")
+                                .description("Name of saved auth state"),
+                        )
+                        .required(&["name"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property(
+                            "existed",
+                            SchemaBuilder::boolean()
+                                .description("False if there was no such state (deleting it again is not an error)"),
+                        )
+                        .property("name", SchemaBuilder::string())
+                        .property(
+                            "deleted",
+                            SchemaBuilder::boolean().description("Whether a state file was removed (same as 'existed')"),
+                        )
+                        .build(),
+                )
+                .example("Delete GitHub auth", json!({"name": "github-prod"}))
+                .errors(&["INVALID_NAME"]),
+
+            MethodInfo::new("browser.state.rename", "Rename a saved auth state")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "name",
+                            SchemaBuilder::string()
+                                .pattern("^[a-zA-Z0-9_-]+$")
+                                .description("Current name of the saved auth state"),
+                        )
+                        .property(
+                            "new_name",
+                            SchemaBuilder::string()
+                                .min_length(1)
+                                .max_length(64)
+                                .pattern("^[a-zA-Z0-9_-]+$")
+                                .description("New name"),
+                        )
+                        .property(
+                            "overwrite",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Replace an existing state called new_name"),
+                        )
+                        .required(&["name", "new_name"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("renamed", SchemaBuilder::boolean())
+                        .property("name", SchemaBuilder::string())
+                        .property("previous_name", SchemaBuilder::string())
+                        .property("overwritten", SchemaBuilder::boolean())
+                        .build(),
+                )
+                .example("Rename a state", json!({"name": "github", "new_name": "github-prod"}))
+                .errors(&["STATE_NOT_FOUND", "STATE_EXISTS", "INVALID_NAME"]),
+
+            MethodInfo::new("browser.cookies.export", "Write the session's cookies to a file as a JSON array")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "path",
+                            SchemaBuilder::string().description("File to write (absolute; the daemon's directory may differ)"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["path"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("count", SchemaBuilder::integer())
+                        .property("path", SchemaBuilder::string())
+                        .build(),
+                )
+                .example("Export cookies", json!({"path": "/tmp/cookies.json"})),
+
+            MethodInfo::new("browser.cookies.import", "Set cookies from a JSON array file (ours or a cookie-export extension's)")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "path",
+                            SchemaBuilder::string().description("JSON file to read (absolute)"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["path"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("count", SchemaBuilder::integer())
+                        .property("path", SchemaBuilder::string())
+                        .build(),
+                )
+                .example("Import cookies", json!({"path": "/tmp/cookies.json"})),
+
+            MethodInfo::new("browser.storage.export", "Write one origin's localStorage to a JSON file")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "path",
+                            SchemaBuilder::string().description("File to write (absolute; the daemon's directory may differ)"),
+                        )
+                        .property(
+                            "origin",
+                            SchemaBuilder::string().description("Origin to export, e.g. https://app.example.com (default: the current page's); must be loaded in the page or an iframe"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["path"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("origin", SchemaBuilder::string())
+                        .property("count", SchemaBuilder::integer())
+                        .property("path", SchemaBuilder::string())
+                        .build(),
+                )
+                .example("Export the current origin", json!({"path": "/tmp/storage.json"}))
+                .example("Export an iframe's origin", json!({"path": "/tmp/widget.json", "origin": "https://widget.example.com"})),
+
+            MethodInfo::new("browser.storage.import", "Add localStorage items from a browser.storage.export file (existing keys are kept)")
+                .schema(
+                    SchemaBuilder::object()
+                        .property(
+                            "path",
+                            SchemaBuilder::string().description("JSON file with origin and items (absolute)"),
                         )
                         .property("session_id", session_param())
-                        .required(&["url"])
+                        .property("endpoint", endpoint_param())
+                        .required(&["path"])
                         .build(),
                 )
                 .returns(
                     SchemaBuilder::object()
-                        .property("url", SchemaBuilder::string().format("uri"))
-                        .property("title", SchemaBuilder::string())
-                        .property("load_time_ms", SchemaBuilder::number())
+                        .property("origin", SchemaBuilder::string())
+                        .property("count", SchemaBuilder::integer())
+                        .property("path", SchemaBuilder::string())
                         .build(),
                 )
-                .example("Navigate to Google", json!({"url": "https://google.com"}))
-                .example(
-                    "Wait for network idle",
-                    json!({"url": "https://example.com", "wait_until": "networkidle"}),
-                )
-                .errors(&["NAVIGATION_FAILED", "TIMEOUT"]),
+                .example("Import storage", json!({"path": "/tmp/storage.json"})),
 
-            MethodInfo::new("browser.snapshot", "Get ARIA accessibility tree with @eN refs for element targeting")
+            MethodInfo::new("browser.indexeddb.get_all", "Read every record of an IndexedDB object store for the current page's origin")
                 .schema(
                     SchemaBuilder::object()
+                        .property("db", SchemaBuilder::string().description("Database name (never created if missing)"))
+                        .property("store", SchemaBuilder::string().description("Object store name"))
                         .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["db", "store"])
                         .build(),
                 )
                 .returns(
                     SchemaBuilder::object()
+                        .property("db", SchemaBuilder::string())
+                        .property("store", SchemaBuilder::string())
+                        .property("count", SchemaBuilder::integer())
                         .property(
-                            "snapshot",
-                            SchemaBuilder::string()
-                                .description("ARIA tree with @eN refs for clicking/filling"),
+                            "records",
+                            SchemaBuilder::array()
+                                .items(SchemaBuilder::object())
+                                .description("{key, value} in key order; dates as ISO strings, binary data as byte arrays"),
                         )
-                        .property("url", SchemaBuilder::string().format("uri"))
-                        .property("title", SchemaBuilder::string())
                         .build(),
                 )
-                .example("Get page snapshot", json!({})),
+                .example("Read cached tokens", json!({"db": "app", "store": "tokens"})),
 
-            MethodInfo::new("browser.screenshot", "Capture screenshot as base64 or save to file")
+            MethodInfo::new("browser.indexeddb.list_stores", "List the object stores of an IndexedDB database for the current page's origin")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("db", SchemaBuilder::string().description("Database name (never created if missing)"))
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["db"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("db", SchemaBuilder::string())
+                        .property("stores", SchemaBuilder::array().items(SchemaBuilder::string()))
+                        .build(),
+                )
+                .example("List stores", json!({"db": "app"})),
+
+            // ================================================================
+            // Session Management
+            // ================================================================
+            MethodInfo::new("browser.session.new", "Create isolated session with separate cookies/storage")
                 .schema(
                     SchemaBuilder::object()
                         .property(
-                            "path",
+                            "id",
                             SchemaBuilder::string()
-                                .description("File path to save screenshot (optional, returns base64 if omitted)"),
+                                .description("Session ID (generated if omitted)")
+                                .pattern("^[a-zA-Z0-9_-]+$"),
                         )
                         .property(
-                            "full_page",
+                            "name",
+                            SchemaBuilder::string()
+                                .description("Optional friendly name for the session"),
+                        )
+                        .property(
+                            "evict_idle",
                             SchemaBuilder::boolean()
                                 .default_value(json!(false))
-                                .description("Capture full scrollable page"),
+                                .description("At --max-sessions, close the least recently used session instead of failing"),
                         )
-                        .property("session_id", session_param())
-                        .build(),
-                )
-                .returns(
-                    SchemaBuilder::object()
                         .property(
-                            "base64",
+                            "state",
                             SchemaBuilder::string()
-                                .description("Base64-encoded PNG (if no path specified)"),
+                                .description("Saved auth state (browser.state.save) to restore: cookies before any navigation, then localStorage"),
                         )
                         .property(
-                            "path",
+                            "url",
                             SchemaBuilder::string()
-                                .description("Saved file path (if path was specified)"),
+                                .description("Page to open once the state is restored (default: stay on about:blank)"),
                         )
-                        .property("width", SchemaBuilder::integer())
-                        .property("height", SchemaBuilder::integer())
                         .build(),
                 )
-                .example("Get base64 screenshot", json!({}))
-                .example("Save to file", json!({"path": "/tmp/screenshot.png", "full_page": true})),
-
-            // ================================================================
-            // Interaction
-            // ================================================================
-            MethodInfo::new("browser.click", "Click element by @eN ref or CSS selector")
-                .schema(
+                .returns(
                     SchemaBuilder::object()
+                        .property("success", SchemaBuilder::boolean())
+                        .property("session_id", SchemaBuilder::string())
+                        .property("name", SchemaBuilder::string())
+                        .property("state", SchemaBuilder::string().description("Restored state, if any"))
                         .property(
-                            "selector",
-                            SchemaBuilder::string()
-                                .description("@eN ref from snapshot or CSS selector"),
+                            "cookie_domains",
+                            SchemaBuilder::array()
+                                .items(SchemaBuilder::string())
+                                .description("Domains of the restored cookies"),
                         )
+                        .property("local_storage_items", SchemaBuilder::integer().description("localStorage items restored"))
                         .property(
-                            "button",
-                            SchemaBuilder::string()
-                                .enum_values(&["left", "right", "middle"])
-                                .default_value(json!("left")),
+                            "local_storage_origins",
+                            SchemaBuilder::array()
+                                .items(SchemaBuilder::string())
+                                .description("Origins whose localStorage was restored"),
                         )
+                        .property("url", SchemaBuilder::string().description("Page the session was left on"))
+                        .build(),
+                )
+                .example("Create named session", json!({"id": "cart", "name": "shopping-cart"}))
+                .example("Create anonymous session", json!({}))
+                .example("Make room if at the limit", json!({"id": "cart", "evict_idle": true}))
+                .example("Start logged in", json!({"id": "gh", "state": "github-prod", "url": "https://github.com/settings"}))
+                .errors(&["SESSION_LIMIT_REACHED"]),
+
+            MethodInfo::new("browser.session.clone", "Create a session with copies of another session's cookies and localStorage")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("source_id", SchemaBuilder::string().description("Session to copy (\"default\" for the default session)"))
+                        .property("id", SchemaBuilder::string().description("ID for the new session (generated if omitted)").pattern("^[a-zA-Z0-9_-]+$"))
+                        .property("name", SchemaBuilder::string().description("Optional friendly name for the new session"))
                         .property(
-                            "click_count",
-                            SchemaBuilder::integer()
-                                .minimum(1)
-                                .maximum(3)
-                                .default_value(json!(1))
-                                .description("1=click, 2=double-click, 3=triple-click"),
+                            "navigate",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Leave the new session on the source's current URL (otherwise about:blank)"),
                         )
-                        .property("session_id", session_param())
-                        .required(&["selector"])
+                        .required(&["source_id"])
                         .build(),
                 )
                 .returns(
                     SchemaBuilder::object()
-                        .property("clicked", SchemaBuilder::boolean())
-                        .property("selector", SchemaBuilder::string())
+                        .property("session_id", SchemaBuilder::string())
+                        .property("source_session_id", SchemaBuilder::string())
+                        .property("cookies", SchemaBuilder::integer().description("Cookies copied, across all domains"))
+                        .property("local_storage_items", SchemaBuilder::integer().description("localStorage items copied for the source's current origin"))
+                        .property("url", SchemaBuilder::string().description("Where the new session was left, with navigate"))
                         .build(),
                 )
-                .example("Click by ref", json!({"selector": "@e15"}))
-                .example("Double-click", json!({"selector": "@e20", "click_count": 2}))
-                .errors(&["ELEMENT_NOT_FOUND", "ELEMENT_NOT_VISIBLE"]),
+                .example("Fan out a logged-in session", json!({"source_id": "login", "id": "worker-1", "navigate": true}))
+                .example("Copy the default session", json!({"source_id": "default"}))
+                .errors(&["SESSION_NOT_FOUND", "SESSION_LIMIT_REACHED"]),
 
-            MethodInfo::new("browser.fill", "Fill input field with value")
+            MethodInfo::new("browser.session.attach", "Create a session that drives an already-open tab (e.g. a logged-in tab in connect mode)")
                 .schema(
                     SchemaBuilder::object()
+                        .property("id", SchemaBuilder::string().description("Session ID to create").pattern("^[a-zA-Z0-9_-]+$"))
                         .property(
-                            "selector",
-                            SchemaBuilder::string()
-                                .description("@eN ref from snapshot or CSS selector"),
+                            "target_id",
+                            SchemaBuilder::string().description("Target ID from browser.targets"),
                         )
                         .property(
-                            "value",
-                            SchemaBuilder::string().description("Text to fill"),
+                            "url",
+                            SchemaBuilder::string().description("URL substring; the first tab no session drives wins"),
                         )
                         .property(
-                            "clear",
-                            SchemaBuilder::boolean()
-                                .default_value(json!(true))
-                                .description("Clear existing content before filling"),
+                            "title",
+                            SchemaBuilder::string().description("Title substring (case-insensitive); the first tab no session drives wins"),
                         )
-                        .property("session_id", session_param())
-                        .required(&["selector", "value"])
+                        .required(&["id"])
                         .build(),
                 )
                 .returns(
                     SchemaBuilder::object()
-                        .property("filled", SchemaBuilder::boolean())
-                        .property("selector", SchemaBuilder::string())
+                        .property("target_id", SchemaBuilder::string())
+                        .property("url", SchemaBuilder::string())
+                        .property("title", SchemaBuilder::string())
+                        .property("session_id", SchemaBuilder::string())
                         .build(),
                 )
-                .example("Fill search box", json!({"selector": "@e5", "value": "search query"}))
-                .errors(&["ELEMENT_NOT_FOUND", "ELEMENT_NOT_EDITABLE"]),
+                .example("Attach to Gmail", json!({"id": "gmail", "url": "mail.google.com"}))
+                .example("Attach by title", json!({"id": "docs", "title": "Quarterly plan"}))
+                .errors(&["TAB_NOT_FOUND"]),
 
-            MethodInfo::new("browser.press", "Press a keyboard key")
+            MethodInfo::new("browser.targets", "List tabs that browser.session.attach can bind to")
                 .schema(
                     SchemaBuilder::object()
                         .property(
-                            "key",
-                            SchemaBuilder::string()
-                                .description("Key name: Enter, Tab, Escape, ArrowDown, etc."),
+                            "all",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Include tabs a session already drives"),
                         )
-                        .property("session_id", session_param())
-                        .required(&["key"])
                         .build(),
                 )
                 .returns(
                     SchemaBuilder::object()
-                        .property("pressed", SchemaBuilder::boolean())
-                        .property("key", SchemaBuilder::string())
+                        .property(
+                            "targets",
+                            SchemaBuilder::array().items(
+                                SchemaBuilder::object()
+                                    .property("target_id", SchemaBuilder::string())
+                                    .property("url", SchemaBuilder::string())
+                                    .property("title", SchemaBuilder::string())
+                                    .property("session_id", SchemaBuilder::string()),
+                            ),
+                        )
+                        .property("count", SchemaBuilder::integer())
                         .build(),
                 )
-                .example("Press Enter", json!({"key": "Enter"}))
-                .example("Press Escape", json!({"key": "Escape"})),
+                .example("Tabs free to attach", json!({})),
 
-            MethodInfo::new("browser.select", "Select an option from a dropdown")
-                .schema(
+            MethodInfo::new("browser.session.list", "List all active browser sessions")
+                .schema(SchemaBuilder::object().build())
+                .returns(
                     SchemaBuilder::object()
                         .property(
-                            "selector",
-                            SchemaBuilder::string()
-                                .description("@eN ref or CSS selector for <select> element"),
-                        )
-                        .property(
-                            "value",
-                            SchemaBuilder::string().description("Option value to select"),
+                            "sessions",
+                            SchemaBuilder::array().items(
+                                SchemaBuilder::object()
+                                    .property("id", SchemaBuilder::string())
+                                    .property("name", SchemaBuilder::string())
+                                    .property("created_at", SchemaBuilder::string().format("date-time"))
+                                    .property("url", SchemaBuilder::string().format("uri"))
+                                    .property("title", SchemaBuilder::string())
+                                    .property("active", SchemaBuilder::boolean())
+                                    .property("headers", SchemaBuilder::object())
+                                    .property("auth_username", SchemaBuilder::string())
+                                    .property("media", SchemaBuilder::object())
+                                    .property("metadata", SchemaBuilder::object()),
+                            ),
                         )
-                        .property("session_id", session_param())
-                        .required(&["selector", "value"])
+                        .property("count", SchemaBuilder::integer())
+                        .build(),
+                )
+                .example("List sessions", json!({})),
+
+            MethodInfo::new("browser.session.info", "Get one session's details, with its current URL and title")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("session_id", SchemaBuilder::string().description("Session ID (default session if omitted)"))
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("id", SchemaBuilder::string())
+                        .property("name", SchemaBuilder::string())
+                        .property("created_at", SchemaBuilder::string().format("date-time"))
+                        .property("url", SchemaBuilder::string().format("uri"))
+                        .property("title", SchemaBuilder::string())
+                        .property("active", SchemaBuilder::boolean())
+                        .property("metadata", SchemaBuilder::object())
+                        .build(),
+                )
+                .example("Inspect a session", json!({"session_id": "cart"}))
+                .errors(&["SESSION_NOT_FOUND"]),
+
+            MethodInfo::new("browser.session.set_metadata", "Label a session with a key/value pair (shown by session.list)")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("key", SchemaBuilder::string().description("Metadata key"))
+                        .property("value", SchemaBuilder::string().description("Value; replaces any previous value for the key"))
+                        .property("session_id", SchemaBuilder::string().description("Session to label (default session if omitted)"))
+                        .required(&["key", "value"])
                         .build(),
                 )
                 .returns(
                     SchemaBuilder::object()
-                        .property("selected", SchemaBuilder::boolean())
-                        .property("value", SchemaBuilder::string())
+                        .property("success", SchemaBuilder::boolean())
+                        .property("metadata", SchemaBuilder::object().description("All of the session's metadata"))
                         .build(),
                 )
-                .example("Select option", json!({"selector": "@e10", "value": "option2"}))
-                .errors(&["ELEMENT_NOT_FOUND", "OPTION_NOT_FOUND"]),
+                .example("Tag a session", json!({"session_id": "abc123", "key": "owner", "value": "checkout-tests"}))
+                .errors(&["SESSION_NOT_FOUND"]),
 
-            MethodInfo::new("browser.check", "Set checkbox or radio button state")
+            MethodInfo::new("browser.session.get_metadata", "Get a session's metadata labels")
                 .schema(
                     SchemaBuilder::object()
-                        .property(
-                            "selector",
-                            SchemaBuilder::string()
-                                .description("@eN ref or CSS selector"),
-                        )
-                        .property(
-                            "checked",
-                            SchemaBuilder::boolean()
-                                .default_value(json!(true))
-                                .description("Desired checked state"),
-                        )
-                        .property("session_id", session_param())
-                        .required(&["selector"])
+                        .property("session_id", SchemaBuilder::string().description("Session ID (default session if omitted)"))
                         .build(),
                 )
                 .returns(
                     SchemaBuilder::object()
-                        .property("checked", SchemaBuilder::boolean())
+                        .property("metadata", SchemaBuilder::object())
                         .build(),
                 )
-                .example("Check checkbox", json!({"selector": "@e8"}))
-                .example("Uncheck", json!({"selector": "@e8", "checked": false})),
+                .example("Read metadata", json!({"session_id": "abc123"}))
+                .errors(&["SESSION_NOT_FOUND"]),
 
-            MethodInfo::new("browser.hover", "Hover over an element")
+            MethodInfo::new("browser.session.set_idle_timeout", "Close custom sessions after a period without commands")
                 .schema(
                     SchemaBuilder::object()
                         .property(
-                            "selector",
-                            SchemaBuilder::string()
-                                .description("@eN ref or CSS selector"),
+                            "secs",
+                            SchemaBuilder::integer()
+                                .minimum(0)
+                                .description("Idle seconds before a session is closed (0 = never). Checked once a minute; the default session is never closed"),
                         )
-                        .property("session_id", session_param())
-                        .required(&["selector"])
+                        .required(&["secs"])
                         .build(),
                 )
                 .returns(
                     SchemaBuilder::object()
-                        .property("hovered", SchemaBuilder::boolean())
+                        .property("success", SchemaBuilder::boolean())
+                        .property("idle_timeout_secs", SchemaBuilder::integer())
                         .build(),
                 )
-                .example("Hover over menu", json!({"selector": "@e12"}))
-                .errors(&["ELEMENT_NOT_FOUND"]),
+                .example("Close after 10 idle minutes", json!({"secs": 600}))
+                .example("Never close", json!({"secs": 0})),
 
-            MethodInfo::new("browser.scroll", "Scroll page or element")
+            MethodInfo::new("browser.session.close", "Close and dispose a browser session")
                 .schema(
                     SchemaBuilder::object()
                         .property(
-                            "selector",
-                            SchemaBuilder::string()
-                                .description("@eN ref or CSS selector to scroll into view"),
+                            "session_id",
+                            SchemaBuilder::string().description("Session ID to close"),
                         )
+                        .required(&["session_id"])
+                        .build(),
+                )
+                .returns(
+                    SchemaBuilder::object()
+                        .property("closed", SchemaBuilder::boolean())
+                        .property("session_id", SchemaBuilder::string())
+                        .build(),
+                )
+                .example("Close session", json!({"session_id": "abc123"}))
+                .errors(&["SESSION_NOT_FOUND"]),
+
+            MethodInfo::new("browser.tabs.list", "List open tabs (page targets)")
+                .schema(SchemaBuilder::object().build())
+                .returns(
+                    SchemaBuilder::object()
                         .property(
-                            "direction",
-                            SchemaBuilder::string()
-                                .enum_values(&["up", "down", "left", "right"])
-                                .description("Scroll direction (if no selector)"),
+                            "tabs",
+                            SchemaBuilder::array().items(
+                                SchemaBuilder::object()
+                                    .property("target_id", SchemaBuilder::string())
+                                    .property("url", SchemaBuilder::string())
+                                    .property("title", SchemaBuilder::string())
+                                    .property("active", SchemaBuilder::boolean())
+                                    .property("session_id", SchemaBuilder::string()),
+                            ),
                         )
+                        .property("count", SchemaBuilder::integer())
+                        .build(),
+                )
+                .example("List tabs", json!({})),
+
+            MethodInfo::new("browser.tabs.switch", "Drive another tab from the default session")
+                .schema(
+                    SchemaBuilder::object()
                         .property(
-                            "amount",
-                            SchemaBuilder::integer()
-                                .minimum(0)
-                                .default_value(json!(500))
-                                .description("Pixels to scroll (if using direction)"),
+                            "target_id",
+                            SchemaBuilder::string().description("Target ID from browser.tabs.list"),
                         )
-                        .property("session_id", session_param())
+                        .required(&["target_id"])
                         .build(),
                 )
                 .returns(
                     SchemaBuilder::object()
-                        .property("scrolled", SchemaBuilder::boolean())
+                        .property("success", SchemaBuilder::boolean())
+                        .property("target_id", SchemaBuilder::string())
                         .build(),
                 )
-                .example("Scroll to element", json!({"selector": "@e50"}))
-                .example("Scroll down", json!({"direction": "down", "amount": 1000})),
+                .example("Switch tab", json!({"target_id": "9A1B2C3D4E5F"}))
+                .errors(&["TAB_NOT_FOUND"]),
 
-            MethodInfo::new("browser.press_combo", "Press key with modifiers (Ctrl, Shift, Alt, Meta)")
+            MethodInfo::new("browser.tabs.attach", "Drive an existing tab, chosen by URL substring or index, from the default session")
                 .schema(
                     SchemaBuilder::object()
                         .property(
-                            "key",
-                            SchemaBuilder::string().description("Main key to press"),
-                        )
-                        .property(
-                            "modifiers",
-                            SchemaBuilder::array()
-                                .items(
-                                    SchemaBuilder::string()
-                                        .enum_values(&["ctrl", "shift", "alt", "meta"]),
-                                )
-                                .description("Modifier keys to hold"),
+                            "url",
+                            SchemaBuilder::string().description("URL substring; the first matching tab wins"),
+                        )
+                        .property(
+                            "index",
+                            SchemaBuilder::integer()
+                                .minimum(0)
+                                .description("Position in browser.tabs.list"),
                         )
-                        .property("session_id", session_param())
-                        .required(&["key", "modifiers"])
                         .build(),
                 )
                 .returns(
                     SchemaBuilder::object()
-                        .property("pressed", SchemaBuilder::boolean())
+                        .property("target_id", SchemaBuilder::string())
+                        .property("url", SchemaBuilder::string())
+                        .property("title", SchemaBuilder::string())
+                        .property("session_id", SchemaBuilder::string())
                         .build(),
                 )
-                .example("Select all (Ctrl+A)", json!({"key": "a", "modifiers": ["ctrl"]}))
-                .example("Copy (Cmd+C on Mac)", json!({"key": "c", "modifiers": ["meta"]})),
+                .example("Attach to Gmail", json!({"url": "mail.google.com"}))
+                .example("Attach to the second tab", json!({"index": 1}))
+                .errors(&["TAB_NOT_FOUND"]),
 
-            MethodInfo::new("browser.upload", "Upload a file to a file input element")
+            MethodInfo::new("browser.tabs.open_new", "Open a URL in a new tab of the default context, driven by its own session")
                 .schema(
                     SchemaBuilder::object()
                         .property(
-                            "selector",
-                            SchemaBuilder::string()
-                                .description("@eN ref or CSS selector for file input"),
-                        )
-                        .property(
-                            "path",
+                            "url",
                             SchemaBuilder::string()
-                                .description("Absolute path to file to upload"),
+                                .format("uri")
+                                .description("URL to open"),
                         )
-                        .property("session_id", session_param())
-                        .required(&["selector", "path"])
+                        .required(&["url"])
                         .build(),
                 )
                 .returns(
                     SchemaBuilder::object()
-                        .property("uploaded", SchemaBuilder::boolean())
-                        .property("filename", SchemaBuilder::string())
+                        .property(
+                            "session_id",
+                            SchemaBuilder::string().description("Pass as session_id to drive the tab"),
+                        )
+                        .property("target_id", SchemaBuilder::string())
+                        .property("url", SchemaBuilder::string())
+                        .property("title", SchemaBuilder::string())
                         .build(),
                 )
-                .example("Upload file", json!({"selector": "@e30", "path": "/tmp/document.pdf"}))
-                .errors(&["ELEMENT_NOT_FOUND", "FILE_NOT_FOUND"]),
+                .example("Open tab", json!({"url": "https://example.com"})),
 
-            // ================================================================
-            // Auth State Management
-            // ================================================================
-            MethodInfo::new("browser.state.save", "Save auth state (cookies + localStorage) for reuse")
+            MethodInfo::new("browser.tabs.close", "Close a session's tab (or, with force, a user-opened tab)")
                 .schema(
                     SchemaBuilder::object()
                         .property(
-                            "name",
+                            "session_id",
+                            SchemaBuilder::string().description("Session whose tab to close"),
+                        )
+                        .property(
+                            "target_id",
                             SchemaBuilder::string()
-                                .min_length(1)
-                                .max_length(64)
-                                .pattern("^[a-zA-Z0-9_-]+$")
-                                .description("Name for this auth state"),
+                                .description("Target ID of a tab no session drives (from browser.tabs.list)"),
+                        )
+                        .property(
+                            "force",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Allow closing tabs the daemon didn't open"),
                         )
-                        .property("session_id", session_param())
-                        .required(&["name"])
                         .build(),
                 )
                 .returns(
                     SchemaBuilder::object()
-                        .property("saved", SchemaBuilder::boolean())
-                        .property("name", SchemaBuilder::string())
-                        .property("path", SchemaBuilder::string())
+                        .property("closed", SchemaBuilder::boolean())
+                        .property("id", SchemaBuilder::string())
                         .build(),
                 )
-                .example("Save GitHub auth", json!({"name": "github-prod"})),
+                .example("Close a tab session", json!({"session_id": "tab-9a1b2c3d"}))
+                .example("Close a user tab", json!({"target_id": "9A1B2C3D4E5F", "force": true}))
+                .errors(&["SESSION_NOT_FOUND", "TAB_NOT_FOUND"]),
 
-            MethodInfo::new("browser.state.load", "Load previously saved auth state")
+            MethodInfo::new("browser.pages.list", "List a session's pages: its own tab and popups opened from it")
                 .schema(
                     SchemaBuilder::object()
-                        .property(
-                            "name",
-                            SchemaBuilder::string().description("Name of saved auth state"),
-                        )
                         .property("session_id", session_param())
-                        .required(&["name"])
+                        .property("endpoint", endpoint_param())
                         .build(),
                 )
                 .returns(
                     SchemaBuilder::object()
-                        .property("loaded", SchemaBuilder::boolean())
-                        .property("name", SchemaBuilder::string())
+                        .property(
+                            "pages",
+                            SchemaBuilder::array().items(page_info_schema()),
+                        )
+                        .property("active", SchemaBuilder::integer().description("Index of the page commands target"))
+                        .property(
+                            "closed",
+                            SchemaBuilder::array()
+                                .items(
+                                    SchemaBuilder::object()
+                                        .property("target_id", SchemaBuilder::string())
+                                        .property("switched_to", SchemaBuilder::integer()),
+                                )
+                                .description("Active popups that closed since the last listing; commands moved back to their opener"),
+                        )
                         .build(),
                 )
-                .example("Load GitHub auth", json!({"name": "github-prod"}))
-                .errors(&["STATE_NOT_FOUND"]),
+                .example("List pages after a popup opened", json!({}))
+                .errors(&["SESSION_NOT_FOUND"]),
 
-            MethodInfo::new("browser.state.list", "List all saved auth states")
-                .schema(SchemaBuilder::object().build())
-                .returns(
+            MethodInfo::new("browser.pages.switch", "Target another of the session's pages (e.g. an OAuth popup) with subsequent commands")
+                .schema(
                     SchemaBuilder::object()
                         .property(
-                            "states",
-                            SchemaBuilder::array().items(
-                                SchemaBuilder::object()
-                                    .property("name", SchemaBuilder::string())
-                                    .property("created_at", SchemaBuilder::string().format("date-time"))
-                                    .property("size_bytes", SchemaBuilder::integer()),
-                            ),
+                            "index",
+                            SchemaBuilder::integer().description("Page index from browser.pages.list (0 = the session's own tab)"),
                         )
-                        .property("count", SchemaBuilder::integer())
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["index"])
                         .build(),
                 )
-                .example("List auth states", json!({})),
+                .returns(page_info_schema().build())
+                .example("Switch to a popup", json!({"index": 1}))
+                .errors(&["SESSION_NOT_FOUND", "PAGE_NOT_FOUND"]),
 
-            // ================================================================
-            // Session Management
-            // ================================================================
-            MethodInfo::new("browser.session.new", "Create isolated session with separate cookies/storage")
+            MethodInfo::new("browser.tab.new", "Open a tab in the session's browser context and target it with subsequent commands")
                 .schema(
                     SchemaBuilder::object()
                         .property(
-                            "name",
+                            "url",
                             SchemaBuilder::string()
-                                .description("Optional friendly name for the session"),
+                                .format("uri")
+                                .description("URL to open (default about:blank)"),
                         )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .build(),
+                )
+                .returns(tab_info_schema().build())
+                .example("Open a second tab", json!({"url": "https://example.com"}))
+                .errors(&["SESSION_NOT_FOUND"]),
+
+            MethodInfo::new("browser.tab.list", "List the tabs in the session's browser context (in connect mode, the user's tabs too)")
+                .schema(
+                    SchemaBuilder::object()
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
                         .build(),
                 )
                 .returns(
                     SchemaBuilder::object()
-                        .property("session_id", SchemaBuilder::string())
-                        .property("name", SchemaBuilder::string())
+                        .property("tabs", SchemaBuilder::array().items(tab_info_schema()))
+                        .property("count", SchemaBuilder::integer())
                         .build(),
                 )
-                .example("Create named session", json!({"name": "shopping-cart"}))
-                .example("Create anonymous session", json!({})),
+                .example("List the session's tabs", json!({}))
+                .errors(&["SESSION_NOT_FOUND"]),
 
-            MethodInfo::new("browser.session.list", "List all active browser sessions")
-                .schema(SchemaBuilder::object().build())
-                .returns(
+            MethodInfo::new("browser.tab.switch", "Target another tab in the session's browser context with subsequent commands")
+                .schema(
                     SchemaBuilder::object()
                         .property(
-                            "sessions",
-                            SchemaBuilder::array().items(
-                                SchemaBuilder::object()
-                                    .property("session_id", SchemaBuilder::string())
-                                    .property("name", SchemaBuilder::string())
-                                    .property("created_at", SchemaBuilder::string().format("date-time"))
-                                    .property("current_url", SchemaBuilder::string().format("uri")),
-                            ),
+                            "target_id",
+                            SchemaBuilder::string().description("Target ID from browser.tab.list"),
                         )
-                        .property("count", SchemaBuilder::integer())
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["target_id"])
                         .build(),
                 )
-                .example("List sessions", json!({})),
+                .returns(tab_info_schema().build())
+                .example("Switch tab", json!({"target_id": "9A1B2C3D4E5F"}))
+                .errors(&["SESSION_NOT_FOUND", "PAGE_NOT_FOUND"]),
 
-            MethodInfo::new("browser.session.close", "Close and dispose a browser session")
+            MethodInfo::new("browser.tab.close", "Close a tab in the session's browser context; commands move back to the session's tab")
                 .schema(
                     SchemaBuilder::object()
                         .property(
-                            "session_id",
-                            SchemaBuilder::string().description("Session ID to close"),
+                            "target_id",
+                            SchemaBuilder::string().description("Target ID from browser.tab.list"),
                         )
-                        .required(&["session_id"])
+                        .property(
+                            "force",
+                            SchemaBuilder::boolean()
+                                .default_value(json!(false))
+                                .description("Allow closing tabs the session didn't open or switch to"),
+                        )
+                        .property("session_id", session_param())
+                        .property("endpoint", endpoint_param())
+                        .required(&["target_id"])
                         .build(),
                 )
                 .returns(
                     SchemaBuilder::object()
                         .property("closed", SchemaBuilder::boolean())
-                        .property("session_id", SchemaBuilder::string())
+                        .property("target_id", SchemaBuilder::string())
                         .build(),
                 )
-                .example("Close session", json!({"session_id": "abc123"}))
-                .errors(&["SESSION_NOT_FOUND"]),
+                .example("Close a tab", json!({"target_id": "9A1B2C3D4E5F"}))
+                .errors(&["SESSION_NOT_FOUND", "PAGE_NOT_FOUND"]),
 
             // ================================================================
             // Extension Methods (requires Chrome extension)
             // ================================================================
+            MethodInfo::new("browser.extension.status", "Extension bridge connection, traffic and request queue status")
+                .schema(SchemaBuilder::object().build())
+                .returns(
+                    SchemaBuilder::object()
+                        .property("bridge_enabled", SchemaBuilder::boolean())
+                        .property("connected", SchemaBuilder::boolean())
+                        .property("port", SchemaBuilder::integer().description("Null when the bridge is off"))
+                        .property("messages_sent", SchemaBuilder::integer().description("Requests sent to the extension"))
+                        .property("messages_received", SchemaBuilder::integer().description("Messages received from the extension"))
+                        .property(
+                            "last_connected_at",
+                            SchemaBuilder::string().description("When the extension last connected (RFC 3339); null if never"),
+                        )
+                        .property(
+                            "queue_capacity",
+                            SchemaBuilder::integer().description("Null unless started with --extension-queue"),
+                        )
+                        .property("queued_count", SchemaBuilder::integer())
+                        .property("dropped_count", SchemaBuilder::integer())
+                        .property("reconnect_attempts", SchemaBuilder::integer())
+                        .build(),
+                )
+                .example("Check the extension", json!({})),
+
             MethodInfo::new("browser.tabs.group", "[Extension] Group tabs together")
                 .schema(
                     SchemaBuilder::object()
@@ -1352,6 +7908,75 @@ impl FgpService for BrowserService {
 mod tests {
     use super::*;
     use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const LAUNCH_TIME: Duration = Duration::from_millis(200);
+
+    /// Launcher for a Chrome that isn't there.
+    #[derive(Default)]
+    struct MissingChrome {
+        launches: Arc<AtomicUsize>,
+    }
+
+    impl MissingChrome {
+        fn fail(&self) -> BoxFuture<'static, Result<BrowserClient>> {
+            let launches = self.launches.clone();
+            async move {
+                launches.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(LAUNCH_TIME).await;
+                anyhow::bail!("Chrome not found")
+            }
+            .boxed()
+        }
+    }
+
+    impl Launcher for MissingChrome {
+        fn launch(&self, _: PathBuf, _: bool, _: ViewportSize, _: Option<BrowserChannel>) -> BoxFuture<'static, Result<BrowserClient>> {
+            self.fail()
+        }
+
+        fn connect(&self, _: &str) -> BoxFuture<'static, Result<BrowserClient>> {
+            self.fail()
+        }
+    }
+
+    #[test]
+    fn test_concurrent_callers_share_a_failed_launch() {
+        let runtime = Runtime::new().unwrap();
+        let launcher = Arc::new(MissingChrome::default());
+        let slot = Arc::new(ClientSlot {
+            launcher: launcher.clone(),
+            ..Default::default()
+        });
+        // Its own endpoint, so the cooldown doesn't reach other tests
+        let url = "http://10.255.0.1:9222";
+        let started = Instant::now();
+
+        let errors: Vec<String> = runtime.block_on(async {
+            let callers: Vec<_> = (0..8)
+                .map(|_| {
+                    let slot = slot.clone();
+                    tokio::spawn(async move {
+                        BrowserService::get_or_init_client(&slot, Path::new("/tmp"), true, ViewportSize::default(), None, Some(url))
+                            .await
+                            .map(|_| ())
+                    })
+                })
+                .collect();
+
+            let mut errors = Vec::new();
+            for caller in callers {
+                errors.push(caller.await.unwrap().unwrap_err().to_string());
+            }
+            errors
+        });
+
+        assert_eq!(launcher.launches.load(Ordering::SeqCst), 1);
+        assert!(errors.iter().all(|e| e == "Chrome not found"));
+        // Nobody waited for a second launch
+        assert!(started.elapsed() < LAUNCH_TIME * 2);
+        assert!(runtime.block_on(slot.current()).is_none());
+    }
 
     #[test]
     fn test_get_session_id_with_session_id() {
@@ -1372,6 +7997,249 @@ mod tests {
         assert_eq!(session_id, Some("my-session".to_string()));
     }
 
+    #[test]
+    fn test_get_same_origin_only() {
+        let mut params = HashMap::new();
+        assert!(!BrowserService::get_same_origin_only(&params));
+
+        params.insert("same_origin".to_string(), json!(true));
+        assert!(BrowserService::get_same_origin_only(&params));
+
+        params.insert("same_origin_only".to_string(), json!(false));
+        assert!(!BrowserService::get_same_origin_only(&params));
+    }
+
+    #[test]
+    fn test_lane_key() {
+        let mut params = HashMap::new();
+        assert_eq!(BrowserService::lane_key("browser.open", &params, None).as_deref(), Some("default"));
+        assert_eq!(BrowserService::lane_key("browser.session.list", &params, None), None);
+
+        // Naming the default session (as BiDi's "default" context does) shares its lane
+        params.insert("session_id".to_string(), json!("default"));
+        assert_eq!(BrowserService::lane_key("browser.open", &params, None).as_deref(), Some("default"));
+
+        params.insert("session_id".to_string(), json!("agent-a"));
+        assert_eq!(BrowserService::lane_key("browser.open", &params, None).as_deref(), Some("agent-a"));
+
+        // Creating or closing a session waits in that session's lane
+        let params = HashMap::from([("id".to_string(), json!("agent-b"))]);
+        assert_eq!(BrowserService::lane_key("session.close", &params, None).as_deref(), Some("agent-b"));
+
+        // Cloning waits behind the session it copies
+        let params = HashMap::from([("source_id".to_string(), json!("login")), ("id".to_string(), json!("worker-1"))]);
+        assert_eq!(BrowserService::lane_key("session.clone", &params, None).as_deref(), Some("login"));
+
+        // The same session name on another Chrome doesn't wait
+        let params = HashMap::new();
+        assert_eq!(
+            BrowserService::lane_key("browser.open", &params, Some("http://10.0.0.2:9222")).as_deref(),
+            Some("http://10.0.0.2:9222#default")
+        );
+    }
+
+    #[test]
+    fn test_pooled_endpoint() {
+        let pooled = |endpoint, own| BrowserService::pooled_endpoint(endpoint, own).unwrap();
+        assert_eq!(pooled("http://10.0.0.2:9222/", None).as_deref(), Some("http://10.0.0.2:9222"));
+        assert_eq!(pooled("10.0.0.2:9222", None), pooled("http://10.0.0.2:9222/", None));
+        // The service's own Chrome, however it's spelled, isn't pooled
+        assert_eq!(pooled("http://localhost:9222/", Some("http://localhost:9222")), None);
+        assert_eq!(pooled("localhost:9222", Some("http://localhost:9222/")), None);
+    }
+
+    #[test]
+    #[ignore = "launches Chrome"]
+    fn test_sessions_run_concurrently_through_service() {
+        let service = BrowserService::new(true, ViewportSize::default(), None).unwrap();
+        let ids = ["lanes-test-a", "lanes-test-b"];
+        for id in ids {
+            service.dispatch("browser.session.new", HashMap::from([("id".to_string(), json!(id))])).unwrap();
+        }
+
+        // Nothing downloads, so each call waits out its timeout
+        let wait = |id: &str| {
+            let params = HashMap::from([("session_id".to_string(), json!(id)), ("timeout_ms".to_string(), json!(2000))]);
+            let start = Instant::now();
+            assert!(service.dispatch("browser.downloads.wait", params).is_err());
+            (start, Instant::now())
+        };
+        // A new session opens and gets listed while both calls run
+        let create = || {
+            std::thread::sleep(Duration::from_millis(200));
+            let start = Instant::now();
+            service.dispatch("browser.session.new", HashMap::from([("id".to_string(), json!("lanes-test-c"))])).unwrap();
+            let listed = service.dispatch("browser.session.list", HashMap::new()).unwrap();
+            assert!(listed.to_string().contains("lanes-test-c"));
+            (start, Instant::now())
+        };
+        let (a, b, c) = std::thread::scope(|s| {
+            let a = s.spawn(|| wait(ids[0]));
+            let b = s.spawn(|| wait(ids[1]));
+            let c = s.spawn(create);
+            (a.join().unwrap(), b.join().unwrap(), c.join().unwrap())
+        });
+
+        // Each session's call started before the other's finished
+        assert!(a.0 < b.1 && b.0 < a.1);
+        assert!(a.0 < c.0 && c.1 < a.1 && c.1 < b.1);
+        for id in ids.into_iter().chain(["lanes-test-c"]) {
+            service.dispatch("browser.session.close", HashMap::from([("id".to_string(), json!(id))])).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_client_pool_evicts_idle_endpoints() {
+        let pool = ClientPool::new(Duration::ZERO);
+        let lanes = Arc::default();
+        let config = ClientConfig {
+            max_sessions: 3,
+            ..Default::default()
+        };
+        let (first, stale) = pool.checkout("http://10.0.0.1:9222", config, &lanes);
+        assert!(stale.is_empty());
+        // New clients for the endpoint get the service's settings
+        assert_eq!(first.config.lock().unwrap().max_sessions, 3);
+
+        // Still held by a call, so it stays
+        let (_, stale) = pool.checkout("http://10.0.0.2:9222", config, &lanes);
+        assert!(stale.is_empty());
+        assert_eq!(pool.endpoints(), vec!["http://10.0.0.1:9222", "http://10.0.0.2:9222"]);
+
+        drop(first);
+        let (_, stale) = pool.checkout("http://10.0.0.2:9222", config, &lanes);
+        let evicted: Vec<&str> = stale.iter().map(|(url, _)| url.as_str()).collect();
+        assert_eq!(evicted, vec!["http://10.0.0.1:9222"]);
+        assert_eq!(pool.endpoints(), vec!["http://10.0.0.2:9222"]);
+        assert!(pool.get("http://10.0.0.1:9222").is_none());
+    }
+
+    #[test]
+    fn test_client_slot_keeps_restarts_without_a_client() {
+        let slot = ClientSlot::new(
+            ClientConfig {
+                idle_timeout_secs: 60,
+                ..Default::default()
+            },
+            None,
+        );
+        slot.browser_died(vec!["a".to_string(), "b".to_string()]);
+        // Until a relaunch succeeds, the slot has no client but keeps the rest
+        assert!(slot.client.try_read().unwrap().is_none());
+        assert_eq!(slot.restart_count(), 1);
+        assert_eq!(
+            slot.restarts.lock().unwrap().lost_sessions,
+            Some(vec!["a".to_string(), "b".to_string()])
+        );
+        assert_eq!(slot.config.lock().unwrap().idle_timeout_secs, 60);
+    }
+
+    #[test]
+    fn test_parse_text_range() {
+        let mut params = HashMap::new();
+        assert_eq!(BrowserService::parse_text_range(&params).unwrap(), None);
+
+        params.insert("start".to_string(), json!(2));
+        assert!(BrowserService::parse_text_range(&params).is_err());
+
+        params.insert("end".to_string(), json!(5));
+        assert_eq!(BrowserService::parse_text_range(&params).unwrap(), Some((2, 5)));
+
+        params.insert("end".to_string(), json!(1));
+        assert!(BrowserService::parse_text_range(&params).is_err());
+    }
+
+    #[test]
+    fn test_parse_points() {
+        let mut params = HashMap::new();
+        assert!(BrowserService::parse_points(&params, "points").unwrap().is_empty());
+
+        params.insert("points".to_string(), json!([{"x": 10, "y": 20.5}, {"x": 30, "y": 40}]));
+        let points = BrowserService::parse_points(&params, "points").unwrap();
+        assert_eq!((points[0].x, points[0].y), (10.0, 20.5));
+        assert_eq!(points.len(), 2);
+
+        params.insert("points".to_string(), json!([{"x": 10}]));
+        assert!(BrowserService::parse_points(&params, "points").is_err());
+    }
+
+    #[test]
+    fn test_parse_select_values() {
+        let mut params = HashMap::new();
+        assert!(BrowserService::parse_select_values(&params).is_err());
+
+        params.insert("value".to_string(), json!("us"));
+        assert_eq!(BrowserService::parse_select_values(&params).unwrap(), vec!["us"]);
+
+        params.insert("value".to_string(), json!(["us", "Canada"]));
+        assert_eq!(BrowserService::parse_select_values(&params).unwrap(), vec!["us", "Canada"]);
+
+        params.insert("value".to_string(), json!(["us", 1]));
+        assert!(BrowserService::parse_select_values(&params).is_err());
+    }
+
+    #[test]
+    fn test_parse_upload_content() {
+        let mut params = HashMap::new();
+        assert!(BrowserService::parse_upload_content(&params).unwrap().is_none());
+
+        params.insert("content_base64".to_string(), json!("aGVsbG8="));
+        assert!(BrowserService::parse_upload_content(&params).is_err());
+
+        params.insert("filename".to_string(), json!("hello.txt"));
+        let (filename, bytes) = BrowserService::parse_upload_content(&params).unwrap().unwrap();
+        assert_eq!((filename.as_str(), bytes.as_slice()), ("hello.txt", b"hello".as_slice()));
+
+        params.insert("path".to_string(), json!("/tmp/a.pdf"));
+        assert!(BrowserService::parse_upload_content(&params).is_err());
+
+        params.remove("path");
+        params.insert("content_base64".to_string(), json!("not base64!"));
+        assert!(BrowserService::parse_upload_content(&params).is_err());
+    }
+
+    #[test]
+    fn test_parse_upload_paths() {
+        let mut params = HashMap::new();
+        assert!(BrowserService::parse_upload_paths(&params).is_err());
+
+        params.insert("path".to_string(), json!("/tmp/a.pdf"));
+        assert_eq!(BrowserService::parse_upload_paths(&params).unwrap(), vec!["/tmp/a.pdf"]);
+
+        // paths wins over path
+        params.insert("paths".to_string(), json!(["/tmp/a.jpg", "/tmp/b.jpg"]));
+        assert_eq!(BrowserService::parse_upload_paths(&params).unwrap(), vec!["/tmp/a.jpg", "/tmp/b.jpg"]);
+
+        params.insert("paths".to_string(), json!("/tmp/a.jpg"));
+        assert!(BrowserService::parse_upload_paths(&params).is_err());
+    }
+
+    #[test]
+    fn test_parse_key_presses() {
+        let mut params = HashMap::new();
+        assert!(BrowserService::parse_key_presses(&params).is_err());
+
+        params.insert("keys".to_string(), json!(["j", {"key": "s", "modifiers": ["ctrl"], "delay_after_ms": 50}]));
+        let keys = BrowserService::parse_key_presses(&params).unwrap();
+        assert_eq!((keys[0].key.as_str(), keys[0].modifiers.len(), keys[0].delay_after_ms), ("j", 0, 0));
+        assert_eq!((keys[1].modifiers.clone(), keys[1].delay_after_ms), (vec!["ctrl".to_string()], 50));
+
+        params.insert("keys".to_string(), json!([{"modifiers": ["ctrl"]}]));
+        assert!(BrowserService::parse_key_presses(&params).is_err());
+    }
+
+    #[test]
+    fn test_parse_mouse_button() {
+        let mut params = HashMap::new();
+        assert_eq!(BrowserService::parse_mouse_button(&params).unwrap(), MouseButton::Left);
+
+        params.insert("button".to_string(), json!("right"));
+        assert_eq!(BrowserService::parse_mouse_button(&params).unwrap(), MouseButton::Right);
+
+        params.insert("button".to_string(), json!("back"));
+        assert!(BrowserService::parse_mouse_button(&params).is_err());
+    }
+
     #[test]
     fn test_get_session_id_missing() {
         let mut params = HashMap::new();
@@ -1391,6 +8259,183 @@ mod tests {
         assert_eq!(session_id, Some("preferred".to_string()));
     }
 
+    #[test]
+    fn test_parse_screenshot_format() {
+        assert_eq!(
+            BrowserService::parse_screenshot_format(None, None).unwrap(),
+            ScreenshotFormat::Png
+        );
+        assert_eq!(
+            BrowserService::parse_screenshot_format(Some("jpg"), Some(50)).unwrap(),
+            ScreenshotFormat::Jpeg { quality: 50 }
+        );
+        assert_eq!(
+            BrowserService::parse_screenshot_format(Some("webp"), None).unwrap(),
+            ScreenshotFormat::Webp { quality: 80 }
+        );
+        assert!(BrowserService::parse_screenshot_format(Some("gif"), None).is_err());
+        assert!(BrowserService::parse_screenshot_format(Some("jpeg"), Some(101)).is_err());
+        assert!(BrowserService::parse_screenshot_format(Some("jpeg"), Some(-1)).is_err());
+    }
+
+    #[test]
+    fn test_parse_emulation() {
+        let params = |v: Value| -> HashMap<String, Value> { serde_json::from_value(v).unwrap() };
+
+        let preset = BrowserService::parse_emulation(&params(json!({"preset": "pixel-7"})))
+            .unwrap()
+            .unwrap();
+        assert_eq!((preset.width, preset.height), (412, 915));
+        assert!(preset.is_mobile);
+
+        let overridden = BrowserService::parse_emulation(&params(
+            json!({"preset": "ipad", "width": 1024, "user_agent": "test-agent"}),
+        ))
+        .unwrap()
+        .unwrap();
+        assert_eq!((overridden.width, overridden.height), (1024, 1180));
+        assert_eq!(overridden.user_agent.as_deref(), Some("test-agent"));
+
+        let custom = BrowserService::parse_emulation(&params(json!({"width": 800, "height": 600})))
+            .unwrap()
+            .unwrap();
+        assert_eq!(custom.device_scale_factor, 1.0);
+        assert!(!custom.is_mobile);
+        assert!(custom.user_agent.is_none());
+
+        assert!(BrowserService::parse_emulation(&params(json!({"reset": true})))
+            .unwrap()
+            .is_none());
+        assert!(BrowserService::parse_emulation(&params(json!({"preset": "nokia"}))).is_err());
+        assert!(BrowserService::parse_emulation(&params(json!({"width": 800}))).is_err());
+        assert!(BrowserService::parse_emulation(&params(json!({"width": 0, "height": 600}))).is_err());
+    }
+
+    #[test]
+    fn test_parse_media_emulation() {
+        let params = |v: Value| -> HashMap<String, Value> { serde_json::from_value(v).unwrap() };
+
+        let dark = BrowserService::parse_media_emulation(&params(
+            json!({"color_scheme": "dark", "reduced_motion": true}),
+        ))
+        .unwrap()
+        .unwrap();
+        assert_eq!(dark.color_scheme.as_deref(), Some("dark"));
+        assert_eq!(dark.reduced_motion, Some(true));
+        assert_eq!(dark.media, None);
+
+        assert!(BrowserService::parse_media_emulation(&params(json!({"reset": true})))
+            .unwrap()
+            .is_none());
+        assert!(BrowserService::parse_media_emulation(&params(json!({}))).is_err());
+        assert!(BrowserService::parse_media_emulation(&params(json!({"media": "tv"}))).is_err());
+    }
+
+    #[test]
+    fn test_parse_network_conditions() {
+        let params = |v: Value| -> HashMap<String, Value> { serde_json::from_value(v).unwrap() };
+
+        let slow = BrowserService::parse_network_conditions(&params(json!({"profile": "slow-3g"})))
+            .unwrap()
+            .unwrap();
+        assert_eq!(slow.profile.as_deref(), Some("slow-3g"));
+
+        let custom = BrowserService::parse_network_conditions(&params(
+            json!({"profile": "fast-3g", "latency_ms": 100}),
+        ))
+        .unwrap()
+        .unwrap();
+        assert_eq!(custom.profile, None);
+        assert_eq!(custom.latency_ms, 100.0);
+        assert_eq!(custom.download_kbps, Some(1440.0));
+
+        let explicit = BrowserService::parse_network_conditions(&params(json!({"download_kbps": 256})))
+            .unwrap()
+            .unwrap();
+        assert_eq!(explicit.download_kbps, Some(256.0));
+        assert_eq!(explicit.upload_kbps, None);
+
+        // `network.emulate` spellings: preset, CDP units, offline shortcut
+        let cdp = BrowserService::parse_network_conditions(&params(
+            json!({"preset": "slow-3g", "download_throughput": 50_000, "latency": 400}),
+        ))
+        .unwrap()
+        .unwrap();
+        assert_eq!(cdp.download_kbps, Some(400.0));
+        assert_eq!(cdp.latency_ms, 400.0);
+        let offline = BrowserService::parse_network_conditions(&params(json!({"offline": true})))
+            .unwrap()
+            .unwrap();
+        assert!(offline.offline);
+
+        assert!(BrowserService::parse_network_conditions(&params(json!({"reset": true})))
+            .unwrap()
+            .is_none());
+        assert!(BrowserService::parse_network_conditions(&params(json!({"preset": "none"})))
+            .unwrap()
+            .is_none());
+        assert!(BrowserService::parse_network_conditions(&params(json!({}))).is_err());
+        assert!(BrowserService::parse_network_conditions(&params(json!({"profile": "5g"}))).is_err());
+        assert!(BrowserService::parse_network_conditions(&params(json!({"latency_ms": -5}))).is_err());
+    }
+
+    #[test]
+    fn test_parse_geolocation() {
+        let params = |v: Value| -> HashMap<String, Value> { serde_json::from_value(v).unwrap() };
+
+        assert_eq!(
+            BrowserService::parse_geolocation(&params(json!({"latitude": 52.52, "longitude": 13.405})))
+                .unwrap(),
+            (52.52, 13.405, 0.0)
+        );
+        assert!(BrowserService::parse_geolocation(&params(json!({"latitude": 91, "longitude": 0}))).is_err());
+        assert!(BrowserService::parse_geolocation(&params(json!({"latitude": 0, "longitude": -181}))).is_err());
+        assert!(BrowserService::parse_geolocation(&params(json!({"latitude": 0}))).is_err());
+        assert!(BrowserService::parse_geolocation(
+            &params(json!({"latitude": 0, "longitude": 0, "accuracy": -1}))
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_validate_state_name() {
+        assert!(BrowserService::validate_state_name("github-prod").is_ok());
+        assert!(BrowserService::validate_state_name("user_1").is_ok());
+        assert!(BrowserService::validate_state_name("").is_err());
+        assert!(BrowserService::validate_state_name("../secrets").is_err());
+        assert!(BrowserService::validate_state_name("a/b").is_err());
+        assert!(BrowserService::validate_state_name("name.json").is_err());
+    }
+
+    #[test]
+    fn test_validate_session_id() {
+        assert!(BrowserService::validate_session_id("agent-1").is_ok());
+        assert!(BrowserService::validate_session_id(&BrowserService::generate_session_id()).is_ok());
+        assert!(BrowserService::validate_session_id("").is_err());
+        assert!(BrowserService::validate_session_id("../../../home/alice").is_err());
+        assert!(BrowserService::validate_session_id("a b").is_err());
+    }
+
+    #[test]
+    fn test_rename_state_file() {
+        let dir = std::env::temp_dir().join(format!("fgp-state-rename-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("old.json"), "{}").unwrap();
+        std::fs::write(dir.join("taken.json"), "{}").unwrap();
+
+        assert!(BrowserService::rename_state_file(&dir, "old", "../escape", false).is_err());
+        assert!(BrowserService::rename_state_file(&dir, "missing", "new", false).is_err());
+        let err = BrowserService::rename_state_file(&dir, "old", "taken", false).unwrap_err();
+        assert!(err.to_string().starts_with("STATE_EXISTS"));
+
+        assert!(!BrowserService::rename_state_file(&dir, "old", "new", false).unwrap());
+        assert!(!dir.join("old.json").exists());
+        assert!(BrowserService::rename_state_file(&dir, "new", "taken", true).unwrap());
+        assert!(dir.join("taken.json").exists() && !dir.join("new.json").exists());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
     #[test]
     fn test_get_session_id_ignores_non_string() {
         let mut params = HashMap::new();